@@ -5,14 +5,32 @@ use axum::Json;
 
 use crate::api::error::ApiError;
 use crate::api::state::ApiState;
+use crate::backtest::copy::{CopyBacktestReport, CopyBacktestRequest};
 use crate::backtest::{BacktestRequest, BacktestResult};
 
 pub async fn run(
     State(state): State<Arc<ApiState>>,
-    Json(req): Json<BacktestRequest>,
+    Json(mut req): Json<BacktestRequest>,
 ) -> Result<Json<BacktestResult>, ApiError> {
+    req.fee_bps.get_or_insert(state.default_fee_bps);
     crate::backtest::runner::run(&req, &state.ch)
         .await
         .map(Json)
         .map_err(|e| ApiError::Validation(e.to_string()))
 }
+
+pub async fn copy(
+    State(state): State<Arc<ApiState>>,
+    Json(mut req): Json<CopyBacktestRequest>,
+) -> Result<Json<CopyBacktestReport>, ApiError> {
+    req.fee_bps.get_or_insert(state.default_fee_bps);
+    crate::backtest::copy::run(
+        &req,
+        &state.ch,
+        &state.http,
+        &state.relayer.network().data_api_url,
+    )
+    .await
+    .map(Json)
+    .map_err(|e| ApiError::Validation(e.to_string()))
+}
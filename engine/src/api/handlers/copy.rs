@@ -1,12 +1,14 @@
 use std::sync::Arc;
 
-use axum::extract::State;
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::Json;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::api::error::ApiError;
 use crate::api::state::ApiState;
+use crate::storage::postgres::{self, CopyModeStats};
+use crate::watcher::discovery::{self, CandidateFilter, CandidateList};
 
 #[derive(Deserialize)]
 pub struct CopyWatchRequest {
@@ -27,6 +29,56 @@ pub async fn unwatch(
     redis_key_op(&state, &req.leader_address, RedisOp::Del).await
 }
 
+#[derive(Serialize)]
+pub struct CopyStatsResponse {
+    pub copy_relationship_id: i64,
+    pub live: Option<CopyModeStats>,
+    pub paper: Option<CopyModeStats>,
+}
+
+/// Copy activity of a relationship, with paper copies reported apart from live ones.
+pub async fn stats(
+    State(state): State<Arc<ApiState>>,
+    Path(id): Path<i64>,
+) -> Result<Json<CopyStatsResponse>, ApiError> {
+    let rows = postgres::load_copy_stats(&state.db, id)
+        .await
+        .map_err(|e| {
+            tracing::error!(id, error = %e, "copy_stats_load_failed");
+            ApiError::Internal(e.to_string())
+        })?;
+    let (paper, live): (Vec<_>, Vec<_>) = rows.into_iter().partition(|r| r.is_paper);
+    Ok(Json(CopyStatsResponse {
+        copy_relationship_id: id,
+        live: live.into_iter().next(),
+        paper: paper.into_iter().next(),
+    }))
+}
+
+/// Wallets ranked by the leader discovery job, filtered by the query.
+pub async fn candidates(
+    State(state): State<Arc<ApiState>>,
+    Query(filter): Query<CandidateFilter>,
+) -> Result<Json<CandidateList>, ApiError> {
+    let Some(ref redis) = state.redis else {
+        return Err(ApiError::ServiceUnavailable);
+    };
+    if filter
+        .min_win_rate
+        .is_some_and(|w| !(0.0..=1.0).contains(&w))
+    {
+        return Err(ApiError::Validation(
+            "min_win_rate must be between 0 and 1".into(),
+        ));
+    }
+    let mut list = discovery::load(&mut redis.clone()).await.map_err(|e| {
+        tracing::error!(error = %e, "leader_candidates_load_failed");
+        ApiError::Internal(e.to_string())
+    })?;
+    list.candidates = filter.apply(list.candidates);
+    Ok(Json(list))
+}
+
 enum RedisOp {
     Set,
     Del,
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use crate::api::error::ApiError;
+use crate::api::state::ApiState;
+use crate::storage::postgres::DeadLetter;
+
+#[derive(Deserialize)]
+pub struct DeadLetterQuery {
+    pub limit: Option<i64>,
+}
+
+pub async fn dead_letters(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<DeadLetterQuery>,
+) -> Result<Json<Vec<DeadLetter>>, ApiError> {
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let dead_letters = state.journal.dead_letters(limit).await.map_err(|e| {
+        tracing::error!(error = %e, "dead_letters_load_failed");
+        ApiError::Internal(e.to_string())
+    })?;
+    Ok(Json(dead_letters))
+}
+
+#[derive(Serialize)]
+pub struct RequeueResponse {
+    pub order_id: uuid::Uuid,
+    pub wallet_id: u64,
+}
+
+pub async fn requeue(
+    State(state): State<Arc<ApiState>>,
+    Path(id): Path<i64>,
+) -> Result<Json<RequeueResponse>, ApiError> {
+    let order = state
+        .journal
+        .requeue_dead_letter(&state.exec_queue, id)
+        .await
+        .map_err(|e| {
+            tracing::error!(id, error = %e, "dead_letter_requeue_failed");
+            ApiError::Internal(e.to_string())
+        })?
+        .ok_or_else(|| ApiError::NotFound(format!("no pending dead letter {id}")))?;
+
+    tracing::info!(id, order_id = %order.id, "dead_letter_requeued");
+    Ok(Json(RequeueResponse {
+        order_id: order.id,
+        wallet_id: order.wallet_id,
+    }))
+}
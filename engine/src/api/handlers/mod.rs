@@ -1,5 +1,6 @@
 pub mod backtest;
 pub mod copy;
+pub mod execution;
 pub mod metrics;
 pub mod safe;
 pub mod stats;
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use axum::extract::{Query, State};
+use axum::extract::{Path, Query, State};
 use axum::Json;
 use serde::Deserialize;
 use time::Duration;
@@ -9,6 +9,7 @@ use crate::api::error::ApiError;
 use crate::api::state::ApiState;
 use crate::stats::queries::{self, MlDatasetParams, StatsParams};
 use crate::stats::types::{MlDatasetResponse, SlotStatsResponse};
+use crate::watcher::scorecard::{self, LeaderScorecard};
 
 #[derive(Deserialize)]
 pub struct StatsQuery {
@@ -17,6 +18,9 @@ pub struct StatsQuery {
     pub symbols: Option<String>,
     #[serde(default = "default_hours")]
     pub hours: f64,
+    /// Taker fee rate; the engine's configured rate when omitted.
+    #[serde(default)]
+    pub fee_bps: Option<u16>,
 }
 
 fn default_hours() -> f64 {
@@ -78,6 +82,7 @@ pub async fn slots(
         slot_duration: q.slot_duration,
         symbols,
         hours: q.hours,
+        fee_bps: q.fee_bps.unwrap_or(state.default_fee_bps),
     };
 
     let (summary, heatmap, calibration, by_symbol, stoploss_sweep, by_hour, by_day) =
@@ -153,6 +158,42 @@ pub async fn ml_dataset(
     }))
 }
 
+#[derive(Deserialize)]
+pub struct LeaderQuery {
+    #[serde(default = "default_leader_days")]
+    pub days: u32,
+}
+
+fn default_leader_days() -> u32 {
+    7
+}
+
+pub async fn leader(
+    State(state): State<Arc<ApiState>>,
+    Path(address): Path<String>,
+    Query(q): Query<LeaderQuery>,
+) -> Result<Json<LeaderScorecard>, ApiError> {
+    if !(1..=30).contains(&q.days) {
+        return Err(ApiError::Validation("days must be between 1 and 30".into()));
+    }
+    let network = state.relayer.network();
+    let card = scorecard::build(
+        &state.ch,
+        &state.db,
+        &state.http,
+        &network.data_api_url,
+        &network.gamma_api_url,
+        &address,
+        i64::from(q.days) * 86_400,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(address = %address, error = %e, "leader_scorecard_failed");
+        ApiError::Internal(e.to_string())
+    })?;
+    Ok(Json(card))
+}
+
 #[cfg(test)]
 mod tests {
     use super::parse_symbols;
@@ -1,15 +1,23 @@
 use std::sync::Arc;
 
+use alloy::primitives::{Address, U256};
 use axum::extract::{Path, State};
+use axum::http::StatusCode;
 use axum::Json;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+use crate::api::error::ApiError;
 use crate::api::state::ApiState;
+use crate::execution::balances::BalanceSnapshot;
+use crate::execution::safe_tx::{safe_tx_status, SafeCall, SafeTxState};
+use crate::storage::postgres::{self, RedemptionRow, SafeTransactionRow};
 
 #[derive(Serialize)]
 pub struct WalletStateResponse {
     pub wallet_id: u64,
     pub assignments: Vec<AssignmentState>,
+    /// USDC and token balances net of in-flight orders; `None` when unknown.
+    pub balances: Option<BalanceSnapshot>,
 }
 
 #[derive(Serialize)]
@@ -17,7 +25,10 @@ pub struct AssignmentState {
     pub strategy_id: u64,
     pub markets: Vec<String>,
     pub position: Option<PositionSnapshot>,
+    /// Realized PnL net of fees.
     pub pnl: f64,
+    pub gross_pnl: f64,
+    pub fees_paid: f64,
 }
 
 #[derive(Serialize)]
@@ -26,12 +37,14 @@ pub struct PositionSnapshot {
     pub entry_price: f64,
     pub size_usdc: f64,
     pub entry_at: i64,
+    pub entry_fee_usdc: f64,
 }
 
 pub async fn state(
     State(app): State<Arc<ApiState>>,
     Path(wallet_id): Path<u64>,
 ) -> Json<WalletStateResponse> {
+    let balances = app.balances.snapshot(wallet_id).await;
     let reg = app.registry.read().await;
     let mut assignments = Vec::new();
 
@@ -50,12 +63,15 @@ pub async fn state(
                     entry_price: p.entry_price,
                     size_usdc: p.size_usdc,
                     entry_at: p.entry_at,
+                    entry_fee_usdc: p.entry_fee_usdc,
                 });
                 assignments.push(AssignmentState {
                     strategy_id: a.strategy_id,
                     markets: a.markets.clone(),
                     position,
                     pnl: state_lock.pnl,
+                    gross_pnl: state_lock.gross_pnl,
+                    fees_paid: state_lock.fees_paid,
                 });
             }
         }
@@ -64,5 +80,270 @@ pub async fn state(
     Json(WalletStateResponse {
         wallet_id,
         assignments,
+        balances,
     })
 }
+
+#[derive(Serialize)]
+pub struct WalletRedemptionsResponse {
+    pub wallet_id: u64,
+    /// USDC freed by confirmed redemptions.
+    pub redeemed_usdc: f64,
+    /// USDC still waiting on submitted redemptions.
+    pub pending_usdc: f64,
+    pub redemptions: Vec<RedemptionRow>,
+}
+
+pub async fn redemptions(
+    State(app): State<Arc<ApiState>>,
+    Path(wallet_id): Path<u64>,
+) -> Result<Json<WalletRedemptionsResponse>, ApiError> {
+    let redemptions = postgres::load_wallet_redemptions(&app.db, wallet_id as i64, 500)
+        .await
+        .map_err(|e| {
+            tracing::error!(wallet_id, error = %e, "wallet_redemptions_load_failed");
+            ApiError::Internal(e.to_string())
+        })?;
+    let total = |state: &str| -> f64 {
+        redemptions
+            .iter()
+            .filter(|r| r.state == state)
+            .map(|r| r.expected_usdc)
+            .sum()
+    };
+    Ok(Json(WalletRedemptionsResponse {
+        wallet_id,
+        redeemed_usdc: total("confirmed"),
+        pending_usdc: total("submitted"),
+        redemptions,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct WithdrawRequest {
+    pub to_address: String,
+    pub amount_usdc: f64,
+}
+
+pub async fn withdraw(
+    State(app): State<Arc<ApiState>>,
+    Path(wallet_id): Path<u64>,
+    Json(req): Json<WithdrawRequest>,
+) -> Result<Json<SafeTransactionRow>, ApiError> {
+    let recipient = parse_address("to_address", &req.to_address)?;
+    if !(req.amount_usdc.is_finite() && req.amount_usdc > 0.0) {
+        return Err(ApiError::Validation("amount_usdc must be positive".into()));
+    }
+    require_safe(&app, wallet_id)?;
+    // Never send funds the Safe may not hold: no balance, no withdrawal.
+    let Some(balances) = app.balances.snapshot(wallet_id).await else {
+        return Err(ApiError::Validation(format!(
+            "balance of wallet {wallet_id} could not be read; try again shortly"
+        )));
+    };
+    if req.amount_usdc > balances.usdc_available {
+        return Err(ApiError::Validation(format!(
+            "amount_usdc exceeds available balance of {:.2}",
+            balances.usdc_available
+        )));
+    }
+    let call = SafeCall::usdc_withdraw(app.relayer.network(), recipient, req.amount_usdc)
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    submit(&app, wallet_id, call).await.map(Json)
+}
+
+/// Any other Safe transaction the web app may issue on a wallet's behalf.
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SafeTransactionRequest {
+    /// USDC allowance for `spender`: omitted for unlimited, `0` to revoke.
+    Approve {
+        spender: String,
+        amount_usdc: Option<f64>,
+    },
+    /// Conditional token operator approval.
+    CtfApproval { operator: String, approved: bool },
+    /// Send `shares` of an outcome token out of the Safe.
+    TokenTransfer {
+        to_address: String,
+        token_id: String,
+        shares: f64,
+    },
+}
+
+pub async fn submit_transaction(
+    State(app): State<Arc<ApiState>>,
+    Path(wallet_id): Path<u64>,
+    Json(req): Json<SafeTransactionRequest>,
+) -> Result<Json<SafeTransactionRow>, ApiError> {
+    let network = app.relayer.network();
+    let call =
+        match req {
+            SafeTransactionRequest::Approve {
+                spender,
+                amount_usdc,
+            } => SafeCall::usdc_approve(network, parse_address("spender", &spender)?, amount_usdc),
+            SafeTransactionRequest::CtfApproval { operator, approved } => Ok(
+                SafeCall::ctf_approval(network, parse_address("operator", &operator)?, approved),
+            ),
+            SafeTransactionRequest::TokenTransfer {
+                to_address,
+                token_id,
+                shares,
+            } => {
+                let token_id: U256 = token_id
+                    .parse()
+                    .map_err(|_| ApiError::Validation("token_id must be a uint256".into()))?;
+                let safe = app
+                    .wallet_keys
+                    .get_safe_address(wallet_id)
+                    .map_err(|e| ApiError::Validation(e.to_string()))?;
+                SafeCall::token_transfer(
+                    network,
+                    safe,
+                    parse_address("to_address", &to_address)?,
+                    token_id,
+                    shares,
+                )
+            }
+        }
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    submit(&app, wallet_id, call).await.map(Json)
+}
+
+#[derive(Serialize)]
+pub struct WalletTransactionsResponse {
+    pub wallet_id: u64,
+    pub transactions: Vec<SafeTransactionRow>,
+}
+
+/// Recent Safe transactions of a wallet. Submitted ones are refreshed from the
+/// relayer, and their new state persisted, before answering.
+pub async fn transactions(
+    State(app): State<Arc<ApiState>>,
+    Path(wallet_id): Path<u64>,
+) -> Result<Json<WalletTransactionsResponse>, ApiError> {
+    let mut transactions = postgres::load_wallet_safe_transactions(&app.db, wallet_id as i64, 200)
+        .await
+        .map_err(|e| {
+            tracing::error!(wallet_id, error = %e, "wallet_transactions_load_failed");
+            ApiError::Internal(e.to_string())
+        })?;
+
+    for tx in transactions
+        .iter_mut()
+        .filter(|tx| tx.state == SafeTxState::Submitted.as_str())
+    {
+        let (state, tx_hash) = match safe_tx_status(&app.relayer, &tx.relayer_tx_id).await {
+            Ok(status) => status,
+            Err(e) => {
+                tracing::warn!(id = tx.id, error = %e, "safe_tx_status_failed");
+                continue;
+            }
+        };
+        if state == SafeTxState::Submitted {
+            continue;
+        }
+        if let Err(e) = postgres::update_safe_transaction_state(
+            &app.db,
+            tx.id,
+            state.as_str(),
+            tx_hash.as_deref(),
+        )
+        .await
+        {
+            tracing::warn!(id = tx.id, error = %e, "safe_tx_update_failed");
+            continue;
+        }
+        tx.state = state.as_str().to_string();
+        tx.tx_hash = tx_hash.or(tx.tx_hash.take());
+    }
+
+    Ok(Json(WalletTransactionsResponse {
+        wallet_id,
+        transactions,
+    }))
+}
+
+/// Sign and submit `call` through the relayer, then persist it.
+async fn submit(
+    app: &ApiState,
+    wallet_id: u64,
+    call: SafeCall,
+) -> Result<SafeTransactionRow, ApiError> {
+    require_safe(app, wallet_id)?;
+    let submission = app.relayer.execute(wallet_id, &call).await.map_err(|e| {
+        tracing::error!(wallet_id, kind = call.kind.as_str(), error = %e, "safe_tx_submit_failed");
+        ApiError::Internal(format!("safe transaction failed: {e}"))
+    })?;
+    tracing::info!(
+        wallet_id,
+        kind = call.kind.as_str(),
+        nonce = submission.nonce,
+        tx_id = %submission.transaction_id,
+        "safe_tx_submitted"
+    );
+    postgres::insert_safe_transaction(&app.db, wallet_id as i64, &call, &submission)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                wallet_id,
+                tx_id = %submission.transaction_id,
+                error = %e,
+                "safe_tx_insert_failed"
+            );
+            ApiError::Internal(e.to_string())
+        })
+}
+
+fn require_safe(app: &ApiState, wallet_id: u64) -> Result<(), ApiError> {
+    match app.wallet_keys.get_safe_address(wallet_id) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(ApiError::Validation(format!(
+            "wallet {wallet_id} has no deployed Safe"
+        ))),
+    }
+}
+
+fn parse_address(field: &str, value: &str) -> Result<Address, ApiError> {
+    value
+        .parse()
+        .map_err(|_| ApiError::Validation(format!("{field} is not a valid address")))
+}
+
+#[derive(Serialize)]
+pub struct ApiKeyResponse {
+    pub wallet_id: u64,
+    pub api_key: String,
+    pub nonce: u64,
+}
+
+/// Replace the wallet's CLOB API key with a fresh one (also lifts a revocation).
+pub async fn rotate_api_key(
+    State(app): State<Arc<ApiState>>,
+    Path(wallet_id): Path<u64>,
+) -> Result<Json<ApiKeyResponse>, ApiError> {
+    let creds = app.api_keys.rotate(wallet_id).await.map_err(|e| {
+        tracing::error!(wallet_id, error = %e, "api_key_rotate_failed");
+        ApiError::Internal(format!("api key rotation failed: {e}"))
+    })?;
+    tracing::info!(wallet_id, nonce = creds.nonce, "api_key_rotated");
+    Ok(Json(ApiKeyResponse {
+        wallet_id,
+        api_key: creds.credentials.api_key,
+        nonce: creds.nonce,
+    }))
+}
+
+/// Delete the wallet's CLOB API key; its orders fail until the key is rotated.
+pub async fn revoke_api_key(
+    State(app): State<Arc<ApiState>>,
+    Path(wallet_id): Path<u64>,
+) -> Result<StatusCode, ApiError> {
+    app.api_keys.revoke(wallet_id).await.map_err(|e| {
+        tracing::error!(wallet_id, error = %e, "api_key_revoke_failed");
+        ApiError::Internal(format!("api key revocation failed: {e}"))
+    })?;
+    tracing::info!(wallet_id, "api_key_revoked");
+    Ok(StatusCode::OK)
+}
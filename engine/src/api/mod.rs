@@ -7,7 +7,7 @@ mod tests;
 
 use std::sync::Arc;
 
-use axum::routing::{get, post};
+use axum::routing::{delete, get, post};
 use axum::Router;
 
 use state::ApiState;
@@ -28,20 +28,55 @@ pub fn router(state: Arc<ApiState>) -> Router {
             post(handlers::strategy::unkill),
         )
         .route("/internal/wallet/{id}/state", get(handlers::wallet::state))
+        .route(
+            "/internal/wallet/{id}/redemptions",
+            get(handlers::wallet::redemptions),
+        )
+        .route(
+            "/internal/wallet/{id}/withdraw",
+            post(handlers::wallet::withdraw),
+        )
+        .route(
+            "/internal/wallet/{id}/transactions",
+            get(handlers::wallet::transactions).post(handlers::wallet::submit_transaction),
+        )
+        .route(
+            "/internal/wallet/{id}/api-key",
+            delete(handlers::wallet::revoke_api_key),
+        )
+        .route(
+            "/internal/wallet/{id}/api-key/rotate",
+            post(handlers::wallet::rotate_api_key),
+        )
         .route(
             "/internal/wallet/deploy-safe",
             post(handlers::safe::deploy_safe),
         )
         .route("/internal/backtest/run", post(handlers::backtest::run))
+        .route("/internal/backtest/copy", post(handlers::backtest::copy))
         .route("/internal/engine/status", get(handlers::status::status))
         .route("/internal/copy/watch", post(handlers::copy::watch))
         .route("/internal/copy/unwatch", post(handlers::copy::unwatch))
+        .route("/internal/copy/{id}/stats", get(handlers::copy::stats))
+        .route("/internal/copy/candidates", get(handlers::copy::candidates))
+        .route(
+            "/internal/execution/dead-letters",
+            get(handlers::execution::dead_letters),
+        )
+        .route(
+            "/internal/execution/dead-letters/{id}/requeue",
+            post(handlers::execution::requeue),
+        )
         .route("/metrics", get(handlers::metrics::render))
         .route("/internal/stats/slots", get(handlers::stats::slots))
         .route(
             "/internal/stats/slots/ml-dataset",
             get(handlers::stats::ml_dataset),
         )
+        .route(
+            "/internal/stats/leaders/{address}",
+            get(handlers::stats::leader),
+        )
         .with_state(state)
 }
 
@@ -3,9 +3,16 @@ use std::sync::Arc;
 
 use clickhouse::Client as ChClient;
 use metrics_exporter_prometheus::PrometheusHandle;
+use sqlx::PgPool;
+use tokio::sync::Mutex;
 
+use crate::execution::balances::BalanceService;
+use crate::execution::credentials::CredentialManager;
+use crate::execution::journal::ExecutionJournal;
+use crate::execution::queue::ExecutionQueue;
 use crate::execution::relayer::RelayerClient;
 use crate::execution::wallet::WalletKeyStore;
+use crate::proxy::HttpPool;
 use crate::strategy::registry::AssignmentRegistry;
 
 pub struct ApiState {
@@ -17,4 +24,12 @@ pub struct ApiState {
     pub prometheus: PrometheusHandle,
     pub wallet_keys: Arc<WalletKeyStore>,
     pub relayer: Arc<RelayerClient>,
+    pub balances: Arc<BalanceService>,
+    pub api_keys: Arc<CredentialManager>,
+    pub db: PgPool,
+    pub journal: Arc<ExecutionJournal>,
+    pub exec_queue: Arc<Mutex<ExecutionQueue>>,
+    pub http: HttpPool,
+    /// Taker fee rate for backtests and stats requests that omit `fee_bps`.
+    pub default_fee_bps: u16,
 }
@@ -17,15 +17,33 @@ fn test_state() -> Arc<ApiState> {
         .unwrap(),
     );
     let http = crate::proxy::HttpPool::new(&[], std::time::Duration::from_secs(5)).unwrap();
+    let db = sqlx::postgres::PgPoolOptions::new()
+        .acquire_timeout(std::time::Duration::from_millis(200))
+        .connect_lazy("postgres://localhost:1/test")
+        .unwrap();
+    let network = Arc::new(crate::network::NetworkProfile::local());
+    let api_keys = Arc::new(crate::execution::credentials::CredentialManager::new(
+        http.clone(),
+        network.clone(),
+        db.clone(),
+        wallet_keys.clone(),
+    ));
+    let balances = Arc::new(crate::execution::balances::BalanceService::new(
+        http.clone(),
+        network.clone(),
+        api_keys.clone(),
+        wallet_keys.clone(),
+    ));
     let relayer = Arc::new(crate::execution::relayer::RelayerClient::new(
-        http,
-        "http://localhost:9999",
+        http.clone(),
+        network,
         crate::execution::orders::BuilderCredentials {
             api_key: String::new(),
             secret: String::new(),
             passphrase: String::new(),
         },
         wallet_keys.clone(),
+        db.clone(),
     ));
     Arc::new(ApiState {
         registry: crate::strategy::registry::AssignmentRegistry::new(),
@@ -36,6 +54,15 @@ fn test_state() -> Arc<ApiState> {
         prometheus: handle,
         wallet_keys,
         relayer,
+        balances,
+        api_keys,
+        db: db.clone(),
+        journal: Arc::new(crate::execution::journal::ExecutionJournal::new(db, 120)),
+        exec_queue: Arc::new(tokio::sync::Mutex::new(
+            crate::execution::queue::ExecutionQueue::new(100),
+        )),
+        http,
+        default_fee_bps: crate::execution::fees::DEFAULT_FEE_BPS,
     })
 }
 
@@ -100,6 +127,7 @@ async fn test_wallet_state_empty() {
     let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
     assert_eq!(json["wallet_id"], 999);
     assert_eq!(json["assignments"], serde_json::json!([]));
+    assert!(json["balances"].is_null(), "unknown wallet has no balances");
 }
 
 #[tokio::test]
@@ -131,6 +159,8 @@ async fn test_wallet_state_with_assignment() {
     assert_eq!(json["wallet_id"], 42);
     assert_eq!(json["assignments"].as_array().unwrap().len(), 1);
     assert_eq!(json["assignments"][0]["strategy_id"], 200);
+    assert_eq!(json["assignments"][0]["gross_pnl"], 0.0);
+    assert_eq!(json["assignments"][0]["fees_paid"], 0.0);
 }
 
 #[tokio::test]
@@ -271,3 +301,110 @@ async fn test_ml_dataset_rejects_invalid_slot_duration() {
         .unwrap()
         .contains("slot_duration must be one of"));
 }
+
+#[tokio::test]
+async fn test_requeue_dead_letter_rejects_non_numeric_id() {
+    let state = test_state();
+    let app = super::router(state);
+
+    let req = Request::builder()
+        .method("POST")
+        .uri("/internal/execution/dead-letters/abc/requeue")
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_withdraw_validates_request() {
+    let state = test_state();
+
+    for (body, expected) in [
+        (
+            serde_json::json!({"to_address": "not-an-address", "amount_usdc": 5.0}),
+            "to_address is not a valid address",
+        ),
+        (
+            serde_json::json!({
+                "to_address": "0x00000000000000000000000000000000000000bb",
+                "amount_usdc": -1.0
+            }),
+            "amount_usdc must be positive",
+        ),
+        (
+            serde_json::json!({
+                "to_address": "0x00000000000000000000000000000000000000bb",
+                "amount_usdc": 5.0
+            }),
+            "has no deployed Safe",
+        ),
+    ] {
+        let req = Request::builder()
+            .method("POST")
+            .uri("/internal/wallet/1/withdraw")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let resp = super::router(state.clone()).oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(json["error"].as_str().unwrap().contains(expected));
+    }
+}
+
+#[tokio::test]
+async fn test_withdraw_rejects_unreadable_balance() {
+    let state = test_state();
+    let signer = alloy::signers::local::PrivateKeySigner::random();
+    let encrypted = state.wallet_keys.encrypt_key(&signer.to_bytes().0).unwrap();
+    state.wallet_keys.store_key(2, &encrypted).unwrap();
+    state
+        .wallet_keys
+        .store_safe_address(2, alloy::primitives::Address::repeat_byte(0xaa))
+        .unwrap();
+
+    // Nothing serves the CLOB: the balance cannot be read, so nothing is sent.
+    let body = serde_json::json!({
+        "to_address": "0x00000000000000000000000000000000000000bb",
+        "amount_usdc": 5.0
+    });
+    let req = Request::builder()
+        .method("POST")
+        .uri("/internal/wallet/2/withdraw")
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let resp = super::router(state).oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(json["error"]
+        .as_str()
+        .unwrap()
+        .contains("could not be read"));
+}
+
+#[tokio::test]
+async fn test_submit_transaction_rejects_bad_token_id() {
+    let state = test_state();
+    let app = super::router(state);
+
+    let body = serde_json::json!({
+        "kind": "token_transfer",
+        "to_address": "0x00000000000000000000000000000000000000bb",
+        "token_id": "abc",
+        "shares": 1.0
+    });
+    let req = Request::builder()
+        .method("POST")
+        .uri("/internal/wallet/1/transactions")
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
@@ -0,0 +1,644 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use alloy::primitives::Address;
+use clickhouse::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use time::{Duration, OffsetDateTime};
+
+use super::metrics;
+use super::runner::{mid_price, simulate_entry_fill, simulate_exit_fill};
+use super::{BacktestResult, BacktestTrade, ExitReason, Side};
+use crate::execution::fees::DEFAULT_FEE_BPS;
+use crate::fetcher::models::Tick;
+use crate::proxy::HttpPool;
+use crate::storage::postgres::CopyRelationship;
+use crate::strategy::Outcome;
+use crate::watcher::filters::CopyFilters;
+use crate::watcher::polymarket::{
+    build_copy_order, fetch_portfolio_value, fetch_trade_history, LeaderTrade, TradeContext,
+    FLATTEN_BELOW, HISTORY_MAX_PAGES,
+};
+use crate::watcher::sizing::{Bankrolls, SizeMode};
+
+const MAX_RANGE_DAYS: i64 = 31;
+const MAX_DELAY_SECS: u32 = 300;
+/// A copy fills at the first snapshot within this many seconds of the
+/// leader's trade plus the delay, or is skipped.
+const SNAPSHOT_TOLERANCE_SECS: i64 = 30;
+/// Snapshots are read past `date_to` so copies still held can settle when
+/// their slot resolves.
+const RESOLUTION_GRACE: Duration = Duration::days(1);
+
+// ---------------------------------------------------------------------------
+// Request / report
+// ---------------------------------------------------------------------------
+
+/// Replay of a leader's trades through a copy configuration, shaped like
+/// `copy_relationships`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CopyBacktestRequest {
+    pub leader_address: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub date_from: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub date_to: OffsetDateTime,
+    #[serde(default = "default_size_mode")]
+    pub size_mode: String,
+    pub size_value: f64,
+    #[serde(default = "default_max_position_usdc")]
+    pub max_position_usdc: f64,
+    pub min_size_usdc: Option<f64>,
+    pub max_size_usdc: Option<f64>,
+    pub markets_filter: Option<Value>,
+    #[serde(default)]
+    pub copy_filters: CopyFilters,
+    /// Seconds between the leader's fill and the simulated copy.
+    #[serde(default = "default_delay_secs")]
+    pub delay_secs: u32,
+    /// Taker fee rate charged on every simulated entry and exit fill;
+    /// the engine's configured rate when omitted.
+    #[serde(default)]
+    pub fee_bps: Option<u16>,
+    /// Follower's USDC at `date_from`. Required by balance-relative size
+    /// modes; when set, entries the balance cannot cover are skipped.
+    pub starting_balance_usdc: Option<f64>,
+}
+
+fn default_size_mode() -> String {
+    "proportional".to_string()
+}
+
+fn default_max_position_usdc() -> f64 {
+    100.0
+}
+
+fn default_delay_secs() -> u32 {
+    1
+}
+
+impl CopyBacktestRequest {
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.date_from >= self.date_to {
+            return Err("date_from must be before date_to");
+        }
+        if self.date_to - self.date_from > Duration::days(MAX_RANGE_DAYS) {
+            return Err("date range must be at most 31 days");
+        }
+        if self.leader_address.parse::<Address>().is_err() {
+            return Err("leader_address must be a wallet address");
+        }
+        if self.size_value <= 0.0 {
+            return Err("size_value must be > 0");
+        }
+        if self.max_position_usdc <= 0.0 {
+            return Err("max_position_usdc must be > 0");
+        }
+        if self.delay_secs > MAX_DELAY_SECS {
+            return Err("delay_secs must be at most 300");
+        }
+        if SizeMode::parse(&self.size_mode).needs_follower_balance()
+            && self.starting_balance_usdc.is_none_or(|b| b <= 0.0)
+        {
+            return Err("starting_balance_usdc is required by this size_mode");
+        }
+        Ok(())
+    }
+
+    /// Paper relationship the live watcher would copy with.
+    fn follower(&self) -> CopyRelationship {
+        CopyRelationship {
+            id: 0,
+            follower_wallet_id: 0,
+            size_mode: self.size_mode.clone(),
+            size_value: self.size_value,
+            max_position_usdc: self.max_position_usdc,
+            min_size_usdc: self.min_size_usdc,
+            max_size_usdc: self.max_size_usdc,
+            markets_filter: self.markets_filter.clone(),
+            execution_algo: None,
+            filters: self.copy_filters.clone(),
+            risk_limits: Default::default(),
+            is_paper: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CopyBacktestReport {
+    #[serde(flatten)]
+    pub result: BacktestResult,
+    pub leader_trades: u32,
+    /// Leader entries and exits the follower copied.
+    pub copied_trades: u32,
+    /// Leader trades not copied, by reason.
+    pub skipped: BTreeMap<&'static str, u32>,
+    pub ending_balance_usdc: Option<f64>,
+    /// The leader traded more in range than the data API pages back to: the
+    /// oldest trades are missing from the replay.
+    pub truncated: bool,
+}
+
+// ---------------------------------------------------------------------------
+// Replay
+// ---------------------------------------------------------------------------
+
+/// Copy `trades` (oldest first) as the live watcher would, filling each copy
+/// against the first snapshot of its market after the leader's trade plus
+/// `delay_secs`. Copies are settled on the leader's exits and on slot
+/// resolution; the rest close at their market's last snapshot.
+pub fn simulate(
+    req: &CopyBacktestRequest,
+    trades: &[LeaderTrade],
+    ticks: Vec<Tick>,
+    leader_value: Option<f64>,
+) -> CopyBacktestReport {
+    let mut replay = Replay::new(req, ticks, leader_value);
+    for trade in trades {
+        replay.trade(trade);
+    }
+    replay.finish(trades.len() as u32)
+}
+
+struct Replay<'a> {
+    req: &'a CopyBacktestRequest,
+    follower: CopyRelationship,
+    fee_bps: u16,
+    /// Snapshots per market slug, oldest first.
+    ticks: HashMap<String, Vec<Tick>>,
+    /// Each market's winner, with the end of its slot.
+    resolutions: HashMap<String, (OffsetDateTime, i8)>,
+    leader_value: Option<f64>,
+    balance: Option<f64>,
+    /// Leader's shares per token, from the replayed trades.
+    leader_held: HashMap<String, f64>,
+    /// Open copied entries per token.
+    lots: HashMap<String, Vec<BacktestTrade>>,
+    closed: Vec<BacktestTrade>,
+    copied: u32,
+    skipped: BTreeMap<&'static str, u32>,
+}
+
+impl<'a> Replay<'a> {
+    fn new(req: &'a CopyBacktestRequest, ticks: Vec<Tick>, leader_value: Option<f64>) -> Self {
+        let mut by_symbol: HashMap<String, Vec<Tick>> = HashMap::new();
+        for tick in ticks {
+            by_symbol.entry(tick.symbol.clone()).or_default().push(tick);
+        }
+        let mut resolutions = HashMap::new();
+        for (symbol, ticks) in &mut by_symbol {
+            ticks.sort_by_key(|t| t.captured_at);
+            // The resolver writes the winner onto every snapshot of the slot;
+            // it only applies from the slot's end.
+            if let Some(resolution) = ticks.iter().find_map(|t| {
+                let end = i64::from(t.slot_ts) + i64::from(t.slot_duration);
+                Some((OffsetDateTime::from_unix_timestamp(end).ok()?, t.winner?))
+            }) {
+                resolutions.insert(symbol.clone(), resolution);
+            }
+        }
+        Self {
+            req,
+            follower: req.follower(),
+            fee_bps: req.fee_bps.unwrap_or(DEFAULT_FEE_BPS),
+            ticks: by_symbol,
+            resolutions,
+            leader_value,
+            balance: req.starting_balance_usdc,
+            leader_held: HashMap::new(),
+            lots: HashMap::new(),
+            closed: Vec::new(),
+            copied: 0,
+            skipped: BTreeMap::new(),
+        }
+    }
+
+    fn trade(&mut self, trade: &LeaderTrade) {
+        let fill_at = trade.timestamp + i64::from(self.req.delay_secs);
+        self.settle_resolved(Some(fill_at));
+
+        // Exits mirror the leader's fraction of their position, counted from
+        // the replayed trades: sells of shares bought before `date_from` have
+        // no copy to close.
+        let held = self.leader_held.entry(trade.asset.clone()).or_insert(0.0);
+        let before = *held;
+        *held = if trade.is_sell() {
+            (before - trade.size).max(0.0)
+        } else {
+            before + trade.size
+        };
+        let exit_fraction = (trade.is_sell() && before > 0.0).then(|| {
+            if *held < before * FLATTEN_BELOW {
+                1.0
+            } else {
+                (trade.size / before).min(1.0)
+            }
+        });
+
+        let Some(tick) = self.snapshot(&trade.slug, fill_at) else {
+            return self.skip("no_snapshot");
+        };
+        let outcome = trade.outcome();
+        let best_ask = match outcome {
+            Outcome::Up => tick.ask_up,
+            Outcome::Down => tick.ask_down,
+        } as f64;
+        let ctx = TradeContext {
+            market: None,
+            slugs: [trade.slug.as_str(), trade.event_slug.as_str()]
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .collect(),
+            now: fill_at,
+            best_ask: Some(best_ask).filter(|a| *a > 0.0),
+        };
+        let bankrolls = Bankrolls {
+            follower: self.balance,
+            leader: self.leader_value,
+        };
+        let exit_shares = exit_fraction.map(|f| f * self.copied_shares(&trade.asset));
+        let order = match build_copy_order(
+            trade,
+            &self.follower,
+            &self.req.leader_address,
+            &ctx,
+            bankrolls,
+            exit_shares,
+        ) {
+            Ok(order) => order,
+            Err(reason) => return self.skip(reason.as_str()),
+        };
+
+        match exit_fraction {
+            Some(fraction) => self.exit(&trade.asset, &tick, fraction),
+            None => self.enter(&trade.asset, outcome, &tick, order.size_usdc),
+        }
+    }
+
+    fn enter(&mut self, asset: &str, outcome: Outcome, tick: &Tick, size_usdc: f64) {
+        if self.balance.is_some_and(|b| size_usdc > b) {
+            return self.skip("insufficient_balance");
+        }
+        let Some(fill) = simulate_entry_fill(outcome, tick, size_usdc) else {
+            return self.skip("no_liquidity");
+        };
+        if let Some(balance) = self.balance.as_mut() {
+            *balance -= size_usdc;
+        }
+        self.lots
+            .entry(asset.to_string())
+            .or_default()
+            .push(BacktestTrade {
+                symbol: tick.symbol.clone(),
+                outcome,
+                side: Side::Buy,
+                entry_price: fill.average_price,
+                entry_reference_price: fill.reference_price,
+                entry_slippage_bps: fill.slippage_bps,
+                entry_book_depth_usdc: fill.book_depth_usdc,
+                entry_depth_ratio: fill.depth_ratio,
+                exit_price: None,
+                exit_reference_price: None,
+                exit_slippage_bps: None,
+                exit_book_depth_usdc: None,
+                exit_depth_ratio: None,
+                size_usdc,
+                pnl_usdc: 0.0,
+                gross_pnl_usdc: 0.0,
+                fees_usdc: 0.0,
+                entry_at: tick.captured_at,
+                exit_at: None,
+                exit_reason: None,
+            });
+        self.copied += 1;
+    }
+
+    /// Close `fraction` of every copied entry of `asset` in one fill, since
+    /// they share the book.
+    fn exit(&mut self, asset: &str, tick: &Tick, fraction: f64) {
+        let Some(lots) = self.lots.remove(asset) else {
+            return self.skip("no_copied_position");
+        };
+        let shares = fraction * self.copied_shares_of(&lots);
+        // An entry price of 1 makes the size the number of shares sold.
+        let Some(fill) = simulate_exit_fill(lots[0].outcome, tick, shares, 1.0) else {
+            self.lots.insert(asset.to_string(), lots);
+            return self.skip("no_liquidity");
+        };
+
+        let mut remaining = Vec::new();
+        for mut lot in lots {
+            let mut closed = lot.clone();
+            if fraction < 1.0 {
+                closed.size_usdc = lot.size_usdc * fraction;
+                lot.size_usdc -= closed.size_usdc;
+                remaining.push(lot);
+            }
+            closed.settle(fill.average_price, self.fee_bps);
+            closed.exit_reference_price = Some(fill.reference_price);
+            closed.exit_slippage_bps = Some(fill.slippage_bps);
+            closed.exit_book_depth_usdc = Some(fill.book_depth_usdc);
+            closed.exit_depth_ratio = Some(fill.depth_ratio);
+            closed.exit_at = Some(tick.captured_at);
+            closed.exit_reason = Some(ExitReason::Signal);
+            self.close(closed);
+        }
+        if !remaining.is_empty() {
+            self.lots.insert(asset.to_string(), remaining);
+        }
+        self.copied += 1;
+    }
+
+    /// Settle the copies of markets resolved by `now`, or of every resolved
+    /// market when `None`.
+    fn settle_resolved(&mut self, now: Option<i64>) {
+        let resolved: Vec<(String, OffsetDateTime, i8)> = self
+            .lots
+            .iter()
+            .filter_map(|(asset, lots)| {
+                let &(at, winner) = self.resolutions.get(&lots.first()?.symbol)?;
+                now.is_none_or(|now| at.unix_timestamp() <= now)
+                    .then(|| (asset.clone(), at, winner))
+            })
+            .collect();
+        for (asset, at, winner) in resolved {
+            for mut lot in self.lots.remove(&asset).unwrap_or_default() {
+                let won = matches!((lot.outcome, winner), (Outcome::Up, 1) | (Outcome::Down, 2));
+                lot.settle(if won { 1.0 } else { 0.0 }, self.fee_bps);
+                lot.exit_at = Some(at);
+                lot.exit_reason = Some(ExitReason::SlotResolved);
+                self.close(lot);
+            }
+        }
+    }
+
+    fn finish(mut self, leader_trades: u32) -> CopyBacktestReport {
+        self.settle_resolved(None);
+
+        // Copies of unresolved markets close at the last snapshot, as
+        // strategy backtests do.
+        for mut lot in std::mem::take(&mut self.lots).into_values().flatten() {
+            let Some(last) = self.ticks.get(&lot.symbol).and_then(|t| t.last()) else {
+                continue;
+            };
+            match simulate_exit_fill(lot.outcome, last, lot.size_usdc, lot.entry_price) {
+                Some(fill) => {
+                    lot.settle(fill.average_price, self.fee_bps);
+                    lot.exit_reference_price = Some(fill.reference_price);
+                    lot.exit_slippage_bps = Some(fill.slippage_bps);
+                    lot.exit_book_depth_usdc = Some(fill.book_depth_usdc);
+                    lot.exit_depth_ratio = Some(fill.depth_ratio);
+                }
+                None => lot.settle(mid_price(lot.outcome, last), self.fee_bps),
+            }
+            lot.exit_at = Some(last.captured_at);
+            lot.exit_reason = Some(ExitReason::EndOfData);
+            self.close(lot);
+        }
+
+        self.closed.sort_by_key(|t| (t.exit_at, t.entry_at));
+        CopyBacktestReport {
+            result: metrics::compute(self.closed),
+            leader_trades,
+            copied_trades: self.copied,
+            skipped: self.skipped,
+            ending_balance_usdc: self.balance,
+            truncated: false,
+        }
+    }
+
+    fn snapshot(&self, symbol: &str, at: i64) -> Option<Tick> {
+        let ticks = self.ticks.get(symbol)?;
+        let i = ticks.partition_point(|t| t.captured_at.unix_timestamp() < at);
+        ticks
+            .get(i)
+            .filter(|t| t.captured_at.unix_timestamp() <= at + SNAPSHOT_TOLERANCE_SECS)
+            .cloned()
+    }
+
+    fn copied_shares(&self, asset: &str) -> f64 {
+        self.lots
+            .get(asset)
+            .map_or(0.0, |lots| self.copied_shares_of(lots))
+    }
+
+    fn copied_shares_of(&self, lots: &[BacktestTrade]) -> f64 {
+        lots.iter().map(|l| l.size_usdc / l.entry_price).sum()
+    }
+
+    /// Record a settled copy and credit its proceeds to the balance.
+    fn close(&mut self, trade: BacktestTrade) {
+        if let Some(balance) = self.balance.as_mut() {
+            *balance += trade.size_usdc + trade.pnl_usdc;
+        }
+        self.closed.push(trade);
+    }
+
+    fn skip(&mut self, reason: &'static str) {
+        *self.skipped.entry(reason).or_default() += 1;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Entry point
+// ---------------------------------------------------------------------------
+
+/// Fetch the leader's trades in range and the snapshots of their markets, then
+/// replay them. Mirror-allocation sizing uses the leader's current portfolio
+/// value, as no history of it is recorded.
+pub async fn run(
+    req: &CopyBacktestRequest,
+    ch_client: &Client,
+    http: &HttpPool,
+    data_api_url: &str,
+) -> anyhow::Result<CopyBacktestReport> {
+    req.validate().map_err(anyhow::Error::msg)?;
+
+    let to = req.date_to.unix_timestamp();
+    let history = fetch_trade_history(
+        data_api_url,
+        http,
+        &req.leader_address,
+        req.date_from.unix_timestamp(),
+        HISTORY_MAX_PAGES,
+    )
+    .await?;
+    let mut trades: Vec<LeaderTrade> = history
+        .trades
+        .into_iter()
+        .filter(|t| t.timestamp <= to)
+        .collect();
+    trades.sort_by_key(|t| (t.timestamp, t.log_index));
+
+    let leader_value = if SizeMode::parse(&req.size_mode).needs_leader_value() {
+        fetch_portfolio_value(http, data_api_url, &req.leader_address).await?
+    } else {
+        None
+    };
+
+    let symbols: Vec<String> = trades
+        .iter()
+        .map(|t| t.slug.clone())
+        .filter(|s| !s.is_empty())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let ticks = crate::storage::clickhouse::fetch_slot_ticks(
+        ch_client,
+        &symbols,
+        req.date_from,
+        req.date_to + RESOLUTION_GRACE,
+    )
+    .await?;
+
+    let mut report = simulate(req, &trades, ticks, leader_value);
+    report.truncated = history.truncated;
+    Ok(report)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::fees::round_trip_fee_usdc;
+    use crate::strategy::test_utils::test_tick;
+
+    const SLOT: &str = "btc-updown-15m-1700000000";
+
+    fn request() -> CopyBacktestRequest {
+        serde_json::from_value(serde_json::json!({
+            "leader_address": "0x1111111111111111111111111111111111111111",
+            "date_from": "2023-11-14T22:00:00Z",
+            "date_to": "2023-11-15T22:00:00Z",
+            "size_mode": "fixed",
+            "size_value": 10.0,
+            "delay_secs": 2,
+            "fee_bps": 0,
+        }))
+        .unwrap()
+    }
+
+    fn trade(side: &str, size: f64, timestamp: i64, slug: &str) -> LeaderTrade {
+        serde_json::from_value(serde_json::json!({
+            "side": side, "asset": "tok_up", "conditionId": "c", "size": size,
+            "price": 0.5, "timestamp": timestamp, "transactionHash": "0xaa",
+            "outcome": "Up", "slug": slug,
+        }))
+        .unwrap()
+    }
+
+    fn tick(at: i64, ask_up: f32, winner: Option<i8>) -> Tick {
+        let mut tick = test_tick();
+        tick.captured_at = OffsetDateTime::from_unix_timestamp(at).unwrap();
+        tick.ask_up = ask_up;
+        tick.winner = winner;
+        tick
+    }
+
+    #[test]
+    fn test_entry_fills_after_delay_and_settles_on_resolution() {
+        let ticks = vec![
+            tick(1_700_000_100, 0.50, None),
+            tick(1_700_000_102, 0.62, None),
+            tick(1_700_000_905, 0.62, Some(1)),
+        ];
+        let trades = [trade("BUY", 100.0, 1_700_000_100, SLOT)];
+        let report = simulate(&request(), &trades, ticks, None);
+
+        assert_eq!(report.copied_trades, 1);
+        assert_eq!(report.result.total_trades, 1);
+        let copy = &report.result.trades[0];
+        // Filled at the snapshot two seconds after the leader, not theirs.
+        assert!((copy.entry_price - 0.62).abs() < 1e-6);
+        assert_eq!(copy.exit_reason, Some(ExitReason::SlotResolved));
+        assert!((copy.pnl_usdc - (1.0 - 0.62) / 0.62 * 10.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_omitted_fee_rate_still_charges_fees() {
+        let ticks = vec![
+            tick(1_700_000_102, 0.62, None),
+            tick(1_700_000_905, 0.62, Some(1)),
+        ];
+        let trades = [trade("BUY", 100.0, 1_700_000_100, SLOT)];
+        let mut req = request();
+        req.fee_bps = None;
+        let report = simulate(&req, &trades, ticks, None);
+
+        let fees = round_trip_fee_usdc(DEFAULT_FEE_BPS, 0.62, 1.0, 10.0);
+        assert!(fees > 0.0);
+        assert!((report.result.total_fees_usdc - fees).abs() < 1e-6);
+        let gross = (1.0 - 0.62) / 0.62 * 10.0;
+        assert!((report.result.trades[0].pnl_usdc - (gross - fees)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_exit_mirrors_leader_fraction() {
+        // The winner is on every snapshot but only settles at the slot's end.
+        let ticks = vec![
+            tick(1_700_000_102, 0.62, Some(2)),
+            tick(1_700_000_202, 0.62, Some(2)),
+            tick(1_700_000_905, 0.62, Some(2)),
+        ];
+        let trades = [
+            trade("BUY", 100.0, 1_700_000_100, SLOT),
+            trade("SELL", 50.0, 1_700_000_200, SLOT),
+        ];
+        let mut req = request();
+        req.starting_balance_usdc = Some(100.0);
+        let report = simulate(&req, &trades, ticks, None);
+
+        assert_eq!(report.copied_trades, 2);
+        let trades = &report.result.trades;
+        assert_eq!(trades.len(), 2);
+        // Half sold at the 0.60 bid, the other half lost on resolution.
+        assert_eq!(trades[0].exit_reason, Some(ExitReason::Signal));
+        assert!((trades[0].size_usdc - 5.0).abs() < 1e-6);
+        assert!((trades[0].exit_price.unwrap() - 0.60).abs() < 1e-6);
+        assert_eq!(trades[1].exit_reason, Some(ExitReason::SlotResolved));
+        assert!((trades[1].pnl_usdc + 5.0).abs() < 1e-6);
+        let ending = 100.0 - 10.0 + 5.0 * 0.60 / 0.62;
+        assert!((report.ending_balance_usdc.unwrap() - ending).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_skips_are_counted_by_reason() {
+        let ticks = vec![tick(1_700_000_102, 0.62, None)];
+        let trades = [
+            trade("BUY", 100.0, 1_700_000_000, SLOT),
+            trade("BUY", 100.0, 1_700_000_100, "eth-updown-15m-1700000000"),
+            trade("SELL", 10.0, 1_700_000_100, SLOT),
+        ];
+        let report = simulate(&request(), &trades, ticks, None);
+
+        assert_eq!(report.leader_trades, 3);
+        assert_eq!(report.copied_trades, 0);
+        assert_eq!(report.skipped.get("no_snapshot"), Some(&2));
+        assert_eq!(report.skipped.get("no_copied_position"), Some(&1));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(request().validate().is_ok());
+
+        let mut req = request();
+        req.size_mode = "percent_of_follower_balance".into();
+        assert_eq!(
+            req.validate(),
+            Err("starting_balance_usdc is required by this size_mode")
+        );
+        req.starting_balance_usdc = Some(500.0);
+        assert!(req.validate().is_ok());
+
+        let mut req = request();
+        req.leader_address = "not-an-address".into();
+        assert!(req.validate().is_err());
+
+        let mut req = request();
+        req.date_to = req.date_from + Duration::days(40);
+        assert_eq!(req.validate(), Err("date range must be at most 31 days"));
+    }
+}
@@ -9,6 +9,8 @@ pub fn compute(trades: Vec<BacktestTrade>) -> BacktestResult {
             total_trades: 0,
             win_rate: 0.0,
             total_pnl_usdc: 0.0,
+            gross_pnl_usdc: 0.0,
+            total_fees_usdc: 0.0,
             max_drawdown: 0.0,
             sharpe_ratio: 0.0,
             trades,
@@ -18,6 +20,8 @@ pub fn compute(trades: Vec<BacktestTrade>) -> BacktestResult {
     let wins = closed.iter().filter(|t| t.pnl_usdc > 0.0).count();
     let win_rate = wins as f64 / total_trades as f64;
     let total_pnl_usdc: f64 = closed.iter().map(|t| t.pnl_usdc).sum();
+    let gross_pnl_usdc: f64 = closed.iter().map(|t| t.gross_pnl_usdc).sum();
+    let total_fees_usdc: f64 = closed.iter().map(|t| t.fees_usdc).sum();
     let max_drawdown = compute_max_drawdown(&closed);
     let sharpe_ratio = compute_sharpe(&closed);
 
@@ -25,6 +29,8 @@ pub fn compute(trades: Vec<BacktestTrade>) -> BacktestResult {
         total_trades,
         win_rate,
         total_pnl_usdc,
+        gross_pnl_usdc,
+        total_fees_usdc,
         max_drawdown,
         sharpe_ratio,
         trades,
@@ -90,6 +96,8 @@ mod tests {
             exit_depth_ratio: Some(0.5),
             size_usdc: size,
             pnl_usdc: pnl,
+            gross_pnl_usdc: pnl,
+            fees_usdc: 0.0,
             entry_at: OffsetDateTime::from_unix_timestamp(1700000000).unwrap(),
             exit_at: Some(OffsetDateTime::from_unix_timestamp(1700000900).unwrap()),
             exit_reason: Some(ExitReason::Signal),
@@ -153,4 +161,18 @@ mod tests {
         assert_eq!(result.total_trades, 1);
         assert!((result.total_pnl_usdc - 10.0).abs() < f64::EPSILON);
     }
+
+    #[test]
+    fn test_compute_reports_gross_and_fees_separately() {
+        let mut scalp = trade(-0.5, 100.0);
+        scalp.gross_pnl_usdc = 1.5;
+        scalp.fees_usdc = 2.0;
+
+        let result = compute(vec![scalp]);
+        assert!((result.gross_pnl_usdc - 1.5).abs() < f64::EPSILON);
+        assert!((result.total_fees_usdc - 2.0).abs() < f64::EPSILON);
+        assert!((result.total_pnl_usdc + 0.5).abs() < f64::EPSILON);
+        // Win rate counts net outcomes.
+        assert!((result.win_rate).abs() < f64::EPSILON);
+    }
 }
@@ -1,3 +1,4 @@
+pub mod copy;
 pub mod metrics;
 pub mod runner;
 
@@ -5,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use time::OffsetDateTime;
 
+use crate::execution::fees::round_trip_fee_usdc;
 use crate::strategy::Outcome;
 
 const DEFAULT_WINDOW_SIZE: usize = 200;
@@ -19,6 +21,10 @@ pub struct BacktestRequest {
     pub date_to: OffsetDateTime,
     #[serde(default = "default_window_size")]
     pub window_size: usize,
+    /// Taker fee rate charged on every simulated entry and exit fill;
+    /// the engine's configured rate when omitted.
+    #[serde(default)]
+    pub fee_bps: Option<u16>,
 }
 
 fn default_window_size() -> usize {
@@ -41,7 +47,12 @@ impl BacktestRequest {
 pub struct BacktestResult {
     pub total_trades: u32,
     pub win_rate: f64,
+    /// Net of fees.
     pub total_pnl_usdc: f64,
+    #[serde(default)]
+    pub gross_pnl_usdc: f64,
+    #[serde(default)]
+    pub total_fees_usdc: f64,
     pub max_drawdown: f64,
     /// Per-trade Sharpe ratio (not annualized). Risk-free rate assumed 0.
     pub sharpe_ratio: f64,
@@ -81,7 +92,12 @@ pub struct BacktestTrade {
     pub exit_book_depth_usdc: Option<f64>,
     pub exit_depth_ratio: Option<f64>,
     pub size_usdc: f64,
+    /// Net of `fees_usdc`.
     pub pnl_usdc: f64,
+    #[serde(default)]
+    pub gross_pnl_usdc: f64,
+    #[serde(default)]
+    pub fees_usdc: f64,
     #[serde(with = "time::serde::rfc3339")]
     pub entry_at: OffsetDateTime,
     #[serde(with = "time::serde::rfc3339::option")]
@@ -96,6 +112,19 @@ pub fn compute_pnl(entry_price: f64, exit_price: f64, size_usdc: f64) -> f64 {
     (exit_price - entry_price) / entry_price * size_usdc
 }
 
+impl BacktestTrade {
+    /// Record the exit price and split the trade's PnL into gross, entry and
+    /// exit taker fees, and net.
+    pub fn settle(&mut self, exit_price: f64, fee_bps: u16) {
+        let gross = compute_pnl(self.entry_price, exit_price, self.size_usdc);
+        let fees = round_trip_fee_usdc(fee_bps, self.entry_price, exit_price, self.size_usdc);
+        self.exit_price = Some(exit_price);
+        self.gross_pnl_usdc = gross;
+        self.fees_usdc = fees;
+        self.pnl_usdc = gross - fees;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,6 +136,8 @@ mod tests {
             total_trades: 5,
             win_rate: 0.6,
             total_pnl_usdc: 42.5,
+            gross_pnl_usdc: 45.0,
+            total_fees_usdc: 2.5,
             max_drawdown: 0.15,
             sharpe_ratio: 1.2,
             trades: vec![BacktestTrade {
@@ -125,6 +156,8 @@ mod tests {
                 exit_depth_ratio: Some(0.8333),
                 size_usdc: 50.0,
                 pnl_usdc: 4.84,
+                gross_pnl_usdc: 4.84,
+                fees_usdc: 0.0,
                 entry_at: OffsetDateTime::from_unix_timestamp(1700000450).unwrap(),
                 exit_at: Some(OffsetDateTime::from_unix_timestamp(1700000900).unwrap()),
                 exit_reason: Some(ExitReason::TakeProfit),
@@ -148,6 +181,7 @@ mod tests {
             date_from: OffsetDateTime::from_unix_timestamp(1700000000).unwrap(),
             date_to: OffsetDateTime::from_unix_timestamp(1700001000).unwrap(),
             window_size: 200,
+            fee_bps: None,
         };
         assert!(req.validate().is_ok());
     }
@@ -160,6 +194,7 @@ mod tests {
             date_from: OffsetDateTime::from_unix_timestamp(1700001000).unwrap(),
             date_to: OffsetDateTime::from_unix_timestamp(1700000000).unwrap(),
             window_size: 200,
+            fee_bps: None,
         };
         assert_eq!(req.validate(), Err("date_from must be before date_to"));
     }
@@ -174,4 +209,37 @@ mod tests {
         let pnl = compute_pnl(0.62, 0.53, 50.0);
         assert!(pnl < 0.0);
     }
+
+    #[test]
+    fn test_settle_splits_gross_fees_and_net() {
+        let mut trade: BacktestTrade = serde_json::from_value(serde_json::json!({
+            "symbol": "btc-updown-15m-1700000000",
+            "outcome": "Up",
+            "side": "buy",
+            "entry_price": 0.50,
+            "entry_reference_price": 0.50,
+            "entry_slippage_bps": 0.0,
+            "entry_book_depth_usdc": 100.0,
+            "entry_depth_ratio": 0.5,
+            "exit_price": null,
+            "exit_reference_price": null,
+            "exit_slippage_bps": null,
+            "exit_book_depth_usdc": null,
+            "exit_depth_ratio": null,
+            "size_usdc": 50.0,
+            "pnl_usdc": 0.0,
+            "entry_at": "2023-11-14T22:13:20Z",
+            "exit_at": null,
+            "exit_reason": null,
+        }))
+        .unwrap();
+
+        // Gross (0.51-0.50)/0.50*50 = 1.00; fees on 100 shares at 200 bps:
+        // entry 1.00 + exit 0.98 → net -0.98, profitable gross but not net.
+        trade.settle(0.51, 200);
+        assert_eq!(trade.exit_price, Some(0.51));
+        assert!((trade.gross_pnl_usdc - 1.0).abs() < 1e-9);
+        assert!((trade.fees_usdc - 1.98).abs() < 1e-9);
+        assert!((trade.pnl_usdc + 0.98).abs() < 1e-9);
+    }
 }
@@ -4,7 +4,8 @@ use clickhouse::Client;
 use serde_json::Value;
 
 use super::metrics;
-use super::{compute_pnl, BacktestRequest, BacktestResult, BacktestTrade, ExitReason, Side};
+use super::{BacktestRequest, BacktestResult, BacktestTrade, ExitReason, Side};
+use crate::execution::fees::{taker_fee_usdc, DEFAULT_FEE_BPS};
 use crate::fetcher::models::Tick;
 use crate::strategy::bandit;
 use crate::strategy::interpreter::{evaluate, evaluate_with_caches};
@@ -22,6 +23,7 @@ pub struct BacktestEngine {
     markets: HashMap<String, MarketContext>,
     trades: Vec<BacktestTrade>,
     model_scores: Option<BacktestModelScores>,
+    fee_bps: u16,
 }
 
 struct MarketContext {
@@ -36,12 +38,12 @@ struct BacktestModelScores {
 }
 
 #[derive(Debug, Clone, Copy)]
-struct SimulatedFill {
-    average_price: f64,
-    reference_price: f64,
-    slippage_bps: f64,
-    book_depth_usdc: f64,
-    depth_ratio: f64,
+pub(super) struct SimulatedFill {
+    pub(super) average_price: f64,
+    pub(super) reference_price: f64,
+    pub(super) slippage_bps: f64,
+    pub(super) book_depth_usdc: f64,
+    pub(super) depth_ratio: f64,
 }
 
 impl BacktestEngine {
@@ -60,13 +62,21 @@ impl BacktestEngine {
             markets: HashMap::new(),
             trades: Vec::new(),
             model_scores,
+            fee_bps: 0,
         }
     }
 
+    /// Charge `fee_bps` taker fees on every simulated entry and exit fill.
+    pub fn with_fee_bps(mut self, fee_bps: u16) -> Self {
+        self.fee_bps = fee_bps;
+        self
+    }
+
     pub fn process_tick(&mut self, tick: &Tick) {
         self.seed_model_scores_for_tick(tick);
 
         let window_size = self.window_size;
+        let fee_bps = self.fee_bps;
         let ctx = self
             .markets
             .entry(tick.symbol.clone())
@@ -89,9 +99,7 @@ impl BacktestEngine {
                     (trade.outcome, winner),
                     (Outcome::Up, 1) | (Outcome::Down, 2)
                 );
-                let exit = if won { 1.0 } else { 0.0 };
-                trade.exit_price = Some(exit);
-                trade.pnl_usdc = compute_pnl(trade.entry_price, exit, trade.size_usdc);
+                trade.settle(if won { 1.0 } else { 0.0 }, fee_bps);
                 trade.exit_at = Some(tick.captured_at);
                 trade.exit_reason = Some(ExitReason::SlotResolved);
                 ctx.state.position = None;
@@ -128,6 +136,11 @@ impl BacktestEngine {
                     size_usdc,
                     entry_at: tick.captured_at.unix_timestamp(),
                     symbol: tick.symbol.clone(),
+                    entry_fee_usdc: taker_fee_usdc(
+                        fee_bps,
+                        entry_fill.average_price,
+                        size_usdc / entry_fill.average_price,
+                    ),
                 });
                 bandit::record_entry_fill(
                     &self.graph,
@@ -135,6 +148,7 @@ impl BacktestEngine {
                     &tick.symbol,
                     entry_fill.average_price,
                     tick.captured_at.unix_timestamp(),
+                    fee_bps,
                 );
                 ctx.open_trade = Some(BacktestTrade {
                     symbol: tick.symbol.clone(),
@@ -152,6 +166,8 @@ impl BacktestEngine {
                     exit_depth_ratio: None,
                     size_usdc,
                     pnl_usdc: 0.0,
+                    gross_pnl_usdc: 0.0,
+                    fees_usdc: 0.0,
                     entry_at: tick.captured_at,
                     exit_at: None,
                     exit_reason: None,
@@ -172,18 +188,21 @@ impl BacktestEngine {
                             size_usdc: trade.size_usdc,
                             entry_at: trade.entry_at.unix_timestamp(),
                             symbol: trade.symbol.clone(),
+                            entry_fee_usdc: taker_fee_usdc(
+                                fee_bps,
+                                trade.entry_price,
+                                trade.size_usdc / trade.entry_price,
+                            ),
                         });
                         ctx.open_trade = Some(trade);
                         return;
                     };
 
-                    trade.exit_price = Some(exit_fill.average_price);
+                    trade.settle(exit_fill.average_price, fee_bps);
                     trade.exit_reference_price = Some(exit_fill.reference_price);
                     trade.exit_slippage_bps = Some(exit_fill.slippage_bps);
                     trade.exit_book_depth_usdc = Some(exit_fill.book_depth_usdc);
                     trade.exit_depth_ratio = Some(exit_fill.depth_ratio);
-                    trade.pnl_usdc =
-                        compute_pnl(trade.entry_price, exit_fill.average_price, trade.size_usdc);
                     trade.exit_at = Some(tick.captured_at);
                     trade.exit_reason = Some(map_exit_reason(&order_type));
                     self.trades.push(trade);
@@ -254,12 +273,11 @@ impl BacktestEngine {
                                 ),
                             }
                         };
-                    trade.exit_price = Some(exit);
+                    trade.settle(exit, self.fee_bps);
                     trade.exit_reference_price = reference_price;
                     trade.exit_slippage_bps = slippage_bps;
                     trade.exit_book_depth_usdc = book_depth_usdc;
                     trade.exit_depth_ratio = depth_ratio;
-                    trade.pnl_usdc = compute_pnl(trade.entry_price, exit, trade.size_usdc);
                     trade.exit_at = Some(last_tick.captured_at);
                     trade.exit_reason = Some(reason);
                     self.trades.push(trade);
@@ -270,14 +288,18 @@ impl BacktestEngine {
     }
 }
 
-fn mid_price(outcome: Outcome, tick: &Tick) -> f64 {
+pub(super) fn mid_price(outcome: Outcome, tick: &Tick) -> f64 {
     match outcome {
         Outcome::Up => tick.mid_up as f64,
         Outcome::Down => tick.mid_down as f64,
     }
 }
 
-fn simulate_entry_fill(outcome: Outcome, tick: &Tick, size_usdc: f64) -> Option<SimulatedFill> {
+pub(super) fn simulate_entry_fill(
+    outcome: Outcome,
+    tick: &Tick,
+    size_usdc: f64,
+) -> Option<SimulatedFill> {
     if size_usdc <= 0.0 {
         return None;
     }
@@ -301,7 +323,7 @@ fn simulate_entry_fill(outcome: Outcome, tick: &Tick, size_usdc: f64) -> Option<
     simulate_buy_usdc_fill(&levels, size_usdc)
 }
 
-fn simulate_exit_fill(
+pub(super) fn simulate_exit_fill(
     outcome: Outcome,
     tick: &Tick,
     size_usdc: f64,
@@ -462,7 +484,8 @@ pub async fn run(req: &BacktestRequest, ch_client: &Client) -> anyhow::Result<Ba
     )?;
 
     if model_urls.is_empty() {
-        let mut engine = BacktestEngine::new(req.strategy_graph.clone(), req.window_size);
+        let mut engine = BacktestEngine::new(req.strategy_graph.clone(), req.window_size)
+            .with_fee_bps(req.fee_bps.unwrap_or(DEFAULT_FEE_BPS));
 
         while let Some(tick) = cursor.next().await? {
             engine.process_tick(&tick);
@@ -481,7 +504,8 @@ pub async fn run(req: &BacktestRequest, ch_client: &Client) -> anyhow::Result<Ba
         req.strategy_graph.clone(),
         req.window_size,
         Some(model_scores),
-    );
+    )
+    .with_fee_bps(req.fee_bps.unwrap_or(DEFAULT_FEE_BPS));
 
     for tick in &ticks {
         engine.process_tick(tick);
@@ -0,0 +1,337 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{ExecutionOrder, Side};
+use crate::fetcher::models::OrderBook;
+
+/// Smallest child order worth sending; the CLOB refuses marketable orders
+/// under $1 of notional.
+pub const MIN_CHILD_USDC: f64 = 1.0;
+
+// ---------------------------------------------------------------------------
+// ExecAlgo
+// ---------------------------------------------------------------------------
+
+/// How a large order is worked on the book instead of being sent at once.
+///
+/// Configured as JSON, e.g. `{"algo": "twap", "duration_secs": 60, "slices": 6}`
+/// in a strategy graph's `execution` block (entries only) or a copy
+/// relationship's `execution_algo` column.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "algo", rename_all = "snake_case")]
+pub enum ExecAlgo {
+    /// Equal slices spread evenly over `duration_secs`.
+    Twap { duration_secs: u32, slices: u32 },
+    /// Clips of at most `clip_usdc`. The executor awaits each fill before
+    /// popping the next order, so only one clip is ever on the book.
+    Iceberg { clip_usdc: f64 },
+    /// Take only the liquidity priced within `max_bps` of the best level.
+    WithinBps { max_bps: u32 },
+}
+
+impl ExecAlgo {
+    pub fn from_value(value: &Value) -> Option<Self> {
+        if value.is_null() {
+            return None;
+        }
+        match serde_json::from_value(value.clone()) {
+            Ok(algo) => Some(algo),
+            Err(e) => {
+                tracing::warn!(error = %e, config = %value, "exec_algo_invalid");
+                None
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Slicing
+// ---------------------------------------------------------------------------
+
+/// A child order and how long after slicing it should be queued.
+#[derive(Debug, Clone)]
+pub struct ChildOrder {
+    pub delay: Duration,
+    pub order: ExecutionOrder,
+}
+
+/// Split `parent` into child orders according to `algo`.
+///
+/// Children get fresh ids (and so their own salts), point back to the parent
+/// through `parent_id`, and carry no algo themselves. `book` is the current
+/// book of the parent's token; only `WithinBps` needs it. An empty result
+/// means there is nothing worth sending.
+pub fn slice(parent: &ExecutionOrder, algo: ExecAlgo, book: Option<&OrderBook>) -> Vec<ChildOrder> {
+    match algo {
+        ExecAlgo::Twap {
+            duration_secs,
+            slices,
+        } => {
+            let max_slices = (parent.size_usdc / MIN_CHILD_USDC).floor().max(1.0) as u32;
+            let n = slices.clamp(1, max_slices);
+            let step = Duration::from_secs(u64::from(duration_secs)) / n;
+            split_even(parent.size_usdc, n)
+                .into_iter()
+                .enumerate()
+                .map(|(i, size)| ChildOrder {
+                    delay: step * i as u32,
+                    order: child(parent, size),
+                })
+                .collect()
+        }
+        ExecAlgo::Iceberg { clip_usdc } => {
+            let clip = clip_usdc.max(MIN_CHILD_USDC);
+            let n = (parent.size_usdc / clip).ceil().max(1.0) as u32;
+            let mut sizes = vec![clip; n as usize - 1];
+            let remainder = round_cents(parent.size_usdc - clip * f64::from(n - 1));
+            match sizes.last_mut() {
+                // Fold a dust remainder into the previous clip.
+                Some(last) if remainder < MIN_CHILD_USDC => *last = round_cents(*last + remainder),
+                _ => sizes.push(remainder),
+            }
+            sizes
+                .into_iter()
+                .map(|size| ChildOrder {
+                    delay: Duration::ZERO,
+                    order: child(parent, size),
+                })
+                .collect()
+        }
+        ExecAlgo::WithinBps { max_bps } => within_bps(parent, max_bps, book).into_iter().collect(),
+    }
+}
+
+fn within_bps(
+    parent: &ExecutionOrder,
+    max_bps: u32,
+    book: Option<&OrderBook>,
+) -> Option<ChildOrder> {
+    let band = f64::from(max_bps) / 10_000.0;
+    let levels = book.map(|b| match parent.side {
+        Side::Buy => &b.asks,
+        Side::Sell => &b.bids,
+    });
+    let best = levels
+        .and_then(|l| l.first())
+        .map(|l| f64::from(l.price))
+        .or(parent.reference_price)
+        .or(parent.price)?;
+    let limit = match parent.side {
+        Side::Buy => best * (1.0 + band),
+        Side::Sell => best * (1.0 - band),
+    };
+
+    // Without a book the band still caps the price, but not the size.
+    let size = match levels {
+        Some(levels) => {
+            let depth: f64 = levels
+                .iter()
+                .map(|l| (f64::from(l.price), f64::from(l.size)))
+                .take_while(|(price, _)| match parent.side {
+                    Side::Buy => *price <= limit + 1e-9,
+                    Side::Sell => *price >= limit - 1e-9,
+                })
+                .map(|(price, shares)| price * shares)
+                .sum();
+            round_cents(parent.size_usdc.min(depth))
+        }
+        None => parent.size_usdc,
+    };
+    if size < MIN_CHILD_USDC {
+        return None;
+    }
+
+    let mut order = child(parent, size);
+    let capped = |current: f64| match parent.side {
+        Side::Buy => current.min(limit),
+        Side::Sell => current.max(limit),
+    };
+    match order.price {
+        Some(price) => order.price = Some(capped(price)),
+        None => order.reference_price = Some(order.reference_price.map_or(limit, capped)),
+    }
+    Some(ChildOrder {
+        delay: Duration::ZERO,
+        order,
+    })
+}
+
+fn child(parent: &ExecutionOrder, size_usdc: f64) -> ExecutionOrder {
+    ExecutionOrder {
+        id: Uuid::new_v4(),
+        size_usdc,
+        algo: None,
+        parent_id: Some(parent.id),
+        ..parent.clone()
+    }
+}
+
+/// `n` cent-rounded sizes summing to `total`; the last absorbs the rounding.
+fn split_even(total: f64, n: u32) -> Vec<f64> {
+    let base = round_cents(total / f64::from(n));
+    let mut sizes = vec![base; n as usize - 1];
+    sizes.push(round_cents(total - base * f64::from(n - 1)));
+    sizes
+}
+
+fn round_cents(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::OrderPriority;
+    use crate::fetcher::models::Level;
+    use crate::strategy::{OrderType, Outcome};
+
+    fn make_order(size_usdc: f64) -> ExecutionOrder {
+        ExecutionOrder {
+            id: Uuid::new_v4(),
+            wallet_id: 1,
+            strategy_id: Some(2),
+            copy_relationship_id: None,
+            symbol: "btc-updown-15m-1700000000".to_string(),
+            token_id: "tok".to_string(),
+            side: Side::Buy,
+            outcome: Outcome::Up,
+            price: None,
+            reference_price: Some(0.50),
+            size_usdc,
+            order_type: OrderType::Market,
+            priority: OrderPriority::StrategyMarket,
+            created_at: 1_700_000_000,
+            leader_address: String::new(),
+            leader_tx_hash: String::new(),
+            is_paper: false,
+            market: None,
+            algo: None,
+            parent_id: None,
+            closes: None,
+        }
+    }
+
+    fn level(price: f32, size: f32) -> Level {
+        Level { price, size }
+    }
+
+    fn total(children: &[ChildOrder]) -> f64 {
+        round_cents(children.iter().map(|c| c.order.size_usdc).sum())
+    }
+
+    #[test]
+    fn test_from_value() {
+        let v = serde_json::json!({"algo": "twap", "duration_secs": 60, "slices": 6});
+        assert_eq!(
+            ExecAlgo::from_value(&v),
+            Some(ExecAlgo::Twap {
+                duration_secs: 60,
+                slices: 6
+            })
+        );
+        let v = serde_json::json!({"algo": "within_bps", "max_bps": 50});
+        assert_eq!(
+            ExecAlgo::from_value(&v),
+            Some(ExecAlgo::WithinBps { max_bps: 50 })
+        );
+        assert_eq!(ExecAlgo::from_value(&Value::Null), None);
+        assert_eq!(
+            ExecAlgo::from_value(&serde_json::json!({"algo": "vwap"})),
+            None
+        );
+    }
+
+    #[test]
+    fn test_twap_spreads_even_slices() {
+        let parent = make_order(100.0);
+        let algo = ExecAlgo::Twap {
+            duration_secs: 30,
+            slices: 3,
+        };
+        let children = slice(&parent, algo, None);
+        assert_eq!(children.len(), 3);
+        assert_eq!(total(&children), 100.0);
+        assert_eq!(children[0].order.size_usdc, 33.33);
+        assert_eq!(children[2].order.size_usdc, 33.34);
+        assert_eq!(children[0].delay, Duration::ZERO);
+        assert_eq!(children[2].delay, Duration::from_secs(20));
+        for c in &children {
+            assert_eq!(c.order.parent_id, Some(parent.id));
+            assert_ne!(c.order.id, parent.id, "children need their own salt");
+            assert!(c.order.algo.is_none());
+        }
+    }
+
+    #[test]
+    fn test_twap_never_slices_below_min_child() {
+        let algo = ExecAlgo::Twap {
+            duration_secs: 60,
+            slices: 10,
+        };
+        let children = slice(&make_order(3.5), algo, None);
+        assert_eq!(children.len(), 3);
+        assert_eq!(total(&children), 3.5);
+    }
+
+    #[test]
+    fn test_iceberg_clips_and_folds_dust() {
+        let children = slice(
+            &make_order(25.0),
+            ExecAlgo::Iceberg { clip_usdc: 10.0 },
+            None,
+        );
+        let sizes: Vec<f64> = children.iter().map(|c| c.order.size_usdc).collect();
+        assert_eq!(sizes, vec![10.0, 10.0, 5.0]);
+
+        let children = slice(
+            &make_order(20.5),
+            ExecAlgo::Iceberg { clip_usdc: 10.0 },
+            None,
+        );
+        let sizes: Vec<f64> = children.iter().map(|c| c.order.size_usdc).collect();
+        assert_eq!(sizes, vec![10.0, 10.5]);
+    }
+
+    #[test]
+    fn test_within_bps_caps_size_to_band_depth() {
+        let book = OrderBook {
+            bids: vec![level(0.49, 100.0)],
+            asks: vec![level(0.50, 20.0), level(0.502, 30.0), level(0.52, 500.0)],
+        };
+        let children = slice(
+            &make_order(100.0),
+            ExecAlgo::WithinBps { max_bps: 50 },
+            Some(&book),
+        );
+        assert_eq!(children.len(), 1);
+        let child = &children[0].order;
+        // 20 * 0.50 + 30 * 0.502 = 25.06 within 50bps of 0.50
+        assert_eq!(child.size_usdc, 25.06);
+        assert!((child.reference_price.unwrap() - 0.50).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_within_bps_caps_limit_price_and_skips_thin_books() {
+        let mut parent = make_order(10.0);
+        parent.price = Some(0.60);
+        let book = OrderBook {
+            bids: vec![],
+            asks: vec![level(0.50, 100.0)],
+        };
+        let children = slice(&parent, ExecAlgo::WithinBps { max_bps: 100 }, Some(&book));
+        assert!((children[0].order.price.unwrap() - 0.505).abs() < 1e-9);
+
+        let thin = OrderBook {
+            bids: vec![],
+            asks: vec![level(0.50, 1.0)],
+        };
+        assert!(slice(&parent, ExecAlgo::WithinBps { max_bps: 100 }, Some(&thin)).is_empty());
+    }
+}
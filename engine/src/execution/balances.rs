@@ -0,0 +1,721 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use alloy::primitives::Address;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::warn;
+use uuid::Uuid;
+
+use super::credentials::CredentialManager;
+use super::wallet::WalletKeyStore;
+use super::{ExecutionOrder, OrderResult, OrderStatus, Side};
+use crate::network::NetworkProfile;
+use crate::proxy::HttpPool;
+
+const BALANCE_TTL: Duration = Duration::from_secs(30);
+/// USDC and conditional tokens both use 6 decimals on Polygon.
+const TOKEN_UNIT: f64 = 1_000_000.0;
+const BALANCE_EPSILON: f64 = 1e-6;
+
+// ---------------------------------------------------------------------------
+// API response types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+struct BalanceAllowanceResponse {
+    balance: String,
+    #[serde(default)]
+    allowance: Option<String>,
+    /// Newer CLOB versions report one allowance per exchange contract.
+    #[serde(default)]
+    allowances: HashMap<String, String>,
+}
+
+/// A position as reported by the data API `/positions` endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DataApiPosition {
+    pub asset: String,
+    pub size: f64,
+    /// Market slug — the engine's slot symbol.
+    #[serde(default)]
+    pub slug: String,
+    /// Outcome label as displayed by Polymarket (`Up` / `Down`).
+    #[serde(default)]
+    pub outcome: Option<String>,
+    #[serde(default, rename = "conditionId")]
+    pub condition_id: String,
+    /// 0 for the first (Yes / Up) outcome, 1 for the second.
+    #[serde(default, rename = "outcomeIndex")]
+    pub outcome_index: Option<u32>,
+    /// True once the market has resolved and the tokens can be redeemed.
+    #[serde(default)]
+    pub redeemable: bool,
+    #[serde(default, rename = "negativeRisk")]
+    pub negative_risk: bool,
+    #[serde(default, rename = "currentValue")]
+    pub current_value: f64,
+}
+
+/// Fetch every open position held by `address` from the data API.
+pub async fn fetch_positions(
+    http: &HttpPool,
+    data_api_url: &str,
+    address: &Address,
+) -> Result<Vec<DataApiPosition>> {
+    let url = format!(
+        "{}/positions?user={:?}&sizeThreshold=0",
+        data_api_url.trim_end_matches('/'),
+        address
+    );
+    let positions = http
+        .proxied()
+        .get(&url)
+        .send()
+        .await
+        .context("positions request failed")?
+        .error_for_status()
+        .context("positions request rejected")?
+        .json()
+        .await
+        .context("failed to parse positions response")?;
+    Ok(positions)
+}
+
+fn parse_units(raw: &str) -> f64 {
+    raw.parse::<f64>().unwrap_or(0.0) / TOKEN_UNIT
+}
+
+// ---------------------------------------------------------------------------
+// BalanceRejection
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceRejection {
+    InsufficientUsdc,
+    InsufficientAllowance,
+    TokenNotHeld,
+    InsufficientTokens,
+}
+
+impl BalanceRejection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::InsufficientUsdc => "insufficient_usdc",
+            Self::InsufficientAllowance => "insufficient_allowance",
+            Self::TokenNotHeld => "token_not_held",
+            Self::InsufficientTokens => "insufficient_tokens",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Balance book
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+struct WalletBalance {
+    usdc: f64,
+    /// Allowance granted to `CtfExchange`.
+    usdc_allowance: f64,
+    /// Allowance granted to `NegRiskCtfExchange`.
+    neg_risk_usdc_allowance: f64,
+    /// Conditional-token shares by token id.
+    tokens: HashMap<String, f64>,
+    fetched_at: Instant,
+}
+
+/// Amount held back from a wallet's balance while an order is in flight.
+#[derive(Debug, Clone)]
+struct Reservation {
+    wallet_id: u64,
+    token_id: String,
+    usdc: f64,
+    shares: f64,
+}
+
+#[derive(Debug, Default)]
+struct BalanceBook {
+    wallets: HashMap<u64, WalletBalance>,
+    reservations: HashMap<Uuid, Reservation>,
+}
+
+impl WalletBalance {
+    /// Allowance of the exchange an order settles through; the larger of the
+    /// two while its market is unknown, leaving the CLOB as final judge.
+    fn allowance(&self, neg_risk: Option<bool>) -> f64 {
+        match neg_risk {
+            Some(true) => self.neg_risk_usdc_allowance,
+            Some(false) => self.usdc_allowance,
+            None => self.usdc_allowance.max(self.neg_risk_usdc_allowance),
+        }
+    }
+}
+
+impl BalanceBook {
+    fn reserved_usdc(&self, wallet_id: u64) -> f64 {
+        self.reservations
+            .values()
+            .filter(|r| r.wallet_id == wallet_id)
+            .map(|r| r.usdc)
+            .sum()
+    }
+
+    fn reserved_shares(&self, wallet_id: u64, token_id: &str) -> f64 {
+        self.reservations
+            .values()
+            .filter(|r| r.wallet_id == wallet_id && r.token_id == token_id)
+            .map(|r| r.shares)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenBalance {
+    pub token_id: String,
+    pub shares: f64,
+    pub reserved_shares: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BalanceSnapshot {
+    pub usdc: f64,
+    pub usdc_allowance: f64,
+    pub neg_risk_usdc_allowance: f64,
+    pub usdc_reserved: f64,
+    pub usdc_available: f64,
+    pub tokens: Vec<TokenBalance>,
+    pub age_secs: u64,
+}
+
+/// Shares an order moves at its limit (or reference) price.
+fn order_shares(order: &ExecutionOrder) -> f64 {
+    order.shares_at(order.price.or(order.reference_price).unwrap_or(0.5))
+}
+
+// ---------------------------------------------------------------------------
+// BalanceService
+// ---------------------------------------------------------------------------
+
+/// Tracks each wallet's USDC and conditional-token balances so orders the Safe
+/// cannot cover are rejected before signing.
+///
+/// Balances come from the CLOB `/balance-allowance` endpoint and the data API
+/// `/positions` endpoint, cached for [`BALANCE_TTL`]. Amounts of in-flight
+/// orders are reserved until their result is known.
+pub struct BalanceService {
+    http: HttpPool,
+    network: Arc<NetworkProfile>,
+    clob_url: String,
+    data_api_url: String,
+    api_keys: Arc<CredentialManager>,
+    wallet_keys: Arc<WalletKeyStore>,
+    book: RwLock<BalanceBook>,
+}
+
+impl BalanceService {
+    pub fn new(
+        http: HttpPool,
+        network: Arc<NetworkProfile>,
+        api_keys: Arc<CredentialManager>,
+        wallet_keys: Arc<WalletKeyStore>,
+    ) -> Self {
+        Self {
+            http,
+            clob_url: network.clob_api_url.trim_end_matches('/').to_string(),
+            data_api_url: network.data_api_url.trim_end_matches('/').to_string(),
+            network,
+            api_keys,
+            wallet_keys,
+            book: RwLock::new(BalanceBook::default()),
+        }
+    }
+
+    /// Re-fetch USDC balance/allowance and token positions for a wallet.
+    pub async fn refresh(&self, wallet_id: u64) -> Result<()> {
+        let safe_address = self
+            .wallet_keys
+            .get_safe_address(wallet_id)
+            .context("failed to get Safe address for wallet")?;
+
+        let (usdc, usdc_allowance, neg_risk_usdc_allowance) =
+            self.fetch_collateral(wallet_id).await?;
+        let positions = fetch_positions(&self.http, &self.data_api_url, &safe_address).await?;
+        let tokens = positions
+            .into_iter()
+            .filter(|p| p.size > 0.0)
+            .map(|p| (p.asset, p.size))
+            .collect();
+
+        self.book.write().await.wallets.insert(
+            wallet_id,
+            WalletBalance {
+                usdc,
+                usdc_allowance,
+                neg_risk_usdc_allowance,
+                tokens,
+                fetched_at: Instant::now(),
+            },
+        );
+        Ok(())
+    }
+
+    /// USDC balance plus the allowances of `CtfExchange` and `NegRiskCtfExchange`.
+    async fn fetch_collateral(&self, wallet_id: u64) -> Result<(f64, f64, f64)> {
+        let path = "/balance-allowance";
+        let headers = self.api_keys.l2_headers(wallet_id, "GET", path, "").await?;
+        let url = format!(
+            "{}{}?asset_type=COLLATERAL&signature_type=2",
+            self.clob_url, path
+        );
+
+        let resp: BalanceAllowanceResponse = self
+            .http
+            .proxied()
+            .get(&url)
+            .headers(headers)
+            .send()
+            .await
+            .context("balance-allowance request failed")?
+            .error_for_status()
+            .context("balance-allowance request rejected")?
+            .json()
+            .await
+            .context("failed to parse balance-allowance response")?;
+
+        // Older CLOB versions report a single allowance covering both exchanges.
+        let legacy = resp.allowance.as_deref().map_or(0.0, parse_units);
+        let allowance_of = |exchange: Address| {
+            resp.allowances
+                .iter()
+                .find(|(spender, _)| spender.parse::<Address>().ok() == Some(exchange))
+                .map_or(legacy, |(_, v)| parse_units(v))
+        };
+        Ok((
+            parse_units(&resp.balance),
+            allowance_of(self.network.ctf_exchange),
+            allowance_of(self.network.neg_risk_exchange),
+        ))
+    }
+
+    async fn ensure_fresh(&self, wallet_id: u64) {
+        let fresh = self
+            .book
+            .read()
+            .await
+            .wallets
+            .get(&wallet_id)
+            .is_some_and(|b| b.fetched_at.elapsed() < BALANCE_TTL);
+        if !fresh {
+            if let Err(e) = self.refresh(wallet_id).await {
+                warn!(wallet_id, error = %e, "wallet_balance_refresh_failed");
+            }
+        }
+    }
+
+    /// Reserve the USDC (buys) or shares (sells) an order needs.
+    ///
+    /// Paper orders always pass. When no balance could be fetched the order
+    /// passes too and the CLOB remains the final judge.
+    pub async fn reserve(&self, order: &ExecutionOrder) -> Result<(), BalanceRejection> {
+        if order.is_paper {
+            return Ok(());
+        }
+        self.ensure_fresh(order.wallet_id).await;
+
+        let mut book = self.book.write().await;
+        let Some(balance) = book.wallets.get(&order.wallet_id) else {
+            return Ok(());
+        };
+
+        let reservation = match order.side {
+            Side::Buy => {
+                let reserved = book.reserved_usdc(order.wallet_id);
+                if balance.usdc - reserved + BALANCE_EPSILON < order.size_usdc {
+                    return Err(BalanceRejection::InsufficientUsdc);
+                }
+                let allowance = balance.allowance(order.market.map(|m| m.neg_risk));
+                if allowance - reserved + BALANCE_EPSILON < order.size_usdc {
+                    return Err(BalanceRejection::InsufficientAllowance);
+                }
+                Reservation {
+                    wallet_id: order.wallet_id,
+                    token_id: order.token_id.clone(),
+                    usdc: order.size_usdc,
+                    shares: 0.0,
+                }
+            }
+            Side::Sell => {
+                // Unresolved token ids cannot be checked against positions.
+                if order.token_id.is_empty() {
+                    return Ok(());
+                }
+                let held = balance.tokens.get(&order.token_id).copied().unwrap_or(0.0);
+                if held <= BALANCE_EPSILON {
+                    return Err(BalanceRejection::TokenNotHeld);
+                }
+                let shares = order_shares(order);
+                let reserved = book.reserved_shares(order.wallet_id, &order.token_id);
+                if held - reserved + BALANCE_EPSILON < shares {
+                    return Err(BalanceRejection::InsufficientTokens);
+                }
+                Reservation {
+                    wallet_id: order.wallet_id,
+                    token_id: order.token_id.clone(),
+                    usdc: 0.0,
+                    shares,
+                }
+            }
+        };
+
+        book.reservations.insert(order.id, reservation);
+        Ok(())
+    }
+
+    /// Release an order's reservation and apply its fill to the cached balance
+    /// until the next refresh.
+    pub async fn settle(&self, order: &ExecutionOrder, result: &OrderResult) {
+        let mut book = self.book.write().await;
+        if book.reservations.remove(&order.id).is_none() {
+            return;
+        }
+        if result.status != OrderStatus::Filled {
+            return;
+        }
+        let Some(balance) = book.wallets.get_mut(&order.wallet_id) else {
+            return;
+        };
+
+        let price = result
+            .filled_price
+            .or(order.price)
+            .or(order.reference_price);
+        let shares = match price {
+            Some(p) => order.shares_at(p),
+            None => order_shares(order),
+        };
+        match order.side {
+            Side::Buy => {
+                balance.usdc = (balance.usdc - order.size_usdc).max(0.0);
+                let neg_risk = order.market.map(|m| m.neg_risk);
+                if neg_risk != Some(true) {
+                    balance.usdc_allowance = (balance.usdc_allowance - order.size_usdc).max(0.0);
+                }
+                if neg_risk != Some(false) {
+                    balance.neg_risk_usdc_allowance =
+                        (balance.neg_risk_usdc_allowance - order.size_usdc).max(0.0);
+                }
+                *balance.tokens.entry(order.token_id.clone()).or_insert(0.0) += shares;
+            }
+            Side::Sell => {
+                balance.usdc += match price {
+                    Some(p) if p > 0.0 => shares * p,
+                    _ => order.size_usdc,
+                };
+                if let Some(held) = balance.tokens.get_mut(&order.token_id) {
+                    *held = (*held - shares).max(0.0);
+                }
+            }
+        }
+    }
+
+    /// Current balances net of reservations, refreshing stale data first.
+    pub async fn snapshot(&self, wallet_id: u64) -> Option<BalanceSnapshot> {
+        self.ensure_fresh(wallet_id).await;
+
+        let book = self.book.read().await;
+        let balance = book.wallets.get(&wallet_id)?;
+        let usdc_reserved = book.reserved_usdc(wallet_id);
+        let mut tokens: Vec<TokenBalance> = balance
+            .tokens
+            .iter()
+            .map(|(token_id, shares)| TokenBalance {
+                token_id: token_id.clone(),
+                shares: *shares,
+                reserved_shares: book.reserved_shares(wallet_id, token_id),
+            })
+            .collect();
+        tokens.sort_by(|a, b| a.token_id.cmp(&b.token_id));
+
+        Some(BalanceSnapshot {
+            usdc: balance.usdc,
+            usdc_allowance: balance.usdc_allowance,
+            neg_risk_usdc_allowance: balance.neg_risk_usdc_allowance,
+            usdc_reserved,
+            usdc_available: (balance.usdc - usdc_reserved).max(0.0),
+            tokens,
+            age_secs: balance.fetched_at.elapsed().as_secs(),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::OrderPriority;
+    use crate::network::NetworkProfile;
+    use crate::strategy::{OrderType, Outcome};
+    use axum::extract::Query;
+    use axum::http::HeaderMap as AxumHeaders;
+    use axum::{routing::get, Json, Router};
+    use tokio::net::TcpListener;
+
+    const SAFE: &str = "0x00000000000000000000000000000000000000aa";
+
+    async fn stub_server() -> String {
+        let app = Router::new()
+            .route(
+                "/balance-allowance",
+                get(
+                    |headers: AxumHeaders, Query(q): Query<HashMap<String, String>>| async move {
+                        assert_eq!(q.get("asset_type").map(String::as_str), Some("COLLATERAL"));
+                        assert!(headers.contains_key("POLY_SIGNATURE"));
+                        assert_eq!(headers["POLY_API_KEY"], "wallet-key");
+                        Json(serde_json::json!({
+                            "balance": "25000000",
+                            "allowances": {
+                                "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E": "1000000000",
+                                "0xC5d563A36AE78145C45a50134d48A1215220f80a": "0"
+                            }
+                        }))
+                    },
+                ),
+            )
+            .route(
+                "/auth/derive-api-key",
+                get(|| async {
+                    Json(serde_json::json!({
+                        "apiKey": "wallet-key",
+                        "secret": "c2VjcmV0",
+                        "passphrase": "pass"
+                    }))
+                }),
+            )
+            .route(
+                "/positions",
+                get(|| async {
+                    Json(serde_json::json!([
+                        { "asset": "111", "conditionId": "0xc1", "size": 40.0, "outcome": "Up" },
+                        { "asset": "222", "conditionId": "0xc2", "size": 0.0, "outcome": "Down" }
+                    ]))
+                }),
+            );
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{addr}")
+    }
+
+    async fn make_service() -> BalanceService {
+        let url = stub_server().await;
+        let wallet_keys = Arc::new(
+            WalletKeyStore::new("0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef")
+                .unwrap(),
+        );
+        wallet_keys
+            .store_safe_address(1, SAFE.parse().unwrap())
+            .unwrap();
+        let encrypted = wallet_keys.encrypt_key(&[0xABu8; 32]).unwrap();
+        wallet_keys.store_key(1, &encrypted).unwrap();
+        let http = HttpPool::new(&[], Duration::from_secs(5)).unwrap();
+        let db = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(200))
+            .connect_lazy("postgres://localhost:1/test")
+            .unwrap();
+        let network = Arc::new(NetworkProfile {
+            clob_api_url: url.clone(),
+            data_api_url: url,
+            ..NetworkProfile::polygon()
+        });
+        let api_keys = Arc::new(CredentialManager::new(
+            http.clone(),
+            network.clone(),
+            db,
+            wallet_keys.clone(),
+        ));
+        BalanceService::new(http, network, api_keys, wallet_keys)
+    }
+
+    fn order(side: Side, token_id: &str, size_usdc: f64) -> ExecutionOrder {
+        ExecutionOrder {
+            id: Uuid::new_v4(),
+            wallet_id: 1,
+            strategy_id: Some(1),
+            copy_relationship_id: None,
+            symbol: "btc-updown-15m-1700000000".into(),
+            token_id: token_id.into(),
+            side,
+            outcome: Outcome::Up,
+            price: Some(0.5),
+            reference_price: Some(0.5),
+            size_usdc,
+            order_type: OrderType::Limit { price: 0.5 },
+            priority: OrderPriority::Limit,
+            created_at: 1_700_000_000,
+            leader_address: String::new(),
+            leader_tx_hash: String::new(),
+            is_paper: false,
+            market: None,
+            algo: None,
+            parent_id: None,
+            closes: None,
+        }
+    }
+
+    fn filled() -> OrderResult {
+        OrderResult {
+            polymarket_order_id: "pm".into(),
+            status: OrderStatus::Filled,
+            filled_price: Some(0.5),
+            fee_bps: Some(0),
+            reject_reason: None,
+            clob_rejection: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_parses_collateral_and_positions() {
+        let service = make_service().await;
+        service.refresh(1).await.unwrap();
+
+        let snapshot = service.snapshot(1).await.unwrap();
+        assert!((snapshot.usdc - 25.0).abs() < 1e-9);
+        assert!((snapshot.usdc_allowance - 1000.0).abs() < 1e-9);
+        assert_eq!(snapshot.neg_risk_usdc_allowance, 0.0);
+        assert_eq!(snapshot.tokens.len(), 1, "zero-size positions are dropped");
+        assert_eq!(snapshot.tokens[0].token_id, "111");
+    }
+
+    #[tokio::test]
+    async fn test_buys_reserve_usdc_until_settled() {
+        let service = make_service().await;
+        let first = order(Side::Buy, "111", 20.0);
+        assert_eq!(service.reserve(&first).await, Ok(()));
+        assert_eq!(
+            service.reserve(&order(Side::Buy, "111", 10.0)).await,
+            Err(BalanceRejection::InsufficientUsdc)
+        );
+
+        let failed = OrderResult {
+            status: OrderStatus::Failed,
+            ..filled()
+        };
+        service.settle(&first, &failed).await;
+        assert_eq!(
+            service.reserve(&order(Side::Buy, "111", 10.0)).await,
+            Ok(())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_buys_check_allowance_of_their_exchange() {
+        let service = make_service().await;
+        let market = |neg_risk| {
+            Some(crate::execution::MarketMeta {
+                neg_risk,
+                tick_size: 0.01,
+                min_order_size: 0.0,
+            })
+        };
+
+        // Only CtfExchange is approved in the stub.
+        let mut neg_risk = order(Side::Buy, "111", 10.0);
+        neg_risk.market = market(true);
+        assert_eq!(
+            service.reserve(&neg_risk).await,
+            Err(BalanceRejection::InsufficientAllowance)
+        );
+        let mut regular = order(Side::Buy, "111", 10.0);
+        regular.market = market(false);
+        assert_eq!(service.reserve(&regular).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_sells_blocked_for_tokens_not_held() {
+        let service = make_service().await;
+        assert_eq!(
+            service.reserve(&order(Side::Sell, "222", 5.0)).await,
+            Err(BalanceRejection::TokenNotHeld)
+        );
+        // 40 shares at 0.5 cover 20 USDC of exits.
+        assert_eq!(
+            service.reserve(&order(Side::Sell, "111", 15.0)).await,
+            Ok(())
+        );
+        assert_eq!(
+            service.reserve(&order(Side::Sell, "111", 10.0)).await,
+            Err(BalanceRejection::InsufficientTokens)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exit_below_entry_sells_position_shares() {
+        let service = make_service().await;
+        // The 40 shares held were bought for 20 USDC; the exit is at 0.30.
+        let mut exit = order(Side::Sell, "111", 20.0);
+        exit.price = Some(0.30);
+        exit.closes = Some(crate::strategy::state::Position {
+            outcome: Outcome::Up,
+            entry_price: 0.50,
+            size_usdc: 20.0,
+            entry_at: 0,
+            symbol: exit.symbol.clone(),
+            entry_fee_usdc: 0.0,
+        });
+        assert_eq!(service.reserve(&exit).await, Ok(()));
+
+        let result = OrderResult {
+            filled_price: Some(0.30),
+            ..filled()
+        };
+        service.settle(&exit, &result).await;
+        let snapshot = service.snapshot(1).await.unwrap();
+        let sold = snapshot
+            .tokens
+            .iter()
+            .find(|t| t.token_id == "111")
+            .unwrap();
+        assert!(sold.shares.abs() < 1e-9);
+        // 25 USDC held plus 40 shares at 0.30.
+        assert!((snapshot.usdc - 37.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_fill_updates_cached_balance() {
+        let service = make_service().await;
+        let buy = order(Side::Buy, "333", 10.0);
+        service.reserve(&buy).await.unwrap();
+        service.settle(&buy, &filled()).await;
+
+        let snapshot = service.snapshot(1).await.unwrap();
+        assert!((snapshot.usdc - 15.0).abs() < 1e-9);
+        assert_eq!(snapshot.usdc_reserved, 0.0);
+        let bought = snapshot
+            .tokens
+            .iter()
+            .find(|t| t.token_id == "333")
+            .unwrap();
+        assert!((bought.shares - 20.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_paper_and_unknown_wallets_pass() {
+        let service = make_service().await;
+        let mut paper = order(Side::Sell, "999", 5.0);
+        paper.is_paper = true;
+        assert_eq!(service.reserve(&paper).await, Ok(()));
+
+        let mut unknown = order(Side::Buy, "111", 500.0);
+        unknown.wallet_id = 42;
+        assert_eq!(service.reserve(&unknown).await, Ok(()));
+    }
+}
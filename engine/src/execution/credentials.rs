@@ -0,0 +1,553 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use alloy::primitives::{Address, U256};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::SignerSync;
+use alloy::sol;
+use alloy::sol_types::{eip712_domain, SolStruct};
+use anyhow::{Context, Result};
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde::Deserialize;
+use sqlx::PgPool;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, warn};
+
+use super::orders::{clob_auth_headers, now_secs};
+use super::wallet::WalletKeyStore;
+use crate::network::NetworkProfile;
+use crate::proxy::HttpPool;
+use crate::storage::postgres;
+
+/// Fixed statement the CLOB expects in the L1 `ClobAuth` message.
+const CLOB_AUTH_MESSAGE: &str = "This message attests that I control the given wallet";
+
+sol! {
+    #[derive(Debug)]
+    struct ClobAuth {
+        address address;
+        string timestamp;
+        uint256 nonce;
+        string message;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ApiCredentials
+// ---------------------------------------------------------------------------
+
+/// CLOB L2 API credentials, as returned by `/auth/api-key`.
+#[derive(Clone, Deserialize)]
+pub struct ApiCredentials {
+    #[serde(rename = "apiKey")]
+    pub api_key: String,
+    pub secret: String,
+    pub passphrase: String,
+}
+
+/// A wallet's L2 credentials, bound to its signer EOA and L1 nonce.
+#[derive(Clone)]
+pub struct WalletCredentials {
+    pub address: Address,
+    pub nonce: u64,
+    pub credentials: ApiCredentials,
+}
+
+// ---------------------------------------------------------------------------
+// CredentialManager
+// ---------------------------------------------------------------------------
+
+/// Per-wallet CLOB API keys, derived from an L1 signature by the wallet's
+/// signer on first use.
+///
+/// Keys are cached in memory and persisted encrypted in
+/// `wallet_api_credentials`. Persistence is best-effort: the CLOB derives the
+/// same key again from the signer and nonce, so a lost row only costs a round
+/// trip. Rotation bumps the nonce; a revoked wallet gets no key until rotated.
+pub struct CredentialManager {
+    http: HttpPool,
+    network: Arc<NetworkProfile>,
+    clob_url: String,
+    db: PgPool,
+    wallet_keys: Arc<WalletKeyStore>,
+    cache: RwLock<HashMap<u64, WalletCredentials>>,
+    revoked: RwLock<HashSet<u64>>,
+    /// Serializes derivation so concurrent orders don't create keys twice.
+    derive_lock: Mutex<()>,
+}
+
+impl CredentialManager {
+    pub fn new(
+        http: HttpPool,
+        network: Arc<NetworkProfile>,
+        db: PgPool,
+        wallet_keys: Arc<WalletKeyStore>,
+    ) -> Self {
+        Self {
+            http,
+            clob_url: network.clob_api_url.trim_end_matches('/').to_string(),
+            network,
+            db,
+            wallet_keys,
+            cache: RwLock::new(HashMap::new()),
+            revoked: RwLock::new(HashSet::new()),
+            derive_lock: Mutex::new(()),
+        }
+    }
+
+    /// L2 `POLY_*` headers for a request signed with the wallet's own key.
+    pub async fn l2_headers(
+        &self,
+        wallet_id: u64,
+        method: &str,
+        path: &str,
+        body: &str,
+    ) -> Result<HeaderMap> {
+        let creds = self.get(wallet_id).await?;
+        clob_auth_headers(&creds.credentials, &creds.address, method, path, body)
+    }
+
+    /// The wallet's credentials: cached, persisted, or derived from the CLOB.
+    pub async fn get(&self, wallet_id: u64) -> Result<WalletCredentials> {
+        if let Some(creds) = self.cache.read().await.get(&wallet_id) {
+            return Ok(creds.clone());
+        }
+        anyhow::ensure!(
+            !self.revoked.read().await.contains(&wallet_id),
+            "API credentials for wallet {wallet_id} are revoked"
+        );
+
+        let _guard = self.derive_lock.lock().await;
+        if let Some(creds) = self.cache.read().await.get(&wallet_id) {
+            return Ok(creds.clone());
+        }
+
+        let nonce = match self.load(wallet_id).await {
+            Ok(Some(Stored::Active(creds))) => {
+                self.cache.write().await.insert(wallet_id, creds.clone());
+                return Ok(creds);
+            }
+            Ok(Some(Stored::Revoked(_))) => {
+                self.revoked.write().await.insert(wallet_id);
+                anyhow::bail!("API credentials for wallet {wallet_id} are revoked");
+            }
+            Ok(Some(Stored::Stale(nonce))) => nonce,
+            Ok(None) => 0,
+            Err(e) => {
+                warn!(wallet_id, error = %e, "api_credentials_load_failed");
+                0
+            }
+        };
+
+        let creds = self.derive_or_create(wallet_id, nonce).await?;
+        self.store(wallet_id, &creds).await;
+        Ok(creds)
+    }
+
+    /// Drop cached credentials the CLOB refused, so the next request derives
+    /// them again.
+    pub async fn invalidate(&self, wallet_id: u64) {
+        if self.cache.write().await.remove(&wallet_id).is_some() {
+            if let Err(e) =
+                postgres::mark_wallet_api_credentials_stale(&self.db, wallet_id as i64).await
+            {
+                warn!(wallet_id, error = %e, "api_credentials_invalidate_failed");
+            }
+            debug!(wallet_id, "api_credentials_invalidated");
+        }
+    }
+
+    /// Delete the wallet's current key on the CLOB and create a fresh one
+    /// under the next nonce. Also lifts a revocation.
+    pub async fn rotate(&self, wallet_id: u64) -> Result<WalletCredentials> {
+        let _guard = self.derive_lock.lock().await;
+        let cached = self.cache.read().await.get(&wallet_id).map(|c| c.nonce);
+        let previous = match cached {
+            Some(nonce) => Some(nonce),
+            None => match self.load(wallet_id).await? {
+                Some(Stored::Active(creds)) => Some(creds.nonce),
+                Some(Stored::Stale(nonce) | Stored::Revoked(nonce)) => Some(nonce),
+                None => None,
+            },
+        };
+        if let Some(current) = self.cache.write().await.remove(&wallet_id) {
+            if let Err(e) = self.delete_api_key(&current).await {
+                warn!(wallet_id, error = %e, "api_key_delete_failed");
+            }
+        }
+
+        let signer = self.wallet_keys.get_signer(wallet_id)?;
+        let nonce = previous.map_or(0, |n| n + 1);
+        let credentials = self.request_api_key(&signer, nonce, true).await?;
+        let creds = WalletCredentials {
+            address: signer.address(),
+            nonce,
+            credentials,
+        };
+        self.revoked.write().await.remove(&wallet_id);
+        self.store(wallet_id, &creds).await;
+        debug!(wallet_id, nonce, "api_credentials_rotated");
+        Ok(creds)
+    }
+
+    /// Delete the wallet's key on the CLOB and refuse to derive another one
+    /// until [`Self::rotate`] is called.
+    pub async fn revoke(&self, wallet_id: u64) -> Result<()> {
+        let _guard = self.derive_lock.lock().await;
+        let cached = self.cache.write().await.remove(&wallet_id);
+        let current = match cached {
+            Some(creds) => Some(creds),
+            None => match self.load(wallet_id).await? {
+                Some(Stored::Active(creds)) => Some(creds),
+                _ => None,
+            },
+        };
+        if let Some(creds) = &current {
+            self.delete_api_key(creds).await?;
+        }
+        self.revoked.write().await.insert(wallet_id);
+        postgres::revoke_wallet_api_credentials(&self.db, wallet_id as i64).await?;
+        debug!(wallet_id, "api_credentials_revoked");
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // CLOB auth endpoints
+    // -----------------------------------------------------------------------
+
+    /// `GET /auth/derive-api-key`, falling back to `POST /auth/api-key` when
+    /// no key exists for the nonce yet.
+    async fn derive_or_create(&self, wallet_id: u64, nonce: u64) -> Result<WalletCredentials> {
+        let signer = self.wallet_keys.get_signer(wallet_id)?;
+        let credentials = match self.request_api_key(&signer, nonce, false).await {
+            Ok(creds) => creds,
+            Err(e) => {
+                debug!(wallet_id, nonce, error = %e, "api_key_derive_failed");
+                self.request_api_key(&signer, nonce, true).await?
+            }
+        };
+        debug!(wallet_id, nonce, "api_credentials_derived");
+        Ok(WalletCredentials {
+            address: signer.address(),
+            nonce,
+            credentials,
+        })
+    }
+
+    async fn request_api_key(
+        &self,
+        signer: &PrivateKeySigner,
+        nonce: u64,
+        create: bool,
+    ) -> Result<ApiCredentials> {
+        let headers = l1_headers(signer, self.network.chain_id, nonce)?;
+        let request = if create {
+            self.http
+                .proxied()
+                .post(format!("{}/auth/api-key", self.clob_url))
+        } else {
+            self.http
+                .proxied()
+                .get(format!("{}/auth/derive-api-key", self.clob_url))
+        };
+        request
+            .headers(headers)
+            .send()
+            .await
+            .context("CLOB auth request failed")?
+            .error_for_status()
+            .context("CLOB auth request rejected")?
+            .json()
+            .await
+            .context("failed to parse CLOB API credentials")
+    }
+
+    async fn delete_api_key(&self, creds: &WalletCredentials) -> Result<()> {
+        let path = "/auth/api-key";
+        let headers = clob_auth_headers(&creds.credentials, &creds.address, "DELETE", path, "")?;
+        self.http
+            .proxied()
+            .delete(format!("{}{}", self.clob_url, path))
+            .headers(headers)
+            .send()
+            .await
+            .context("CLOB api key deletion failed")?
+            .error_for_status()
+            .context("CLOB api key deletion rejected")?;
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Persistence
+    // -----------------------------------------------------------------------
+
+    async fn load(&self, wallet_id: u64) -> Result<Option<Stored>> {
+        let Some(row) = postgres::load_wallet_api_credentials(&self.db, wallet_id as i64).await?
+        else {
+            return Ok(None);
+        };
+        let nonce = row.nonce as u64;
+        if row.revoked {
+            return Ok(Some(Stored::Revoked(nonce)));
+        }
+        let (Some(secret_enc), Some(passphrase_enc)) = (row.secret_enc, row.passphrase_enc) else {
+            return Ok(Some(Stored::Stale(nonce)));
+        };
+        Ok(Some(Stored::Active(WalletCredentials {
+            address: row.address.parse().context("invalid stored address")?,
+            nonce,
+            credentials: ApiCredentials {
+                api_key: row.api_key,
+                secret: self.wallet_keys.decrypt_secret(&secret_enc)?,
+                passphrase: self.wallet_keys.decrypt_secret(&passphrase_enc)?,
+            },
+        })))
+    }
+
+    async fn store(&self, wallet_id: u64, creds: &WalletCredentials) {
+        self.cache.write().await.insert(wallet_id, creds.clone());
+        let encrypted = self
+            .wallet_keys
+            .encrypt_secret(&creds.credentials.secret)
+            .and_then(|secret| {
+                Ok((
+                    secret,
+                    self.wallet_keys
+                        .encrypt_secret(&creds.credentials.passphrase)?,
+                ))
+            });
+        let result = match encrypted {
+            Ok((secret_enc, passphrase_enc)) => {
+                postgres::upsert_wallet_api_credentials(
+                    &self.db,
+                    wallet_id as i64,
+                    &format!("{:?}", creds.address),
+                    &creds.credentials.api_key,
+                    &secret_enc,
+                    &passphrase_enc,
+                    creds.nonce as i64,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            warn!(wallet_id, error = %e, "api_credentials_persist_failed");
+        }
+    }
+}
+
+/// Persisted state of a wallet's credentials.
+enum Stored {
+    Active(WalletCredentials),
+    /// Refused by the CLOB; re-derive under this nonce.
+    Stale(u64),
+    /// Revoked; rotation resumes after this nonce.
+    Revoked(u64),
+}
+
+/// L1 headers: an EIP-712 `ClobAuth` signature by the wallet's signer.
+fn l1_headers(signer: &PrivateKeySigner, chain_id: u64, nonce: u64) -> Result<HeaderMap> {
+    let timestamp = now_secs().to_string();
+    let auth = ClobAuth {
+        address: signer.address(),
+        timestamp: timestamp.clone(),
+        nonce: U256::from(nonce),
+        message: CLOB_AUTH_MESSAGE.to_string(),
+    };
+    let domain = eip712_domain! {
+        name: "ClobAuthDomain",
+        version: "1",
+        chain_id: chain_id,
+    };
+    let signature = signer
+        .sign_hash_sync(&auth.eip712_signing_hash(&domain))
+        .context("failed to sign ClobAuth")?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "POLY_ADDRESS",
+        HeaderValue::from_str(&format!("{:?}", signer.address()))?,
+    );
+    headers.insert(
+        "POLY_SIGNATURE",
+        HeaderValue::from_str(&format!("0x{}", hex::encode(signature.as_bytes())))?,
+    );
+    headers.insert("POLY_TIMESTAMP", HeaderValue::from_str(&timestamp)?);
+    headers.insert("POLY_NONCE", HeaderValue::from_str(&nonce.to_string())?);
+    Ok(headers)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex as StdMutex;
+    use std::time::Duration;
+
+    use alloy::primitives::Signature;
+    use axum::http::HeaderMap as AxumHeaders;
+    use axum::routing::get;
+    use axum::{Json, Router};
+
+    use super::*;
+
+    type Calls = Arc<StdMutex<Vec<(String, u64)>>>;
+
+    /// A CLOB whose keys are named after the nonce they were created with.
+    async fn spawn_mock_clob(calls: Calls, derive_ok: bool) -> String {
+        let record = |calls: Calls, kind: &'static str| {
+            move |headers: AxumHeaders| async move {
+                let nonce: u64 = headers["POLY_NONCE"].to_str().unwrap().parse().unwrap();
+                calls.lock().unwrap().push((kind.to_string(), nonce));
+                Json(serde_json::json!({
+                    "apiKey": format!("key-{nonce}"),
+                    "secret": "c2VjcmV0",
+                    "passphrase": "pass"
+                }))
+            }
+        };
+        let derive = if derive_ok {
+            get(record(calls.clone(), "derive"))
+        } else {
+            get(|| async { axum::http::StatusCode::BAD_REQUEST })
+        };
+        let delete_calls = calls.clone();
+        let app = Router::new().route("/auth/derive-api-key", derive).route(
+            "/auth/api-key",
+            axum::routing::post(record(calls.clone(), "create")).delete(
+                move |headers: AxumHeaders| async move {
+                    let key = headers["POLY_API_KEY"].to_str().unwrap().to_string();
+                    delete_calls
+                        .lock()
+                        .unwrap()
+                        .push((format!("delete {key}"), 0));
+                    Json(serde_json::json!({}))
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    fn make_manager(url: &str) -> CredentialManager {
+        let wallet_keys = Arc::new(
+            WalletKeyStore::new("0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef")
+                .unwrap(),
+        );
+        let encrypted = wallet_keys.encrypt_key(&[0xABu8; 32]).unwrap();
+        wallet_keys.store_key(1, &encrypted).unwrap();
+        // Unreachable database: persistence failures must not block trading.
+        let db = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(200))
+            .connect_lazy("postgres://localhost:1/test")
+            .unwrap();
+        CredentialManager::new(
+            HttpPool::new(&[], Duration::from_secs(5)).unwrap(),
+            Arc::new(NetworkProfile {
+                clob_api_url: url.into(),
+                ..NetworkProfile::polygon()
+            }),
+            db,
+            wallet_keys,
+        )
+    }
+
+    #[test]
+    fn test_l1_signature_recovers_signer() {
+        let signer = PrivateKeySigner::random();
+        let headers = l1_headers(&signer, 137, 3).unwrap();
+        assert_eq!(headers["POLY_NONCE"], "3");
+
+        let auth = ClobAuth {
+            address: signer.address(),
+            timestamp: headers["POLY_TIMESTAMP"].to_str().unwrap().to_string(),
+            nonce: U256::from(3),
+            message: CLOB_AUTH_MESSAGE.to_string(),
+        };
+        let domain = eip712_domain! {
+            name: "ClobAuthDomain",
+            version: "1",
+            chain_id: 137,
+        };
+        let sig = hex::decode(&headers["POLY_SIGNATURE"].to_str().unwrap()[2..]).unwrap();
+        let sig = Signature::try_from(sig.as_slice()).unwrap();
+        assert_eq!(
+            sig.recover_address_from_prehash(&auth.eip712_signing_hash(&domain))
+                .unwrap(),
+            signer.address()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_derives_once_and_signs_l2_with_signer_address() {
+        let calls: Calls = Arc::default();
+        let url = spawn_mock_clob(calls.clone(), true).await;
+        let manager = make_manager(&url);
+
+        let headers = manager.l2_headers(1, "POST", "/order", "{}").await.unwrap();
+        manager.l2_headers(1, "POST", "/order", "{}").await.unwrap();
+        assert_eq!(headers["POLY_API_KEY"], "key-0");
+        let signer = manager.wallet_keys.get_signer(1).unwrap();
+        assert_eq!(
+            headers["POLY_ADDRESS"],
+            format!("{:?}", signer.address()).as_str()
+        );
+        assert_eq!(*calls.lock().unwrap(), vec![("derive".to_string(), 0)]);
+    }
+
+    #[tokio::test]
+    async fn test_creates_when_derive_fails() {
+        let calls: Calls = Arc::default();
+        let url = spawn_mock_clob(calls.clone(), false).await;
+        let manager = make_manager(&url);
+
+        let creds = manager.get(1).await.unwrap();
+        assert_eq!(creds.credentials.api_key, "key-0");
+        assert_eq!(*calls.lock().unwrap(), vec![("create".to_string(), 0)]);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_deletes_old_key_and_bumps_nonce() {
+        let calls: Calls = Arc::default();
+        let url = spawn_mock_clob(calls.clone(), true).await;
+        let manager = make_manager(&url);
+
+        manager.get(1).await.unwrap();
+        let rotated = manager.rotate(1).await.unwrap();
+        assert_eq!(rotated.nonce, 1);
+        assert_eq!(rotated.credentials.api_key, "key-1");
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                ("derive".to_string(), 0),
+                ("delete key-0".to_string(), 0),
+                ("create".to_string(), 1),
+            ]
+        );
+        assert_eq!(manager.get(1).await.unwrap().credentials.api_key, "key-1");
+    }
+
+    #[tokio::test]
+    async fn test_revoked_wallet_gets_no_key_until_rotated() {
+        let calls: Calls = Arc::default();
+        let url = spawn_mock_clob(calls.clone(), true).await;
+        let manager = make_manager(&url);
+
+        manager.get(1).await.unwrap();
+        // The database is down, so only the in-memory revocation sticks.
+        assert!(manager.revoke(1).await.is_err());
+        let Err(err) = manager.get(1).await else {
+            panic!("revoked wallet must not get credentials");
+        };
+        assert!(err.to_string().contains("revoked"));
+        assert!(calls
+            .lock()
+            .unwrap()
+            .contains(&("delete key-0".to_string(), 0)));
+    }
+}
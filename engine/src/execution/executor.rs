@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -6,10 +7,19 @@ use metrics::{counter, gauge, histogram};
 use sqlx::PgPool;
 use tokio::sync::Mutex;
 use tracing::{error, info, warn};
+use uuid::Uuid;
 
-use super::orders::OrderSubmitter;
+use super::algo::{self, ExecAlgo};
+use super::balances::BalanceService;
+use super::fees::taker_fee_usdc;
+use super::journal::{ExecutionJournal, JournalState};
+use super::normalize::NormalizeRejection;
+use super::orders::{ClobError, OrderSubmitter, PostedOrder};
 use super::queue::ExecutionQueue;
+use super::retry::{self, RetryPolicy};
+use super::risk::RiskEngine;
 use super::{ExecutionOrder, OrderResult, OrderStatus, Side};
+use crate::fetcher::websocket::OrderBookCache;
 use crate::metrics as m;
 use crate::strategy::bandit;
 use crate::strategy::registry::AssignmentRegistry;
@@ -20,14 +30,35 @@ use crate::strategy::Outcome;
 // run — main executor loop
 // ---------------------------------------------------------------------------
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     queue: Arc<Mutex<ExecutionQueue>>,
     submitter: Arc<OrderSubmitter>,
     registry: AssignmentRegistry,
     db: PgPool,
+    journal: Arc<ExecutionJournal>,
+    risk: Arc<RiskEngine>,
+    balances: Arc<BalanceService>,
+    retry_policy: RetryPolicy,
+    books: OrderBookCache,
 ) -> Result<()> {
     info!("executor_started");
 
+    if let Err(e) = risk.refresh(&db).await {
+        warn!(error = %e, "risk_exposure_load_failed");
+    }
+    let recorder = ResultRecorder {
+        registry: registry.clone(),
+        db,
+        risk: risk.clone(),
+        balances: balances.clone(),
+        slices: SliceTracker::default(),
+    };
+    replay_journal(&queue, &submitter, &recorder, &journal).await;
+
+    // Failed submissions so far for orders waiting on a retry.
+    let mut attempts: HashMap<Uuid, u32> = HashMap::new();
+
     loop {
         // 1. Atomic peek + rate-limit + pop (no order loss on rate-limit)
         let order = {
@@ -35,7 +66,7 @@ pub async fn run(
             q.pop_if_allowed()
         };
 
-        let order = match order {
+        let mut order = match order {
             None => {
                 tokio::time::sleep(Duration::from_millis(50)).await;
                 continue;
@@ -48,48 +79,186 @@ pub async fn run(
             }
             Some(Some(o)) => o,
         };
+        // Taken up front so every terminal path drops it; only a retry puts it back.
+        let failed_attempts = attempts.remove(&order.id).unwrap_or(0);
+
+        // 2. Drop orders that waited too long rather than sending them late
+        if journal.is_expired(&order, chrono::Utc::now().timestamp()) {
+            expire_order(&recorder, &journal, &order).await;
+            continue;
+        }
+
+        // 3. Work algo orders through child orders instead of sending them whole
+        if let Some(algo) = order.algo {
+            slice_order(&queue, &recorder, &journal, &books, order, algo).await;
+            continue;
+        }
+
+        // 4. Pre-trade risk check (reserves exposure for accepted entries)
+        let strategy_cap = strategy_max_position(&registry, &order).await;
+        if let Err(rejection) = risk.check(&order, strategy_cap, chrono::Utc::now().timestamp()) {
+            reject_order(&recorder, &journal, &order, rejection.as_str()).await;
+            continue;
+        }
+
+        // 5. Reserve the USDC / shares the Safe needs to cover the order, with the
+        // market pinned so the allowance checked is that of the exchange it signs for
+        if !order.is_paper && order.market.is_none() {
+            order.market = submitter.market_meta(&order.token_id).await.ok();
+        }
+        if let Err(rejection) = balances.reserve(&order).await {
+            reject_order(&recorder, &journal, &order, rejection.as_str()).await;
+            continue;
+        }
 
-        // 2. Submit order (or simulate for paper trading)
+        // 6. Submit order (or simulate for paper trading)
+        journal.mark(order.id, JournalState::Submitting).await;
         let exec_start = std::time::Instant::now();
         let result = if order.is_paper {
-            simulate_paper_fill(&order)
+            simulate_paper_fill(&order, submitter.paper_fee_bps(&order).await)
         } else {
-            match submitter.submit(&order).await {
-                Ok(r) => r,
+            let posted = async {
+                let signed = submitter.sign(&order).await?;
+                journal
+                    .mark_signed(order.id, &signed.order_hash, signed.fee_bps)
+                    .await;
+                submitter.post(&order, &signed).await
+            };
+            match posted.await {
+                Ok(posted) => {
+                    journal
+                        .mark_submitted(order.id, &posted.polymarket_order_id, posted.fee_bps)
+                        .await;
+                    submitter.await_fill(&posted).await
+                }
                 Err(e) => {
+                    // Off-grid or undersized orders never reach the CLOB.
+                    if let Some(rejection) = e.downcast_ref::<NormalizeRejection>() {
+                        reject_order(&recorder, &journal, &order, rejection.as_str()).await;
+                        continue;
+                    }
+
+                    let attempt = failed_attempts + 1;
+                    let retryable = retry::is_retryable(&e);
                     error!(
                         order_id = %order.id,
+                        attempt,
+                        retryable,
                         error = %e,
                         "order_submission_failed"
                     );
-                    OrderResult {
-                        polymarket_order_id: String::new(),
-                        status: OrderStatus::Failed,
-                        filled_price: None,
-                        fee_bps: None,
+                    if retryable {
+                        if let Some(delay) = retry_policy.backoff(attempt) {
+                            attempts.insert(order.id, attempt);
+                            schedule_retry(&queue, &recorder, &journal, order, delay).await;
+                            continue;
+                        }
                     }
+
+                    let result = submission_failure(&e);
+                    if retryable {
+                        // Retries exhausted: record the failure, then park the order.
+                        recorder.record(&order, &result).await;
+                        journal.dead_letter(&order, attempt, &e).await;
+                        counter!(m::ORDERS_DEAD_LETTERED_TOTAL).increment(1);
+                        continue;
+                    }
+                    result
                 }
             }
         };
 
         histogram!(m::ORDER_EXEC_DURATION).record(exec_start.elapsed().as_secs_f64());
+        recorder.record(&order, &result).await;
+        journal.mark(order.id, JournalState::Done).await;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ResultRecorder — state, trade and copy-trade bookkeeping after execution
+// ---------------------------------------------------------------------------
+
+/// Everything that must learn an order's outcome once it is known.
+#[derive(Clone)]
+struct ResultRecorder {
+    registry: AssignmentRegistry,
+    db: PgPool,
+    risk: Arc<RiskEngine>,
+    balances: Arc<BalanceService>,
+    slices: SliceTracker,
+}
+
+/// Children of sliced orders still queued or in flight, by parent id.
+#[derive(Clone, Default)]
+struct SliceTracker(Arc<std::sync::Mutex<HashMap<Uuid, usize>>>);
+
+impl SliceTracker {
+    fn open(&self, parent_id: Uuid, children: usize) {
+        if children > 0 {
+            let mut open = self.0.lock().unwrap_or_else(|e| e.into_inner());
+            *open.entry(parent_id).or_insert(0) += children;
+        }
+    }
+
+    /// Count `order` as finished. True when it ends its entry: always for a
+    /// whole order, and for a child once none of its siblings is left.
+    fn finish(&self, order: &ExecutionOrder) -> bool {
+        let Some(parent_id) = order.parent_id else {
+            return true;
+        };
+        let mut open = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match open.get_mut(&parent_id) {
+            Some(left) if *left > 1 => {
+                *left -= 1;
+                false
+            }
+            _ => {
+                open.remove(&parent_id);
+                true
+            }
+        }
+    }
+}
+
+impl ResultRecorder {
+    /// Drop the order's risk and balance reservations without recording an outcome.
+    async fn release(&self, order: &ExecutionOrder) {
+        let pending = OrderResult {
+            polymarket_order_id: String::new(),
+            status: OrderStatus::Failed,
+            filled_price: None,
+            fee_bps: None,
+            reject_reason: None,
+            clob_rejection: None,
+        };
+        self.risk.settle(order, &pending);
+        self.balances.settle(order, &pending).await;
+    }
+
+    async fn record(&self, order: &ExecutionOrder, result: &OrderResult) {
+        let (registry, db) = (&self.registry, &self.db);
         let status_label = match result.status {
             OrderStatus::Filled => "filled",
             OrderStatus::Cancelled => "cancelled",
             OrderStatus::Failed => "failed",
             OrderStatus::Timeout => "timeout",
+            OrderStatus::Rejected => "rejected",
         };
         counter!(m::ORDERS_TOTAL, "status" => status_label).increment(1);
+        self.risk.settle(order, result);
+        self.balances.settle(order, result).await;
 
-        // 4. Update in-memory strategy state after the execution completes
+        // Update in-memory strategy state after the execution completes
+        let entry_finished = self.slices.finish(order);
         if result.status == OrderStatus::Filled {
-            update_position(&registry, &order, &result).await;
-        } else if matches!(order.side, Side::Buy) {
-            clear_pending_entry(&registry, &order).await;
+            update_position(registry, order, result).await;
+        } else if matches!(order.side, Side::Buy) && entry_finished {
+            // Siblings of a sliced entry may still fill; keep the entry pending until the last.
+            clear_pending_entry(registry, order).await;
         }
 
-        // 5. Write trade to PostgreSQL
-        let trade_id = match crate::storage::postgres::write_trade(&db, &order, &result).await {
+        // Write trade to PostgreSQL
+        let trade_id = match crate::storage::postgres::write_trade(db, order, result).await {
             Ok(trade_id) => Some(trade_id),
             Err(e) => {
                 error!(order_id = %order.id, error = %e, "write_trade_failed");
@@ -99,7 +268,7 @@ pub async fn run(
 
         if let Some(exit_trade_id) = trade_id {
             if let Err(e) =
-                crate::storage::postgres::close_open_entry_trade_on_exit(&db, &order, &result).await
+                crate::storage::postgres::close_open_entry_trade_on_exit(db, order, result).await
             {
                 error!(
                     order_id = %order.id,
@@ -110,21 +279,22 @@ pub async fn run(
             }
         }
 
-        // 6. If copy trade, write copy_trade record
+        // If copy trade, write copy_trade record and move the position ledger
         if let Some(copy_rel_id) = order.copy_relationship_id {
+            if result.status == OrderStatus::Filled {
+                if let Err(e) = crate::storage::postgres::record_copy_fill(db, order, result).await
+                {
+                    error!(order_id = %order.id, error = %e, "record_copy_fill_failed");
+                }
+            }
+
             let outcome_str = match order.outcome {
                 Outcome::Up => "UP",
                 Outcome::Down => "DOWN",
             };
-            let status_str = match result.status {
-                OrderStatus::Filled => "filled",
-                OrderStatus::Cancelled => "cancelled",
-                OrderStatus::Failed => "failed",
-                OrderStatus::Timeout => "timeout",
-            };
 
             if let Err(e) = crate::storage::postgres::write_copy_trade(
-                &db,
+                db,
                 copy_rel_id as i64,
                 None,
                 &order.leader_address,
@@ -134,8 +304,12 @@ pub async fn run(
                 order.size_usdc,
                 &order.leader_tx_hash,
                 result.filled_price,
-                status_str,
-                None,
+                status_label,
+                result
+                    .reject_reason
+                    .as_deref()
+                    .or(result.clob_rejection.map(|r| r.as_str())),
+                order.is_paper,
             )
             .await
             {
@@ -143,7 +317,6 @@ pub async fn run(
             }
         }
 
-        // 7. Log completion
         info!(
             order_id = %order.id,
             wallet_id = order.wallet_id,
@@ -155,16 +328,275 @@ pub async fn run(
     }
 }
 
+// ---------------------------------------------------------------------------
+// replay_journal — recover orders that were in flight when the engine stopped
+// ---------------------------------------------------------------------------
+
+async fn replay_journal(
+    queue: &Arc<Mutex<ExecutionQueue>>,
+    submitter: &Arc<OrderSubmitter>,
+    recorder: &ResultRecorder,
+    journal: &Arc<ExecutionJournal>,
+) {
+    let registry = &recorder.registry;
+    let pending = match journal.load_unfinished().await {
+        Ok(pending) => pending,
+        Err(e) => {
+            warn!(error = %e, "execution_journal_load_failed");
+            return;
+        }
+    };
+
+    // Children left over from sliced orders still count towards their entry.
+    let mut children: HashMap<Uuid, usize> = HashMap::new();
+    for parent_id in pending.iter().filter_map(|e| e.order.parent_id) {
+        *children.entry(parent_id).or_insert(0) += 1;
+    }
+    for (parent_id, count) in children {
+        recorder.slices.open(parent_id, count);
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let mut requeued = 0usize;
+    let mut resumed = 0usize;
+    let mut expired = 0usize;
+
+    for entry in pending {
+        let order = entry.order;
+        if journal.is_expired(&order, now) {
+            expire_order(recorder, journal, &order).await;
+            expired += 1;
+            continue;
+        }
+
+        // Rehydrated strategy state drops its pending entry; re-mark it for buys still in flight.
+        if order.side == Side::Buy {
+            mark_pending_entry(registry, &order).await;
+        }
+
+        let state = JournalState::parse(&entry.state);
+        match (state, entry.polymarket_order_id) {
+            (Some(JournalState::Submitted), Some(polymarket_order_id)) => {
+                // Already accepted by the CLOB — resume status polling only.
+                let posted = PostedOrder {
+                    polymarket_order_id,
+                    fee_bps: entry.fee_bps.unwrap_or(0),
+                };
+                resume_order(submitter, recorder, journal, order, posted);
+                resumed += 1;
+            }
+            (Some(JournalState::Submitting), Some(order_hash)) => {
+                // Signed before the stop: the post may have landed, so only re-sign
+                // once the CLOB confirms it never saw this hash.
+                match submitter.find_order(&order_hash).await {
+                    Ok(true) => {
+                        let posted = PostedOrder {
+                            polymarket_order_id: order_hash,
+                            fee_bps: entry.fee_bps.unwrap_or(0),
+                        };
+                        resume_order(submitter, recorder, journal, order, posted);
+                        resumed += 1;
+                    }
+                    Ok(false) => {
+                        queue.lock().await.push(order);
+                        requeued += 1;
+                    }
+                    Err(e) => {
+                        warn!(order_id = %order.id, error = %e, "journal_order_lookup_failed");
+                        queue.lock().await.push(order);
+                        requeued += 1;
+                    }
+                }
+            }
+            _ => {
+                // Never signed: nothing reached the CLOB, so submit from scratch.
+                queue.lock().await.push(order);
+                requeued += 1;
+            }
+        }
+    }
+
+    counter!(m::ORDERS_REPLAYED_TOTAL, "action" => "requeued").increment(requeued as u64);
+    counter!(m::ORDERS_REPLAYED_TOTAL, "action" => "resumed").increment(resumed as u64);
+    info!(requeued, resumed, expired, "execution_journal_replayed");
+}
+
+/// Poll a replayed order that reached the CLOB through to its result.
+fn resume_order(
+    submitter: &Arc<OrderSubmitter>,
+    recorder: &ResultRecorder,
+    journal: &Arc<ExecutionJournal>,
+    order: ExecutionOrder,
+    posted: PostedOrder,
+) {
+    let submitter = submitter.clone();
+    let recorder = recorder.clone();
+    let journal = journal.clone();
+    tokio::spawn(async move {
+        let result = submitter.await_fill(&posted).await;
+        recorder.record(&order, &result).await;
+        journal.mark(order.id, JournalState::Done).await;
+    });
+}
+
+// ---------------------------------------------------------------------------
+// slice_order — expand an algo order into scheduled child orders
+// ---------------------------------------------------------------------------
+
+async fn slice_order(
+    queue: &Arc<Mutex<ExecutionQueue>>,
+    recorder: &ResultRecorder,
+    journal: &ExecutionJournal,
+    books: &OrderBookCache,
+    parent: ExecutionOrder,
+    algo: ExecAlgo,
+) {
+    let children = {
+        let books = books.read().await;
+        algo::slice(&parent, algo, books.get(&parent.token_id))
+    };
+    info!(
+        order_id = %parent.id,
+        symbol = %parent.symbol,
+        size = parent.size_usdc,
+        algo = ?algo,
+        children = children.len(),
+        "order_sliced"
+    );
+    counter!(m::ORDERS_SLICED_TOTAL).increment(1);
+
+    if children.is_empty() && parent.side == Side::Buy {
+        clear_pending_entry(&recorder.registry, &parent).await;
+    }
+    recorder.slices.open(parent.id, children.len());
+    for child in children {
+        journal.enqueue_after(queue, child.order, child.delay).await;
+    }
+    journal.mark(parent.id, JournalState::Done).await;
+}
+
+// ---------------------------------------------------------------------------
+// Submission failures and retries
+// ---------------------------------------------------------------------------
+
+/// Result for a submission the CLOB refused (`Rejected`) or that never got an
+/// answer (`Failed`).
+fn submission_failure(e: &anyhow::Error) -> OrderResult {
+    let clob_rejection = e.downcast_ref::<ClobError>().map(|c| c.rejection);
+    if let Some(rejection) = clob_rejection {
+        counter!(m::CLOB_REJECTIONS_TOTAL, "reason" => rejection.as_str()).increment(1);
+    }
+    OrderResult {
+        polymarket_order_id: String::new(),
+        status: if clob_rejection.is_some() {
+            OrderStatus::Rejected
+        } else {
+            OrderStatus::Failed
+        },
+        filled_price: None,
+        fee_bps: None,
+        reject_reason: None,
+        clob_rejection,
+    }
+}
+
+/// Push the order back onto the queue after `delay`.
+///
+/// Risk and balance reservations are released meanwhile (the order is checked
+/// again when popped), but the strategy keeps its pending entry so it does not
+/// fire a second order for the same opportunity.
+async fn schedule_retry(
+    queue: &Arc<Mutex<ExecutionQueue>>,
+    recorder: &ResultRecorder,
+    journal: &ExecutionJournal,
+    order: ExecutionOrder,
+    delay: Duration,
+) {
+    counter!(m::ORDER_RETRIES_TOTAL).increment(1);
+    recorder.release(&order).await;
+    journal.mark(order.id, JournalState::Queued).await;
+    let queue = queue.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        queue.lock().await.push(order);
+    });
+}
+
+// ---------------------------------------------------------------------------
+// reject_order — record a pre-trade rejection without touching the CLOB
+// ---------------------------------------------------------------------------
+
+async fn reject_order(
+    recorder: &ResultRecorder,
+    journal: &ExecutionJournal,
+    order: &ExecutionOrder,
+    reason: &str,
+) {
+    warn!(
+        order_id = %order.id,
+        wallet_id = order.wallet_id,
+        symbol = %order.symbol,
+        size = order.size_usdc,
+        reason,
+        "order_rejected_pre_trade"
+    );
+    counter!(m::RISK_REJECTIONS_TOTAL, "reason" => reason.to_string()).increment(1);
+
+    let result = OrderResult {
+        polymarket_order_id: String::new(),
+        status: OrderStatus::Rejected,
+        filled_price: None,
+        fee_bps: None,
+        reject_reason: Some(reason.to_string()),
+        clob_rejection: None,
+    };
+    recorder.record(order, &result).await;
+    journal.mark(order.id, JournalState::Done).await;
+}
+
+async fn strategy_max_position(
+    registry: &AssignmentRegistry,
+    order: &ExecutionOrder,
+) -> Option<f64> {
+    let strategy_id = order.strategy_id?;
+    let reg = registry.read().await;
+    reg.values()
+        .flatten()
+        .find(|a| a.wallet_id == order.wallet_id && a.strategy_id == strategy_id)
+        .map(|a| a.max_position_usdc)
+}
+
+async fn expire_order(
+    recorder: &ResultRecorder,
+    journal: &ExecutionJournal,
+    order: &ExecutionOrder,
+) {
+    warn!(
+        order_id = %order.id,
+        wallet_id = order.wallet_id,
+        symbol = %order.symbol,
+        created_at = order.created_at,
+        "order_expired"
+    );
+    counter!(m::ORDERS_EXPIRED_TOTAL).increment(1);
+    if recorder.slices.finish(order) && order.side == Side::Buy {
+        clear_pending_entry(&recorder.registry, order).await;
+    }
+    journal.mark(order.id, JournalState::Expired).await;
+}
+
 // ---------------------------------------------------------------------------
 // simulate_paper_fill — instant simulated fill for paper trading
 // ---------------------------------------------------------------------------
 
-fn simulate_paper_fill(order: &ExecutionOrder) -> OrderResult {
+fn simulate_paper_fill(order: &ExecutionOrder, fee_bps: u16) -> OrderResult {
     OrderResult {
         polymarket_order_id: format!("paper-{}", order.id),
         status: OrderStatus::Filled,
         filled_price: order.reference_price.or(order.price),
-        fee_bps: Some(0),
+        fee_bps: Some(fee_bps),
+        reject_reason: None,
+        clob_rejection: None,
     }
 }
 
@@ -213,16 +645,40 @@ async fn update_position(
 
     let now = chrono::Utc::now().timestamp();
     state.last_trade_at = Some(now);
+    let fee_bps = result.fee_bps.unwrap_or(0);
 
     match order.side {
         Side::Buy => {
+            let shares = if filled_price > 0.0 {
+                order.size_usdc / filled_price
+            } else {
+                0.0
+            };
             state.pending_entry_symbol = None;
+            let entry_fee_usdc = taker_fee_usdc(fee_bps, filled_price, shares);
+            if order.parent_id.is_some() {
+                if let Some(pos) = state
+                    .position
+                    .as_mut()
+                    .filter(|p| p.symbol == order.symbol && p.outcome == order.outcome)
+                {
+                    // Another child of a sliced entry: average it into the position.
+                    let total_shares = pos.shares() + shares;
+                    pos.size_usdc += order.size_usdc;
+                    if total_shares > 0.0 {
+                        pos.entry_price = pos.size_usdc / total_shares;
+                    }
+                    pos.entry_fee_usdc += entry_fee_usdc;
+                    return;
+                }
+            }
             state.position = Some(Position {
                 outcome: order.outcome,
                 entry_price: filled_price,
                 size_usdc: order.size_usdc,
                 entry_at: now,
                 symbol: order.symbol.clone(),
+                entry_fee_usdc,
             });
             bandit::record_entry_fill(
                 &assignment.graph,
@@ -230,16 +686,19 @@ async fn update_position(
                 &order.symbol,
                 filled_price,
                 now,
+                fee_bps,
             );
         }
         Side::Sell => {
-            if let Some(ref pos) = state.position {
-                let pnl = (filled_price - pos.entry_price) * pos.size_usdc;
-                state.pnl += pnl;
-                state.daily_pnl += pnl;
-                gauge!(m::PNL_USDC).increment(pnl);
+            // The interpreter already cleared the position when emitting the exit.
+            if let Some(pos) = &order.closes {
+                let shares = pos.shares();
+                let gross = (filled_price - pos.entry_price) * shares;
+                let fees = pos.entry_fee_usdc + taker_fee_usdc(fee_bps, filled_price, shares);
+                let net = state.realize(gross, fees);
+                gauge!(m::PNL_USDC).increment(net);
+                gauge!(m::FEES_USDC).increment(fees);
             }
-            state.position = None;
         }
     }
 }
@@ -268,6 +727,31 @@ async fn clear_pending_entry(registry: &AssignmentRegistry, order: &ExecutionOrd
     bandit::clear_pending_choice(&mut state);
 }
 
+async fn mark_pending_entry(registry: &AssignmentRegistry, order: &ExecutionOrder) {
+    let strategy_id = match order.strategy_id {
+        Some(id) => id,
+        None => return,
+    };
+
+    let reg = registry.read().await;
+    let assignment = reg
+        .values()
+        .flatten()
+        .find(|a| a.wallet_id == order.wallet_id && a.strategy_id == strategy_id);
+
+    let Some(assignment) = assignment else {
+        return;
+    };
+
+    let mut state = match assignment.state.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if state.position.is_none() {
+        state.pending_entry_symbol = Some(order.symbol.clone());
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -301,6 +785,10 @@ mod tests {
             leader_address: String::new(),
             leader_tx_hash: String::new(),
             is_paper: false,
+            market: None,
+            algo: None,
+            parent_id: None,
+            closes: None,
         }
     }
 
@@ -310,6 +798,8 @@ mod tests {
             status: OrderStatus::Filled,
             filled_price: Some(price),
             fee_bps: Some(100),
+            reject_reason: None,
+            clob_rejection: None,
         }
     }
 
@@ -354,22 +844,66 @@ mod tests {
             "size_usdc should be 50.0"
         );
         assert_eq!(pos.outcome, Outcome::Up);
+        // 100 bps × min(0.60, 0.40) × (50 / 0.60) shares
+        assert!((pos.entry_fee_usdc - 0.01 * 0.40 * 50.0 / 0.60).abs() < 1e-9);
     }
 
     #[tokio::test]
-    async fn test_update_position_sell_clears_and_updates_pnl() {
+    async fn test_sliced_buy_children_average_into_one_position() {
         let registry = AssignmentRegistry::new();
+        activate(
+            &registry,
+            1,
+            100,
+            serde_json::json!({}),
+            vec!["btc".into()],
+            200.0,
+            false,
+            None,
+        )
+        .await;
 
-        // Create state with an existing position at entry_price=0.50
-        let mut initial_state = StrategyState::new(200);
-        initial_state.position = Some(Position {
+        let parent_id = Uuid::new_v4();
+        let mut first = make_order(1, 100, Side::Buy, 30.0);
+        first.parent_id = Some(parent_id);
+        let mut second = make_order(1, 100, Side::Buy, 20.0);
+        second.parent_id = Some(parent_id);
+
+        update_position(&registry, &first, &make_filled_result(0.50)).await;
+        update_position(&registry, &second, &make_filled_result(0.40)).await;
+
+        let reg = registry.read().await;
+        let state = reg
+            .get("btc")
+            .unwrap()
+            .first()
+            .unwrap()
+            .state
+            .lock()
+            .unwrap();
+        let pos = state.position.as_ref().unwrap();
+        // 60 shares + 50 shares for 50 USDC
+        assert!((pos.size_usdc - 50.0).abs() < 1e-9);
+        assert!((pos.entry_price - 50.0 / 110.0).abs() < 1e-9);
+        assert!((pos.shares() - 110.0).abs() < 1e-9);
+        let expected_fees = 0.01 * 0.50 * 60.0 + 0.01 * 0.40 * 50.0;
+        assert!((pos.entry_fee_usdc - expected_fees).abs() < 1e-9);
+    }
+
+    fn open_position() -> Position {
+        Position {
             outcome: Outcome::Up,
             entry_price: 0.50,
             size_usdc: 50.0,
             entry_at: 0,
-            symbol: "btc".to_string(),
-        });
+            symbol: "btc-updown-15m-1700000000".to_string(),
+            entry_fee_usdc: 0.50,
+        }
+    }
 
+    #[tokio::test]
+    async fn test_update_position_sell_realizes_closed_position() {
+        let registry = AssignmentRegistry::new();
         activate(
             &registry,
             1,
@@ -378,11 +912,12 @@ mod tests {
             vec!["btc".into()],
             200.0,
             false,
-            Some(initial_state),
+            None,
         )
         .await;
 
-        let order = make_order(1, 100, Side::Sell, 50.0);
+        let mut order = make_order(1, 100, Side::Sell, 50.0);
+        order.closes = Some(open_position());
         let result = make_filled_result(0.70);
 
         update_position(&registry, &order, &result).await;
@@ -391,17 +926,56 @@ mod tests {
         let assignment = reg.get("btc").unwrap().first().unwrap();
         let state = assignment.state.lock().unwrap();
 
+        // 50 USDC at 0.50 = 100 shares → gross (0.70 - 0.50) × 100 = 20.0.
+        // Fees: 0.50 paid on entry + 100 bps × min(0.70, 0.30) × 100 = 0.30 on exit.
+        let expected_pnl = 20.0 - 0.80;
         assert!(
-            state.position.is_none(),
-            "position should be cleared after sell"
-        );
-
-        let expected_pnl = (0.70 - 0.50) * 50.0; // 10.0
-        assert!(
-            (state.pnl - expected_pnl).abs() < f64::EPSILON,
+            (state.pnl - expected_pnl).abs() < 1e-9,
             "pnl should be {expected_pnl}, got {}",
             state.pnl
         );
+        assert!((state.gross_pnl - 20.0).abs() < 1e-9);
+        assert!((state.fees_paid - 0.80).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_strategy_exit_realizes_pnl_end_to_end() {
+        use crate::strategy::engine::evaluate_assignment;
+        use crate::strategy::test_utils::test_tick;
+        use crate::tasks::execution_tasks::order_from_output;
+
+        let registry = AssignmentRegistry::new();
+        let mut initial_state = StrategyState::new(200);
+        initial_state.position = Some(open_position());
+        activate(
+            &registry,
+            1,
+            100,
+            serde_json::json!({"mode": "form", "risk": {"take_profit_pct": 10.0}}),
+            vec!["btc".into()],
+            200.0,
+            false,
+            Some(initial_state),
+        )
+        .await;
+
+        // The mid has risen from 0.50 to 0.61: take profit.
+        let output = {
+            let reg = registry.read().await;
+            let assignment = reg.get("btc").unwrap().first().unwrap();
+            evaluate_assignment(assignment, &test_tick(), None, None).unwrap()
+        };
+        let order = order_from_output(&output).unwrap();
+        assert_eq!(order.side, Side::Sell);
+        update_position(&registry, &order, &make_filled_result(0.60)).await;
+
+        let reg = registry.read().await;
+        let state = reg.get("btc").unwrap()[0].state.lock().unwrap();
+        assert!(state.position.is_none());
+        // 100 shares: gross (0.60 - 0.50) × 100 = 10.0, fees 0.50 + 0.40.
+        assert!((state.gross_pnl - 10.0).abs() < 1e-9);
+        assert!((state.fees_paid - 0.90).abs() < 1e-9);
+        assert!((state.pnl - 9.10).abs() < 1e-9);
     }
 
     #[tokio::test]
@@ -436,13 +1010,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_slice_tracker_finishes_entry_on_last_child() {
+        let slices = SliceTracker::default();
+        let parent_id = Uuid::new_v4();
+        slices.open(parent_id, 2);
+
+        let mut child = make_order(1, 100, Side::Buy, 25.0);
+        child.parent_id = Some(parent_id);
+        assert!(!slices.finish(&child), "a sibling is still queued");
+        assert!(slices.finish(&child));
+        assert!(slices.finish(&make_order(1, 100, Side::Buy, 50.0)));
+    }
+
     #[test]
     fn test_simulate_paper_fill_returns_filled_at_order_price() {
         let mut order = make_order(1, 100, Side::Buy, 50.0);
         order.price = Some(0.65);
         order.is_paper = true;
 
-        let result = simulate_paper_fill(&order);
+        let result = simulate_paper_fill(&order, 0);
 
         assert_eq!(result.status, OrderStatus::Filled);
         assert_eq!(result.filled_price, Some(0.65));
@@ -456,7 +1043,7 @@ mod tests {
         order.is_paper = true;
         order.reference_price = Some(0.62);
 
-        let result = simulate_paper_fill(&order);
+        let result = simulate_paper_fill(&order, 0);
 
         assert_eq!(result.status, OrderStatus::Filled);
         assert_eq!(result.filled_price, Some(0.62));
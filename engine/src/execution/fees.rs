@@ -9,6 +9,9 @@ use crate::proxy::HttpPool;
 
 const CACHE_TTL: Duration = Duration::from_secs(60);
 
+/// Taker fee rate simulations charge when the caller names none.
+pub const DEFAULT_FEE_BPS: u16 = 1000;
+
 // ---------------------------------------------------------------------------
 // CachedFee
 // ---------------------------------------------------------------------------
@@ -101,6 +104,44 @@ impl FeeCache {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Fee model
+// ---------------------------------------------------------------------------
+
+/// Taker fee in USDC for a fill of `shares` at `price`.
+///
+/// The CLOB charges `rate × min(price, 1 − price) × shares`, so fees peak at
+/// 50¢ and vanish at the 0/1 resolution payouts.
+pub fn taker_fee_usdc(fee_rate_bps: u16, price: f64, shares: f64) -> f64 {
+    if shares <= 0.0 || !(0.0..=1.0).contains(&price) {
+        return 0.0;
+    }
+    f64::from(fee_rate_bps) / 10_000.0 * price.min(1.0 - price) * shares
+}
+
+/// Entry plus exit taker fees for a position of `size_usdc` bought at
+/// `entry_price` and closed at `exit_price`. A resolution exit (0 or 1) is
+/// a redemption and costs nothing.
+pub fn round_trip_fee_usdc(
+    fee_rate_bps: u16,
+    entry_price: f64,
+    exit_price: f64,
+    size_usdc: f64,
+) -> f64 {
+    if entry_price <= 0.0 {
+        return 0.0;
+    }
+    let shares = size_usdc / entry_price;
+    taker_fee_usdc(fee_rate_bps, entry_price, shares)
+        + taker_fee_usdc(fee_rate_bps, exit_price, shares)
+}
+
+/// Win probability needed for a buy at `price` held to resolution to break
+/// even once the entry fee is paid.
+pub fn breakeven_win_rate(fee_rate_bps: u16, price: f64) -> f64 {
+    (price + taker_fee_usdc(fee_rate_bps, price, 1.0)).min(1.0)
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -157,4 +198,33 @@ mod tests {
             "entry should be expired after 120s (TTL is 60s)"
         );
     }
+
+    #[test]
+    fn test_taker_fee_peaks_at_half_and_vanishes_at_resolution() {
+        // 200 bps × 0.50 × 100 shares = 1.00 USDC
+        assert!((taker_fee_usdc(200, 0.50, 100.0) - 1.0).abs() < 1e-9);
+        // 200 bps × min(0.90, 0.10) × 100 shares = 0.20 USDC
+        assert!((taker_fee_usdc(200, 0.90, 100.0) - 0.2).abs() < 1e-9);
+        assert_eq!(taker_fee_usdc(200, 1.0, 100.0), 0.0);
+        assert_eq!(taker_fee_usdc(200, 0.0, 100.0), 0.0);
+        assert_eq!(taker_fee_usdc(0, 0.50, 100.0), 0.0);
+    }
+
+    #[test]
+    fn test_round_trip_fee_charges_entry_and_exit() {
+        // 50 USDC at 0.50 = 100 shares: entry 1.00 + exit at 0.60 (min 0.40) 0.80
+        let fees = round_trip_fee_usdc(200, 0.50, 0.60, 50.0);
+        assert!((fees - 1.8).abs() < 1e-9, "got {fees}");
+
+        // Held to resolution: only the entry fee applies.
+        let fees = round_trip_fee_usdc(200, 0.50, 1.0, 50.0);
+        assert!((fees - 1.0).abs() < 1e-9, "got {fees}");
+    }
+
+    #[test]
+    fn test_breakeven_win_rate_includes_entry_fee() {
+        assert!((breakeven_win_rate(0, 0.55) - 0.55).abs() < 1e-9);
+        // 0.55 + 0.02 × 0.45 = 0.559
+        assert!((breakeven_win_rate(200, 0.55) - 0.559).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,306 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use sqlx::PgPool;
+use tokio::sync::Mutex;
+use tracing::warn;
+use uuid::Uuid;
+
+use super::orders::ClobError;
+use super::queue::ExecutionQueue;
+use super::ExecutionOrder;
+use crate::storage::postgres::{self, DeadLetter, JournaledOrder};
+
+// ---------------------------------------------------------------------------
+// JournalState
+// ---------------------------------------------------------------------------
+
+/// Lifecycle of an order in the `execution_orders` table.
+///
+/// `queued` → `submitting` → `submitted` → `done`, or `expired` when the order
+/// outlived the configured max age before reaching the CLOB, or
+/// `dead_lettered` when its submission retries were exhausted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalState {
+    Queued,
+    Submitting,
+    Submitted,
+    Done,
+    Expired,
+    DeadLettered,
+}
+
+impl JournalState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Submitting => "submitting",
+            Self::Submitted => "submitted",
+            Self::Done => "done",
+            Self::Expired => "expired",
+            Self::DeadLettered => "dead_lettered",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "queued" => Some(Self::Queued),
+            "submitting" => Some(Self::Submitting),
+            "submitted" => Some(Self::Submitted),
+            "done" => Some(Self::Done),
+            "expired" => Some(Self::Expired),
+            "dead_lettered" => Some(Self::DeadLettered),
+            _ => None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ExecutionJournal
+// ---------------------------------------------------------------------------
+
+/// Persists queued and in-flight orders so they survive engine restarts.
+///
+/// The journal is keyed on `ExecutionOrder.id`. The EIP-712 hash of a signed
+/// order is journaled before it is posted, so a replay can ask the CLOB whether
+/// the post landed instead of re-signing with fresh fee and market data.
+pub struct ExecutionJournal {
+    db: PgPool,
+    max_age_secs: i64,
+}
+
+impl ExecutionJournal {
+    pub fn new(db: PgPool, max_age_secs: i64) -> Self {
+        Self { db, max_age_secs }
+    }
+
+    /// Journal the order, then push it into the in-memory queue.
+    ///
+    /// A journal failure is logged but never drops the order.
+    pub async fn enqueue(&self, queue: &Mutex<ExecutionQueue>, order: ExecutionOrder) {
+        if let Err(e) = postgres::insert_execution_order(&self.db, &order).await {
+            warn!(order_id = %order.id, error = %e, "execution_journal_insert_failed");
+        }
+        queue.lock().await.push(order);
+    }
+
+    /// Journal a child order now but only queue it after `delay`.
+    ///
+    /// A restart before the delay elapses replays the child straight away.
+    pub async fn enqueue_after(
+        &self,
+        queue: &Arc<Mutex<ExecutionQueue>>,
+        order: ExecutionOrder,
+        delay: Duration,
+    ) {
+        if delay.is_zero() {
+            return self.enqueue(queue, order).await;
+        }
+        if let Err(e) = postgres::insert_execution_order(&self.db, &order).await {
+            warn!(order_id = %order.id, error = %e, "execution_journal_insert_failed");
+        }
+        let queue = queue.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            queue.lock().await.push(order);
+        });
+    }
+
+    pub async fn mark(&self, order_id: Uuid, state: JournalState) {
+        self.update(order_id, state, None, None).await;
+    }
+
+    /// Record the hash of the order about to be posted; it is the CLOB order id.
+    pub async fn mark_signed(&self, order_id: Uuid, order_hash: &str, fee_bps: u16) {
+        self.update(
+            order_id,
+            JournalState::Submitting,
+            Some(order_hash),
+            Some(fee_bps),
+        )
+        .await;
+    }
+
+    pub async fn mark_submitted(&self, order_id: Uuid, polymarket_order_id: &str, fee_bps: u16) {
+        self.update(
+            order_id,
+            JournalState::Submitted,
+            Some(polymarket_order_id),
+            Some(fee_bps),
+        )
+        .await;
+    }
+
+    async fn update(
+        &self,
+        order_id: Uuid,
+        state: JournalState,
+        polymarket_order_id: Option<&str>,
+        fee_bps: Option<u16>,
+    ) {
+        if let Err(e) = postgres::update_execution_order_state(
+            &self.db,
+            order_id,
+            state.as_str(),
+            polymarket_order_id,
+            fee_bps,
+        )
+        .await
+        {
+            warn!(
+                %order_id,
+                state = state.as_str(),
+                error = %e,
+                "execution_journal_update_failed"
+            );
+        }
+    }
+
+    /// Park an order whose retries are exhausted so an operator can requeue it.
+    pub async fn dead_letter(&self, order: &ExecutionOrder, attempts: u32, error: &anyhow::Error) {
+        let reason = dead_letter_reason(error);
+        if let Err(e) =
+            postgres::insert_dead_letter(&self.db, order, reason, attempts, &format!("{error:#}"))
+                .await
+        {
+            warn!(order_id = %order.id, error = %e, "dead_letter_insert_failed");
+        }
+        self.mark(order.id, JournalState::DeadLettered).await;
+    }
+
+    /// Dead-lettered orders not yet requeued, newest first.
+    pub async fn dead_letters(&self, limit: i64) -> Result<Vec<DeadLetter>> {
+        postgres::load_dead_letters(&self.db, limit).await
+    }
+
+    /// Put a dead-lettered order back on the queue.
+    ///
+    /// The order keeps its id (and so its salt) but gets a fresh `created_at`,
+    /// otherwise it would be expired as soon as the executor popped it.
+    /// Returns `None` when no pending dead letter has that id.
+    pub async fn requeue_dead_letter(
+        &self,
+        queue: &Mutex<ExecutionQueue>,
+        dead_letter_id: i64,
+    ) -> Result<Option<ExecutionOrder>> {
+        let Some(mut order) = postgres::claim_dead_letter(&self.db, dead_letter_id).await? else {
+            return Ok(None);
+        };
+        order.created_at = chrono::Utc::now().timestamp();
+        postgres::requeue_execution_order(&self.db, &order).await?;
+        queue.lock().await.push(order.clone());
+        Ok(Some(order))
+    }
+
+    /// Orders that were queued, submitting or submitted when the engine stopped.
+    pub async fn load_unfinished(&self) -> Result<Vec<JournaledOrder>> {
+        postgres::load_unfinished_execution_orders(&self.db).await
+    }
+
+    pub fn is_expired(&self, order: &ExecutionOrder, now: i64) -> bool {
+        order_expired(order, now, self.max_age_secs)
+    }
+}
+
+/// Short label for why an order was dead-lettered: the CLOB rejection kind,
+/// or `network` when the request never got an answer.
+fn dead_letter_reason(error: &anyhow::Error) -> &'static str {
+    match error.downcast_ref::<ClobError>() {
+        Some(clob) => clob.rejection.as_str(),
+        None => "network",
+    }
+}
+
+/// An order is stale once it has waited longer than `max_age_secs` since creation.
+/// A non-positive max age disables expiry.
+pub fn order_expired(order: &ExecutionOrder, now: i64, max_age_secs: i64) -> bool {
+    max_age_secs > 0 && now - order.created_at > max_age_secs
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::{OrderPriority, Side};
+    use crate::strategy::{OrderType, Outcome};
+
+    fn make_order(created_at: i64) -> ExecutionOrder {
+        ExecutionOrder {
+            id: Uuid::new_v4(),
+            wallet_id: 1,
+            strategy_id: Some(2),
+            copy_relationship_id: None,
+            symbol: "btc-updown-15m-1700000000".to_string(),
+            token_id: "tok".to_string(),
+            side: Side::Buy,
+            outcome: Outcome::Up,
+            price: None,
+            reference_price: Some(0.55),
+            size_usdc: 10.0,
+            order_type: OrderType::Market,
+            priority: OrderPriority::StrategyMarket,
+            created_at,
+            leader_address: String::new(),
+            leader_tx_hash: String::new(),
+            is_paper: false,
+            market: None,
+            algo: None,
+            parent_id: None,
+            closes: None,
+        }
+    }
+
+    #[test]
+    fn test_journal_state_roundtrip() {
+        for state in [
+            JournalState::Queued,
+            JournalState::Submitting,
+            JournalState::Submitted,
+            JournalState::Done,
+            JournalState::Expired,
+            JournalState::DeadLettered,
+        ] {
+            assert_eq!(JournalState::parse(state.as_str()), Some(state));
+        }
+        assert_eq!(JournalState::parse("bogus"), None);
+    }
+
+    #[test]
+    fn test_order_expiry() {
+        let order = make_order(1_700_000_000);
+        assert!(!order_expired(&order, 1_700_000_060, 120));
+        assert!(order_expired(&order, 1_700_000_121, 120));
+        assert!(
+            !order_expired(&order, 1_800_000_000, 0),
+            "max age of 0 disables expiry"
+        );
+    }
+
+    #[test]
+    fn test_payload_roundtrip_preserves_salt_source() {
+        let order = make_order(1_700_000_000);
+        let payload = serde_json::to_value(&order).unwrap();
+        let restored: ExecutionOrder = serde_json::from_value(payload).unwrap();
+        assert_eq!(restored.id, order.id, "order id doubles as EIP-712 salt");
+        assert_eq!(restored.symbol, order.symbol);
+        assert_eq!(restored.reference_price, Some(0.55));
+    }
+
+    #[test]
+    fn test_dead_letter_reason() {
+        let clob = anyhow::Error::new(ClobError {
+            rejection: crate::execution::ClobRejection::RateLimited,
+            http_status: 429,
+            message: String::new(),
+        });
+        assert_eq!(dead_letter_reason(&clob), "rate_limited");
+        assert_eq!(
+            dead_letter_reason(&anyhow::anyhow!("connection refused")),
+            "network"
+        );
+    }
+}
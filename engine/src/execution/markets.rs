@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+use super::MarketMeta;
+use crate::proxy::HttpPool;
+
+const CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Tick size the CLOB uses when a market does not override it.
+pub const DEFAULT_TICK_SIZE: f64 = 0.01;
+
+// ---------------------------------------------------------------------------
+// CachedMeta
+// ---------------------------------------------------------------------------
+
+struct CachedMeta {
+    meta: MarketMeta,
+    fetched_at: Instant,
+}
+
+impl CachedMeta {
+    fn is_fresh(&self) -> bool {
+        self.fetched_at.elapsed() < CACHE_TTL
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MarketMetaCache
+// ---------------------------------------------------------------------------
+
+/// CLOB lookup of neg-risk and tick size for tokens outside market discovery
+/// (copy trades on arbitrary markets, orders journaled before discovery).
+pub struct MarketMetaCache {
+    cache: RwLock<HashMap<String, CachedMeta>>,
+    http: HttpPool,
+    clob_url: String,
+}
+
+#[derive(Deserialize)]
+struct NegRiskResponse {
+    neg_risk: bool,
+}
+
+#[derive(Deserialize)]
+struct TickSizeResponse {
+    minimum_tick_size: f64,
+}
+
+impl MarketMetaCache {
+    pub fn new(http: HttpPool, clob_url: &str) -> Self {
+        Self {
+            cache: RwLock::new(HashMap::new()),
+            http,
+            clob_url: clob_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub async fn get(&self, token_id: &str) -> Result<MarketMeta> {
+        {
+            let cache = self.cache.read().await;
+            if let Some(entry) = cache.get(token_id) {
+                if entry.is_fresh() {
+                    return Ok(entry.meta);
+                }
+            }
+        }
+
+        let neg_risk: NegRiskResponse = self
+            .http
+            .proxied()
+            .get(format!("{}/neg-risk?token_id={token_id}", self.clob_url))
+            .send()
+            .await
+            .context("neg-risk HTTP request failed")?
+            .json()
+            .await
+            .context("failed to parse neg-risk response")?;
+        let tick: TickSizeResponse = self
+            .http
+            .proxied()
+            .get(format!("{}/tick-size?token_id={token_id}", self.clob_url))
+            .send()
+            .await
+            .context("tick-size HTTP request failed")?
+            .json()
+            .await
+            .context("failed to parse tick-size response")?;
+
+        let meta = MarketMeta {
+            neg_risk: neg_risk.neg_risk,
+            tick_size: tick.minimum_tick_size,
+            min_order_size: 0.0,
+        };
+        self.cache.write().await.insert(
+            token_id.to_string(),
+            CachedMeta {
+                meta,
+                fetched_at: Instant::now(),
+            },
+        );
+        Ok(meta)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::Query;
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_fetches_and_caches_meta_by_token() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let neg_hits = hits.clone();
+        let app = Router::new()
+            .route(
+                "/neg-risk",
+                get(move |Query(q): Query<HashMap<String, String>>| {
+                    neg_hits.fetch_add(1, Ordering::SeqCst);
+                    async move { Json(serde_json::json!({"neg_risk": q["token_id"] == "111"})) }
+                }),
+            )
+            .route(
+                "/tick-size",
+                get(|| async { Json(serde_json::json!({"minimum_tick_size": 0.001})) }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let http = HttpPool::new(&[], Duration::from_secs(5)).unwrap();
+        let cache = MarketMetaCache::new(http, &format!("http://{addr}"));
+
+        let meta = cache.get("111").await.unwrap();
+        assert!(meta.neg_risk);
+        assert!((meta.tick_size - 0.001).abs() < f64::EPSILON);
+        assert!(!cache.get("222").await.unwrap().neg_risk);
+
+        cache.get("111").await.unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 2, "second lookup is cached");
+    }
+}
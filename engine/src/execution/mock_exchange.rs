@@ -0,0 +1,1472 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use alloy::primitives::{keccak256, Address, Signature, U256};
+use alloy::sol_types::SolStruct;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::engine::general_purpose::URL_SAFE as BASE64_URL;
+use base64::Engine as _;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::credentials::ApiCredentials;
+use super::orders::{order_domain, sign_hmac, BuilderCredentials, ClobOrder};
+use super::safe_tx::safe_tx_hash;
+use super::Side;
+use crate::network::NetworkProfile;
+
+/// USDC and conditional tokens both use 6 decimals.
+const UNIT: f64 = 1_000_000.0;
+const EPSILON: f64 = 1e-9;
+
+type Shared = Arc<Mutex<MockState>>;
+type Params = Query<HashMap<String, String>>;
+
+// ---------------------------------------------------------------------------
+// Public types
+// ---------------------------------------------------------------------------
+
+/// Endpoints whose next responses can be scripted to fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MockEndpoint {
+    Order,
+    FeeRate,
+    Submit,
+}
+
+/// A Safe transaction accepted by the mock relayer.
+#[derive(Debug, Clone)]
+pub struct RelayedTx {
+    pub id: String,
+    pub payload: Value,
+    pub state: String,
+}
+
+// ---------------------------------------------------------------------------
+// State
+// ---------------------------------------------------------------------------
+
+struct Account {
+    owner: Address,
+    usdc: f64,
+    tokens: HashMap<String, f64>,
+}
+
+/// Resting liquidity: scripted with [`MockExchange::add_liquidity`] (no
+/// maker) or the unfilled rest of a GTC order.
+struct Level {
+    maker: Option<Address>,
+    order_id: Option<String>,
+    price: f64,
+    shares: f64,
+}
+
+#[derive(Default)]
+struct Book {
+    /// Best (highest) first.
+    bids: Vec<Level>,
+    /// Best (lowest) first.
+    asks: Vec<Level>,
+}
+
+struct PlacedOrder {
+    status: &'static str,
+    avg_price: Option<f64>,
+}
+
+struct MockState {
+    network: NetworkProfile,
+    builder: BuilderCredentials,
+    fee_rate_bps: u16,
+    tick_size: f64,
+    neg_risk: HashSet<String>,
+    /// Issued L2 keys by api key.
+    keys: HashMap<String, (Address, ApiCredentials)>,
+    /// Balances by Safe address.
+    accounts: HashMap<Address, Account>,
+    books: HashMap<String, Book>,
+    orders: HashMap<String, PlacedOrder>,
+    failures: HashMap<MockEndpoint, VecDeque<(u16, String)>>,
+    /// Next Safe nonce by owner EOA.
+    safe_nonces: HashMap<Address, u64>,
+    /// Executed transactions by Safe, as its on-chain nonce.
+    chain_nonces: HashMap<Address, u64>,
+    deployed: HashSet<Address>,
+    relayed: Vec<RelayedTx>,
+    rejections: Vec<String>,
+}
+
+impl MockState {
+    fn scripted_failure(&mut self, endpoint: MockEndpoint) -> Option<Response> {
+        let (status, message) = self.failures.get_mut(&endpoint)?.pop_front()?;
+        Some(self.reject(status, &message))
+    }
+
+    fn reject(&mut self, status: u16, message: &str) -> Response {
+        tracing::warn!(status, message, "mock_exchange_rejected");
+        self.rejections.push(message.to_string());
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_REQUEST);
+        (status, Json(json!({ "error": message }))).into_response()
+    }
+
+    /// Check L2 `POLY_*` headers; returns the key's owner.
+    fn verify_l2(
+        &self,
+        headers: &HeaderMap,
+        method: &str,
+        path: &str,
+        body: &str,
+    ) -> Result<Address, String> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("")
+        };
+        let Some((owner, creds)) = self.keys.get(header("POLY_API_KEY")) else {
+            return Err("Unauthorized/Invalid api key".into());
+        };
+        if header("POLY_PASSPHRASE") != creds.passphrase
+            || !header("POLY_ADDRESS").eq_ignore_ascii_case(&format!("{owner:?}"))
+        {
+            return Err("Unauthorized/Invalid api key".into());
+        }
+        let expected = sign_hmac(&creds.secret, method, path, header("POLY_TIMESTAMP"), body)
+            .map_err(|e| e.to_string())?;
+        if header("POLY_SIGNATURE") != expected {
+            return Err("Unauthorized: invalid L2 signature".into());
+        }
+        Ok(*owner)
+    }
+
+    /// Check builder attribution headers.
+    fn verify_builder(
+        &self,
+        headers: &HeaderMap,
+        method: &str,
+        path: &str,
+        body: &str,
+    ) -> Result<(), String> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("")
+        };
+        if header("POLY_BUILDER_API_KEY") != self.builder.api_key
+            || header("POLY_BUILDER_PASSPHRASE") != self.builder.passphrase
+        {
+            return Err("Unauthorized: unknown builder".into());
+        }
+        let expected = sign_hmac(
+            &self.builder.secret,
+            method,
+            path,
+            header("POLY_BUILDER_TIMESTAMP"),
+            body,
+        )
+        .map_err(|e| e.to_string())?;
+        if header("POLY_BUILDER_SIGNATURE") != expected {
+            return Err("Unauthorized: invalid builder signature".into());
+        }
+        Ok(())
+    }
+
+    fn issue_key(&mut self, owner: Address, nonce: u64) -> ApiCredentials {
+        let seed = keccak256(format!("{owner:?}/{nonce}"));
+        let creds = ApiCredentials {
+            api_key: format!("mock-{}", hex::encode(&seed[..8])),
+            secret: BASE64_URL.encode(seed),
+            passphrase: hex::encode(&seed[8..16]),
+        };
+        self.keys
+            .insert(creds.api_key.clone(), (owner, creds.clone()));
+        creds
+    }
+
+    /// Validate, match and settle a signed order. Returns the order id and status.
+    fn place_order(
+        &mut self,
+        owner: Address,
+        payload: OrderPayload,
+    ) -> Result<(String, &'static str), (u16, String)> {
+        let bad = |message: &str| (400, message.to_string());
+        let o = &payload.order;
+        if o.signer != owner {
+            return Err((
+                401,
+                "Unauthorized: order signer does not own the API key".into(),
+            ));
+        }
+
+        // The signature must hold under the exchange the market really settles on.
+        let neg_risk = self.neg_risk.contains(&o.token_id);
+        let side = match o.side.as_str() {
+            "0" => Side::Buy,
+            "1" => Side::Sell,
+            _ => return Err(bad("invalid order side")),
+        };
+        let clob_order = ClobOrder {
+            salt: uint(&o.salt)?,
+            maker: o.maker,
+            signer: o.signer,
+            taker: o.taker,
+            tokenId: uint(&o.token_id)?,
+            makerAmount: uint(&o.maker_amount)?,
+            takerAmount: uint(&o.taker_amount)?,
+            expiration: uint(&o.expiration)?,
+            nonce: uint(&o.nonce)?,
+            feeRateBps: uint(&o.fee_rate_bps)?,
+            side: if side == Side::Buy { 0 } else { 1 },
+            signatureType: o
+                .signature_type
+                .parse()
+                .map_err(|_| bad("invalid signature type"))?,
+        };
+        let hash = clob_order.eip712_signing_hash(&order_domain(&self.network, neg_risk));
+        let recovered = hex::decode(o.signature.trim_start_matches("0x"))
+            .ok()
+            .and_then(|bytes| Signature::try_from(bytes.as_slice()).ok())
+            .and_then(|sig| sig.recover_address_from_prehash(&hash).ok());
+        if recovered != Some(o.signer) {
+            return Err(bad("invalid signature"));
+        }
+        if clob_order.feeRateBps != U256::from(self.fee_rate_bps) {
+            return Err(bad("invalid fee rate"));
+        }
+        let order_id = format!("{hash:?}");
+        if self.orders.contains_key(&order_id) {
+            return Err(bad("order already exists"));
+        }
+
+        let maker_units = o.maker_amount.parse::<f64>().unwrap_or(0.0) / UNIT;
+        let taker_units = o.taker_amount.parse::<f64>().unwrap_or(0.0) / UNIT;
+        let (usdc, shares) = match side {
+            Side::Buy => (maker_units, taker_units),
+            Side::Sell => (taker_units, maker_units),
+        };
+        if shares <= 0.0 {
+            return Err(bad("invalid order amounts"));
+        }
+        let price = usdc / shares;
+        let ticks = price / self.tick_size;
+        if (ticks - ticks.round()).abs() > 1e-6 {
+            return Err(bad("price breaks minimum tick size rule"));
+        }
+        let funded = self.accounts.get(&o.maker).is_some_and(|a| match side {
+            Side::Buy => a.usdc + EPSILON >= usdc,
+            Side::Sell => a.tokens.get(&o.token_id).copied().unwrap_or(0.0) + EPSILON >= shares,
+        });
+        if !funded {
+            return Err(bad("not enough balance / allowance"));
+        }
+
+        let book = self.books.entry(o.token_id.clone()).or_default();
+        let (opposite, resting) = match side {
+            Side::Buy => (&mut book.asks, &mut book.bids),
+            Side::Sell => (&mut book.bids, &mut book.asks),
+        };
+        let crosses = |level: &Level| match side {
+            Side::Buy => level.price <= price + EPSILON,
+            Side::Sell => level.price + EPSILON >= price,
+        };
+        let available: f64 = opposite
+            .iter()
+            .take_while(|l| crosses(l))
+            .map(|l| l.shares)
+            .sum();
+        if payload.order_type == "FOK" && available + EPSILON < shares {
+            return Err(bad(
+                "order couldn't be fully filled. FOK orders are fully filled or killed.",
+            ));
+        }
+
+        let mut fills = Vec::new();
+        let mut remaining = shares;
+        for level in opposite.iter_mut() {
+            if remaining <= EPSILON || !crosses(level) {
+                break;
+            }
+            let qty = remaining.min(level.shares);
+            level.shares -= qty;
+            remaining -= qty;
+            fills.push((level.maker, level.order_id.clone(), level.price, qty));
+        }
+        opposite.retain(|l| l.shares > EPSILON);
+        let rests = remaining > EPSILON && payload.order_type != "FOK";
+        if rests {
+            resting.push(Level {
+                maker: Some(o.maker),
+                order_id: Some(order_id.clone()),
+                price,
+                shares: remaining,
+            });
+            match side {
+                Side::Buy => resting.sort_by(|a, b| b.price.total_cmp(&a.price)),
+                Side::Sell => resting.sort_by(|a, b| a.price.total_cmp(&b.price)),
+            }
+        }
+        let still_resting: HashSet<String> =
+            opposite.iter().filter_map(|l| l.order_id.clone()).collect();
+
+        // Settle: the taker trades against each maker at the maker's price.
+        let direction = if side == Side::Buy { 1.0 } else { -1.0 };
+        let mut notional = 0.0;
+        let mut filled = 0.0;
+        for (maker, maker_order, fill_price, qty) in fills {
+            notional += fill_price * qty;
+            filled += qty;
+            if let Some(maker) = maker {
+                self.credit(
+                    maker,
+                    &o.token_id,
+                    direction * fill_price * qty,
+                    -direction * qty,
+                );
+            }
+            if let Some(id) = maker_order.filter(|id| !still_resting.contains(id)) {
+                if let Some(placed) = self.orders.get_mut(&id) {
+                    placed.status = "matched";
+                    placed.avg_price.get_or_insert(fill_price);
+                }
+            }
+        }
+        self.credit(
+            o.maker,
+            &o.token_id,
+            -direction * notional,
+            direction * filled,
+        );
+
+        let status = if remaining <= EPSILON {
+            "matched"
+        } else if rests {
+            "live"
+        } else {
+            "cancelled"
+        };
+        self.orders.insert(
+            order_id.clone(),
+            PlacedOrder {
+                status,
+                avg_price: (filled > 0.0).then(|| notional / filled),
+            },
+        );
+        Ok((order_id, status))
+    }
+
+    /// Open (or top up) the Safe `safe` owned by `owner` with `usdc`.
+    fn fund(&mut self, owner: Address, safe: Address, usdc: f64) {
+        self.accounts
+            .entry(safe)
+            .or_insert_with(|| Account {
+                owner,
+                usdc: 0.0,
+                tokens: HashMap::new(),
+            })
+            .usdc += usdc;
+    }
+
+    /// Rest `shares` from an outside counterparty on `side` of the book.
+    fn add_liquidity(&mut self, token_id: &str, side: Side, price: f64, shares: f64) {
+        let book = self.books.entry(token_id.to_string()).or_default();
+        let level = Level {
+            maker: None,
+            order_id: None,
+            price,
+            shares,
+        };
+        match side {
+            Side::Buy => {
+                book.bids.push(level);
+                book.bids.sort_by(|a, b| b.price.total_cmp(&a.price));
+            }
+            Side::Sell => {
+                book.asks.push(level);
+                book.asks.sort_by(|a, b| a.price.total_cmp(&b.price));
+            }
+        }
+    }
+
+    /// Answer the next request to `endpoint` with `status` and `message`.
+    fn fail_next(&mut self, endpoint: MockEndpoint, status: u16, message: &str) {
+        self.failures
+            .entry(endpoint)
+            .or_default()
+            .push_back((status, message.to_string()));
+    }
+
+    fn credit(&mut self, safe: Address, token_id: &str, usdc: f64, shares: f64) {
+        if let Some(account) = self.accounts.get_mut(&safe) {
+            account.usdc += usdc;
+            *account.tokens.entry(token_id.to_string()).or_default() += shares;
+        }
+    }
+
+    /// Validate and record a relayer submission; returns the transaction id.
+    fn relay(&mut self, payload: Value) -> Result<String, String> {
+        let field = |name: &str| payload[name].as_str().unwrap_or_default().to_string();
+        let address = |name: &str| {
+            field(name)
+                .parse::<Address>()
+                .map_err(|_| format!("invalid {name}"))
+        };
+        let from = address("from")?;
+        let proxy_wallet = address("proxyWallet")?;
+        match field("type").as_str() {
+            "SAFE-CREATE" => {
+                self.deployed.insert(proxy_wallet);
+            }
+            "SAFE" => {
+                let nonce: u64 = field("nonce").parse().map_err(|_| "invalid nonce")?;
+                let expected = self.safe_nonces.get(&from).copied().unwrap_or(0);
+                if nonce != expected {
+                    return Err(format!("invalid nonce {nonce}, expected {expected}"));
+                }
+                let data = hex::decode(field("data").trim_start_matches("0x"))
+                    .map_err(|_| "invalid data")?;
+                let hash = safe_tx_hash(
+                    self.network.chain_id,
+                    proxy_wallet,
+                    address("to")?,
+                    &data,
+                    nonce,
+                );
+                // `eth_sign` signatures carry v + 4.
+                let mut sig = hex::decode(field("signature").trim_start_matches("0x"))
+                    .map_err(|_| "invalid signature")?;
+                if sig.len() != 65 || sig[64] < 31 {
+                    return Err("invalid signature".into());
+                }
+                sig[64] -= 4;
+                let signer = Signature::try_from(sig.as_slice())
+                    .ok()
+                    .and_then(|s| s.recover_address_from_msg(hash).ok());
+                if signer != Some(from) {
+                    return Err("invalid signature".into());
+                }
+                self.safe_nonces.insert(from, nonce + 1);
+                self.chain_nonces.insert(proxy_wallet, nonce + 1);
+            }
+            other => return Err(format!("unsupported transaction type {other:?}")),
+        }
+        let id = format!("tx-{}", self.relayed.len() + 1);
+        self.relayed.push(RelayedTx {
+            id: id.clone(),
+            payload,
+            state: "STATE_CONFIRMED".into(),
+        });
+        Ok(id)
+    }
+}
+
+fn uint(value: &str) -> Result<U256, (u16, String)> {
+    U256::from_str_radix(value, 10).map_err(|_| (400, format!("invalid integer {value:?}")))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OrderPayload {
+    order: SignedOrder,
+    order_type: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedOrder {
+    salt: String,
+    maker: Address,
+    signer: Address,
+    taker: Address,
+    token_id: String,
+    maker_amount: String,
+    taker_amount: String,
+    expiration: String,
+    nonce: String,
+    fee_rate_bps: String,
+    side: String,
+    signature_type: String,
+    signature: String,
+}
+
+// ---------------------------------------------------------------------------
+// MockExchange
+// ---------------------------------------------------------------------------
+
+fn shared_state(network: &NetworkProfile, builder: BuilderCredentials) -> Shared {
+    Arc::new(Mutex::new(MockState {
+        network: network.clone(),
+        builder,
+        fee_rate_bps: 0,
+        tick_size: 0.01,
+        neg_risk: HashSet::new(),
+        keys: HashMap::new(),
+        accounts: HashMap::new(),
+        books: HashMap::new(),
+        orders: HashMap::new(),
+        failures: HashMap::new(),
+        safe_nonces: HashMap::new(),
+        chain_nonces: HashMap::new(),
+        deployed: HashSet::new(),
+        relayed: Vec::new(),
+        rejections: Vec::new(),
+    }))
+}
+
+fn router(state: Shared) -> Router {
+    Router::new()
+        .route("/fee-rate", get(fee_rate))
+        .route("/neg-risk", get(neg_risk))
+        .route("/tick-size", get(tick_size))
+        .route("/auth/derive-api-key", get(issue_api_key))
+        .route("/auth/api-key", post(issue_api_key).delete(delete_api_key))
+        .route("/balance-allowance", get(balance_allowance))
+        .route("/positions", get(positions))
+        .route("/order", post(post_order))
+        .route("/data/order/{id}", get(order_status))
+        .route("/nonce", get(relayer_nonce))
+        .route("/deployed", get(deployed))
+        .route("/submit", post(submit))
+        .route("/transaction", get(transaction))
+        .route("/", post(rpc))
+        .route("/mock/fund", post(script_fund))
+        .route("/mock/liquidity", post(script_liquidity))
+        .route("/mock/fail", post(script_failure))
+        .route("/mock/market", post(script_market))
+        .with_state(state)
+}
+
+/// Serve the mock on the local ports `network` points its CLOB, data API and
+/// relayer at, until the process exits. Market data (gamma and the CLOB
+/// websocket) stays live. Balances, liquidity and failures are scripted over
+/// `POST /mock/*`.
+pub async fn serve(network: &NetworkProfile, builder: BuilderCredentials) -> anyhow::Result<()> {
+    let mut addrs = Vec::new();
+    for url in [
+        &network.clob_api_url,
+        &network.data_api_url,
+        &network.relayer_url,
+    ] {
+        let url = reqwest::Url::parse(url)?;
+        let host = url.host_str().unwrap_or_default();
+        if host != "localhost" && host != "127.0.0.1" {
+            anyhow::bail!("mock exchange only serves localhost endpoints, not {url}");
+        }
+        let addr = format!("{host}:{}", url.port_or_known_default().unwrap_or(80));
+        if !addrs.contains(&addr) {
+            addrs.push(addr);
+        }
+    }
+    let app = router(shared_state(network, builder));
+    let mut servers = tokio::task::JoinSet::new();
+    for addr in addrs {
+        let listener = tokio::net::TcpListener::bind(&addr).await?;
+        tracing::info!(%addr, network = %network.name, "mock_exchange_listening");
+        let app = app.clone();
+        servers.spawn(async move { axum::serve(listener, app).await });
+    }
+    while let Some(result) = servers.join_next().await {
+        result??;
+    }
+    Ok(())
+}
+
+/// In-process Polymarket CLOB, data API and builder relayer for end-to-end
+/// execution tests.
+///
+/// Orders must carry valid L2 and builder HMAC headers and an EIP-712
+/// signature for the configured chain and exchange; they then match against a
+/// simple price-time book and move the Safe's balances. Relayer submissions
+/// have their Safe signature and nonce checked and confirm immediately.
+#[cfg(test)]
+pub struct MockExchange {
+    pub url: String,
+    state: Shared,
+}
+
+#[cfg(test)]
+impl MockExchange {
+    /// Serve on an ephemeral local port, expecting orders signed for
+    /// `network` and attributed to `builder`.
+    pub async fn start(network: &NetworkProfile, builder: BuilderCredentials) -> Self {
+        let state = shared_state(network, builder);
+        let app = router(state.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        Self {
+            url: format!("http://{addr}"),
+            state,
+        }
+    }
+
+    /// The configured network with every API pointed at this mock.
+    pub fn network(&self) -> NetworkProfile {
+        NetworkProfile {
+            clob_api_url: self.url.clone(),
+            clob_ws_url: format!("{}/ws/market", self.url.replace("http", "ws")),
+            data_api_url: self.url.clone(),
+            gamma_api_url: self.url.clone(),
+            relayer_url: self.url.clone(),
+            rpc_url: self.url.clone(),
+            ..self.lock().network.clone()
+        }
+    }
+
+    pub fn set_fee_rate(&self, bps: u16) {
+        self.lock().fee_rate_bps = bps;
+    }
+
+    pub fn set_neg_risk(&self, token_id: &str) {
+        self.lock().neg_risk.insert(token_id.to_string());
+    }
+
+    pub fn fund(&self, owner: Address, safe: Address, usdc: f64) {
+        self.lock().fund(owner, safe, usdc);
+    }
+
+    pub fn add_liquidity(&self, token_id: &str, side: Side, price: f64, shares: f64) {
+        self.lock().add_liquidity(token_id, side, price, shares);
+    }
+
+    pub fn fail_next(&self, endpoint: MockEndpoint, status: u16, message: &str) {
+        self.lock().fail_next(endpoint, status, message);
+    }
+
+    pub fn usdc(&self, safe: Address) -> f64 {
+        self.lock().accounts.get(&safe).map_or(0.0, |a| a.usdc)
+    }
+
+    pub fn shares(&self, safe: Address, token_id: &str) -> f64 {
+        self.lock()
+            .accounts
+            .get(&safe)
+            .and_then(|a| a.tokens.get(token_id).copied())
+            .unwrap_or(0.0)
+    }
+
+    pub fn relayed(&self) -> Vec<RelayedTx> {
+        self.lock().relayed.clone()
+    }
+
+    /// Error messages of every rejected request, oldest first.
+    pub fn rejections(&self) -> Vec<String> {
+        self.lock().rejections.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CLOB handlers
+// ---------------------------------------------------------------------------
+
+async fn fee_rate(State(state): State<Shared>) -> Response {
+    let mut state = state.lock().unwrap();
+    if let Some(failure) = state.scripted_failure(MockEndpoint::FeeRate) {
+        return failure;
+    }
+    Json(json!({ "fee_rate_bps": state.fee_rate_bps })).into_response()
+}
+
+async fn neg_risk(State(state): State<Shared>, Query(q): Params) -> Json<Value> {
+    let token_id = q.get("token_id").cloned().unwrap_or_default();
+    Json(json!({ "neg_risk": state.lock().unwrap().neg_risk.contains(&token_id) }))
+}
+
+async fn tick_size(State(state): State<Shared>) -> Json<Value> {
+    Json(json!({ "minimum_tick_size": state.lock().unwrap().tick_size }))
+}
+
+/// Derive and create both hand out the key for `(POLY_ADDRESS, POLY_NONCE)`.
+async fn issue_api_key(State(state): State<Shared>, headers: HeaderMap) -> Response {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let owner = header("POLY_ADDRESS").and_then(|v| v.parse::<Address>().ok());
+    let nonce = header("POLY_NONCE").and_then(|v| v.parse::<u64>().ok());
+    let mut state = state.lock().unwrap();
+    match (owner, nonce) {
+        (Some(owner), Some(nonce)) => {
+            let creds = state.issue_key(owner, nonce);
+            Json(json!({
+                "apiKey": creds.api_key,
+                "secret": creds.secret,
+                "passphrase": creds.passphrase
+            }))
+            .into_response()
+        }
+        _ => state.reject(401, "Unauthorized: missing L1 headers"),
+    }
+}
+
+async fn delete_api_key(State(state): State<Shared>, headers: HeaderMap) -> Response {
+    let mut state = state.lock().unwrap();
+    if let Err(message) = state.verify_l2(&headers, "DELETE", "/auth/api-key", "") {
+        return state.reject(401, &message);
+    }
+    let key = headers
+        .get("POLY_API_KEY")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    state.keys.remove(key);
+    Json(json!({})).into_response()
+}
+
+async fn balance_allowance(State(state): State<Shared>, headers: HeaderMap) -> Response {
+    let mut state = state.lock().unwrap();
+    let owner = match state.verify_l2(&headers, "GET", "/balance-allowance", "") {
+        Ok(owner) => owner,
+        Err(message) => return state.reject(401, &message),
+    };
+    let usdc = state
+        .accounts
+        .values()
+        .find(|a| a.owner == owner)
+        .map_or(0.0, |a| a.usdc);
+    let units = ((usdc * UNIT).round() as u64).to_string();
+    let allowances = json!({
+        format!("{:?}", state.network.ctf_exchange): units,
+        format!("{:?}", state.network.neg_risk_exchange): units,
+    });
+    Json(json!({ "balance": units, "allowances": allowances })).into_response()
+}
+
+async fn post_order(State(state): State<Shared>, headers: HeaderMap, body: String) -> Response {
+    let mut state = state.lock().unwrap();
+    if let Some(failure) = state.scripted_failure(MockEndpoint::Order) {
+        return failure;
+    }
+    let owner = match state
+        .verify_l2(&headers, "POST", "/order", &body)
+        .and_then(|owner| {
+            state.verify_builder(&headers, "POST", "/order", &body)?;
+            Ok(owner)
+        }) {
+        Ok(owner) => owner,
+        Err(message) => return state.reject(401, &message),
+    };
+    let payload: OrderPayload = match serde_json::from_str(&body) {
+        Ok(payload) => payload,
+        Err(e) => return state.reject(400, &format!("invalid order payload: {e}")),
+    };
+    match state.place_order(owner, payload) {
+        Ok((order_id, status)) => Json(json!({
+            "success": true,
+            "orderID": order_id,
+            "status": status,
+            "errorMsg": ""
+        }))
+        .into_response(),
+        Err((status, message)) => state.reject(status, &message),
+    }
+}
+
+async fn order_status(State(state): State<Shared>, Path(id): Path<String>) -> Response {
+    let state = state.lock().unwrap();
+    let Some(order) = state.orders.get(&id) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let trades: Vec<Value> = order
+        .avg_price
+        .map(|p| json!({ "price": p.to_string() }))
+        .into_iter()
+        .collect();
+    Json(json!({ "status": order.status, "associate_trades": trades })).into_response()
+}
+
+// ---------------------------------------------------------------------------
+// Data API handlers
+// ---------------------------------------------------------------------------
+
+async fn positions(State(state): State<Shared>, Query(q): Params) -> Json<Value> {
+    let state = state.lock().unwrap();
+    let user = q.get("user").and_then(|u| u.parse::<Address>().ok());
+    let positions: Vec<Value> = user
+        .and_then(|safe| state.accounts.get(&safe))
+        .map(|a| {
+            a.tokens
+                .iter()
+                .filter(|(_, size)| **size > EPSILON)
+                .map(|(asset, size)| json!({ "asset": asset, "size": size }))
+                .collect()
+        })
+        .unwrap_or_default();
+    Json(Value::Array(positions))
+}
+
+// ---------------------------------------------------------------------------
+// Relayer handlers
+// ---------------------------------------------------------------------------
+
+async fn relayer_nonce(State(state): State<Shared>, Query(q): Params) -> Json<Value> {
+    let owner = q.get("address").and_then(|a| a.parse::<Address>().ok());
+    let state = state.lock().unwrap();
+    let nonce = owner
+        .and_then(|o| state.safe_nonces.get(&o).copied())
+        .unwrap_or(0);
+    Json(json!({ "nonce": nonce.to_string() }))
+}
+
+async fn deployed(State(state): State<Shared>, Query(q): Params) -> Json<Value> {
+    let safe = q.get("address").and_then(|a| a.parse::<Address>().ok());
+    let deployed = safe.is_some_and(|s| state.lock().unwrap().deployed.contains(&s));
+    Json(json!({ "deployed": deployed }))
+}
+
+async fn submit(State(state): State<Shared>, headers: HeaderMap, body: String) -> Response {
+    let mut state = state.lock().unwrap();
+    if let Some(failure) = state.scripted_failure(MockEndpoint::Submit) {
+        return failure;
+    }
+    if let Err(message) = state.verify_builder(&headers, "POST", "/submit", &body) {
+        return state.reject(401, &message);
+    }
+    let result = serde_json::from_str(&body)
+        .map_err(|e| format!("invalid payload: {e}"))
+        .and_then(|payload| state.relay(payload));
+    match result {
+        Ok(id) => Json(json!({ "transactionID": id })).into_response(),
+        Err(message) => state.reject(400, &message),
+    }
+}
+
+async fn transaction(State(state): State<Shared>, Query(q): Params) -> Json<Value> {
+    let id = q.get("id").cloned().unwrap_or_default();
+    let state = state.lock().unwrap();
+    let txs: Vec<Value> = state
+        .relayed
+        .iter()
+        .filter(|tx| tx.id == id)
+        .map(|tx| {
+            json!({
+                "transactionID": tx.id,
+                "transactionHash": format!("{:?}", keccak256(tx.id.as_bytes())),
+                "state": tx.state,
+                "proxyAddress": tx.payload["proxyWallet"],
+            })
+        })
+        .collect();
+    Json(Value::Array(txs))
+}
+
+// ---------------------------------------------------------------------------
+// Chain RPC handlers
+// ---------------------------------------------------------------------------
+
+/// `eth_call` of a Safe's `nonce()`: every relayed transaction confirms at once.
+async fn rpc(State(state): State<Shared>, Json(request): Json<Value>) -> Json<Value> {
+    let safe = request["params"][0]["to"]
+        .as_str()
+        .and_then(|to| to.parse::<Address>().ok());
+    let nonce = safe
+        .and_then(|safe| state.lock().unwrap().chain_nonces.get(&safe).copied())
+        .unwrap_or(0);
+    Json(json!({
+        "jsonrpc": "2.0",
+        "id": request["id"],
+        "result": format!("0x{nonce:064x}"),
+    }))
+}
+
+// ---------------------------------------------------------------------------
+// Scripting handlers
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+struct FundScript {
+    owner: Address,
+    safe: Address,
+    usdc: f64,
+}
+
+async fn script_fund(State(state): State<Shared>, Json(script): Json<FundScript>) -> StatusCode {
+    state
+        .lock()
+        .unwrap()
+        .fund(script.owner, script.safe, script.usdc);
+    StatusCode::NO_CONTENT
+}
+
+#[derive(Deserialize)]
+struct LiquidityScript {
+    token_id: String,
+    side: Side,
+    price: f64,
+    shares: f64,
+}
+
+async fn script_liquidity(
+    State(state): State<Shared>,
+    Json(script): Json<LiquidityScript>,
+) -> StatusCode {
+    state
+        .lock()
+        .unwrap()
+        .add_liquidity(&script.token_id, script.side, script.price, script.shares);
+    StatusCode::NO_CONTENT
+}
+
+#[derive(Deserialize)]
+struct FailureScript {
+    endpoint: MockEndpoint,
+    status: u16,
+    message: String,
+}
+
+async fn script_failure(
+    State(state): State<Shared>,
+    Json(script): Json<FailureScript>,
+) -> StatusCode {
+    state
+        .lock()
+        .unwrap()
+        .fail_next(script.endpoint, script.status, &script.message);
+    StatusCode::NO_CONTENT
+}
+
+#[derive(Deserialize)]
+struct MarketScript {
+    #[serde(default)]
+    fee_rate_bps: Option<u16>,
+    #[serde(default)]
+    neg_risk_token_id: Option<String>,
+}
+
+async fn script_market(
+    State(state): State<Shared>,
+    Json(script): Json<MarketScript>,
+) -> StatusCode {
+    let mut state = state.lock().unwrap();
+    if let Some(bps) = script.fee_rate_bps {
+        state.fee_rate_bps = bps;
+    }
+    if let Some(token_id) = script.neg_risk_token_id {
+        state.neg_risk.insert(token_id);
+    }
+    StatusCode::NO_CONTENT
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use alloy::signers::local::PrivateKeySigner;
+    use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+    use tokio::sync::{mpsc, Mutex as AsyncMutex, RwLock};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::execution::balances::BalanceService;
+    use crate::execution::credentials::CredentialManager;
+    use crate::execution::fees::FeeCache;
+    use crate::execution::journal::ExecutionJournal;
+    use crate::execution::markets::MarketMetaCache;
+    use crate::execution::orders::{ClobError, OrderSubmitter};
+    use crate::execution::queue::ExecutionQueue;
+    use crate::execution::relayer::RelayerClient;
+    use crate::execution::retry::RetryPolicy;
+    use crate::execution::risk::{RiskEngine, RiskLimits};
+    use crate::execution::safe_tx::{safe_tx_status, SafeCall, SafeTxState};
+    use crate::execution::wallet::WalletKeyStore;
+    use crate::execution::{executor, ClobRejection, ExecutionOrder, OrderPriority, OrderStatus};
+    use crate::fetcher::models::ActiveMarket;
+    use crate::fetcher::websocket::OrderBookCache;
+    use crate::proxy::HttpPool;
+    use crate::strategy::engine::evaluate_assignment;
+    use crate::strategy::registry::{activate, AssignmentRegistry};
+    use crate::strategy::test_utils::test_tick;
+    use crate::strategy::{OrderType, Outcome};
+    use crate::tasks::execution_tasks::signal_to_queue;
+
+    const TOKEN: &str = "1234567890";
+
+    fn builder() -> BuilderCredentials {
+        BuilderCredentials {
+            api_key: "builder-key".into(),
+            secret: BASE64_URL.encode(b"builder-secret"),
+            passphrase: "builder-pass".into(),
+        }
+    }
+
+    /// Everything an order needs on the engine side, wired to the mock.
+    struct Harness {
+        mock: MockExchange,
+        network: Arc<NetworkProfile>,
+        http: HttpPool,
+        db: sqlx::PgPool,
+        wallet_keys: Arc<WalletKeyStore>,
+        api_keys: Arc<CredentialManager>,
+        safe: Address,
+    }
+
+    impl Harness {
+        async fn start() -> Self {
+            let mock = MockExchange::start(&NetworkProfile::local(), builder()).await;
+            let network = Arc::new(mock.network());
+            let http = HttpPool::new(&[], Duration::from_secs(5)).unwrap();
+            // Unreachable database: persistence is best-effort throughout.
+            let db = PgPoolOptions::new()
+                .acquire_timeout(Duration::from_millis(200))
+                .connect_lazy("postgres://localhost:1/test")
+                .unwrap();
+            let wallet_keys = Arc::new(
+                WalletKeyStore::new(
+                    "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+                )
+                .unwrap(),
+            );
+            let signer = PrivateKeySigner::random();
+            let encrypted = wallet_keys.encrypt_key(&signer.to_bytes().0).unwrap();
+            wallet_keys.store_key(1, &encrypted).unwrap();
+            let safe = Address::repeat_byte(0xaa);
+            wallet_keys.store_safe_address(1, safe).unwrap();
+            mock.fund(signer.address(), safe, 100.0);
+
+            let api_keys = Arc::new(CredentialManager::new(
+                http.clone(),
+                network.clone(),
+                db.clone(),
+                wallet_keys.clone(),
+            ));
+            Self {
+                mock,
+                network,
+                http,
+                db,
+                wallet_keys,
+                api_keys,
+                safe,
+            }
+        }
+
+        fn submitter(&self, builder: BuilderCredentials) -> OrderSubmitter {
+            OrderSubmitter::new(
+                self.http.clone(),
+                self.network.clone(),
+                builder,
+                self.api_keys.clone(),
+                self.wallet_keys.clone(),
+                Arc::new(FeeCache::new(self.http.clone(), &self.mock.url)),
+                Arc::new(MarketMetaCache::new(self.http.clone(), &self.mock.url)),
+            )
+        }
+    }
+
+    fn order(side: Side, price: Option<f64>, size_usdc: f64) -> ExecutionOrder {
+        ExecutionOrder {
+            id: Uuid::new_v4(),
+            wallet_id: 1,
+            strategy_id: Some(7),
+            copy_relationship_id: None,
+            symbol: "btc-updown-15m-1".into(),
+            token_id: TOKEN.into(),
+            side,
+            outcome: Outcome::Up,
+            price,
+            reference_price: Some(0.55),
+            size_usdc,
+            order_type: OrderType::Market,
+            priority: OrderPriority::StrategyMarket,
+            created_at: chrono::Utc::now().timestamp(),
+            leader_address: String::new(),
+            leader_tx_hash: String::new(),
+            is_paper: false,
+            market: None,
+            algo: None,
+            parent_id: None,
+            closes: None,
+        }
+    }
+
+    fn rejection(err: anyhow::Error) -> ClobRejection {
+        err.downcast_ref::<ClobError>()
+            .expect("a CLOB rejection")
+            .rejection
+    }
+
+    #[tokio::test]
+    async fn test_signed_order_matches_against_book() {
+        let h = Harness::start().await;
+        h.mock.set_fee_rate(100);
+        h.mock.add_liquidity(TOKEN, Side::Sell, 0.55, 50.0);
+        let submitter = h.submitter(builder());
+
+        // Market buy for 11 USDC at the 0.55 reference: 20 shares.
+        let posted = submitter
+            .submit(&order(Side::Buy, None, 11.0))
+            .await
+            .unwrap();
+        assert_eq!(posted.fee_bps, 100);
+        let result = submitter.await_fill(&posted).await;
+        assert_eq!(result.status, OrderStatus::Filled);
+        assert_eq!(result.filled_price, Some(0.55));
+        assert!((h.mock.usdc(h.safe) - 89.0).abs() < 1e-9);
+        assert!((h.mock.shares(h.safe, TOKEN) - 20.0).abs() < 1e-9);
+        assert!(h.mock.rejections().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_bad_signatures_and_unfillable_fok() {
+        let h = Harness::start().await;
+        h.mock.add_liquidity(TOKEN, Side::Sell, 0.55, 5.0);
+
+        // Wrong builder secret.
+        let forged = BuilderCredentials {
+            secret: BASE64_URL.encode(b"not-the-secret"),
+            ..builder()
+        };
+        let err = h
+            .submitter(forged)
+            .submit(&order(Side::Buy, None, 11.0))
+            .await
+            .unwrap_err();
+        assert_eq!(rejection(err), ClobRejection::Auth);
+
+        // Signed for the regular exchange on a neg-risk market.
+        h.mock.set_neg_risk("999");
+        let submitter = h.submitter(builder());
+        let mut neg_risk = order(Side::Buy, None, 11.0);
+        neg_risk.token_id = "999".into();
+        neg_risk.market = Some(crate::execution::MarketMeta {
+            neg_risk: false,
+            tick_size: 0.01,
+            min_order_size: 0.0,
+        });
+        let err = submitter.submit(&neg_risk).await.unwrap_err();
+        assert_eq!(rejection(err), ClobRejection::Auth);
+
+        // Only 5 shares offered: a 20-share FOK is killed and nothing moves.
+        let err = submitter
+            .submit(&order(Side::Buy, None, 11.0))
+            .await
+            .unwrap_err();
+        assert_eq!(rejection(err), ClobRejection::FokNotFilled);
+        assert_eq!(h.mock.usdc(h.safe), 100.0);
+
+        // A sell of tokens the Safe does not hold.
+        let err = submitter
+            .submit(&order(Side::Sell, None, 11.0))
+            .await
+            .unwrap_err();
+        assert_eq!(rejection(err), ClobRejection::InsufficientBalance);
+
+        assert_eq!(
+            h.mock.rejections(),
+            vec![
+                "Unauthorized: invalid builder signature",
+                "invalid signature",
+                "order couldn't be fully filled. FOK orders are fully filled or killed.",
+                "not enough balance / allowance",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scripted_failures_then_recovery() {
+        let h = Harness::start().await;
+        h.mock.add_liquidity(TOKEN, Side::Sell, 0.55, 50.0);
+        h.mock
+            .fail_next(MockEndpoint::Order, 429, "Too many requests");
+        h.mock
+            .fail_next(MockEndpoint::Order, 503, "upstream unavailable");
+        let submitter = h.submitter(builder());
+        let buy = order(Side::Buy, None, 11.0);
+
+        let err = submitter.submit(&buy).await.unwrap_err();
+        let clob = err.downcast_ref::<ClobError>().unwrap();
+        assert_eq!(clob.rejection, ClobRejection::RateLimited);
+        assert!(clob.is_retryable());
+        let err = submitter.submit(&buy).await.unwrap_err();
+        assert!(err.downcast_ref::<ClobError>().unwrap().is_retryable());
+
+        // The third attempt of the same order goes through.
+        assert!(submitter.submit(&buy).await.is_ok());
+        // Replaying it afterwards re-signs the same salt and is refused.
+        assert!(submitter.submit(&buy).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_signed_hash_is_the_clob_order_id() {
+        let h = Harness::start().await;
+        h.mock.add_liquidity(TOKEN, Side::Sell, 0.55, 50.0);
+        let submitter = h.submitter(builder());
+        let buy = order(Side::Buy, None, 11.0);
+
+        let signed = submitter.sign(&buy).await.unwrap();
+        assert!(!submitter.find_order(&signed.order_hash).await.unwrap());
+        let posted = submitter.post(&buy, &signed).await.unwrap();
+        assert_eq!(posted.polymarket_order_id, signed.order_hash);
+        assert!(submitter.find_order(&signed.order_hash).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_relayer_checks_safe_signature_and_nonce() {
+        let h = Harness::start().await;
+        let relayer = RelayerClient::new(
+            h.http.clone(),
+            h.network.clone(),
+            builder(),
+            h.wallet_keys.clone(),
+            h.db.clone(),
+        );
+
+        let call = SafeCall::usdc_withdraw(&h.network, Address::repeat_byte(0xbb), 5.0).unwrap();
+        let first = relayer.execute(1, &call).await.unwrap();
+        let second = relayer.execute(1, &call).await.unwrap();
+        assert_eq!((first.nonce, second.nonce), (0, 1));
+        let (state, hash) = safe_tx_status(&relayer, &second.transaction_id)
+            .await
+            .unwrap();
+        assert_eq!(state, SafeTxState::Confirmed);
+        assert!(hash.is_some());
+        assert_eq!(h.mock.relayed().len(), 2);
+
+        h.mock.fail_next(MockEndpoint::Submit, 500, "relayer down");
+        assert!(relayer.execute(1, &call).await.is_err());
+
+        let forged = RelayerClient::new(
+            h.http.clone(),
+            h.network.clone(),
+            BuilderCredentials {
+                api_key: "someone-else".into(),
+                ..builder()
+            },
+            h.wallet_keys.clone(),
+            h.db.clone(),
+        );
+        assert!(forged.execute(1, &call).await.is_err());
+        assert_eq!(h.mock.relayed().len(), 2);
+    }
+
+    /// Feed one tick through the strategy interpreter and the signal bridge
+    /// into a running executor, as the live engine does, and wait for the
+    /// buy it triggers to fill. Returns the registry holding the position.
+    async fn trade_tick(h: &Harness, db: sqlx::PgPool) -> AssignmentRegistry {
+        h.mock.add_liquidity(TOKEN, Side::Sell, 0.50, 100.0);
+
+        let tick = test_tick();
+        let market = ActiveMarket {
+            condition_id: "0xcid".into(),
+            slug: tick.symbol.clone(),
+            binance_symbol: None,
+            slot_ts: tick.slot_ts,
+            slot_duration: tick.slot_duration,
+            end_time: f64::from(tick.slot_ts + tick.slot_duration),
+            token_up: TOKEN.into(),
+            token_down: "tok_down".into(),
+            ref_price_start: None,
+            neg_risk: false,
+            tick_size: 0.01,
+            min_order_size: 1.0,
+        };
+        let markets = Arc::new(RwLock::new(HashMap::from([(
+            market.condition_id.clone(),
+            market,
+        )])));
+
+        let registry = AssignmentRegistry::new();
+        // A limit buy at 0.60 crosses the 0.50 ask.
+        activate(
+            &registry,
+            1,
+            7,
+            serde_json::json!({
+                "mode": "form",
+                "conditions": [{
+                    "type": "AND",
+                    "rules": [{ "indicator": "mid_up", "operator": ">", "value": 0.5 }]
+                }],
+                "action": {
+                    "signal": "buy",
+                    "outcome": "UP",
+                    "size_mode": "fixed",
+                    "size_usdc": 12,
+                    "order_type": "limit",
+                    "limit_price": 0.60
+                }
+            }),
+            vec!["btc-updown-15m".into()],
+            200.0,
+            false,
+            None,
+        )
+        .await;
+        let queue = Arc::new(AsyncMutex::new(ExecutionQueue::new(100)));
+        let journal = Arc::new(ExecutionJournal::new(db.clone(), 120));
+        let risk = Arc::new(RiskEngine::new(RiskLimits {
+            max_wallet_exposure_usdc: 0.0,
+            max_market_exposure_usdc: 0.0,
+            max_slot_exposure_usdc: 0.0,
+            max_open_positions: 0,
+            max_daily_notional_usdc: 0.0,
+            min_price: 0.0,
+            min_size_usdc: 0.0,
+        }));
+        let balances = Arc::new(BalanceService::new(
+            h.http.clone(),
+            h.network.clone(),
+            h.api_keys.clone(),
+            h.wallet_keys.clone(),
+        ));
+        let executor = tokio::spawn(executor::run(
+            queue.clone(),
+            Arc::new(h.submitter(builder())),
+            registry.clone(),
+            db,
+            journal.clone(),
+            risk,
+            balances.clone(),
+            RetryPolicy {
+                max_retries: 0,
+                base_delay: Duration::from_millis(10),
+                max_delay: Duration::from_millis(10),
+            },
+            OrderBookCache::new(),
+        ));
+        let (signal_tx, signal_rx) = mpsc::channel(4);
+        let bridge = tokio::spawn(signal_to_queue(signal_rx, queue, journal, markets));
+
+        let outputs: Vec<_> = {
+            let reg = registry.read().await;
+            reg["btc-updown-15m"]
+                .iter()
+                .filter_map(|a| evaluate_assignment(a, &tick, None, None))
+                .collect()
+        };
+        assert_eq!(outputs.len(), 1, "the tick should trigger one buy");
+        for output in outputs {
+            signal_tx.send(output).await.unwrap();
+        }
+
+        // 12 USDC at the 0.60 limit buys 20 shares, filled at the 0.50 ask.
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(15);
+        while h.mock.shares(h.safe, TOKEN) < 20.0 - 1e-9 {
+            assert!(tokio::time::Instant::now() < deadline, "order never filled");
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert!((h.mock.usdc(h.safe) - 90.0).abs() < 1e-9);
+
+        let entry_price = loop {
+            let position = {
+                let reg = registry.read().await;
+                let assignment = reg.values().flatten().next().unwrap();
+                let state = assignment.state.lock().unwrap();
+                state.position.as_ref().map(|p| p.entry_price)
+            };
+            if let Some(price) = position {
+                break price;
+            }
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "position never recorded"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        };
+        assert_eq!(entry_price, 0.50);
+        // The balance check before submission read the funded Safe from the mock.
+        assert!(balances.snapshot(1).await.is_some());
+        bridge.abort();
+        executor.abort();
+        registry
+    }
+
+    #[tokio::test]
+    async fn test_tick_flows_through_interpreter_to_fill() {
+        let h = Harness::start().await;
+        trade_tick(&h, h.db.clone()).await;
+    }
+
+    /// Needs a Postgres server: `TEST_DATABASE_URL=postgres://... cargo test
+    /// -- --ignored`. The `trades` table is created in a throwaway schema.
+    #[tokio::test]
+    #[ignore = "needs a Postgres server at TEST_DATABASE_URL"]
+    async fn test_tick_fill_persists_trade_row() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let schema = format!("e2e_{}", Uuid::new_v4().simple());
+        let admin = sqlx::PgPool::connect(&url).await.unwrap();
+        sqlx::query(&format!("CREATE SCHEMA {schema}"))
+            .execute(&admin)
+            .await
+            .unwrap();
+        let options: PgConnectOptions = url.parse().unwrap();
+        let db = PgPoolOptions::new()
+            .connect_with(options.options([("search_path", schema.as_str())]))
+            .await
+            .unwrap();
+        // Columns of the web app's `trades` migrations the executor writes.
+        sqlx::query(
+            "CREATE TABLE trades (
+                id BIGSERIAL PRIMARY KEY,
+                wallet_id BIGINT NOT NULL,
+                strategy_id BIGINT,
+                copy_relationship_id BIGINT,
+                symbol VARCHAR(100),
+                token_id VARCHAR(100),
+                side VARCHAR(10),
+                outcome VARCHAR(10),
+                price NUMERIC(10, 6),
+                size_usdc NUMERIC(18, 6),
+                order_type VARCHAR(20),
+                status VARCHAR(20) NOT NULL DEFAULT 'pending',
+                polymarket_order_id VARCHAR(255),
+                fee_bps SMALLINT,
+                filled_price NUMERIC(10, 6),
+                is_paper BOOLEAN NOT NULL DEFAULT FALSE,
+                reference_price NUMERIC(10, 6),
+                resolved_price NUMERIC(10, 6),
+                fill_slippage_bps NUMERIC(10, 2),
+                fill_slippage_pct NUMERIC(10, 6),
+                executed_at TIMESTAMP,
+                created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+                reject_reason VARCHAR(64),
+                clob_rejection VARCHAR(32),
+                parent_order_id UUID
+            )",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let h = Harness::start().await;
+        trade_tick(&h, db.clone()).await;
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(15);
+        let row = loop {
+            let row: Option<(String, String, String, f64, f64)> = sqlx::query_as(
+                "SELECT status, side, token_id, filled_price::float8, size_usdc::float8 \
+                 FROM trades WHERE wallet_id = 1 AND strategy_id = 7",
+            )
+            .fetch_optional(&db)
+            .await
+            .unwrap();
+            if let Some(row) = row {
+                break row;
+            }
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "trade never written"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        };
+        sqlx::query(&format!("DROP SCHEMA {schema} CASCADE"))
+            .execute(&admin)
+            .await
+            .unwrap();
+
+        assert_eq!(row.0, "filled");
+        assert_eq!(row.1, "buy");
+        assert_eq!(row.2, TOKEN);
+        assert!((row.3 - 0.50).abs() < 1e-9);
+        assert!((row.4 - 12.0).abs() < 1e-9);
+    }
+}
@@ -1,14 +1,28 @@
+pub mod algo;
 pub mod analytics;
+pub mod balances;
+pub mod credentials;
 pub mod executor;
 pub mod fees;
+pub mod journal;
+pub mod markets;
+pub mod mock_exchange;
+pub mod normalize;
 pub mod orders;
 pub mod queue;
+pub mod redemption;
 pub mod relayer;
+pub mod retry;
+pub mod risk;
+pub mod safe_tx;
 pub mod wallet;
 
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use self::algo::ExecAlgo;
+use crate::fetcher::models::ActiveMarket;
+use crate::strategy::state::Position;
 use crate::strategy::{OrderType, Outcome};
 
 // ---------------------------------------------------------------------------
@@ -53,6 +67,30 @@ pub enum Side {
     Sell,
 }
 
+// ---------------------------------------------------------------------------
+// MarketMeta
+// ---------------------------------------------------------------------------
+
+/// Per-market trading parameters needed to sign and size an order.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MarketMeta {
+    /// Signed against `NegRiskCtfExchange` rather than `CtfExchange`.
+    pub neg_risk: bool,
+    pub tick_size: f64,
+    /// Minimum order size in shares; 0 when unknown.
+    pub min_order_size: f64,
+}
+
+impl From<&ActiveMarket> for MarketMeta {
+    fn from(market: &ActiveMarket) -> Self {
+        Self {
+            neg_risk: market.neg_risk,
+            tick_size: market.tick_size,
+            min_order_size: market.min_order_size,
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // ExecutionOrder
 // ---------------------------------------------------------------------------
@@ -83,6 +121,31 @@ pub struct ExecutionOrder {
     /// Paper trading flag — simulated fills instead of real CLOB submission.
     #[serde(default)]
     pub is_paper: bool,
+    /// Market parameters from discovery; `None` lets the submitter look them
+    /// up on the CLOB by token.
+    #[serde(default)]
+    pub market: Option<MarketMeta>,
+    /// Execution algorithm slicing this order into child orders, if any.
+    #[serde(default)]
+    pub algo: Option<ExecAlgo>,
+    /// Parent order this child was sliced from.
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    /// Strategy position a sell exits, whose PnL its fill realizes.
+    #[serde(default)]
+    pub closes: Option<Position>,
+}
+
+impl ExecutionOrder {
+    /// Shares the order moves at `price`. An exit sells the shares of the
+    /// position it closes, whatever the exit price.
+    pub fn shares_at(&self, price: f64) -> f64 {
+        match &self.closes {
+            Some(position) if self.side == Side::Sell => position.shares(),
+            _ if price > 0.0 => self.size_usdc / price,
+            _ => self.size_usdc,
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -95,6 +158,12 @@ pub struct OrderResult {
     pub status: OrderStatus,
     pub filled_price: Option<f64>,
     pub fee_bps: Option<u16>,
+    /// Why the order never reached the CLOB (pre-trade check), if rejected.
+    #[serde(default)]
+    pub reject_reason: Option<String>,
+    /// Why the CLOB refused the order, if it did.
+    #[serde(default)]
+    pub clob_rejection: Option<ClobRejection>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -103,6 +172,74 @@ pub enum OrderStatus {
     Cancelled,
     Failed,
     Timeout,
+    Rejected,
+}
+
+// ---------------------------------------------------------------------------
+// ClobRejection
+// ---------------------------------------------------------------------------
+
+/// Classified CLOB error response for an order the exchange refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClobRejection {
+    InsufficientBalance,
+    InvalidTick,
+    MarketClosed,
+    FokNotFilled,
+    RateLimited,
+    Auth,
+    Unknown,
+}
+
+impl ClobRejection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::InsufficientBalance => "insufficient_balance",
+            Self::InvalidTick => "invalid_tick",
+            Self::MarketClosed => "market_closed",
+            Self::FokNotFilled => "fok_not_filled",
+            Self::RateLimited => "rate_limited",
+            Self::Auth => "auth",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// Whether resubmitting the same order later can succeed. Balance, tick
+    /// and auth problems need intervention; a closed market never reopens.
+    /// Unclassified errors are only retried on a 5xx (see `ClobError`).
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::RateLimited | Self::FokNotFilled)
+    }
+
+    /// Classify a CLOB error from its HTTP status and error message.
+    pub fn classify(http_status: u16, message: &str) -> Self {
+        let msg = message.to_ascii_lowercase();
+        if http_status == 429 || msg.contains("rate limit") || msg.contains("too many requests") {
+            Self::RateLimited
+        } else if http_status == 401
+            || http_status == 403
+            || msg.contains("unauthorized")
+            || msg.contains("invalid api key")
+            || msg.contains("invalid signature")
+        {
+            Self::Auth
+        } else if msg.contains("balance") || msg.contains("allowance") {
+            Self::InsufficientBalance
+        } else if msg.contains("tick") {
+            Self::InvalidTick
+        } else if msg.contains("fok") || msg.contains("fully filled") {
+            Self::FokNotFilled
+        } else if msg.contains("closed")
+            || msg.contains("not accepting orders")
+            || msg.contains("orderbook") && msg.contains("does not exist")
+            || msg.contains("market_not_ready")
+        {
+            Self::MarketClosed
+        } else {
+            Self::Unknown
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -120,4 +257,52 @@ mod tests {
         assert!(OrderPriority::CopyMarket > OrderPriority::StrategyMarket);
         assert!(OrderPriority::StrategyMarket > OrderPriority::Limit);
     }
+
+    #[test]
+    fn test_classify_clob_rejections() {
+        let cases = [
+            (
+                400,
+                "not enough balance / allowance",
+                ClobRejection::InsufficientBalance,
+            ),
+            (
+                400,
+                "order 0xabc is invalid. Price (0.555), breaks minimum tick size rule: 0.01",
+                ClobRejection::InvalidTick,
+            ),
+            (
+                400,
+                "INVALID_ORDER_MIN_TICK_SIZE",
+                ClobRejection::InvalidTick,
+            ),
+            (400, "the market is closed", ClobRejection::MarketClosed),
+            (
+                400,
+                "the orderbook 123 does not exist",
+                ClobRejection::MarketClosed,
+            ),
+            (
+                400,
+                "order couldn't be fully filled. FOK orders are fully filled or killed.",
+                ClobRejection::FokNotFilled,
+            ),
+            (429, "", ClobRejection::RateLimited),
+            (401, "Unauthorized/Invalid api key", ClobRejection::Auth),
+            (500, "internal error", ClobRejection::Unknown),
+        ];
+        for (status, msg, expected) in cases {
+            assert_eq!(ClobRejection::classify(status, msg), expected, "{msg}");
+        }
+    }
+
+    #[test]
+    fn test_clob_rejection_retryability() {
+        assert!(ClobRejection::RateLimited.is_retryable());
+        assert!(ClobRejection::FokNotFilled.is_retryable());
+        assert!(!ClobRejection::Unknown.is_retryable());
+        assert!(!ClobRejection::InsufficientBalance.is_retryable());
+        assert!(!ClobRejection::MarketClosed.is_retryable());
+        assert!(!ClobRejection::Auth.is_retryable());
+    }
 }
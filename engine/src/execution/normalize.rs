@@ -0,0 +1,362 @@
+use std::fmt;
+
+use super::{ExecutionOrder, MarketMeta, Side};
+
+/// USDC and conditional tokens both use 6 decimals on-chain.
+const UNIT_SCALE: u64 = 1_000_000;
+/// The CLOB accepts share sizes with at most 2 decimals.
+const SHARE_CENTS: u64 = 100;
+/// Absorbs float noise such as `0.57 / 0.01 = 56.99999…` before flooring.
+const ROUNDING_EPSILON: f64 = 1e-9;
+
+// ---------------------------------------------------------------------------
+// NormalizeRejection
+// ---------------------------------------------------------------------------
+
+/// Why an order cannot be expressed as a valid CLOB order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizeRejection {
+    MissingPrice,
+    InvalidTickSize,
+    PriceOutOfRange,
+    BelowMinOrderSize,
+}
+
+impl NormalizeRejection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MissingPrice => "missing_price",
+            Self::InvalidTickSize => "invalid_tick_size",
+            Self::PriceOutOfRange => "price_out_of_range",
+            Self::BelowMinOrderSize => "below_min_order_size",
+        }
+    }
+}
+
+impl fmt::Display for NormalizeRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::error::Error for NormalizeRejection {}
+
+// ---------------------------------------------------------------------------
+// NormalizedOrder
+// ---------------------------------------------------------------------------
+
+/// Price and amounts ready to be signed, all on the market's grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormalizedOrder {
+    /// Limit price rounded to the tick size.
+    pub price: f64,
+    /// Shares, rounded down to 2 decimals.
+    pub shares: f64,
+    /// What the maker gives: USDC for a buy, tokens for a sell (6 decimals).
+    pub maker_amount: u64,
+    /// What the maker receives: tokens for a buy, USDC for a sell (6 decimals).
+    pub taker_amount: u64,
+}
+
+/// Round `order` onto `market`'s tick grid and share precision.
+///
+/// Buys round the price down and sells round it up, so normalization never
+/// makes a fill worse than requested. Shares are floored, so a buy never
+/// spends more than `size_usdc` and an exit never sells more than its
+/// position. USDC amounts are derived from integer share and price units, so
+/// `taker / maker` reproduces the rounded price exactly.
+pub fn normalize(
+    order: &ExecutionOrder,
+    market: &MarketMeta,
+) -> Result<NormalizedOrder, NormalizeRejection> {
+    let tick_units = tick_units(market.tick_size)?;
+    let raw_price = order
+        .price
+        .or(order.reference_price)
+        .filter(|p| p.is_finite())
+        .ok_or(NormalizeRejection::MissingPrice)?;
+
+    let raw_ticks = raw_price * UNIT_SCALE as f64 / tick_units as f64;
+    let ticks = match order.side {
+        Side::Buy => (raw_ticks + ROUNDING_EPSILON).floor(),
+        Side::Sell => (raw_ticks - ROUNDING_EPSILON).ceil(),
+    };
+    let max_ticks = (UNIT_SCALE / tick_units - 1) as f64;
+    if ticks < 1.0 || ticks > max_ticks {
+        return Err(NormalizeRejection::PriceOutOfRange);
+    }
+    let price_units = ticks as u64 * tick_units;
+    let price = price_units as f64 / UNIT_SCALE as f64;
+
+    let wanted = order.shares_at(price);
+    let share_cents = if wanted.is_finite() && wanted > 0.0 {
+        (wanted * SHARE_CENTS as f64 + ROUNDING_EPSILON).floor() as u64
+    } else {
+        0
+    };
+    let shares = share_cents as f64 / SHARE_CENTS as f64;
+    if share_cents == 0 || shares + ROUNDING_EPSILON < market.min_order_size {
+        return Err(NormalizeRejection::BelowMinOrderSize);
+    }
+
+    let share_units = share_cents * (UNIT_SCALE / SHARE_CENTS);
+    // Exact: `tick_units` is a multiple of SHARE_CENTS (tick ≥ 0.0001).
+    let usdc_units = share_cents * price_units / SHARE_CENTS;
+
+    let (maker_amount, taker_amount) = match order.side {
+        Side::Buy => (usdc_units, share_units),
+        Side::Sell => (share_units, usdc_units),
+    };
+
+    Ok(NormalizedOrder {
+        price,
+        shares,
+        maker_amount,
+        taker_amount,
+    })
+}
+
+/// Tick size in 6-decimal units. The CLOB uses ticks from 0.1 down to 0.0001,
+/// each dividing 1.0 evenly.
+fn tick_units(tick_size: f64) -> Result<u64, NormalizeRejection> {
+    if !tick_size.is_finite() || tick_size <= 0.0 {
+        return Err(NormalizeRejection::InvalidTickSize);
+    }
+    let units = (tick_size * UNIT_SCALE as f64).round() as u64;
+    if units == 0
+        || units >= UNIT_SCALE
+        || !units.is_multiple_of(SHARE_CENTS)
+        || !UNIT_SCALE.is_multiple_of(units)
+    {
+        return Err(NormalizeRejection::InvalidTickSize);
+    }
+    Ok(units)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::OrderPriority;
+    use crate::strategy::{OrderType, Outcome};
+    use proptest::prelude::*;
+    use uuid::Uuid;
+
+    fn order(side: Side, price: Option<f64>, size_usdc: f64) -> ExecutionOrder {
+        ExecutionOrder {
+            id: Uuid::new_v4(),
+            wallet_id: 1,
+            strategy_id: Some(1),
+            copy_relationship_id: None,
+            symbol: "btc-updown-15m-1700000000".into(),
+            token_id: "123".into(),
+            side,
+            outcome: Outcome::Up,
+            price,
+            reference_price: None,
+            size_usdc,
+            order_type: OrderType::Market,
+            priority: OrderPriority::StrategyMarket,
+            created_at: 0,
+            leader_address: String::new(),
+            leader_tx_hash: String::new(),
+            is_paper: false,
+            market: None,
+            algo: None,
+            parent_id: None,
+            closes: None,
+        }
+    }
+
+    fn meta(tick_size: f64, min_order_size: f64) -> MarketMeta {
+        MarketMeta {
+            neg_risk: false,
+            tick_size,
+            min_order_size,
+        }
+    }
+
+    #[test]
+    fn test_buy_rounds_price_down_and_sell_rounds_up() {
+        let m = meta(0.01, 0.0);
+
+        let buy = normalize(&order(Side::Buy, Some(0.567), 10.0), &m).unwrap();
+        assert!((buy.price - 0.56).abs() < 1e-12);
+
+        let sell = normalize(&order(Side::Sell, Some(0.561), 10.0), &m).unwrap();
+        assert!((sell.price - 0.57).abs() < 1e-12);
+
+        // Already on the grid despite float noise: stays put on both sides.
+        let buy = normalize(&order(Side::Buy, Some(0.57), 10.0), &m).unwrap();
+        let sell = normalize(&order(Side::Sell, Some(0.57), 10.0), &m).unwrap();
+        assert!((buy.price - 0.57).abs() < 1e-12);
+        assert!((sell.price - 0.57).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_buy_amounts() {
+        // 10 USDC at 0.56 → 17.857… shares, floored to 17.85; pays 9.996 USDC.
+        let n = normalize(&order(Side::Buy, Some(0.56), 10.0), &meta(0.01, 0.0)).unwrap();
+        assert!((n.shares - 17.85).abs() < 1e-12);
+        assert_eq!(n.taker_amount, 17_850_000);
+        assert_eq!(n.maker_amount, 9_996_000);
+    }
+
+    #[test]
+    fn test_sell_amounts() {
+        let n = normalize(&order(Side::Sell, Some(0.40), 8.0), &meta(0.01, 0.0)).unwrap();
+        assert_eq!(n.maker_amount, 20_000_000);
+        assert_eq!(n.taker_amount, 8_000_000);
+    }
+
+    #[test]
+    fn test_exit_sells_its_position_shares() {
+        // 8 USDC of entry at 0.40 are 20 shares, sold at 0.25 rather than 32.
+        let mut exit = order(Side::Sell, Some(0.25), 8.0);
+        exit.closes = Some(crate::strategy::state::Position {
+            outcome: Outcome::Up,
+            entry_price: 0.40,
+            size_usdc: 8.0,
+            entry_at: 0,
+            symbol: exit.symbol.clone(),
+            entry_fee_usdc: 0.0,
+        });
+        let n = normalize(&exit, &meta(0.01, 0.0)).unwrap();
+        assert_eq!(n.maker_amount, 20_000_000);
+        assert_eq!(n.taker_amount, 5_000_000);
+    }
+
+    #[test]
+    fn test_falls_back_to_reference_price() {
+        let mut o = order(Side::Buy, None, 5.0);
+        assert_eq!(
+            normalize(&o, &meta(0.01, 0.0)),
+            Err(NormalizeRejection::MissingPrice)
+        );
+        o.reference_price = Some(0.5);
+        assert!(normalize(&o, &meta(0.01, 0.0)).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_price_outside_tick_range() {
+        let m = meta(0.01, 0.0);
+        assert_eq!(
+            normalize(&order(Side::Buy, Some(0.005), 5.0), &m),
+            Err(NormalizeRejection::PriceOutOfRange)
+        );
+        assert_eq!(
+            normalize(&order(Side::Sell, Some(0.995), 5.0), &m),
+            Err(NormalizeRejection::PriceOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_rejects_below_min_order_size() {
+        // 2 USDC at 0.50 = 4 shares, market minimum is 5.
+        assert_eq!(
+            normalize(&order(Side::Buy, Some(0.50), 2.0), &meta(0.01, 5.0)),
+            Err(NormalizeRejection::BelowMinOrderSize)
+        );
+        assert!(normalize(&order(Side::Buy, Some(0.50), 2.5), &meta(0.01, 5.0)).is_ok());
+        assert_eq!(
+            normalize(&order(Side::Buy, Some(0.50), 0.001), &meta(0.01, 0.0)),
+            Err(NormalizeRejection::BelowMinOrderSize)
+        );
+    }
+
+    #[test]
+    fn test_rejects_unsupported_tick_size() {
+        for tick in [0.0, -0.01, 0.003, 0.00001, 1.0, f64::NAN] {
+            assert_eq!(
+                normalize(&order(Side::Buy, Some(0.5), 5.0), &meta(tick, 0.0)),
+                Err(NormalizeRejection::InvalidTickSize),
+                "tick {tick}"
+            );
+        }
+    }
+
+    fn tick_strategy() -> impl Strategy<Value = f64> {
+        prop_oneof![Just(0.1), Just(0.01), Just(0.001), Just(0.0001)]
+    }
+
+    fn side_strategy() -> impl Strategy<Value = Side> {
+        prop_oneof![Just(Side::Buy), Just(Side::Sell)]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_amounts_reproduce_rounded_price_exactly(
+            tick in tick_strategy(),
+            side in side_strategy(),
+            price in 0.0001f64..0.9999,
+            size_usdc in 0.01f64..50_000.0,
+        ) {
+            let Ok(n) = normalize(&order(side, Some(price), size_usdc), &meta(tick, 0.0)) else {
+                return Ok(());
+            };
+            let (usdc, shares) = match side {
+                Side::Buy => (n.maker_amount, n.taker_amount),
+                Side::Sell => (n.taker_amount, n.maker_amount),
+            };
+            let price_units = (n.price * UNIT_SCALE as f64).round() as u64;
+            prop_assert_eq!(usdc as u128 * UNIT_SCALE as u128, shares as u128 * price_units as u128);
+            prop_assert!(shares.is_multiple_of(UNIT_SCALE / SHARE_CENTS));
+            prop_assert_eq!(shares, (n.shares * UNIT_SCALE as f64).round() as u64);
+        }
+
+        #[test]
+        fn prop_price_on_grid_and_never_worse_than_requested(
+            tick in tick_strategy(),
+            side in side_strategy(),
+            price in 0.0001f64..0.9999,
+            size_usdc in 0.01f64..50_000.0,
+        ) {
+            let Ok(n) = normalize(&order(side, Some(price), size_usdc), &meta(tick, 0.0)) else {
+                return Ok(());
+            };
+            let ticks = n.price / tick;
+            prop_assert!((ticks - ticks.round()).abs() < 1e-6);
+            prop_assert!(n.price >= tick - 1e-12 && n.price <= 1.0 - tick + 1e-12);
+            match side {
+                Side::Buy => prop_assert!(n.price <= price + 1e-9),
+                Side::Sell => prop_assert!(n.price >= price - 1e-9),
+            }
+            prop_assert!((n.price - price).abs() < tick + 1e-9);
+        }
+
+        #[test]
+        fn prop_buy_never_spends_more_than_size(
+            tick in tick_strategy(),
+            price in 0.0001f64..0.9999,
+            size_usdc in 0.01f64..50_000.0,
+        ) {
+            let Ok(n) = normalize(&order(Side::Buy, Some(price), size_usdc), &meta(tick, 0.0)) else {
+                return Ok(());
+            };
+            prop_assert!(n.maker_amount as f64 <= size_usdc * UNIT_SCALE as f64 + 1.0);
+            // Flooring to 2-decimal shares loses less than one cent of shares.
+            prop_assert!((size_usdc - n.maker_amount as f64 / UNIT_SCALE as f64) < 0.01 * n.price + 1e-6);
+        }
+
+        #[test]
+        fn prop_min_size_is_enforced(
+            tick in tick_strategy(),
+            side in side_strategy(),
+            price in 0.0001f64..0.9999,
+            size_usdc in 0.01f64..100.0,
+            min_size in 0.0f64..50.0,
+        ) {
+            match normalize(&order(side, Some(price), size_usdc), &meta(tick, min_size)) {
+                Ok(n) => prop_assert!(n.shares + 1e-9 >= min_size),
+                Err(NormalizeRejection::BelowMinOrderSize) => {
+                    let loose = normalize(&order(side, Some(price), size_usdc), &meta(tick, 0.0));
+                    prop_assert!(!loose.is_ok_and(|l| l.shares + 1e-9 >= min_size));
+                }
+                Err(_) => {}
+            }
+        }
+    }
+}
@@ -4,7 +4,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use alloy::primitives::{Address, U256};
 use alloy::signers::SignerSync;
 use alloy::sol;
-use alloy::sol_types::{eip712_domain, SolStruct};
+use alloy::sol_types::{eip712_domain, Eip712Domain, SolStruct};
 use anyhow::{Context, Result};
 use base64::engine::general_purpose::{
     STANDARD as BASE64, URL_SAFE as BASE64_URL, URL_SAFE_NO_PAD as BASE64_URL_NOPAD,
@@ -16,19 +16,15 @@ use serde::Deserialize;
 use sha2::Sha256;
 use tracing::{debug, warn};
 
+use super::credentials::{ApiCredentials, CredentialManager};
 use super::fees::FeeCache;
+use super::markets::MarketMetaCache;
+use super::normalize::{normalize, NormalizedOrder};
 use super::wallet::WalletKeyStore;
-use super::{ExecutionOrder, OrderResult, OrderStatus, Side};
+use super::{ClobRejection, ExecutionOrder, MarketMeta, OrderResult, OrderStatus, Side};
+use crate::network::NetworkProfile;
 use crate::proxy::HttpPool;
 
-// ---------------------------------------------------------------------------
-// Constants
-// ---------------------------------------------------------------------------
-
-const NEG_RISK_EXCHANGE: &str = "0xC5d563A36AE78145C45a50134d48A1215220f80a";
-const CTF_EXCHANGE: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
-const CHAIN_ID: u64 = 137;
-
 // ---------------------------------------------------------------------------
 // EIP-712 Order struct (Polymarket CTF Exchange)
 // ---------------------------------------------------------------------------
@@ -57,10 +53,59 @@ sol! {
 
 #[derive(Debug, Deserialize)]
 struct SubmitOrderResponse {
-    #[serde(rename = "orderID")]
+    #[serde(rename = "orderID", default)]
     order_id: String,
+    success: Option<bool>,
+    #[serde(rename = "errorMsg")]
+    error_msg: Option<String>,
+}
+
+/// A CLOB response refusing the order, classified for retry and reporting.
+#[derive(Debug, Clone)]
+pub struct ClobError {
+    pub rejection: ClobRejection,
+    pub http_status: u16,
+    pub message: String,
+}
+
+impl ClobError {
+    /// Known transient rejections, plus any server-side (5xx) failure.
+    pub fn is_retryable(&self) -> bool {
+        self.rejection.is_retryable() || self.http_status >= 500
+    }
+
+    fn from_response(http_status: u16, body: &str) -> Self {
+        let message = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|v| {
+                v.get("error")
+                    .or_else(|| v.get("errorMsg"))
+                    .and_then(|m| m.as_str())
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| body.to_string());
+        Self {
+            rejection: ClobRejection::classify(http_status, &message),
+            http_status,
+            message,
+        }
+    }
 }
 
+impl std::fmt::Display for ClobError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "order rejected by CLOB ({}, status {}): {}",
+            self.rejection.as_str(),
+            self.http_status,
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for ClobError {}
+
 #[derive(Debug, Deserialize)]
 struct OrderStatusResponse {
     status: Option<String>,
@@ -77,7 +122,24 @@ struct AssociateTrade {
 // OrderSubmitter
 // ---------------------------------------------------------------------------
 
+/// An order accepted by the CLOB whose final status is not known yet.
+#[derive(Debug, Clone)]
+pub struct PostedOrder {
+    pub polymarket_order_id: String,
+    pub fee_bps: u16,
+}
+
+/// A signed order not posted yet.
+#[derive(Debug, Clone)]
+pub struct SignedOrder {
+    /// EIP-712 hash of the order, which the CLOB uses as its order id.
+    pub order_hash: String,
+    pub fee_bps: u16,
+    body: String,
+}
+
 /// Polymarket Builder Program authentication credentials.
+#[derive(Clone)]
 pub struct BuilderCredentials {
     pub api_key: String,
     pub secret: String,
@@ -86,36 +148,67 @@ pub struct BuilderCredentials {
 
 pub struct OrderSubmitter {
     http: HttpPool,
+    network: Arc<NetworkProfile>,
     clob_url: String,
     credentials: BuilderCredentials,
+    api_keys: Arc<CredentialManager>,
     wallet_keys: Arc<WalletKeyStore>,
     fee_cache: Arc<FeeCache>,
-    neg_risk: bool,
+    market_cache: Arc<MarketMetaCache>,
 }
 
 impl OrderSubmitter {
     pub fn new(
         http: HttpPool,
-        clob_url: &str,
+        network: Arc<NetworkProfile>,
         credentials: BuilderCredentials,
+        api_keys: Arc<CredentialManager>,
         wallet_keys: Arc<WalletKeyStore>,
         fee_cache: Arc<FeeCache>,
-        neg_risk: bool,
+        market_cache: Arc<MarketMetaCache>,
     ) -> Self {
         Self {
             http,
-            clob_url: clob_url.trim_end_matches('/').to_string(),
+            clob_url: network.clob_api_url.trim_end_matches('/').to_string(),
+            network,
             credentials,
+            api_keys,
             wallet_keys,
             fee_cache,
-            neg_risk,
+            market_cache,
+        }
+    }
+
+    /// Fee rate a paper fill of `order` is charged, so simulated PnL carries
+    /// the same taker fees as a live fill. Falls back to 0 when the rate
+    /// cannot be looked up.
+    pub async fn paper_fee_bps(&self, order: &ExecutionOrder) -> u16 {
+        if order.token_id.is_empty() {
+            return 0;
+        }
+        match self.fee_cache.get_fee(&order.token_id).await {
+            Ok(bps) => bps,
+            Err(e) => {
+                tracing::warn!(token_id = %order.token_id, error = %e, "paper_fee_lookup_failed");
+                0
+            }
         }
     }
 
-    /// Submit an order to the Polymarket CLOB.
+    /// Submit an order to the Polymarket CLOB: [`Self::sign`] then [`Self::post`].
+    /// The returned [`PostedOrder`] is then resolved with [`Self::await_fill`].
+    #[cfg(test)]
+    pub async fn submit(&self, order: &ExecutionOrder) -> Result<PostedOrder> {
+        let signed = self.sign(order).await?;
+        self.post(order, &signed).await
+    }
+
+    /// Build and sign the CLOB order.
     ///
-    /// Flow: build EIP-712 struct -> sign -> POST /order with Builder headers -> poll status.
-    pub async fn submit(&self, order: &ExecutionOrder) -> Result<OrderResult> {
+    /// Flow: normalize to tick/size grid -> build EIP-712 struct -> sign. An
+    /// order that cannot be normalized fails with a
+    /// [`NormalizeRejection`](super::normalize::NormalizeRejection).
+    pub async fn sign(&self, order: &ExecutionOrder) -> Result<SignedOrder> {
         // 1. Get signer and Safe address (Gnosis Safe = maker, signer = EOA)
         let signer = self
             .wallet_keys
@@ -134,30 +227,16 @@ impl OrderSubmitter {
             .await
             .context("failed to get fee rate")?;
 
-        // 3. Build amounts (USDC has 6 decimals)
-        let size_usdc_wei = (order.size_usdc * 1_000_000.0) as u64;
-        let price = order.price.unwrap_or(0.5); // fallback for market orders
-
-        let (maker_amount, taker_amount) = match order.side {
-            Side::Buy => {
-                // BUY: pay USDC (makerAmount), receive tokens (takerAmount)
-                let taker_amt = if price > 0.0 {
-                    (size_usdc_wei as f64 / price) as u64
-                } else {
-                    size_usdc_wei
-                };
-                (size_usdc_wei, taker_amt)
-            }
-            Side::Sell => {
-                // SELL: send tokens (makerAmount), receive USDC (takerAmount)
-                let maker_amt = if price > 0.0 {
-                    (size_usdc_wei as f64 / price) as u64
-                } else {
-                    size_usdc_wei
-                };
-                (maker_amt, size_usdc_wei)
-            }
+        // 3. Resolve market parameters and round price/amounts onto its grid
+        let market = match order.market {
+            Some(meta) => meta,
+            None => self.market_meta(&order.token_id).await?,
         };
+        let NormalizedOrder {
+            maker_amount,
+            taker_amount,
+            ..
+        } = normalize(order, &market)?;
 
         // 4. Parse token_id as U256 (try decimal first, then hex)
         let token_id_u256 = U256::from_str_radix(&order.token_id, 10)
@@ -189,23 +268,9 @@ impl OrderSubmitter {
             signatureType: 2, // GNOSIS_SAFE
         };
 
-        // 7. Build EIP-712 domain
-        let exchange_address: Address = if self.neg_risk {
-            NEG_RISK_EXCHANGE
-                .parse()
-                .context("invalid neg risk exchange address")?
-        } else {
-            CTF_EXCHANGE
-                .parse()
-                .context("invalid CTF exchange address")?
-        };
-
-        let domain = eip712_domain! {
-            name: "ClobExchange",
-            version: "1",
-            chain_id: CHAIN_ID,
-            verifying_contract: exchange_address,
-        };
+        // 7. Build EIP-712 domain against the market's exchange
+        let neg_risk = market.neg_risk;
+        let domain = order_domain(&self.network, neg_risk);
 
         // 8. Sign the EIP-712 hash
         let signing_hash = clob_order.eip712_signing_hash(&domain);
@@ -243,40 +308,30 @@ impl OrderSubmitter {
                 "signature": signature_hex,
             },
             "orderType": order_type_str,
-            "negRisk": self.neg_risk,
+            "negRisk": neg_risk,
         });
 
         let body = serde_json::to_string(&payload).context("failed to serialize order payload")?;
 
-        // 10. Sign builder request (HMAC-SHA256)
-        let timestamp = now_secs().to_string();
+        Ok(SignedOrder {
+            order_hash: format!("{signing_hash:?}"),
+            fee_bps: fee_rate_bps,
+            body,
+        })
+    }
+
+    /// POST a signed order with the wallet's L2 headers plus builder attribution.
+    pub async fn post(&self, order: &ExecutionOrder, signed: &SignedOrder) -> Result<PostedOrder> {
+        let body = signed.body.clone();
+
+        // 10-11. Sign with the wallet's L2 key, attributed to the builder
         let path = "/order";
-        let hmac_sig = self.sign_builder_request("POST", path, &timestamp, &body)?;
-
-        // 11. Build headers
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            "POLY_ADDRESS",
-            HeaderValue::from_str(&format!("{:?}", safe_address))
-                .context("invalid address header")?,
-        );
-        headers.insert(
-            "POLY_SIGNATURE",
-            HeaderValue::from_str(&hmac_sig).context("invalid signature header")?,
-        );
-        headers.insert(
-            "POLY_TIMESTAMP",
-            HeaderValue::from_str(&timestamp).context("invalid timestamp header")?,
-        );
-        headers.insert(
-            "POLY_API_KEY",
-            HeaderValue::from_str(&self.credentials.api_key).context("invalid api key header")?,
-        );
-        headers.insert(
-            "POLY_PASSPHRASE",
-            HeaderValue::from_str(&self.credentials.passphrase)
-                .context("invalid passphrase header")?,
-        );
+        let mut headers = self
+            .api_keys
+            .l2_headers(order.wallet_id, "POST", path, &body)
+            .await
+            .context("failed to get wallet API credentials")?;
+        headers.extend(builder_headers(&self.credentials, "POST", path, &body)?);
 
         // 12. POST /order
         let url = format!("{}{}", self.clob_url, path);
@@ -296,51 +351,83 @@ impl OrderSubmitter {
         if !resp.status().is_success() {
             let status = resp.status();
             let body = resp.text().await.unwrap_or_default();
-            anyhow::bail!("order submission failed with status {status}: {body}");
+            let error = ClobError::from_response(status.as_u16(), &body);
+            if error.rejection == ClobRejection::Auth {
+                self.api_keys.invalidate(order.wallet_id).await;
+            }
+            return Err(error.into());
         }
 
+        let status = resp.status().as_u16();
         let submit_resp: SubmitOrderResponse = resp
             .json()
             .await
             .context("failed to parse order submission response")?;
 
+        if submit_resp.success == Some(false) || submit_resp.order_id.is_empty() {
+            let message = submit_resp.error_msg.unwrap_or_default();
+            return Err(ClobError {
+                rejection: ClobRejection::classify(status, &message),
+                http_status: status,
+                message,
+            }
+            .into());
+        }
+
         debug!(
             polymarket_order_id = %submit_resp.order_id,
-            "order submitted, polling status"
+            "order submitted"
         );
 
-        // 13. Poll for order status + extract filled price
-        let (status, filled_price) = self.poll_order_status(&submit_resp.order_id).await;
-
-        Ok(OrderResult {
+        Ok(PostedOrder {
             polymarket_order_id: submit_resp.order_id,
-            status,
-            filled_price,
-            fee_bps: Some(fee_rate_bps),
+            fee_bps: signed.fee_bps,
         })
     }
 
-    /// Compute HMAC-SHA256 of `{timestamp}{method}{path}{body}`, base64-encoded.
-    pub(crate) fn sign_builder_request(
-        &self,
-        method: &str,
-        path: &str,
-        timestamp: &str,
-        body: &str,
-    ) -> Result<String> {
-        let secret_bytes = BASE64_URL
-            .decode(&self.credentials.secret)
-            .or_else(|_| BASE64_URL_NOPAD.decode(&self.credentials.secret))
-            .context("builder_secret is not valid base64 (url-safe)")?;
-
-        let mut mac =
-            Hmac::<Sha256>::new_from_slice(&secret_bytes).context("HMAC key creation failed")?;
-
-        let message = format!("{timestamp}{method}{path}{body}");
-        mac.update(message.as_bytes());
-
-        let result = mac.finalize().into_bytes();
-        Ok(BASE64_URL.encode(result))
+    /// Tick size, minimum size and exchange of the market trading `token_id`.
+    pub async fn market_meta(&self, token_id: &str) -> Result<MarketMeta> {
+        self.market_cache
+            .get(token_id)
+            .await
+            .context("failed to resolve market parameters")
+    }
+
+    /// Whether the CLOB knows the order `order_hash`, i.e. whether a post
+    /// interrupted by a restart reached it.
+    pub async fn find_order(&self, order_hash: &str) -> Result<bool> {
+        let url = format!("{}/data/order/{}", self.clob_url, order_hash);
+        let resp = self
+            .http
+            .proxied()
+            .get(&url)
+            .send()
+            .await
+            .context("order lookup HTTP request failed")?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        let found: Option<OrderStatusResponse> = resp
+            .error_for_status()
+            .context("order lookup rejected")?
+            .json()
+            .await
+            .context("failed to parse order lookup response")?;
+        Ok(found.is_some_and(|o| o.status.is_some()))
+    }
+
+    /// Poll a posted order until it reaches a terminal status.
+    pub async fn await_fill(&self, posted: &PostedOrder) -> OrderResult {
+        let (status, filled_price) = self.poll_order_status(&posted.polymarket_order_id).await;
+
+        OrderResult {
+            polymarket_order_id: posted.polymarket_order_id.clone(),
+            status,
+            filled_price,
+            fee_bps: Some(posted.fee_bps),
+            reject_reason: None,
+            clob_rejection: None,
+        }
     }
 
     /// Poll GET /data/order/{id} every 1s, up to 30 times.
@@ -396,8 +483,124 @@ impl OrderSubmitter {
     }
 }
 
+// ---------------------------------------------------------------------------
+// CLOB request authentication
+// ---------------------------------------------------------------------------
+
+/// Compute HMAC-SHA256 of `{timestamp}{method}{path}{body}` with a url-safe
+/// base64 secret, returned url-safe base64-encoded.
+pub(crate) fn sign_hmac(
+    secret: &str,
+    method: &str,
+    path: &str,
+    timestamp: &str,
+    body: &str,
+) -> Result<String> {
+    let secret_bytes = BASE64_URL
+        .decode(secret)
+        .or_else(|_| BASE64_URL_NOPAD.decode(secret))
+        .context("API secret is not valid base64 (url-safe)")?;
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(&secret_bytes).context("HMAC key creation failed")?;
+
+    let message = format!("{timestamp}{method}{path}{body}");
+    mac.update(message.as_bytes());
+
+    let result = mac.finalize().into_bytes();
+    Ok(BASE64_URL.encode(result))
+}
+
+/// L2 `POLY_*` headers for an authenticated CLOB request on behalf of
+/// `address`, the signer the credentials were derived for. `path` excludes the
+/// query string, as the CLOB signs the bare path.
+pub(crate) fn clob_auth_headers(
+    credentials: &ApiCredentials,
+    address: &Address,
+    method: &str,
+    path: &str,
+    body: &str,
+) -> Result<HeaderMap> {
+    let timestamp = now_secs().to_string();
+    let hmac_sig = sign_hmac(&credentials.secret, method, path, &timestamp, body)?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "POLY_ADDRESS",
+        HeaderValue::from_str(&format!("{:?}", address)).context("invalid address header")?,
+    );
+    headers.insert(
+        "POLY_SIGNATURE",
+        HeaderValue::from_str(&hmac_sig).context("invalid signature header")?,
+    );
+    headers.insert(
+        "POLY_TIMESTAMP",
+        HeaderValue::from_str(&timestamp).context("invalid timestamp header")?,
+    );
+    headers.insert(
+        "POLY_API_KEY",
+        HeaderValue::from_str(&credentials.api_key).context("invalid api key header")?,
+    );
+    headers.insert(
+        "POLY_PASSPHRASE",
+        HeaderValue::from_str(&credentials.passphrase).context("invalid passphrase header")?,
+    );
+    Ok(headers)
+}
+
+/// `POLY_BUILDER_*` headers attributing a request to the Builder Program account.
+pub(crate) fn builder_headers(
+    credentials: &BuilderCredentials,
+    method: &str,
+    path: &str,
+    body: &str,
+) -> Result<HeaderMap> {
+    let timestamp = now_secs().to_string();
+    let hmac_sig = sign_hmac(&credentials.secret, method, path, &timestamp, body)?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "POLY_BUILDER_API_KEY",
+        HeaderValue::from_str(&credentials.api_key).context("invalid builder api key header")?,
+    );
+    headers.insert(
+        "POLY_BUILDER_SIGNATURE",
+        HeaderValue::from_str(&hmac_sig).context("invalid builder signature header")?,
+    );
+    headers.insert(
+        "POLY_BUILDER_TIMESTAMP",
+        HeaderValue::from_str(&timestamp).context("invalid builder timestamp header")?,
+    );
+    headers.insert(
+        "POLY_BUILDER_PASSPHRASE",
+        HeaderValue::from_str(&credentials.passphrase)
+            .context("invalid builder passphrase header")?,
+    );
+    Ok(headers)
+}
+
+/// EIP-712 domain of an order on a neg-risk or regular market.
+pub(crate) fn order_domain(network: &NetworkProfile, neg_risk: bool) -> Eip712Domain {
+    eip712_domain! {
+        name: "ClobExchange",
+        version: "1",
+        chain_id: network.chain_id,
+        verifying_contract: exchange_address(network, neg_risk),
+    }
+}
+
+/// Exchange contract an order is signed for: neg-risk markets settle through
+/// `NegRiskCtfExchange`, everything else through `CtfExchange`.
+fn exchange_address(network: &NetworkProfile, neg_risk: bool) -> Address {
+    if neg_risk {
+        network.neg_risk_exchange
+    } else {
+        network.ctf_exchange
+    }
+}
+
 /// Returns current time in seconds since UNIX epoch (matches Polymarket API convention).
-fn now_secs() -> u64 {
+pub(crate) fn now_secs() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("system clock before UNIX epoch")
@@ -410,6 +613,8 @@ fn now_secs() -> u64 {
 
 #[cfg(test)]
 mod tests {
+    use alloy::primitives::address;
+
     use super::*;
     use crate::execution::fees::FeeCache;
     use crate::execution::wallet::WalletKeyStore;
@@ -421,6 +626,7 @@ mod tests {
         );
         let pool = HttpPool::new(&[], std::time::Duration::from_secs(10)).unwrap();
         let fee_cache = Arc::new(FeeCache::new(pool.clone(), "http://localhost"));
+        let market_cache = Arc::new(MarketMetaCache::new(pool.clone(), "http://localhost"));
 
         // Use a base64-encoded secret for HMAC
         let secret = BASE64.encode(b"test-secret-key-for-hmac-signing");
@@ -431,23 +637,43 @@ mod tests {
             passphrase: "test-passphrase".to_string(),
         };
 
+        let db = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://localhost:1/test")
+            .unwrap();
+        let network = Arc::new(NetworkProfile {
+            clob_api_url: "http://localhost:8080".into(),
+            ..NetworkProfile::polygon()
+        });
+        let api_keys = Arc::new(CredentialManager::new(
+            pool.clone(),
+            network.clone(),
+            db,
+            wallet_keys.clone(),
+        ));
+
         OrderSubmitter::new(
             pool,
-            "http://localhost:8080",
+            network,
             credentials,
+            api_keys,
             wallet_keys,
             fee_cache,
-            true,
+            market_cache,
         )
     }
 
-    #[test]
-    fn test_builder_signature() {
+    #[tokio::test]
+    async fn test_builder_signature() {
         let submitter = make_submitter();
 
-        let result = submitter
-            .sign_builder_request("POST", "/order", "1700000000000", r#"{"test":"body"}"#)
-            .unwrap();
+        let result = sign_hmac(
+            &submitter.credentials.secret,
+            "POST",
+            "/order",
+            "1700000000000",
+            r#"{"test":"body"}"#,
+        )
+        .unwrap();
 
         // Verify result is valid base64
         let decoded = BASE64.decode(&result);
@@ -458,21 +684,46 @@ mod tests {
         assert_eq!(bytes.len(), 32, "HMAC-SHA256 should produce 32 bytes");
 
         // Same inputs should produce the same signature (deterministic)
-        let result2 = submitter
-            .sign_builder_request("POST", "/order", "1700000000000", r#"{"test":"body"}"#)
-            .unwrap();
+        let result2 = sign_hmac(
+            &submitter.credentials.secret,
+            "POST",
+            "/order",
+            "1700000000000",
+            r#"{"test":"body"}"#,
+        )
+        .unwrap();
         assert_eq!(result, result2, "HMAC should be deterministic");
 
         // Different inputs should produce different signatures
-        let result3 = submitter
-            .sign_builder_request("GET", "/order", "1700000000000", r#"{"test":"body"}"#)
-            .unwrap();
+        let result3 = sign_hmac(
+            &submitter.credentials.secret,
+            "GET",
+            "/order",
+            "1700000000000",
+            r#"{"test":"body"}"#,
+        )
+        .unwrap();
         assert_ne!(
             result, result3,
             "different method should produce different signature"
         );
     }
 
+    #[test]
+    fn test_builder_headers_attribute_without_l2_auth() {
+        let credentials = BuilderCredentials {
+            api_key: "builder-key".into(),
+            secret: "c2VjcmV0".into(),
+            passphrase: "builder-pass".into(),
+        };
+        let headers = builder_headers(&credentials, "POST", "/order", "{}").unwrap();
+        assert_eq!(headers["POLY_BUILDER_API_KEY"], "builder-key");
+        assert_eq!(headers["POLY_BUILDER_PASSPHRASE"], "builder-pass");
+        assert!(headers.contains_key("POLY_BUILDER_SIGNATURE"));
+        assert!(headers.contains_key("POLY_BUILDER_TIMESTAMP"));
+        assert!(!headers.contains_key("POLY_API_KEY"));
+    }
+
     #[test]
     fn test_now_secs() {
         let ts = now_secs();
@@ -485,4 +736,32 @@ mod tests {
             "timestamp {ts} should be before year 2065"
         );
     }
+
+    #[test]
+    fn test_exchange_address_follows_neg_risk_flag() {
+        let network = NetworkProfile::polygon();
+        assert_eq!(
+            exchange_address(&network, true),
+            address!("C5d563A36AE78145C45a50134d48A1215220f80a")
+        );
+        assert_eq!(
+            exchange_address(&network, false),
+            address!("4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E")
+        );
+    }
+
+    #[test]
+    fn test_clob_error_extracts_message_from_json_body() {
+        let err = ClobError::from_response(400, r#"{"error":"not enough balance / allowance"}"#);
+        assert_eq!(err.rejection, ClobRejection::InsufficientBalance);
+        assert_eq!(err.message, "not enough balance / allowance");
+
+        let err = ClobError::from_response(429, "Too Many Requests");
+        assert_eq!(err.rejection, ClobRejection::RateLimited);
+        assert_eq!(err.message, "Too Many Requests");
+        assert!(err.is_retryable());
+
+        assert!(ClobError::from_response(502, "Bad Gateway").is_retryable());
+        assert!(!ClobError::from_response(400, r#"{"error":"invalid order"}"#).is_retryable());
+    }
 }
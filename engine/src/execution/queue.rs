@@ -181,6 +181,10 @@ mod tests {
             leader_address: String::new(),
             leader_tx_hash: String::new(),
             is_paper: false,
+            market: None,
+            algo: None,
+            parent_id: None,
+            closes: None,
         }
     }
 
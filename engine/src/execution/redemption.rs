@@ -0,0 +1,238 @@
+use std::collections::{BTreeMap, HashSet};
+
+use alloy::primitives::{Address, B256, U256};
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use anyhow::{Context, Result};
+
+use super::balances::DataApiPosition;
+use super::relayer::{RelayerClient, SafeTxSubmission};
+use super::safe_tx::SafeCall;
+use crate::network::NetworkProfile;
+
+/// Conditional tokens use 6 decimals, like USDC.
+const TOKEN_UNIT: f64 = 1_000_000.0;
+
+sol! {
+    interface IConditionalTokens {
+        function redeemPositions(
+            address collateralToken,
+            bytes32 parentCollectionId,
+            bytes32 conditionId,
+            uint256[] indexSets
+        );
+    }
+
+    interface INegRiskAdapter {
+        function redeemPositions(bytes32 conditionId, uint256[] amounts);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// RedemptionPlan
+// ---------------------------------------------------------------------------
+
+/// One `redeemPositions` call: every token the Safe holds in a resolved market.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RedemptionPlan {
+    pub condition_id: String,
+    pub neg_risk: bool,
+    /// Token units held per outcome index (only the neg-risk adapter needs them).
+    pub amounts: [u128; 2],
+    /// USDC the winning tokens pay out.
+    pub expected_usdc: f64,
+}
+
+impl RedemptionPlan {
+    /// Contract the Safe calls: the CTF itself, or the neg-risk adapter.
+    pub fn target(&self, network: &NetworkProfile) -> Address {
+        if self.neg_risk {
+            network.neg_risk_adapter
+        } else {
+            network.conditional_tokens
+        }
+    }
+
+    pub fn calldata(&self, network: &NetworkProfile) -> Result<Vec<u8>> {
+        let condition_id: B256 = self
+            .condition_id
+            .parse()
+            .with_context(|| format!("invalid condition id {}", self.condition_id))?;
+        let data = if self.neg_risk {
+            INegRiskAdapter::redeemPositionsCall {
+                conditionId: condition_id,
+                amounts: self.amounts.iter().map(|&a| U256::from(a)).collect(),
+            }
+            .abi_encode()
+        } else {
+            // Binary markets: index sets 0b01 and 0b10 cover both outcomes.
+            IConditionalTokens::redeemPositionsCall {
+                collateralToken: network.usdc,
+                parentCollectionId: B256::ZERO,
+                conditionId: condition_id,
+                indexSets: vec![U256::from(1), U256::from(2)],
+            }
+            .abi_encode()
+        };
+        Ok(data)
+    }
+}
+
+/// Group the Safe's redeemable positions into one plan per resolved market.
+///
+/// Markets in `skip` (already redeemed or in flight) and markets where only
+/// losing tokens are left are ignored.
+pub fn plan_redemptions(
+    positions: &[DataApiPosition],
+    skip: &HashSet<String>,
+) -> Vec<RedemptionPlan> {
+    let mut plans: BTreeMap<&str, RedemptionPlan> = BTreeMap::new();
+    for p in positions {
+        if !p.redeemable || p.size <= 0.0 || p.condition_id.is_empty() {
+            continue;
+        }
+        if skip.contains(&p.condition_id) {
+            continue;
+        }
+        let plan = plans
+            .entry(p.condition_id.as_str())
+            .or_insert_with(|| RedemptionPlan {
+                condition_id: p.condition_id.clone(),
+                neg_risk: p.negative_risk,
+                amounts: [0, 0],
+                expected_usdc: 0.0,
+            });
+        let index = p.outcome_index.unwrap_or(0).min(1) as usize;
+        plan.amounts[index] += (p.size * TOKEN_UNIT).floor() as u128;
+        plan.expected_usdc += p.current_value;
+    }
+    plans
+        .into_values()
+        .filter(|plan| plan.expected_usdc > 0.0)
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Relayer round trip
+// ---------------------------------------------------------------------------
+
+/// Submit the plan's `redeemPositions` call from the wallet's Safe.
+pub async fn submit_redemption(
+    relayer: &RelayerClient,
+    wallet_id: u64,
+    plan: &RedemptionPlan,
+) -> Result<SafeTxSubmission> {
+    let network = relayer.network();
+    let call = SafeCall::redeem(
+        plan.target(network),
+        plan.calldata(network)?,
+        plan.expected_usdc,
+        &plan.condition_id,
+    );
+    relayer.execute(wallet_id, &call).await
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::relayer::test_support::{spawn_mock_relayer, test_relayer};
+
+    const CONDITION: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+
+    fn position(condition_id: &str, index: u32, size: f64, value: f64) -> DataApiPosition {
+        DataApiPosition {
+            asset: format!("{condition_id}-{index}"),
+            size,
+            slug: "btc-updown-15m-1700000000".into(),
+            outcome: None,
+            condition_id: condition_id.into(),
+            outcome_index: Some(index),
+            redeemable: true,
+            negative_risk: false,
+            current_value: value,
+        }
+    }
+
+    #[test]
+    fn test_plan_groups_by_condition_and_skips_losers() {
+        let loser = "0x2222222222222222222222222222222222222222222222222222222222222222";
+        let mut open = position(CONDITION, 0, 5.0, 2.5);
+        open.redeemable = false;
+        let positions = vec![
+            position(CONDITION, 0, 10.0, 10.0),
+            position(CONDITION, 1, 4.0, 0.0),
+            position(loser, 1, 8.0, 0.0),
+            open,
+        ];
+
+        let plans = plan_redemptions(&positions, &HashSet::new());
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].condition_id, CONDITION);
+        assert_eq!(plans[0].amounts, [10_000_000, 4_000_000]);
+        assert!((plans[0].expected_usdc - 10.0).abs() < 1e-9);
+
+        let skip = HashSet::from([CONDITION.to_string()]);
+        assert!(plan_redemptions(&positions, &skip).is_empty());
+    }
+
+    #[test]
+    fn test_calldata_targets_ctf_or_neg_risk_adapter() {
+        let network = NetworkProfile::polygon();
+        let mut plan = RedemptionPlan {
+            condition_id: CONDITION.into(),
+            neg_risk: false,
+            amounts: [10_000_000, 0],
+            expected_usdc: 10.0,
+        };
+        assert_eq!(plan.target(&network), network.conditional_tokens);
+        let data = plan.calldata(&network).unwrap();
+        assert_eq!(data[..4], IConditionalTokens::redeemPositionsCall::SELECTOR);
+        let decoded = IConditionalTokens::redeemPositionsCall::abi_decode(&data).unwrap();
+        assert_eq!(decoded.conditionId, CONDITION.parse::<B256>().unwrap());
+        assert_eq!(decoded.collateralToken, network.usdc);
+        assert_eq!(decoded.indexSets, vec![U256::from(1), U256::from(2)]);
+
+        plan.neg_risk = true;
+        assert_eq!(plan.target(&network), network.neg_risk_adapter);
+        let data = plan.calldata(&network).unwrap();
+        let decoded = INegRiskAdapter::redeemPositionsCall::abi_decode(&data).unwrap();
+        assert_eq!(decoded.amounts, vec![U256::from(10_000_000u64), U256::ZERO]);
+    }
+
+    #[tokio::test]
+    async fn test_redemption_round_trip_against_mock_relayer() {
+        let (url, submitted) = spawn_mock_relayer().await;
+        let (relayer, _, safe) = test_relayer(&url);
+
+        let plan = RedemptionPlan {
+            condition_id: CONDITION.into(),
+            neg_risk: false,
+            amounts: [10_000_000, 0],
+            expected_usdc: 10.0,
+        };
+        let submission = submit_redemption(&relayer, 1, &plan).await.unwrap();
+        assert_eq!(submission.transaction_id, "tx-1");
+
+        let body = submitted.lock().unwrap()[0].clone();
+        assert_eq!(body["proxyWallet"], format!("{safe:?}"));
+        assert_eq!(
+            body["to"].as_str().unwrap().to_lowercase(),
+            format!("{:?}", relayer.network().conditional_tokens).to_lowercase()
+        );
+        assert_eq!(
+            body["data"],
+            format!(
+                "0x{}",
+                hex::encode(plan.calldata(relayer.network()).unwrap())
+            )
+        );
+        assert_eq!(
+            body["metadata"],
+            format!("Redeem positions for {CONDITION}")
+        );
+    }
+}
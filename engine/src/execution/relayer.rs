@@ -1,45 +1,23 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use alloy::primitives::{keccak256, Address, Bytes, U256};
+use alloy::primitives::{keccak256, Address, Bytes, B256, U256};
 use alloy::signers::SignerSync;
 use alloy::sol;
-use alloy::sol_types::{eip712_domain, SolStruct};
+use alloy::sol_types::{eip712_domain, SolCall, SolStruct};
 use anyhow::{Context, Result};
-use base64::engine::general_purpose::{
-    URL_SAFE as BASE64_URL, URL_SAFE_NO_PAD as BASE64_URL_NOPAD,
-};
-use base64::Engine as _;
-use hmac::{Hmac, Mac};
-use reqwest::header::{HeaderMap, HeaderValue};
 use serde::{Deserialize, Serialize};
-use sha2::Sha256;
+use sqlx::PgPool;
+use tokio::sync::Mutex;
 use tracing::{debug, warn};
 
-use super::orders::BuilderCredentials;
+use super::orders::{builder_headers, BuilderCredentials};
+use super::safe_tx::SafeCall;
 use super::wallet::WalletKeyStore;
+use crate::network::NetworkProfile;
 use crate::proxy::HttpPool;
 
-// ---------------------------------------------------------------------------
-// Polygon contract addresses & constants
-// ---------------------------------------------------------------------------
-
-const SAFE_FACTORY: &str = "0xaacFeEa03eb1561C4e67d661e40682Bd20E3541b";
-/// USDC.e on Polygon
-const USDC_ADDRESS: &str = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174";
-const NEG_RISK_EXCHANGE: &str = "0xC5d563A36AE78145C45a50134d48A1215220f80a";
-const CTF_EXCHANGE: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
-/// Polygon chain ID
-const CHAIN_ID: u64 = 137;
-/// Init code hash for CREATE2 Safe derivation (from Polymarket SDK)
-const SAFE_INIT_CODE_HASH: [u8; 32] = {
-    // 0x2bce2127ff07fb632d16c8347c4ebf501f4841168bed00d9e6ef715ddb6fcecf
-    [
-        0x2b, 0xce, 0x21, 0x27, 0xff, 0x07, 0xfb, 0x63, 0x2d, 0x16, 0xc8, 0x34, 0x7c, 0x4e, 0xbf,
-        0x50, 0x1f, 0x48, 0x41, 0x16, 0x8b, 0xed, 0x00, 0xd9, 0xe6, 0xef, 0x71, 0x5d, 0xdb, 0x6f,
-        0xce, 0xcf,
-    ]
-};
-
 // ---------------------------------------------------------------------------
 // EIP-712 CreateProxy type (matches Polymarket Safe Factory)
 // ---------------------------------------------------------------------------
@@ -51,6 +29,10 @@ sol! {
         uint256 payment;
         address paymentReceiver;
     }
+
+    interface ISafe {
+        function nonce() view returns (uint256);
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -63,10 +45,9 @@ struct NoncePayload {
 }
 
 #[derive(Debug, Deserialize)]
-struct RelayPayload {
-    #[allow(dead_code)]
-    address: String,
-    nonce: String,
+struct RpcResponse {
+    result: Option<String>,
+    error: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -87,11 +68,36 @@ pub struct RelayerTransaction {
     pub proxy_address: Option<String>,
 }
 
+/// Coarse lifecycle of a relayer transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelayerTxState {
+    Pending,
+    Confirmed,
+    Failed,
+}
+
+impl RelayerTxState {
+    pub fn parse(state: &str) -> Self {
+        match state {
+            "STATE_MINED" | "STATE_CONFIRMED" | "STATE_EXECUTED" => Self::Confirmed,
+            "STATE_FAILED" | "STATE_INVALID" => Self::Failed,
+            _ => Self::Pending,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct DeployedResponse {
     deployed: bool,
 }
 
+/// A Safe transaction accepted by the relayer.
+#[derive(Debug, Clone)]
+pub struct SafeTxSubmission {
+    pub transaction_id: String,
+    pub nonce: u64,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct SafeDeployResult {
     pub safe_address: String,
@@ -104,34 +110,53 @@ pub struct SafeDeployResult {
 
 pub struct RelayerClient {
     http: HttpPool,
+    network: Arc<NetworkProfile>,
     relayer_url: String,
     credentials: BuilderCredentials,
     wallet_keys: Arc<WalletKeyStore>,
+    db: PgPool,
+    /// Submissions per Safe, as `(transaction id, nonce)`, that the chain may
+    /// not count yet: its nonce only advances once a transaction is mined, so
+    /// back-to-back submissions are tracked here until one fails. Seeded from
+    /// the persisted `safe_transactions` when a Safe is first used.
+    nonces: Mutex<HashMap<Address, Vec<(String, u64)>>>,
 }
 
 impl RelayerClient {
     pub fn new(
         http: HttpPool,
-        relayer_url: &str,
+        network: Arc<NetworkProfile>,
         credentials: BuilderCredentials,
         wallet_keys: Arc<WalletKeyStore>,
+        db: PgPool,
     ) -> Self {
         Self {
             http,
-            relayer_url: relayer_url.trim_end_matches('/').to_string(),
+            relayer_url: network.relayer_url.trim_end_matches('/').to_string(),
+            network,
             credentials,
             wallet_keys,
+            db,
+            nonces: Mutex::new(HashMap::new()),
         }
     }
 
+    pub fn network(&self) -> &NetworkProfile {
+        &self.network
+    }
+
     /// Deploy a Gnosis Safe for the given wallet, then approve USDC on both exchanges.
     pub async fn deploy_safe(&self, wallet_id: u64) -> Result<SafeDeployResult> {
         let signer = self.wallet_keys.get_signer(wallet_id)?;
         let signer_address = signer.address();
 
         // 1. Derive the Safe address via CREATE2 (deterministic, before on-chain deployment)
-        let safe_factory: Address = SAFE_FACTORY.parse().unwrap();
-        let proxy_wallet = derive_safe_address(&signer_address, &safe_factory);
+        let safe_factory = self.network.safe_factory;
+        let proxy_wallet = derive_safe_address(
+            &signer_address,
+            &safe_factory,
+            &self.network.safe_init_code_hash,
+        );
         debug!(%wallet_id, ?signer_address, ?proxy_wallet, "deploying_safe");
 
         // 2. Check if already deployed (using derived Safe address)
@@ -148,7 +173,7 @@ impl RelayerClient {
         // 3. Sign EIP-712 CreateProxy typed data
         let domain = eip712_domain! {
             name: "Polymarket Contract Proxy Factory",
-            chain_id: CHAIN_ID,
+            chain_id: self.network.chain_id,
             verifying_contract: safe_factory,
         };
 
@@ -169,7 +194,7 @@ impl RelayerClient {
         let create_payload = serde_json::json!({
             "type": "SAFE-CREATE",
             "from": format!("{:?}", signer_address),
-            "to": SAFE_FACTORY,
+            "to": format!("{:?}", safe_factory),
             "proxyWallet": format!("{:?}", proxy_wallet),
             "data": "0x",
             "signature": sig_hex,
@@ -198,7 +223,7 @@ impl RelayerClient {
         self.wallet_keys.store_safe_address(wallet_id, addr)?;
 
         // 6. Approve USDC on both CTF and NegRisk exchanges
-        self.approve_usdc(wallet_id, &safe_address).await?;
+        self.approve_usdc(wallet_id).await?;
 
         Ok(SafeDeployResult {
             safe_address,
@@ -206,55 +231,116 @@ impl RelayerClient {
         })
     }
 
-    /// Approve USDC spending for both CTF and NegRisk exchanges.
-    async fn approve_usdc(&self, wallet_id: u64, safe_address: &str) -> Result<()> {
-        debug!(%wallet_id, %safe_address, "approving_usdc");
+    /// Approve max USDC spending for both CTF and NegRisk exchanges.
+    async fn approve_usdc(&self, wallet_id: u64) -> Result<()> {
+        debug!(%wallet_id, "approving_usdc");
 
-        let safe_addr: Address = safe_address.parse()?;
-        let relay = self.get_relay_payload(&safe_addr).await?;
-
-        // ERC20 approve(address,uint256) selector = 0x095ea7b3
-        // Approve max uint256 for both exchanges
-        let max_uint = "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
-        let ctf_padded = format!("{:0>64}", &CTF_EXCHANGE[2..]);
-        let neg_risk_padded = format!("{:0>64}", &NEG_RISK_EXCHANGE[2..]);
+        for exchange in [self.network.ctf_exchange, self.network.neg_risk_exchange] {
+            let call = SafeCall::usdc_approve(&self.network, exchange, None)?;
+            let submission = self.execute(wallet_id, &call).await?;
+            self.poll_transaction(&submission.transaction_id).await?;
+        }
 
-        let approve_ctf_data = format!("0x095ea7b3{ctf_padded}{max_uint}");
-        let approve_neg_data = format!("0x095ea7b3{neg_risk_padded}{max_uint}");
+        debug!(%wallet_id, "usdc_approved_for_both_exchanges");
+        Ok(())
+    }
 
-        // Submit CTF approval
-        let payload = serde_json::json!({
-            "type": "SAFE",
-            "from": safe_address,
-            "to": USDC_ADDRESS,
-            "data": approve_ctf_data,
-            "signature": "",
-            "signatureParams": {},
-            "nonce": relay.nonce,
-            "metadata": "USDC approval for CTF Exchange"
-        });
+    /// Sign `call` with the wallet's owner key and submit it for execution by
+    /// the wallet's Safe. See [`Self::transaction`] for its status.
+    ///
+    /// Submissions are serialized so that each one gets its own Safe nonce:
+    /// the Safe's on-chain nonce (or the relayer's, if ahead) past any
+    /// submission still pending.
+    pub async fn execute(&self, wallet_id: u64, call: &SafeCall) -> Result<SafeTxSubmission> {
+        let signer = self.wallet_keys.get_signer(wallet_id)?;
+        let safe = self.wallet_keys.get_safe_address(wallet_id)?;
 
-        let tx_id = self.submit_transaction(&payload).await?;
-        self.poll_transaction(&tx_id).await?;
+        let mut nonces = self.nonces.lock().await;
+        let chain_nonce = self.safe_nonce(&safe).await?;
+        let relayer_nonce: u64 = self
+            .get_nonce(&signer.address())
+            .await?
+            .parse()
+            .context("relayer returned a non-numeric nonce")?;
+        if let Entry::Vacant(slot) = nonces.entry(safe) {
+            match crate::storage::postgres::load_submitted_safe_nonces(&self.db, wallet_id as i64)
+                .await
+            {
+                Ok(submitted) => {
+                    slot.insert(
+                        submitted
+                            .into_iter()
+                            .map(|(id, n)| (id, n.max(0) as u64))
+                            .collect(),
+                    );
+                }
+                Err(e) => warn!(%wallet_id, error = %e, "submitted_safe_nonces_load_failed"),
+            }
+        }
+        let floor = chain_nonce.max(relayer_nonce);
+        let pending = nonces.entry(safe).or_default();
+        pending.retain(|&(_, n)| n >= floor);
+        let nonce = pending.iter().map(|&(_, n)| n + 1).fold(floor, u64::max);
 
-        // Submit NegRisk approval
-        let relay2 = self.get_relay_payload(&safe_addr).await?;
-        let payload2 = serde_json::json!({
+        let signature = call.sign(&signer, self.network.chain_id, safe, nonce)?;
+        let payload = serde_json::json!({
             "type": "SAFE",
-            "from": safe_address,
-            "to": USDC_ADDRESS,
-            "data": approve_neg_data,
-            "signature": "",
-            "signatureParams": {},
-            "nonce": relay2.nonce,
-            "metadata": "USDC approval for NegRisk Exchange"
+            "from": format!("{:?}", signer.address()),
+            "to": format!("{:?}", call.to),
+            "proxyWallet": format!("{:?}", safe),
+            "data": format!("0x{}", hex::encode(&call.data)),
+            "nonce": nonce.to_string(),
+            "signature": signature,
+            "signatureParams": {
+                "gasPrice": "0",
+                "operation": "0",
+                "safeTxnGas": "0",
+                "baseGas": "0",
+                "gasToken": format!("{:?}", Address::ZERO),
+                "refundReceiver": format!("{:?}", Address::ZERO)
+            },
+            "metadata": call.description
         });
+        let transaction_id = self.submit_transaction(&payload).await?;
+        nonces
+            .entry(safe)
+            .or_default()
+            .push((transaction_id.clone(), nonce));
+
+        debug!(%wallet_id, ?safe, nonce, kind = call.kind.as_str(), %transaction_id, "safe_tx_submitted");
+        Ok(SafeTxSubmission {
+            transaction_id,
+            nonce,
+        })
+    }
 
-        let tx_id2 = self.submit_transaction(&payload2).await?;
-        self.poll_transaction(&tx_id2).await?;
-
-        debug!(%wallet_id, "usdc_approved_for_both_exchanges");
-        Ok(())
+    /// Current state of a relayer transaction, or `None` if the relayer does
+    /// not know it (yet).
+    pub async fn transaction(&self, tx_id: &str) -> Result<Option<RelayerTransaction>> {
+        let url = format!("{}/transaction?id={}", self.relayer_url, tx_id);
+        let txs: Vec<RelayerTransaction> = self
+            .http
+            .proxied()
+            .get(&url)
+            .send()
+            .await
+            .context("relayer transaction request failed")?
+            .json()
+            .await
+            .context("failed to parse relayer transaction")?;
+        let tx = txs.into_iter().next();
+
+        // A failed transaction leaves its nonce unused: fall back to the relayer's.
+        if tx
+            .as_ref()
+            .is_some_and(|tx| RelayerTxState::parse(&tx.state) == RelayerTxState::Failed)
+        {
+            self.nonces
+                .lock()
+                .await
+                .retain(|_, pending| !pending.iter().any(|(id, _)| id == tx_id));
+        }
+        Ok(tx)
     }
 
     // -----------------------------------------------------------------------
@@ -267,39 +353,50 @@ impl RelayerClient {
         Ok(resp.deployed)
     }
 
-    #[allow(dead_code)]
+    /// Next Safe nonce for the owner `address`.
     async fn get_nonce(&self, address: &Address) -> Result<String> {
         let url = format!("{}/nonce?address={:?}&type=SAFE", self.relayer_url, address);
         let resp: NoncePayload = self.http.proxied().get(&url).send().await?.json().await?;
         Ok(resp.nonce)
     }
 
-    async fn get_relay_payload(&self, address: &Address) -> Result<RelayPayload> {
-        let url = format!(
-            "{}/relay-payload?address={:?}&type=SAFE",
-            self.relayer_url, address
-        );
-        let resp: RelayPayload = self.http.proxied().get(&url).send().await?.json().await?;
-        Ok(resp)
+    /// Nonce of the Safe contract at `safe`, read from the chain.
+    async fn safe_nonce(&self, safe: &Address) -> Result<u64> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_call",
+            "params": [{
+                "to": format!("{safe:?}"),
+                "data": format!("0x{}", hex::encode(ISafe::nonceCall {}.abi_encode())),
+            }, "latest"],
+        });
+        let resp: RpcResponse = self
+            .http
+            .direct()
+            .post(&self.network.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .context("Safe nonce RPC request failed")?
+            .json()
+            .await
+            .context("failed to parse Safe nonce RPC response")?;
+        if let Some(error) = resp.error {
+            anyhow::bail!("Safe nonce RPC call failed: {error}");
+        }
+        let result = resp
+            .result
+            .context("Safe nonce RPC response has no result")?;
+        let nonce = U256::from_str_radix(result.trim_start_matches("0x"), 16)
+            .context("Safe nonce RPC returned a non-numeric result")?;
+        u64::try_from(nonce).context("Safe nonce out of range")
     }
 
     async fn submit_transaction(&self, payload: &serde_json::Value) -> Result<String> {
         let body = serde_json::to_string(payload)?;
 
-        let timestamp = now_secs().to_string();
-        let hmac_sig = self.sign_request("POST", "/submit", &timestamp, &body)?;
-
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            "POLY_BUILDER_API_KEY",
-            HeaderValue::from_str(&self.credentials.api_key)?,
-        );
-        headers.insert("POLY_BUILDER_SIGNATURE", HeaderValue::from_str(&hmac_sig)?);
-        headers.insert("POLY_BUILDER_TIMESTAMP", HeaderValue::from_str(&timestamp)?);
-        headers.insert(
-            "POLY_BUILDER_PASSPHRASE",
-            HeaderValue::from_str(&self.credentials.passphrase)?,
-        );
+        let headers = builder_headers(&self.credentials, "POST", "/submit", &body)?;
 
         let url = format!("{}/submit", self.relayer_url);
         let resp = self
@@ -326,72 +423,39 @@ impl RelayerClient {
         for attempt in 1..=60 {
             tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 
-            let url = format!("{}/transaction?id={}", self.relayer_url, tx_id);
-            let resp = match self.http.proxied().get(&url).send().await {
-                Ok(r) => r,
+            let tx = match self.transaction(tx_id).await {
+                Ok(Some(tx)) => tx,
+                Ok(None) => continue,
                 Err(e) => {
                     warn!(attempt, %tx_id, error = %e, "relayer_poll_failed");
                     continue;
                 }
             };
 
-            let txs: Vec<RelayerTransaction> = match resp.json().await {
-                Ok(t) => t,
-                Err(e) => {
-                    warn!(attempt, %tx_id, error = %e, "relayer_poll_parse_failed");
-                    continue;
+            match RelayerTxState::parse(&tx.state) {
+                RelayerTxState::Confirmed => {
+                    debug!(attempt, %tx_id, state = %tx.state, "relayer_tx_confirmed");
+                    return Ok(tx);
                 }
-            };
-
-            if let Some(tx) = txs.first() {
-                match tx.state.as_str() {
-                    "STATE_MINED" | "STATE_CONFIRMED" | "STATE_EXECUTED" => {
-                        debug!(attempt, %tx_id, state = %tx.state, "relayer_tx_confirmed");
-                        return Ok(tx.clone());
-                    }
-                    "STATE_FAILED" | "STATE_INVALID" => {
-                        anyhow::bail!(
-                            "relayer transaction {tx_id} failed with state: {}",
-                            tx.state
-                        );
-                    }
-                    _ => {
-                        debug!(attempt, %tx_id, state = %tx.state, "relayer_tx_pending");
-                    }
+                RelayerTxState::Failed => {
+                    anyhow::bail!(
+                        "relayer transaction {tx_id} failed with state: {}",
+                        tx.state
+                    );
+                }
+                RelayerTxState::Pending => {
+                    debug!(attempt, %tx_id, state = %tx.state, "relayer_tx_pending");
                 }
             }
         }
 
         anyhow::bail!("relayer transaction {tx_id} poll timed out after 120s");
     }
-
-    /// HMAC-SHA256 signature for builder auth.
-    fn sign_request(
-        &self,
-        method: &str,
-        path: &str,
-        timestamp: &str,
-        body: &str,
-    ) -> Result<String> {
-        let secret_bytes = BASE64_URL
-            .decode(&self.credentials.secret)
-            .or_else(|_| BASE64_URL_NOPAD.decode(&self.credentials.secret))
-            .context("builder_secret is not valid base64 (url-safe)")?;
-
-        let mut mac =
-            Hmac::<Sha256>::new_from_slice(&secret_bytes).context("HMAC key creation failed")?;
-
-        let message = format!("{timestamp}{method}{path}{body}");
-        mac.update(message.as_bytes());
-
-        let result = mac.finalize().into_bytes();
-        Ok(BASE64_URL.encode(result))
-    }
 }
 
 /// Derive Safe address via CREATE2 (deterministic, before on-chain deployment).
 /// Matches the Polymarket SDK `deriveSafe(address, safeFactory)`.
-fn derive_safe_address(owner: &Address, factory: &Address) -> Address {
+fn derive_safe_address(owner: &Address, factory: &Address, init_code_hash: &B256) -> Address {
     // salt = keccak256(abi.encode(owner))
     let mut salt_input = [0u8; 32];
     salt_input[12..].copy_from_slice(owner.as_slice());
@@ -402,15 +466,123 @@ fn derive_safe_address(owner: &Address, factory: &Address) -> Address {
     create2_input.push(0xff);
     create2_input.extend_from_slice(factory.as_slice());
     create2_input.extend_from_slice(salt.as_slice());
-    create2_input.extend_from_slice(&SAFE_INIT_CODE_HASH);
+    create2_input.extend_from_slice(init_code_hash.as_slice());
 
     let hash = keccak256(Bytes::from(create2_input));
     Address::from_slice(&hash[12..])
 }
 
-fn now_secs() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .expect("system clock before UNIX epoch")
-        .as_secs()
+// ---------------------------------------------------------------------------
+// Test support
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use std::sync::Mutex as StdMutex;
+
+    use alloy::signers::local::PrivateKeySigner;
+    use axum::extract::State;
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+
+    use super::*;
+
+    pub type Submitted = Arc<StdMutex<Vec<serde_json::Value>>>;
+
+    /// A relayer that reports nonce 7, accepts every submission as `tx-1`
+    /// and reports it confirmed with hash `0xabc`.
+    pub async fn spawn_mock_relayer() -> (String, Submitted) {
+        spawn_mock_relayer_with_state("STATE_CONFIRMED").await
+    }
+
+    /// Like [`spawn_mock_relayer`], with `tx-1` reported in `state`.
+    pub async fn spawn_mock_relayer_with_state(state: &'static str) -> (String, Submitted) {
+        spawn_mock_relayer_on_chain(state, 7).await
+    }
+
+    /// Like [`spawn_mock_relayer_with_state`], also answering `eth_call`s for
+    /// the Safe's nonce with `chain_nonce`.
+    pub async fn spawn_mock_relayer_on_chain(
+        state: &'static str,
+        chain_nonce: u64,
+    ) -> (String, Submitted) {
+        let submitted: Submitted = Arc::default();
+        let app =
+            Router::new()
+                .route(
+                    "/nonce",
+                    get(|| async { Json(serde_json::json!({"nonce": "7"})) }),
+                )
+                .route(
+                    "/",
+                    post(move || async move {
+                        Json(serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "id": 1,
+                            "result": format!("0x{chain_nonce:064x}")
+                        }))
+                    }),
+                )
+                .route(
+                    "/submit",
+                    post(
+                        |State(submitted): State<Submitted>,
+                         Json(body): Json<serde_json::Value>| async move {
+                            submitted.lock().unwrap().push(body);
+                            Json(serde_json::json!({"transactionID": "tx-1"}))
+                        },
+                    ),
+                )
+                .route(
+                    "/transaction",
+                    get(move || async move {
+                        Json(serde_json::json!([{
+                            "transactionID": "tx-1",
+                            "transactionHash": "0xabc",
+                            "state": state
+                        }]))
+                    }),
+                )
+                .with_state(submitted.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{addr}"), submitted)
+    }
+
+    /// A client for wallet 1 with a random owner key and Safe `0x…aa`.
+    pub fn test_relayer(url: &str) -> (RelayerClient, PrivateKeySigner, Address) {
+        let network = NetworkProfile {
+            relayer_url: url.into(),
+            rpc_url: url.into(),
+            ..NetworkProfile::polygon()
+        };
+        let wallet_keys = Arc::new(
+            WalletKeyStore::new("0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef")
+                .unwrap(),
+        );
+        let signer = PrivateKeySigner::random();
+        let encrypted = wallet_keys.encrypt_key(&signer.to_bytes().0).unwrap();
+        wallet_keys.store_key(1, &encrypted).unwrap();
+        let safe: Address = "0x00000000000000000000000000000000000000aa"
+            .parse()
+            .unwrap();
+        wallet_keys.store_safe_address(1, safe).unwrap();
+
+        let relayer = RelayerClient::new(
+            HttpPool::new(&[], std::time::Duration::from_secs(5)).unwrap(),
+            Arc::new(network),
+            BuilderCredentials {
+                api_key: "key".into(),
+                secret: "c2VjcmV0".into(),
+                passphrase: "pass".into(),
+            },
+            wallet_keys,
+            sqlx::postgres::PgPoolOptions::new()
+                .acquire_timeout(std::time::Duration::from_millis(200))
+                .connect_lazy("postgres://localhost:1/test")
+                .unwrap(),
+        );
+        (relayer, signer, safe)
+    }
 }
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use super::orders::ClobError;
+use crate::config::Config;
+
+// ---------------------------------------------------------------------------
+// RetryPolicy
+// ---------------------------------------------------------------------------
+
+/// Exponential backoff for order submissions that failed transiently.
+///
+/// Retries reuse the order's id, which is its EIP-712 salt, so a resubmission
+/// re-signs the identical CLOB order and can never fill twice.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Resubmissions allowed after the first attempt; 0 disables retries.
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(cfg: &Config) -> Self {
+        Self {
+            max_retries: cfg.order_max_retries,
+            base_delay: Duration::from_millis(cfg.order_retry_base_ms),
+            max_delay: Duration::from_millis(cfg.order_retry_max_ms),
+        }
+    }
+
+    /// Delay before the next attempt after `attempts` failed submissions,
+    /// or `None` once the retry budget is spent.
+    pub fn backoff(&self, attempts: u32) -> Option<Duration> {
+        if attempts == 0 || attempts > self.max_retries {
+            return None;
+        }
+        let factor = 1u32.checked_shl(attempts - 1).unwrap_or(u32::MAX);
+        Some(
+            self.base_delay
+                .checked_mul(factor)
+                .unwrap_or(self.max_delay)
+                .min(self.max_delay),
+        )
+    }
+}
+
+/// Whether a submission error is worth retrying: transient CLOB rejections,
+/// 5xx responses, and network or proxy failures that never reached the CLOB.
+pub fn is_retryable(err: &anyhow::Error) -> bool {
+    if let Some(clob) = err.downcast_ref::<ClobError>() {
+        return clob.is_retryable();
+    }
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_timeout() || e.is_connect() || e.is_request())
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::ClobRejection;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(3),
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let p = policy();
+        assert_eq!(p.backoff(1), Some(Duration::from_millis(500)));
+        assert_eq!(p.backoff(2), Some(Duration::from_millis(1000)));
+        assert_eq!(p.backoff(3), Some(Duration::from_millis(2000)));
+        assert_eq!(p.backoff(4), Some(Duration::from_secs(3)), "capped");
+        assert_eq!(p.backoff(5), None, "budget spent");
+    }
+
+    #[test]
+    fn test_zero_retries_never_backs_off() {
+        let p = RetryPolicy {
+            max_retries: 0,
+            ..policy()
+        };
+        assert_eq!(p.backoff(1), None);
+    }
+
+    #[test]
+    fn test_is_retryable_classification() {
+        let rate_limited = anyhow::Error::new(ClobError {
+            rejection: ClobRejection::RateLimited,
+            http_status: 429,
+            message: String::new(),
+        });
+        assert!(is_retryable(&rate_limited));
+
+        let no_balance = anyhow::Error::new(ClobError {
+            rejection: ClobRejection::InsufficientBalance,
+            http_status: 400,
+            message: "not enough balance".into(),
+        });
+        assert!(!is_retryable(&no_balance));
+
+        assert!(!is_retryable(&anyhow::anyhow!(
+            "failed to sign EIP-712 hash"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_connection_failure_is_retryable() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let err = reqwest::get(format!("http://{addr}/order"))
+            .await
+            .map_err(anyhow::Error::new)
+            .map(|_| ())
+            .unwrap_err()
+            .context("order submission HTTP request failed");
+        assert!(is_retryable(&err));
+    }
+}
@@ -0,0 +1,576 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::{ExecutionOrder, OrderResult, OrderStatus, Side};
+use crate::config::Config;
+use crate::storage::postgres::{self, DailyNotional, OpenExposure};
+use crate::strategy::Outcome;
+
+const EXPOSURE_EPSILON: f64 = 1e-6;
+
+// ---------------------------------------------------------------------------
+// RiskLimits
+// ---------------------------------------------------------------------------
+
+/// Pre-trade limits applied to every entry before it reaches the CLOB.
+/// A non-positive limit disables that check.
+#[derive(Debug, Clone)]
+pub struct RiskLimits {
+    pub max_wallet_exposure_usdc: f64,
+    pub max_market_exposure_usdc: f64,
+    pub max_slot_exposure_usdc: f64,
+    pub max_open_positions: usize,
+    pub max_daily_notional_usdc: f64,
+    pub min_price: f64,
+    pub min_size_usdc: f64,
+}
+
+impl RiskLimits {
+    pub fn from_config(cfg: &Config) -> Self {
+        Self {
+            max_wallet_exposure_usdc: cfg.risk_max_wallet_exposure_usdc,
+            max_market_exposure_usdc: cfg.risk_max_market_exposure_usdc,
+            max_slot_exposure_usdc: cfg.risk_max_slot_exposure_usdc,
+            max_open_positions: cfg.risk_max_open_positions,
+            max_daily_notional_usdc: cfg.risk_max_daily_notional_usdc,
+            min_price: cfg.risk_min_price,
+            min_size_usdc: cfg.risk_min_size_usdc,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// RiskRejection
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiskRejection {
+    MinSize,
+    MinPrice,
+    StrategyMaxPosition,
+    WalletExposure,
+    MarketExposure,
+    SlotExposure,
+    MaxOpenPositions,
+    DailyNotional,
+}
+
+impl RiskRejection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MinSize => "min_size",
+            Self::MinPrice => "min_price",
+            Self::StrategyMaxPosition => "strategy_max_position",
+            Self::WalletExposure => "wallet_exposure",
+            Self::MarketExposure => "market_exposure",
+            Self::SlotExposure => "slot_exposure",
+            Self::MaxOpenPositions => "max_open_positions",
+            Self::DailyNotional => "daily_notional",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Exposure book
+// ---------------------------------------------------------------------------
+
+/// One open position: a wallet's holding in one slot/outcome, owned by
+/// either a strategy or a copy relationship.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PositionKey {
+    wallet_id: u64,
+    is_paper: bool,
+    symbol: String,
+    outcome: Outcome,
+    strategy_id: Option<u64>,
+    copy_relationship_id: Option<u64>,
+}
+
+impl PositionKey {
+    fn from_order(order: &ExecutionOrder) -> Self {
+        Self {
+            wallet_id: order.wallet_id,
+            is_paper: order.is_paper,
+            symbol: order.symbol.clone(),
+            outcome: order.outcome,
+            strategy_id: order.strategy_id,
+            copy_relationship_id: order.copy_relationship_id,
+        }
+    }
+
+    fn same_wallet(&self, other: &Self) -> bool {
+        self.wallet_id == other.wallet_id && self.is_paper == other.is_paper
+    }
+}
+
+#[derive(Debug, Default)]
+struct ExposureBook {
+    /// Filled entries that have not been exited or resolved yet.
+    filled: HashMap<PositionKey, f64>,
+    /// Entries that passed the check and are waiting on the CLOB.
+    reserved: HashMap<Uuid, (PositionKey, f64)>,
+    /// Entry notional per (wallet, is_paper) for the current UTC day.
+    daily: HashMap<(u64, bool), f64>,
+    day: i64,
+}
+
+impl ExposureBook {
+    fn positions(&self) -> impl Iterator<Item = (&PositionKey, f64)> {
+        self.filled
+            .iter()
+            .map(|(k, v)| (k, *v))
+            .chain(self.reserved.values().map(|(k, v)| (k, *v)))
+    }
+
+    fn exposure(&self, filter: impl Fn(&PositionKey) -> bool) -> f64 {
+        self.positions()
+            .filter(|(k, _)| filter(k))
+            .map(|(_, v)| v)
+            .sum()
+    }
+
+    fn roll_day(&mut self, now: i64) {
+        let day = now.div_euclid(86_400);
+        if day != self.day {
+            self.day = day;
+            self.daily.clear();
+        }
+    }
+}
+
+/// Market prefix of a slot symbol: the asset segment (`btc` in
+/// `btc-updown-15m-1700000000`), shared by every duration of that asset.
+pub fn market_prefix(symbol: &str) -> &str {
+    symbol.split('-').next().unwrap_or(symbol)
+}
+
+// ---------------------------------------------------------------------------
+// RiskEngine
+// ---------------------------------------------------------------------------
+
+/// Pre-trade check stage between the execution queue and order submission.
+///
+/// Only entries are limited — exits always pass so a wallet can reduce risk.
+/// Exposure is the sum of filled entries (refreshed from `trades`) and entries
+/// reserved while in flight.
+pub struct RiskEngine {
+    limits: RiskLimits,
+    book: Mutex<ExposureBook>,
+}
+
+impl RiskEngine {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits,
+            book: Mutex::new(ExposureBook::default()),
+        }
+    }
+
+    /// Check an order against all limits and reserve its notional on success.
+    /// `strategy_cap` is the assignment's `max_position_usdc`, if any.
+    pub fn check(
+        &self,
+        order: &ExecutionOrder,
+        strategy_cap: Option<f64>,
+        now: i64,
+    ) -> Result<(), RiskRejection> {
+        if order.side == Side::Sell {
+            return Ok(());
+        }
+
+        let limits = &self.limits;
+        let size = order.size_usdc;
+        if limits.min_size_usdc > 0.0 && size < limits.min_size_usdc {
+            return Err(RiskRejection::MinSize);
+        }
+        if let Some(price) = order.price.or(order.reference_price) {
+            if limits.min_price > 0.0 && price < limits.min_price {
+                return Err(RiskRejection::MinPrice);
+            }
+        }
+
+        let key = PositionKey::from_order(order);
+        let mut book = self.book.lock().unwrap_or_else(|e| e.into_inner());
+        book.roll_day(now);
+
+        if let (Some(cap), Some(strategy_id)) = (strategy_cap, order.strategy_id) {
+            let held = book.exposure(|k| k.same_wallet(&key) && k.strategy_id == Some(strategy_id));
+            if cap > 0.0 && held + size > cap + EXPOSURE_EPSILON {
+                return Err(RiskRejection::StrategyMaxPosition);
+            }
+        }
+
+        let wallet = book.exposure(|k| k.same_wallet(&key));
+        if exceeds(wallet + size, limits.max_wallet_exposure_usdc) {
+            return Err(RiskRejection::WalletExposure);
+        }
+
+        let prefix = market_prefix(&order.symbol);
+        let market = book.exposure(|k| k.same_wallet(&key) && market_prefix(&k.symbol) == prefix);
+        if exceeds(market + size, limits.max_market_exposure_usdc) {
+            return Err(RiskRejection::MarketExposure);
+        }
+
+        let slot = book.exposure(|k| k.same_wallet(&key) && k.symbol == order.symbol);
+        if exceeds(slot + size, limits.max_slot_exposure_usdc) {
+            return Err(RiskRejection::SlotExposure);
+        }
+
+        if limits.max_open_positions > 0 {
+            let open: HashSet<&PositionKey> = book
+                .positions()
+                .filter(|(k, v)| k.same_wallet(&key) && *v > EXPOSURE_EPSILON)
+                .map(|(k, _)| k)
+                .collect();
+            let adds_position = !open.contains(&key);
+            if adds_position && open.len() >= limits.max_open_positions {
+                return Err(RiskRejection::MaxOpenPositions);
+            }
+        }
+
+        let daily = book
+            .daily
+            .get(&(order.wallet_id, order.is_paper))
+            .copied()
+            .unwrap_or(0.0);
+        if exceeds(daily + size, limits.max_daily_notional_usdc) {
+            return Err(RiskRejection::DailyNotional);
+        }
+
+        *book
+            .daily
+            .entry((order.wallet_id, order.is_paper))
+            .or_insert(0.0) += size;
+        book.reserved.insert(order.id, (key, size));
+        Ok(())
+    }
+
+    /// Release the reservation for an executed order and apply its fill.
+    pub fn settle(&self, order: &ExecutionOrder, result: &OrderResult) {
+        let mut book = self.book.lock().unwrap_or_else(|e| e.into_inner());
+        let reserved = book.reserved.remove(&order.id);
+        let key = PositionKey::from_order(order);
+        let filled = result.status == OrderStatus::Filled;
+
+        match order.side {
+            Side::Buy if filled => {
+                *book.filled.entry(key).or_insert(0.0) += order.size_usdc;
+            }
+            Side::Buy => {
+                // Never traded: give the notional back to the daily budget.
+                if let Some((_, size)) = reserved {
+                    if let Some(daily) = book.daily.get_mut(&(order.wallet_id, order.is_paper)) {
+                        *daily = (*daily - size).max(0.0);
+                    }
+                }
+            }
+            Side::Sell if filled => {
+                // Strategies always exit their whole position; copy exits may be partial.
+                if order.strategy_id.is_some() {
+                    book.filled.remove(&key);
+                } else if let Some(held) = book.filled.get_mut(&key) {
+                    *held -= order.size_usdc;
+                    if *held <= EXPOSURE_EPSILON {
+                        book.filled.remove(&key);
+                    }
+                }
+            }
+            Side::Sell => {}
+        }
+    }
+
+    /// Replace filled exposure and daily notional with the persisted view,
+    /// keeping in-flight reservations on top.
+    fn apply_snapshot(&self, open: Vec<OpenExposure>, daily: Vec<DailyNotional>, now: i64) {
+        let mut book = self.book.lock().unwrap_or_else(|e| e.into_inner());
+        book.roll_day(now);
+
+        book.filled = open
+            .into_iter()
+            .map(|row| {
+                let key = PositionKey {
+                    wallet_id: row.wallet_id as u64,
+                    is_paper: row.is_paper,
+                    symbol: row.symbol,
+                    outcome: if row.outcome == "DOWN" {
+                        Outcome::Down
+                    } else {
+                        Outcome::Up
+                    },
+                    strategy_id: row.strategy_id.map(|id| id as u64),
+                    copy_relationship_id: row.copy_relationship_id.map(|id| id as u64),
+                };
+                (key, row.size_usdc)
+            })
+            .collect();
+
+        let mut totals: HashMap<(u64, bool), f64> = daily
+            .into_iter()
+            .map(|row| ((row.wallet_id as u64, row.is_paper), row.notional_usdc))
+            .collect();
+        for (key, size) in book.reserved.values() {
+            *totals.entry((key.wallet_id, key.is_paper)).or_insert(0.0) += size;
+        }
+        book.daily = totals;
+    }
+
+    pub async fn refresh(&self, db: &PgPool) -> Result<()> {
+        let open = postgres::load_open_exposures(db).await?;
+        let daily = postgres::load_daily_entry_notional(db).await?;
+        self.apply_snapshot(open, daily, chrono::Utc::now().timestamp());
+        Ok(())
+    }
+}
+
+fn exceeds(total: f64, limit: f64) -> bool {
+    limit > 0.0 && total > limit + EXPOSURE_EPSILON
+}
+
+// ---------------------------------------------------------------------------
+// run_risk_refresh — periodic resync of exposure from `trades`
+// ---------------------------------------------------------------------------
+
+pub async fn run_risk_refresh(risk: std::sync::Arc<RiskEngine>, db: PgPool) -> Result<()> {
+    let mut interval = tokio::time::interval(Duration::from_secs(30));
+
+    loop {
+        interval.tick().await;
+        if let Err(e) = risk.refresh(&db).await {
+            tracing::warn!(error = %e, "risk_exposure_refresh_failed");
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::OrderPriority;
+    use crate::strategy::OrderType;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn limits() -> RiskLimits {
+        RiskLimits {
+            max_wallet_exposure_usdc: 100.0,
+            max_market_exposure_usdc: 60.0,
+            max_slot_exposure_usdc: 40.0,
+            max_open_positions: 3,
+            max_daily_notional_usdc: 150.0,
+            min_price: 0.02,
+            min_size_usdc: 1.0,
+        }
+    }
+
+    fn buy(strategy_id: u64, symbol: &str, size_usdc: f64) -> ExecutionOrder {
+        ExecutionOrder {
+            id: Uuid::new_v4(),
+            wallet_id: 1,
+            strategy_id: Some(strategy_id),
+            copy_relationship_id: None,
+            symbol: symbol.to_string(),
+            token_id: String::new(),
+            side: Side::Buy,
+            outcome: Outcome::Up,
+            price: None,
+            reference_price: Some(0.5),
+            size_usdc,
+            order_type: OrderType::Market,
+            priority: OrderPriority::StrategyMarket,
+            created_at: NOW,
+            leader_address: String::new(),
+            leader_tx_hash: String::new(),
+            is_paper: false,
+            market: None,
+            algo: None,
+            parent_id: None,
+            closes: None,
+        }
+    }
+
+    fn result(status: OrderStatus) -> OrderResult {
+        OrderResult {
+            polymarket_order_id: "pm".into(),
+            status,
+            filled_price: Some(0.5),
+            fee_bps: Some(0),
+            reject_reason: None,
+            clob_rejection: None,
+        }
+    }
+
+    #[test]
+    fn test_market_prefix() {
+        assert_eq!(market_prefix("btc-updown-15m-1700000000"), "btc");
+        assert_eq!(market_prefix("eth"), "eth");
+    }
+
+    #[test]
+    fn test_min_size_and_price() {
+        let risk = RiskEngine::new(limits());
+        assert_eq!(
+            risk.check(&buy(1, "btc-updown-5m-1", 0.5), None, NOW),
+            Err(RiskRejection::MinSize)
+        );
+        let mut cheap = buy(1, "btc-updown-5m-1", 5.0);
+        cheap.reference_price = Some(0.01);
+        assert_eq!(risk.check(&cheap, None, NOW), Err(RiskRejection::MinPrice));
+    }
+
+    #[test]
+    fn test_strategies_cannot_stack_on_same_slot() {
+        let risk = RiskEngine::new(limits());
+        for strategy_id in 1..=2 {
+            assert!(risk
+                .check(&buy(strategy_id, "btc-updown-5m-1", 20.0), None, NOW)
+                .is_ok());
+        }
+        assert_eq!(
+            risk.check(&buy(3, "btc-updown-5m-1", 20.0), None, NOW),
+            Err(RiskRejection::SlotExposure)
+        );
+    }
+
+    #[test]
+    fn test_market_prefix_and_wallet_limits() {
+        let risk = RiskEngine::new(limits());
+        assert!(risk
+            .check(&buy(1, "btc-updown-5m-1", 30.0), None, NOW)
+            .is_ok());
+        assert!(risk
+            .check(&buy(2, "btc-updown-15m-2", 30.0), None, NOW)
+            .is_ok());
+        assert_eq!(
+            risk.check(&buy(3, "btc-updown-15m-3", 10.0), None, NOW),
+            Err(RiskRejection::MarketExposure)
+        );
+        assert!(risk
+            .check(&buy(3, "eth-updown-15m-3", 35.0), None, NOW)
+            .is_ok());
+        assert_eq!(
+            risk.check(&buy(4, "sol-updown-15m-3", 10.0), None, NOW),
+            Err(RiskRejection::WalletExposure)
+        );
+    }
+
+    #[test]
+    fn test_strategy_max_position_enforced() {
+        let risk = RiskEngine::new(limits());
+        assert!(risk
+            .check(&buy(1, "btc-updown-5m-1", 15.0), Some(25.0), NOW)
+            .is_ok());
+        assert_eq!(
+            risk.check(&buy(1, "eth-updown-5m-1", 15.0), Some(25.0), NOW),
+            Err(RiskRejection::StrategyMaxPosition)
+        );
+    }
+
+    #[test]
+    fn test_max_open_positions() {
+        let mut l = limits();
+        l.max_open_positions = 2;
+        let risk = RiskEngine::new(l);
+        assert!(risk
+            .check(&buy(1, "btc-updown-5m-1", 5.0), None, NOW)
+            .is_ok());
+        assert!(risk
+            .check(&buy(2, "eth-updown-5m-1", 5.0), None, NOW)
+            .is_ok());
+        assert_eq!(
+            risk.check(&buy(3, "sol-updown-5m-1", 5.0), None, NOW),
+            Err(RiskRejection::MaxOpenPositions)
+        );
+    }
+
+    #[test]
+    fn test_unfilled_entry_releases_reservation_and_daily_budget() {
+        let mut l = limits();
+        l.max_daily_notional_usdc = 30.0;
+        let risk = RiskEngine::new(l);
+        let first = buy(1, "btc-updown-5m-1", 25.0);
+        assert!(risk.check(&first, None, NOW).is_ok());
+        assert_eq!(
+            risk.check(&buy(2, "eth-updown-5m-1", 10.0), None, NOW),
+            Err(RiskRejection::DailyNotional)
+        );
+
+        risk.settle(&first, &result(OrderStatus::Failed));
+        assert!(risk
+            .check(&buy(2, "eth-updown-5m-1", 10.0), None, NOW)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_daily_budget_resets_next_day() {
+        let mut l = limits();
+        l.max_daily_notional_usdc = 20.0;
+        let risk = RiskEngine::new(l);
+        let entry = buy(1, "btc-updown-5m-1", 20.0);
+        assert!(risk.check(&entry, None, NOW).is_ok());
+        risk.settle(&entry, &result(OrderStatus::Filled));
+        let mut exit = buy(1, "btc-updown-5m-1", 20.0);
+        exit.side = Side::Sell;
+        risk.settle(&exit, &result(OrderStatus::Filled));
+
+        assert_eq!(
+            risk.check(&buy(1, "eth-updown-5m-1", 5.0), None, NOW),
+            Err(RiskRejection::DailyNotional)
+        );
+        assert!(risk
+            .check(&buy(1, "eth-updown-5m-1", 5.0), None, NOW + 86_400)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_sells_always_pass_and_release_exposure() {
+        let risk = RiskEngine::new(limits());
+        let entry = buy(1, "btc-updown-5m-1", 40.0);
+        assert!(risk.check(&entry, None, NOW).is_ok());
+        risk.settle(&entry, &result(OrderStatus::Filled));
+
+        let mut exit = entry.clone();
+        exit.id = Uuid::new_v4();
+        exit.side = Side::Sell;
+        assert!(risk.check(&exit, None, NOW).is_ok());
+        risk.settle(&exit, &result(OrderStatus::Filled));
+
+        assert!(risk
+            .check(&buy(2, "btc-updown-5m-1", 40.0), None, NOW)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_snapshot_keeps_in_flight_reservations() {
+        let risk = RiskEngine::new(limits());
+        assert!(risk
+            .check(&buy(1, "btc-updown-5m-1", 30.0), None, NOW)
+            .is_ok());
+
+        risk.apply_snapshot(
+            vec![OpenExposure {
+                wallet_id: 1,
+                strategy_id: Some(9),
+                copy_relationship_id: None,
+                symbol: "btc-updown-5m-1".into(),
+                outcome: "DOWN".into(),
+                is_paper: false,
+                size_usdc: 10.0,
+            }],
+            vec![],
+            NOW,
+        );
+
+        assert_eq!(
+            risk.check(&buy(2, "btc-updown-5m-1", 5.0), None, NOW),
+            Err(RiskRejection::SlotExposure)
+        );
+    }
+}
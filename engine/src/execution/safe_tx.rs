@@ -0,0 +1,441 @@
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::SignerSync;
+use alloy::sol;
+use alloy::sol_types::{eip712_domain, Eip712Domain, SolCall, SolStruct};
+use anyhow::{Context, Result};
+
+use super::relayer::{RelayerClient, RelayerTxState};
+use crate::network::NetworkProfile;
+
+/// USDC and conditional tokens both use 6 decimals.
+const TOKEN_UNIT: f64 = 1_000_000.0;
+
+sol! {
+    /// Safe v1.3 transaction, signed by the owner EOA under the Safe's domain.
+    #[derive(Debug)]
+    struct SafeTx {
+        address to;
+        uint256 value;
+        bytes data;
+        uint8 operation;
+        uint256 safeTxGas;
+        uint256 baseGas;
+        uint256 gasPrice;
+        address gasToken;
+        address refundReceiver;
+        uint256 nonce;
+    }
+
+    interface IERC20 {
+        function transfer(address to, uint256 amount);
+        function approve(address spender, uint256 amount);
+    }
+
+    interface IERC1155 {
+        function safeTransferFrom(address from, address to, uint256 id, uint256 amount, bytes data);
+        function setApprovalForAll(address operator, bool approved);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SafeTxKind / SafeTxState
+// ---------------------------------------------------------------------------
+
+/// What a Safe transaction does; stored in `safe_transactions.kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafeTxKind {
+    Withdraw,
+    Approve,
+    CtfApproval,
+    TokenTransfer,
+    Redeem,
+}
+
+impl SafeTxKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Withdraw => "withdraw",
+            Self::Approve => "approve",
+            Self::CtfApproval => "ctf_approval",
+            Self::TokenTransfer => "token_transfer",
+            Self::Redeem => "redeem",
+        }
+    }
+}
+
+/// Lifecycle of a Safe transaction as persisted (`redemptions.state`,
+/// `safe_transactions.state`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafeTxState {
+    Submitted,
+    Confirmed,
+    Failed,
+}
+
+impl SafeTxState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Submitted => "submitted",
+            Self::Confirmed => "confirmed",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl From<RelayerTxState> for SafeTxState {
+    fn from(state: RelayerTxState) -> Self {
+        match state {
+            RelayerTxState::Pending => Self::Submitted,
+            RelayerTxState::Confirmed => Self::Confirmed,
+            RelayerTxState::Failed => Self::Failed,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SafeCall
+// ---------------------------------------------------------------------------
+
+/// A single `CALL` executed by a wallet's Safe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafeCall {
+    pub kind: SafeTxKind,
+    pub to: Address,
+    pub data: Vec<u8>,
+    /// Counterparty of the call (recipient, spender or operator).
+    pub counterparty: Option<Address>,
+    /// USDC (or shares) moved or approved; `None` for unlimited or n/a.
+    pub amount: Option<f64>,
+    pub description: String,
+}
+
+impl SafeCall {
+    /// Transfer USDC out of the Safe to `recipient`.
+    pub fn usdc_withdraw(
+        network: &NetworkProfile,
+        recipient: Address,
+        amount_usdc: f64,
+    ) -> Result<Self> {
+        let units = token_units(amount_usdc)?;
+        Ok(Self {
+            kind: SafeTxKind::Withdraw,
+            to: network.usdc,
+            data: IERC20::transferCall {
+                to: recipient,
+                amount: units,
+            }
+            .abi_encode(),
+            counterparty: Some(recipient),
+            amount: Some(amount_usdc),
+            description: format!("Withdraw {amount_usdc} USDC to {recipient:?}"),
+        })
+    }
+
+    /// Set the USDC allowance of `spender`; `None` approves max uint256 and
+    /// `Some(0.0)` revokes.
+    pub fn usdc_approve(
+        network: &NetworkProfile,
+        spender: Address,
+        amount_usdc: Option<f64>,
+    ) -> Result<Self> {
+        let (amount, description) = match amount_usdc {
+            Some(0.0) => (U256::ZERO, format!("Revoke USDC approval for {spender:?}")),
+            Some(usdc) => (
+                token_units(usdc)?,
+                format!("Approve {usdc} USDC for {spender:?}"),
+            ),
+            None => (U256::MAX, format!("USDC approval for {spender:?}")),
+        };
+        Ok(Self {
+            kind: SafeTxKind::Approve,
+            to: network.usdc,
+            data: IERC20::approveCall { spender, amount }.abi_encode(),
+            counterparty: Some(spender),
+            amount: amount_usdc,
+            description,
+        })
+    }
+
+    /// Grant or revoke `operator` over all of the Safe's conditional tokens.
+    pub fn ctf_approval(network: &NetworkProfile, operator: Address, approved: bool) -> Self {
+        let verb = if approved { "Approve" } else { "Revoke" };
+        Self {
+            kind: SafeTxKind::CtfApproval,
+            to: network.conditional_tokens,
+            data: IERC1155::setApprovalForAllCall { operator, approved }.abi_encode(),
+            counterparty: Some(operator),
+            amount: None,
+            description: format!("{verb} conditional token operator {operator:?}"),
+        }
+    }
+
+    /// Move `shares` of the conditional token `token_id` from `safe` to `recipient`.
+    pub fn token_transfer(
+        network: &NetworkProfile,
+        safe: Address,
+        recipient: Address,
+        token_id: U256,
+        shares: f64,
+    ) -> Result<Self> {
+        Ok(Self {
+            kind: SafeTxKind::TokenTransfer,
+            to: network.conditional_tokens,
+            data: IERC1155::safeTransferFromCall {
+                from: safe,
+                to: recipient,
+                id: token_id,
+                amount: token_units(shares)?,
+                data: Bytes::new(),
+            }
+            .abi_encode(),
+            counterparty: Some(recipient),
+            amount: Some(shares),
+            description: format!("Transfer {shares} shares of {token_id} to {recipient:?}"),
+        })
+    }
+
+    /// Arbitrary `redeemPositions` call built by the redeemer.
+    pub fn redeem(to: Address, data: Vec<u8>, expected_usdc: f64, condition_id: &str) -> Self {
+        Self {
+            kind: SafeTxKind::Redeem,
+            to,
+            data,
+            counterparty: None,
+            amount: Some(expected_usdc),
+            description: format!("Redeem positions for {condition_id}"),
+        }
+    }
+
+    /// Owner signature over the Safe transaction hash, as the Safe expects an
+    /// `eth_sign` signature: EIP-191 prefixed, with `v` shifted by 4.
+    pub fn sign(
+        &self,
+        signer: &PrivateKeySigner,
+        chain_id: u64,
+        safe: Address,
+        nonce: u64,
+    ) -> Result<String> {
+        let hash = safe_tx_hash(chain_id, safe, self.to, &self.data, nonce);
+        let signature = signer
+            .sign_message_sync(hash.as_slice())
+            .context("failed to sign SafeTx")?;
+        let mut bytes = signature.as_bytes();
+        bytes[64] += 4;
+        Ok(format!("0x{}", hex::encode(bytes)))
+    }
+}
+
+/// EIP-712 hash of a plain `CALL` from `safe` to `to`, as its owner signs it.
+pub(crate) fn safe_tx_hash(
+    chain_id: u64,
+    safe: Address,
+    to: Address,
+    data: &[u8],
+    nonce: u64,
+) -> B256 {
+    let tx = SafeTx {
+        to,
+        value: U256::ZERO,
+        data: Bytes::copy_from_slice(data),
+        operation: 0,
+        safeTxGas: U256::ZERO,
+        baseGas: U256::ZERO,
+        gasPrice: U256::ZERO,
+        gasToken: Address::ZERO,
+        refundReceiver: Address::ZERO,
+        nonce: U256::from(nonce),
+    };
+    tx.eip712_signing_hash(&safe_domain(chain_id, safe))
+}
+
+/// Safe v1.3 domain: no name or version, just chain and Safe address.
+fn safe_domain(chain_id: u64, safe: Address) -> Eip712Domain {
+    eip712_domain! {
+        chain_id: chain_id,
+        verifying_contract: safe,
+    }
+}
+
+fn token_units(amount: f64) -> Result<U256> {
+    anyhow::ensure!(
+        amount.is_finite() && amount > 0.0,
+        "amount must be positive, got {amount}"
+    );
+    Ok(U256::from((amount * TOKEN_UNIT).round() as u128))
+}
+
+// ---------------------------------------------------------------------------
+// Status
+// ---------------------------------------------------------------------------
+
+/// Current state and transaction hash of a submitted Safe transaction.
+pub async fn safe_tx_status(
+    relayer: &RelayerClient,
+    tx_id: &str,
+) -> Result<(SafeTxState, Option<String>)> {
+    Ok(match relayer.transaction(tx_id).await? {
+        Some(tx) => (RelayerTxState::parse(&tx.state).into(), tx.transaction_hash),
+        None => (SafeTxState::Submitted, None),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Signature;
+
+    use super::*;
+    use crate::execution::relayer::test_support::{
+        spawn_mock_relayer, spawn_mock_relayer_on_chain, spawn_mock_relayer_with_state,
+        test_relayer,
+    };
+
+    fn addr(last: u8) -> Address {
+        let mut bytes = [0u8; 20];
+        bytes[19] = last;
+        Address::from(bytes)
+    }
+
+    #[test]
+    fn test_withdraw_encodes_usdc_transfer() {
+        let network = NetworkProfile::polygon();
+        let call = SafeCall::usdc_withdraw(&network, addr(0xbb), 12.5).unwrap();
+        assert_eq!(call.to, network.usdc);
+        let decoded = IERC20::transferCall::abi_decode(&call.data).unwrap();
+        assert_eq!(decoded.to, addr(0xbb));
+        assert_eq!(decoded.amount, U256::from(12_500_000u64));
+
+        assert!(SafeCall::usdc_withdraw(&network, addr(0xbb), 0.0).is_err());
+        assert!(SafeCall::usdc_withdraw(&network, addr(0xbb), f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_approve_max_reset_and_revoke() {
+        let network = NetworkProfile::polygon();
+        let amount = |call: SafeCall| IERC20::approveCall::abi_decode(&call.data).unwrap().amount;
+        assert_eq!(
+            amount(SafeCall::usdc_approve(&network, addr(1), None).unwrap()),
+            U256::MAX
+        );
+        assert_eq!(
+            amount(SafeCall::usdc_approve(&network, addr(1), Some(50.0)).unwrap()),
+            U256::from(50_000_000u64)
+        );
+        assert_eq!(
+            amount(SafeCall::usdc_approve(&network, addr(1), Some(0.0)).unwrap()),
+            U256::ZERO
+        );
+
+        let revoke = SafeCall::ctf_approval(&network, addr(2), false);
+        assert_eq!(revoke.to, network.conditional_tokens);
+        let decoded = IERC1155::setApprovalForAllCall::abi_decode(&revoke.data).unwrap();
+        assert!(!decoded.approved);
+    }
+
+    #[test]
+    fn test_token_transfer_encodes_erc1155() {
+        let call = SafeCall::token_transfer(
+            &NetworkProfile::polygon(),
+            addr(0xaa),
+            addr(0xbb),
+            U256::from(42),
+            3.0,
+        )
+        .unwrap();
+        let decoded = IERC1155::safeTransferFromCall::abi_decode(&call.data).unwrap();
+        assert_eq!(decoded.from, addr(0xaa));
+        assert_eq!(decoded.to, addr(0xbb));
+        assert_eq!(decoded.id, U256::from(42));
+        assert_eq!(decoded.amount, U256::from(3_000_000u64));
+    }
+
+    #[test]
+    fn test_signature_recovers_owner() {
+        let signer = PrivateKeySigner::random();
+        let safe = addr(0xaa);
+        let call = SafeCall::usdc_withdraw(&NetworkProfile::polygon(), addr(0xbb), 1.0).unwrap();
+        let sig_hex = call.sign(&signer, 137, safe, 3).unwrap();
+
+        let mut bytes = hex::decode(sig_hex.trim_start_matches("0x")).unwrap();
+        assert!(
+            bytes[64] == 31 || bytes[64] == 32,
+            "eth_sign v is 27/28 + 4"
+        );
+        bytes[64] -= 4;
+        let signature = Signature::try_from(bytes.as_slice()).unwrap();
+        let hash = safe_tx_hash(137, safe, call.to, &call.data, 3);
+        assert_eq!(
+            signature.recover_address_from_msg(hash).unwrap(),
+            signer.address()
+        );
+
+        // The nonce is part of the signed payload.
+        assert_ne!(call.sign(&signer, 137, safe, 4).unwrap(), sig_hex);
+        // So is the chain: a testnet signature is not valid on mainnet.
+        assert_ne!(call.sign(&signer, 80002, safe, 3).unwrap(), sig_hex);
+    }
+
+    #[tokio::test]
+    async fn test_failed_transaction_releases_its_nonce() {
+        let (url, _) = spawn_mock_relayer_with_state("STATE_FAILED").await;
+        let (relayer, _, _) = test_relayer(&url);
+
+        let call = SafeCall::usdc_withdraw(relayer.network(), addr(0xbb), 5.0).unwrap();
+        let first = relayer.execute(1, &call).await.unwrap();
+        assert_eq!(
+            safe_tx_status(&relayer, &first.transaction_id)
+                .await
+                .unwrap()
+                .0,
+            SafeTxState::Failed
+        );
+        // Nonce 7 was never used on-chain, so the retry takes it again.
+        let retry = relayer.execute(1, &call).await.unwrap();
+        assert_eq!((first.nonce, retry.nonce), (7, 7));
+    }
+
+    #[tokio::test]
+    async fn test_execute_takes_a_mined_nonce_from_the_chain() {
+        // The Safe has executed up to nonce 8 while the relayer still says 7,
+        // as after a restart that lost the submissions in flight.
+        let (url, _) = spawn_mock_relayer_on_chain("STATE_CONFIRMED", 9).await;
+        let (relayer, _, _) = test_relayer(&url);
+
+        let call = SafeCall::usdc_withdraw(relayer.network(), addr(0xbb), 5.0).unwrap();
+        let submission = relayer.execute(1, &call).await.unwrap();
+        assert_eq!(submission.nonce, 9);
+    }
+
+    #[tokio::test]
+    async fn test_execute_signs_and_advances_nonce() {
+        let (url, submitted) = spawn_mock_relayer().await;
+        let (relayer, signer, safe) = test_relayer(&url);
+
+        let call = SafeCall::usdc_withdraw(relayer.network(), addr(0xbb), 5.0).unwrap();
+        let first = relayer.execute(1, &call).await.unwrap();
+        let second = relayer.execute(1, &call).await.unwrap();
+        assert_eq!(first.transaction_id, "tx-1");
+        // The relayer still reports 7 until the first one is mined.
+        assert_eq!((first.nonce, second.nonce), (7, 8));
+
+        let body = submitted.lock().unwrap()[0].clone();
+        assert_eq!(body["type"], "SAFE");
+        assert_eq!(body["from"], format!("{:?}", signer.address()));
+        assert_eq!(body["proxyWallet"], format!("{safe:?}"));
+        assert_eq!(body["nonce"], "7");
+        assert_eq!(body["data"], format!("0x{}", hex::encode(&call.data)));
+        assert_eq!(
+            body["signature"],
+            call.sign(&signer, relayer.network().chain_id, safe, 7)
+                .unwrap()
+        );
+
+        let (state, tx_hash) = safe_tx_status(&relayer, "tx-1").await.unwrap();
+        assert_eq!(state, SafeTxState::Confirmed);
+        assert_eq!(tx_hash.as_deref(), Some("0xabc"));
+    }
+}
@@ -69,9 +69,6 @@ impl WalletKeyStore {
 
     /// Decrypts the stored key and returns a `PrivateKeySigner`.
     ///
-    /// Handles PHP format: `iv(12) || tag(16) || ciphertext`.
-    /// The `aes_gcm` crate expects `ciphertext || tag`, so we rearrange.
-    ///
     /// The decrypted bytes are zeroized immediately after signer creation.
     pub fn get_signer(&self, wallet_id: u64) -> Result<PrivateKeySigner> {
         let keys = self
@@ -83,26 +80,7 @@ impl WalletKeyStore {
             .get(&wallet_id)
             .with_context(|| format!("no key stored for wallet {wallet_id}"))?;
 
-        anyhow::ensure!(
-            encrypted.len() >= 28,
-            "encrypted payload too short (need iv + tag + ciphertext, got {} bytes)",
-            encrypted.len()
-        );
-
-        // PHP layout: iv(12) || tag(16) || ciphertext
-        let nonce = Nonce::from_slice(&encrypted[..12]);
-        let tag = &encrypted[12..28];
-        let ciphertext = &encrypted[28..];
-
-        // aes_gcm expects: ciphertext || tag
-        let mut ct_with_tag = Vec::with_capacity(ciphertext.len() + 16);
-        ct_with_tag.extend_from_slice(ciphertext);
-        ct_with_tag.extend_from_slice(tag);
-
-        let mut decrypted = self
-            .cipher
-            .decrypt(nonce, ct_with_tag.as_slice())
-            .map_err(|e| anyhow::anyhow!("decryption failed: {e}"))?;
+        let mut decrypted = self.decrypt(encrypted)?;
 
         // PHP encrypts the hex string of the private key, not raw bytes.
         // Convert from hex string to 32-byte raw key for the signer.
@@ -133,12 +111,17 @@ impl WalletKeyStore {
     /// then encrypted.
     #[allow(dead_code)]
     pub fn encrypt_key(&self, private_key_bytes: &[u8]) -> Result<String> {
-        let hex_string = hex::encode(private_key_bytes);
+        self.encrypt_secret(&hex::encode(private_key_bytes))
+    }
+
+    /// Encrypts an arbitrary secret (e.g. CLOB API credentials) in the same
+    /// PHP-compatible base64 format as wallet keys.
+    pub fn encrypt_secret(&self, plaintext: &str) -> Result<String> {
         let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
         // aes_gcm produces ciphertext || tag (last 16 bytes)
         let ct_with_tag = self
             .cipher
-            .encrypt(&nonce, hex_string.as_bytes())
+            .encrypt(&nonce, plaintext.as_bytes())
             .map_err(|e| anyhow::anyhow!("encryption failed: {e}"))?;
 
         let tag_start = ct_with_tag.len() - 16;
@@ -153,6 +136,39 @@ impl WalletKeyStore {
         Ok(BASE64.encode(&payload))
     }
 
+    /// Decrypts a payload produced by [`Self::encrypt_secret`].
+    pub fn decrypt_secret(&self, encrypted_b64: &str) -> Result<String> {
+        let raw = BASE64
+            .decode(encrypted_b64)
+            .context("invalid base64 in encrypted secret")?;
+        let decrypted = self.decrypt(&raw)?;
+        String::from_utf8(decrypted).context("decrypted secret is not valid UTF-8")
+    }
+
+    /// Handles PHP format: `iv(12) || tag(16) || ciphertext`.
+    /// The `aes_gcm` crate expects `ciphertext || tag`, so we rearrange.
+    fn decrypt(&self, encrypted: &[u8]) -> Result<Vec<u8>> {
+        anyhow::ensure!(
+            encrypted.len() >= 28,
+            "encrypted payload too short (need iv + tag + ciphertext, got {} bytes)",
+            encrypted.len()
+        );
+
+        // PHP layout: iv(12) || tag(16) || ciphertext
+        let nonce = Nonce::from_slice(&encrypted[..12]);
+        let tag = &encrypted[12..28];
+        let ciphertext = &encrypted[28..];
+
+        // aes_gcm expects: ciphertext || tag
+        let mut ct_with_tag = Vec::with_capacity(ciphertext.len() + 16);
+        ct_with_tag.extend_from_slice(ciphertext);
+        ct_with_tag.extend_from_slice(tag);
+
+        self.cipher
+            .decrypt(nonce, ct_with_tag.as_slice())
+            .map_err(|e| anyhow::anyhow!("decryption failed: {e}"))
+    }
+
     /// Returns whether a key is stored for the given wallet.
     #[allow(dead_code)]
     pub fn has_key(&self, wallet_id: u64) -> bool {
@@ -216,6 +232,17 @@ mod tests {
         assert!(signer.is_ok(), "round-trip decrypt should succeed");
     }
 
+    #[test]
+    fn test_secret_roundtrip() {
+        let store = WalletKeyStore::new(TEST_KEY).unwrap();
+        let encrypted = store.encrypt_secret("api-secret").unwrap();
+        assert_ne!(encrypted, store.encrypt_secret("api-secret").unwrap());
+        assert_eq!(store.decrypt_secret(&encrypted).unwrap(), "api-secret");
+
+        let other = WalletKeyStore::new("another-encryption-key-of-32-chars!!").unwrap();
+        assert!(other.decrypt_secret(&encrypted).is_err());
+    }
+
     #[test]
     fn test_missing_wallet_key() {
         let store = WalletKeyStore::new(TEST_KEY).unwrap();
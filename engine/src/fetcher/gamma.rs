@@ -4,6 +4,7 @@ use std::collections::HashMap;
 
 use super::models::ActiveMarket;
 use crate::config::MarketSource;
+use crate::execution::markets::DEFAULT_TICK_SIZE;
 use crate::proxy::HttpPool;
 
 #[derive(Debug, Deserialize)]
@@ -18,6 +19,10 @@ struct GammaMarket {
     outcomes: Option<String>,
     clob_token_ids: Option<String>,
     end_date: Option<String>,
+    #[serde(default)]
+    neg_risk: bool,
+    order_price_min_tick_size: Option<f64>,
+    order_min_size: Option<f64>,
 }
 
 fn duration_suffix(secs: u32) -> &'static str {
@@ -106,6 +111,12 @@ pub async fn discover_markets(
                             } else {
                                 None
                             },
+                            neg_risk: mkt.neg_risk,
+                            tick_size: mkt
+                                .order_price_min_tick_size
+                                .filter(|t| *t > 0.0)
+                                .unwrap_or(DEFAULT_TICK_SIZE),
+                            min_order_size: mkt.order_min_size.unwrap_or(0.0),
                         });
                         break;
                     }
@@ -69,6 +69,9 @@ pub struct ActiveMarket {
     pub token_up: String,
     pub token_down: String,
     pub ref_price_start: Option<f32>,
+    pub neg_risk: bool,
+    pub tick_size: f64,
+    pub min_order_size: f64,
 }
 
 #[derive(Debug, Clone, Row, Serialize, Deserialize)]
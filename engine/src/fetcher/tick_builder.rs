@@ -228,6 +228,9 @@ mod tests {
             token_up: "tok_up".into(),
             token_down: "tok_down".into(),
             ref_price_start: Some(50000.0),
+            neg_risk: false,
+            tick_size: 0.01,
+            min_order_size: 5.0,
         }
     }
 
@@ -6,6 +6,8 @@ mod fetcher;
 mod healthcheck;
 mod kafka;
 mod metrics;
+mod network;
+mod notifier;
 mod proxy;
 mod stats;
 mod storage;
@@ -30,8 +32,19 @@ use tasks::SharedState;
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
-    let prometheus_handle = metrics::init();
     let cfg = Config::from_env()?;
+    if std::env::args().nth(1).as_deref() == Some("mock-exchange") {
+        return execution::mock_exchange::serve(
+            &cfg.network,
+            execution::orders::BuilderCredentials {
+                api_key: cfg.builder_api_key.clone(),
+                secret: cfg.builder_secret.clone(),
+                passphrase: cfg.builder_passphrase.clone(),
+            },
+        )
+        .await;
+    }
+    let prometheus_handle = metrics::init();
     tracing::info!(sources = cfg.sources.len(), "craftstrat_engine_starting");
 
     healthcheck::wait_for_services(&cfg.clickhouse_url, &cfg.redis_url, &cfg.database_url).await?;
@@ -58,18 +71,6 @@ async fn main() -> anyhow::Result<()> {
     let ch_client = storage::clickhouse::create_client(&state.config.clickhouse_url);
     let redis_client = redis::Client::open(state.config.redis_url.as_str())?;
     let redis_conn = redis_client.get_multiplexed_tokio_connection().await?;
-    // Builder Relayer client (for Safe deployment via API handlers)
-    let relayer_credentials = execution::orders::BuilderCredentials {
-        api_key: state.config.builder_api_key.clone(),
-        secret: state.config.builder_secret.clone(),
-        passphrase: state.config.builder_passphrase.clone(),
-    };
-    let relayer_client = std::sync::Arc::new(execution::relayer::RelayerClient::new(
-        state.http.clone(),
-        &state.config.relayer_url,
-        relayer_credentials,
-        handles.wallet_keys.clone(),
-    ));
 
     let api_state = std::sync::Arc::new(api::state::ApiState {
         registry: handles.registry,
@@ -79,7 +80,14 @@ async fn main() -> anyhow::Result<()> {
         tick_count: std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0)),
         prometheus: prometheus_handle,
         wallet_keys: handles.wallet_keys,
-        relayer: relayer_client,
+        relayer: handles.relayer,
+        balances: handles.balances,
+        api_keys: handles.api_keys,
+        db: handles.db,
+        journal: handles.journal,
+        exec_queue: handles.exec_queue,
+        http: state.http.clone(),
+        default_fee_bps: state.config.default_fee_bps,
     });
     let api_port = state.config.api_port;
     tasks.spawn(async move { api::serve(api_state, api_port).await });
@@ -10,8 +10,21 @@ pub const STRATEGY_EVAL_DURATION: &str = "craftstrat_strategy_eval_duration_seco
 pub const SIGNALS_TOTAL: &str = "craftstrat_signals_total";
 pub const ORDERS_TOTAL: &str = "craftstrat_orders_total";
 pub const ORDER_EXEC_DURATION: &str = "craftstrat_order_execution_duration_seconds";
+pub const ORDERS_EXPIRED_TOTAL: &str = "craftstrat_orders_expired_total";
+pub const ORDERS_REPLAYED_TOTAL: &str = "craftstrat_orders_replayed_total";
+pub const ORDERS_SLICED_TOTAL: &str = "craftstrat_orders_sliced_total";
+pub const ORDER_RETRIES_TOTAL: &str = "craftstrat_order_retries_total";
+pub const ORDERS_DEAD_LETTERED_TOTAL: &str = "craftstrat_orders_dead_lettered_total";
+pub const RISK_REJECTIONS_TOTAL: &str = "craftstrat_risk_rejections_total";
+pub const CLOB_REJECTIONS_TOTAL: &str = "craftstrat_clob_rejections_total";
+pub const RECONCILE_DIVERGENCES_TOTAL: &str = "craftstrat_reconcile_divergences_total";
+pub const RECONCILE_CORRECTIONS_TOTAL: &str = "craftstrat_reconcile_corrections_total";
 pub const PNL_USDC: &str = "craftstrat_pnl_usdc";
+pub const FEES_USDC: &str = "craftstrat_fees_usdc";
+pub const REDEMPTIONS_TOTAL: &str = "craftstrat_redemptions_total";
+pub const REDEEMED_USDC: &str = "craftstrat_redeemed_usdc";
 pub const COPY_TRADES_TOTAL: &str = "craftstrat_copy_trades_total";
+pub const COPY_DETECTION_LATENCY: &str = "craftstrat_copy_detection_latency_seconds";
 pub const ACTIVE_WALLETS: &str = "craftstrat_active_wallets";
 pub const ACTIVE_ASSIGNMENTS: &str = "craftstrat_active_assignments";
 pub const WS_RECONNECTIONS_TOTAL: &str = "craftstrat_ws_reconnections_total";
@@ -34,7 +47,12 @@ pub fn init() -> PrometheusHandle {
             metrics_exporter_prometheus::Matcher::Full(ORDER_EXEC_DURATION.to_string()),
             &[0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0],
         )
-        .expect("failed to set exec buckets");
+        .expect("failed to set exec buckets")
+        .set_buckets_for_metric(
+            metrics_exporter_prometheus::Matcher::Full(COPY_DETECTION_LATENCY.to_string()),
+            &[0.25, 0.5, 1.0, 2.0, 3.0, 5.0, 10.0, 30.0, 60.0, 120.0],
+        )
+        .expect("failed to set copy detection buckets");
 
     let handle = builder
         .install_recorder()
@@ -61,8 +79,57 @@ fn describe_metrics() {
         ORDER_EXEC_DURATION,
         "Time to submit an order to Polymarket (seconds)"
     );
-    metrics::describe_gauge!(PNL_USDC, "Cumulative realized PnL in USDC");
+    metrics::describe_counter!(
+        ORDERS_EXPIRED_TOTAL,
+        "Total orders dropped for exceeding the max queue age"
+    );
+    metrics::describe_counter!(
+        ORDERS_REPLAYED_TOTAL,
+        "Total journaled orders recovered on startup by action"
+    );
+    metrics::describe_counter!(
+        ORDERS_SLICED_TOTAL,
+        "Total algo orders split into child orders"
+    );
+    metrics::describe_counter!(
+        ORDER_RETRIES_TOTAL,
+        "Total order submissions rescheduled after a transient failure"
+    );
+    metrics::describe_counter!(
+        ORDERS_DEAD_LETTERED_TOTAL,
+        "Total orders dead-lettered after exhausting their retries"
+    );
+    metrics::describe_counter!(
+        RISK_REJECTIONS_TOTAL,
+        "Total orders rejected by pre-trade risk and balance checks by reason"
+    );
+    metrics::describe_counter!(
+        CLOB_REJECTIONS_TOTAL,
+        "Total orders refused by the CLOB by classified reason"
+    );
+    metrics::describe_counter!(
+        RECONCILE_DIVERGENCES_TOTAL,
+        "Total position divergences between engine and exchange by kind"
+    );
+    metrics::describe_counter!(
+        RECONCILE_CORRECTIONS_TOTAL,
+        "Total phantom engine positions cleared by the reconciler"
+    );
+    metrics::describe_gauge!(PNL_USDC, "Cumulative realized PnL in USDC, net of fees");
+    metrics::describe_gauge!(FEES_USDC, "Cumulative taker fees paid on closed positions");
+    metrics::describe_counter!(
+        REDEMPTIONS_TOTAL,
+        "Total winning-position redemptions by state"
+    );
+    metrics::describe_gauge!(
+        REDEEMED_USDC,
+        "Cumulative USDC freed by confirmed redemptions"
+    );
     metrics::describe_counter!(COPY_TRADES_TOTAL, "Total copy trading orders");
+    metrics::describe_histogram!(
+        COPY_DETECTION_LATENCY,
+        "Time from a leader fill to its detection by source (seconds)"
+    );
     metrics::describe_gauge!(
         ACTIVE_WALLETS,
         "Number of wallets with active strategy assignments"
@@ -0,0 +1,192 @@
+use alloy::primitives::{address, b256, Address, B256};
+use anyhow::{Context, Result};
+
+/// Chain, contract set and API endpoints the engine signs for and talks to.
+///
+/// Selected with `ENGINE_NETWORK` (`polygon`, `amoy` or `local`). Every field
+/// can be overridden by its own env var, so a profile is only a starting point:
+/// `local` assumes an anvil fork of Polygon (same contracts) with the CLOB,
+/// data API and relayer served by `craftstrat-engine mock-exchange`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkProfile {
+    pub name: String,
+    pub chain_id: u64,
+    pub ctf_exchange: Address,
+    pub neg_risk_exchange: Address,
+    pub neg_risk_adapter: Address,
+    pub conditional_tokens: Address,
+    /// Collateral token (USDC.e on Polygon).
+    pub usdc: Address,
+    pub safe_factory: Address,
+    /// Init code hash for CREATE2 Safe address derivation.
+    pub safe_init_code_hash: B256,
+    pub clob_api_url: String,
+    pub clob_ws_url: String,
+    pub data_api_url: String,
+    pub gamma_api_url: String,
+    pub relayer_url: String,
+    /// JSON-RPC endpoint for chain reads such as Safe nonces.
+    pub rpc_url: String,
+}
+
+impl NetworkProfile {
+    /// Polygon mainnet, as used in production.
+    pub fn polygon() -> Self {
+        Self {
+            name: "polygon".into(),
+            chain_id: 137,
+            ctf_exchange: address!("4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E"),
+            neg_risk_exchange: address!("C5d563A36AE78145C45a50134d48A1215220f80a"),
+            neg_risk_adapter: address!("d91E80cF2E7be2e162c6513ceD06f1dD0dA35296"),
+            conditional_tokens: address!("4D97DCd97eC945f40cF65F87097ACe5EA0476045"),
+            usdc: address!("2791Bca1f2de4661ED88A30C99A7a9449Aa84174"),
+            safe_factory: address!("aacFeEa03eb1561C4e67d661e40682Bd20E3541b"),
+            safe_init_code_hash: b256!(
+                "2bce2127ff07fb632d16c8347c4ebf501f4841168bed00d9e6ef715ddb6fcecf"
+            ),
+            clob_api_url: "https://clob.polymarket.com".into(),
+            clob_ws_url: "wss://ws-subscriptions-clob.polymarket.com/ws/market".into(),
+            data_api_url: "https://data-api.polymarket.com".into(),
+            gamma_api_url: "https://gamma-api.polymarket.com".into(),
+            relayer_url: "https://relayer-v2.polymarket.com".into(),
+            rpc_url: "https://polygon-rpc.com".into(),
+        }
+    }
+
+    /// Polygon Amoy testnet deployment of the exchange contracts. The API
+    /// endpoints default to production hosts and are normally overridden.
+    pub fn amoy() -> Self {
+        Self {
+            name: "amoy".into(),
+            chain_id: 80002,
+            ctf_exchange: address!("dFE02Eb6733538f8Ea35D585af8DE5958AD99E40"),
+            neg_risk_exchange: address!("C5d563A36AE78145C45a50134d48A1215220f80a"),
+            neg_risk_adapter: address!("d91E80cF2E7be2e162c6513ceD06f1dD0dA35296"),
+            conditional_tokens: address!("69308FB512518e39F9b16112fA8d994F4e2Bf8bB"),
+            usdc: address!("9c4e1703476e875070ee25b56a58b008cfb8fa78"),
+            rpc_url: "https://rpc-amoy.polygon.technology".into(),
+            ..Self::polygon()
+        }
+    }
+
+    /// Anvil fork of Polygon trading against the mock exchange on localhost,
+    /// for CI. Market discovery and the book feed stay on production.
+    pub fn local() -> Self {
+        Self {
+            name: "local".into(),
+            chain_id: 31337,
+            clob_api_url: "http://localhost:9100".into(),
+            data_api_url: "http://localhost:9101".into(),
+            relayer_url: "http://localhost:9102".into(),
+            rpc_url: "http://localhost:8545".into(),
+            ..Self::polygon()
+        }
+    }
+
+    pub fn by_name(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "polygon" | "mainnet" => Ok(Self::polygon()),
+            "amoy" => Ok(Self::amoy()),
+            "local" | "anvil" => Ok(Self::local()),
+            other => anyhow::bail!("unknown ENGINE_NETWORK {other:?} (polygon, amoy or local)"),
+        }
+    }
+
+    /// Profile named by `ENGINE_NETWORK` (default `polygon`) with per-field
+    /// env overrides applied.
+    pub fn from_env() -> Result<Self> {
+        let name = std::env::var("ENGINE_NETWORK").unwrap_or_else(|_| "polygon".into());
+        let mut profile = Self::by_name(&name)?;
+        profile.apply_overrides(|key| std::env::var(key).ok())?;
+        Ok(profile)
+    }
+
+    fn apply_overrides(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<()> {
+        if let Some(v) = var("ENGINE_CHAIN_ID") {
+            self.chain_id = v.parse().context("ENGINE_CHAIN_ID must be an integer")?;
+        }
+        for (key, field) in [
+            ("ENGINE_CTF_EXCHANGE", &mut self.ctf_exchange),
+            ("ENGINE_NEG_RISK_EXCHANGE", &mut self.neg_risk_exchange),
+            ("ENGINE_NEG_RISK_ADAPTER", &mut self.neg_risk_adapter),
+            ("ENGINE_CONDITIONAL_TOKENS", &mut self.conditional_tokens),
+            ("ENGINE_USDC_ADDRESS", &mut self.usdc),
+            ("ENGINE_SAFE_FACTORY", &mut self.safe_factory),
+        ] {
+            if let Some(v) = var(key) {
+                *field = v
+                    .parse()
+                    .with_context(|| format!("{key} is not an address"))?;
+            }
+        }
+        if let Some(v) = var("ENGINE_SAFE_INIT_CODE_HASH") {
+            self.safe_init_code_hash = v
+                .parse()
+                .context("ENGINE_SAFE_INIT_CODE_HASH is not a 32-byte hash")?;
+        }
+        for (key, field) in [
+            ("POLYMARKET_CLOB_URL", &mut self.clob_api_url),
+            ("CLOB_WS_URL", &mut self.clob_ws_url),
+            ("POLYMARKET_DATA_API_URL", &mut self.data_api_url),
+            ("GAMMA_API_URL", &mut self.gamma_api_url),
+            ("POLYMARKET_RELAYER_URL", &mut self.relayer_url),
+            ("POLYGON_RPC_URL", &mut self.rpc_url),
+        ] {
+            if let Some(v) = var(key) {
+                *field = v;
+            }
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_profiles_by_name() {
+        assert_eq!(NetworkProfile::by_name("Polygon").unwrap().chain_id, 137);
+        assert_eq!(NetworkProfile::by_name("amoy").unwrap().chain_id, 80002);
+        let local = NetworkProfile::by_name("local").unwrap();
+        assert_eq!(local.chain_id, 31337);
+        // A fork keeps the mainnet contracts.
+        assert_eq!(local.ctf_exchange, NetworkProfile::polygon().ctf_exchange);
+        assert!(local.clob_api_url.starts_with("http://localhost"));
+        assert!(NetworkProfile::by_name("goerli").is_err());
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let vars = HashMap::from([
+            ("ENGINE_CHAIN_ID", "1337"),
+            (
+                "ENGINE_USDC_ADDRESS",
+                "0x00000000000000000000000000000000000000cc",
+            ),
+            ("POLYMARKET_CLOB_URL", "http://clob.test"),
+        ]);
+        let mut profile = NetworkProfile::polygon();
+        profile
+            .apply_overrides(|k| vars.get(k).map(|v| v.to_string()))
+            .unwrap();
+        assert_eq!(profile.chain_id, 1337);
+        assert_eq!(
+            profile.usdc,
+            address!("00000000000000000000000000000000000000cc")
+        );
+        assert_eq!(profile.clob_api_url, "http://clob.test");
+        assert_eq!(profile.relayer_url, NetworkProfile::polygon().relayer_url);
+
+        let bad = HashMap::from([("ENGINE_SAFE_FACTORY", "nope")]);
+        assert!(profile
+            .apply_overrides(|k| bad.get(k).map(|v| v.to_string()))
+            .is_err());
+    }
+}
@@ -0,0 +1,42 @@
+use anyhow::{Context, Result};
+
+use crate::proxy::HttpPool;
+
+/// Sends user notifications through the web app's internal endpoint, which
+/// resolves the wallet's owner and delivers by database and optionally mail.
+#[derive(Clone)]
+pub struct WebNotifier {
+    http: HttpPool,
+    url: String,
+}
+
+impl WebNotifier {
+    pub fn new(http: HttpPool, web_internal_url: &str) -> Self {
+        Self {
+            http,
+            url: format!(
+                "{}/internal/notification/send",
+                web_internal_url.trim_end_matches('/')
+            ),
+        }
+    }
+
+    /// Notify the owner of `wallet_id`. `mail` also sends an email.
+    pub async fn send(&self, wallet_id: i64, title: &str, message: &str, mail: bool) -> Result<()> {
+        self.http
+            .direct()
+            .post(&self.url)
+            .json(&serde_json::json!({
+                "wallet_id": wallet_id,
+                "strategy_name": title,
+                "message": message,
+                "channel": if mail { "mail" } else { "database" },
+            }))
+            .send()
+            .await
+            .context("notification request failed")?
+            .error_for_status()
+            .context("notification rejected")?;
+        Ok(())
+    }
+}
@@ -2,6 +2,8 @@ use anyhow::{Context, Result};
 use clickhouse::Client;
 use tokio::io::AsyncBufReadExt;
 
+use crate::execution::fees::breakeven_win_rate;
+
 use super::types::{
     CalibrationPoint, HeatmapCell, MlDatasetRow, StoplossThreshold, Summary, SymbolStats, TimeStats,
 };
@@ -10,6 +12,8 @@ pub struct StatsParams {
     pub slot_duration: u32,
     pub symbols: Vec<String>,
     pub hours: f64,
+    /// Taker fee rate used for fee-adjusted breakeven figures.
+    pub fee_bps: u16,
 }
 
 impl StatsParams {
@@ -79,6 +83,7 @@ mod tests {
             slot_duration: 900,
             symbols: vec!["btc".into(), "eth".into()],
             hours: 24.0,
+            fee_bps: 0,
         };
 
         assert_eq!(
@@ -320,10 +325,13 @@ pub async fn fetch_calibration(
 
     let mut points = Vec::new();
     while let Some(row) = cursor.next().await? {
+        let breakeven_win_rate = breakeven_win_rate(params.fee_bps, row.avg_bid);
         points.push(CalibrationPoint {
             bid_bucket: row.bid_bucket,
             avg_bid: row.avg_bid,
             win_rate: row.win_rate,
+            breakeven_win_rate,
+            net_edge: row.win_rate - breakeven_win_rate,
             sample_count: row.sample_count,
         });
     }
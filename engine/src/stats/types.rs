@@ -89,6 +89,10 @@ pub struct CalibrationPoint {
     pub bid_bucket: f64,
     pub avg_bid: f64,
     pub win_rate: f64,
+    /// Win rate a buy at `avg_bid` needs to break even after the taker fee.
+    pub breakeven_win_rate: f64,
+    /// `win_rate - breakeven_win_rate`; negative buckets lose money net of fees.
+    pub net_edge: f64,
     pub sample_count: u64,
 }
 
@@ -88,3 +88,27 @@ pub fn fetch_ticks(
     query = query.bind(from_ms).bind(to_ms);
     Ok(query.fetch::<Tick>()?)
 }
+
+/// Snapshots of exactly `symbols` between the two instants, by symbol then time.
+pub async fn fetch_slot_ticks(
+    client: &Client,
+    symbols: &[String],
+    date_from: time::OffsetDateTime,
+    date_to: time::OffsetDateTime,
+) -> Result<Vec<Tick>> {
+    if symbols.is_empty() {
+        return Ok(Vec::new());
+    }
+    let from_ms = (date_from.unix_timestamp_nanos() / 1_000_000) as i64;
+    let to_ms = (date_to.unix_timestamp_nanos() / 1_000_000) as i64;
+    let ticks = client
+        .query(
+            "SELECT ?fields FROM slot_snapshots WHERE symbol IN ? AND captured_at >= fromUnixTimestamp64Milli(?) AND captured_at <= fromUnixTimestamp64Milli(?) ORDER BY symbol, captured_at ASC",
+        )
+        .bind(symbols)
+        .bind(from_ms)
+        .bind(to_ms)
+        .fetch_all::<Tick>()
+        .await?;
+    Ok(ticks)
+}
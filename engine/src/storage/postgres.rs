@@ -1,9 +1,17 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
 use serde_json::Value;
 use sqlx::PgPool;
 
+use crate::execution::algo::ExecAlgo;
+use crate::execution::redemption::RedemptionPlan;
+use crate::execution::relayer::SafeTxSubmission;
+use crate::execution::safe_tx::SafeCall;
 use crate::execution::{ExecutionOrder, OrderResult, OrderStatus, Side};
 use crate::strategy::OrderType;
+use crate::watcher::filters::CopyFilters;
+use crate::watcher::risk::{CopyRealization, CopyRiskLimits, CopyRiskState};
 
 // ---------------------------------------------------------------------------
 // Connection pool
@@ -59,6 +67,7 @@ pub async fn write_trade(
         OrderStatus::Cancelled => "cancelled",
         OrderStatus::Failed => "failed",
         OrderStatus::Timeout => "timeout",
+        OrderStatus::Rejected => "rejected",
     };
 
     let trade_id = sqlx::query_scalar::<_, i64>(
@@ -69,12 +78,14 @@ pub async fn write_trade(
             order_type, price, size_usdc,
             polymarket_order_id, status, is_paper,
             reference_price, filled_price, resolved_price, fee_bps,
-            fill_slippage_bps, fill_slippage_pct, executed_at, created_at
+            fill_slippage_bps, fill_slippage_pct, executed_at, created_at,
+            reject_reason, clob_rejection, parent_order_id
         )
         VALUES (
             $1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
             $11, $12, $13, $14, $15, $16, $17,
-            $18, $19, to_timestamp($20), to_timestamp($21)
+            $18, $19, to_timestamp($20), to_timestamp($21),
+            $22, $23, $24
         )
         RETURNING id
         "#,
@@ -100,6 +111,9 @@ pub async fn write_trade(
     .bind(fill_slippage_pct)
     .bind(executed_at)
     .bind(order.created_at)
+    .bind(result.reject_reason.as_deref())
+    .bind(result.clob_rejection.map(|r| r.as_str()))
+    .bind(order.parent_id)
     .fetch_one(pool)
     .await?;
 
@@ -133,9 +147,9 @@ pub async fn close_open_entry_trade_on_exit(
         crate::strategy::Outcome::Down => "DOWN",
     };
 
-    let entry_trade_id = sqlx::query_scalar::<_, i64>(
+    let entry = sqlx::query_as::<_, (i64, Option<uuid::Uuid>)>(
         r#"
-        SELECT id
+        SELECT id, parent_order_id
         FROM trades
         WHERE wallet_id = $1
             AND strategy_id = $2
@@ -156,22 +170,675 @@ pub async fn close_open_entry_trade_on_exit(
     .fetch_optional(pool)
     .await?;
 
-    let Some(entry_trade_id) = entry_trade_id else {
+    let Some((entry_trade_id, parent_order_id)) = entry else {
         return Ok(None);
     };
 
+    // A sliced entry filled as several child trades; the exit closes all of them.
     sqlx::query(
-        "UPDATE trades SET status = $1, resolved_price = $2 WHERE id = $3 AND status = 'filled'",
+        r#"
+        UPDATE trades SET status = $1, resolved_price = $2
+        WHERE status = 'filled'
+            AND (id = $3 OR (parent_order_id = $4 AND side = 'buy'))
+        "#,
     )
     .bind("closed")
     .bind(resolved_price)
     .bind(entry_trade_id)
+    .bind(parent_order_id)
     .execute(pool)
     .await?;
 
     Ok(Some(entry_trade_id))
 }
 
+// ---------------------------------------------------------------------------
+// Pre-trade risk exposure
+// ---------------------------------------------------------------------------
+
+/// Open (filled, unexited, unresolved) entry notional per position.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct OpenExposure {
+    pub wallet_id: i64,
+    pub strategy_id: Option<i64>,
+    pub copy_relationship_id: Option<i64>,
+    pub symbol: String,
+    pub outcome: String,
+    pub is_paper: bool,
+    pub size_usdc: f64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DailyNotional {
+    pub wallet_id: i64,
+    pub is_paper: bool,
+    pub notional_usdc: f64,
+}
+
+pub async fn load_open_exposures(pool: &PgPool) -> Result<Vec<OpenExposure>> {
+    let rows = sqlx::query_as::<_, OpenExposure>(
+        r#"
+        SELECT wallet_id, strategy_id, copy_relationship_id, symbol,
+               COALESCE(outcome, 'UP') AS outcome, is_paper,
+               COALESCE(SUM(size_usdc), 0)::float8 AS size_usdc
+        FROM trades
+        WHERE side = 'buy' AND status = 'filled' AND symbol IS NOT NULL
+        GROUP BY wallet_id, strategy_id, copy_relationship_id, symbol, COALESCE(outcome, 'UP'), is_paper
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Entry notional traded since midnight UTC, per wallet.
+pub async fn load_daily_entry_notional(pool: &PgPool) -> Result<Vec<DailyNotional>> {
+    let rows = sqlx::query_as::<_, DailyNotional>(
+        r#"
+        SELECT wallet_id, is_paper, COALESCE(SUM(size_usdc), 0)::float8 AS notional_usdc
+        FROM trades
+        WHERE side = 'buy'
+            AND status IN ('filled', 'closed', 'won', 'lost')
+            AND created_at >= date_trunc('day', now())
+        GROUP BY wallet_id, is_paper
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// A live strategy entry still open in `trades`, used by the position reconciler.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct OpenLiveTrade {
+    pub wallet_id: i64,
+    pub symbol: String,
+    pub outcome: String,
+    pub size_usdc: f64,
+    pub price: Option<f64>,
+    pub executed_ts: i64,
+}
+
+pub async fn load_open_live_trades(pool: &PgPool) -> Result<Vec<OpenLiveTrade>> {
+    let rows = sqlx::query_as::<_, OpenLiveTrade>(
+        r#"
+        SELECT wallet_id, symbol,
+               COALESCE(outcome, 'UP') AS outcome,
+               COALESCE(size_usdc, 0)::float8 AS size_usdc,
+               COALESCE(filled_price, price)::float8 AS price,
+               EXTRACT(EPOCH FROM COALESCE(executed_at, created_at))::bigint AS executed_ts
+        FROM trades
+        WHERE side = 'buy' AND status = 'filled' AND is_paper = false AND symbol IS NOT NULL
+          AND copy_relationship_id IS NULL
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Shares of a token a live wallet holds from copied fills, per the copy
+/// ledger. Copy trades are keyed by condition id and stay open after exits,
+/// so the reconciler reads copies from here rather than from `trades`.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LiveCopyHolding {
+    pub wallet_id: i64,
+    pub token_id: String,
+    pub outcome: String,
+    pub shares: f64,
+    pub updated_ts: i64,
+}
+
+pub async fn load_live_copy_holdings(pool: &PgPool) -> Result<Vec<LiveCopyHolding>> {
+    let rows = sqlx::query_as::<_, LiveCopyHolding>(
+        r#"
+        SELECT r.follower_wallet_id AS wallet_id, p.token_id,
+               COALESCE(p.outcome, 'Up') AS outcome,
+               p.shares::float8 AS shares,
+               EXTRACT(EPOCH FROM COALESCE(p.updated_at, p.created_at))::bigint AS updated_ts
+        FROM copy_positions p
+        JOIN copy_relationships r ON r.id = p.copy_relationship_id
+        WHERE p.is_paper = false AND p.shares > 0
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+// ---------------------------------------------------------------------------
+// Redemptions
+// ---------------------------------------------------------------------------
+
+/// Live wallets holding trades that resolved as winners recently.
+pub async fn load_redemption_wallets(pool: &PgPool) -> Result<Vec<i64>> {
+    let rows = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT DISTINCT wallet_id
+        FROM trades
+        WHERE status = 'won' AND is_paper = false
+          AND created_at > now() - interval '7 days'
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Conditions already redeemed, or being redeemed, for a wallet.
+pub async fn load_active_redemption_conditions(
+    pool: &PgPool,
+    wallet_id: i64,
+) -> Result<Vec<String>> {
+    let rows = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT condition_id FROM redemptions
+        WHERE wallet_id = $1 AND state IN ('submitted', 'confirmed')
+        "#,
+    )
+    .bind(wallet_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Record a submitted redemption; a previously failed one is replaced.
+pub async fn insert_redemption(
+    pool: &PgPool,
+    wallet_id: i64,
+    plan: &RedemptionPlan,
+    relayer_tx_id: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO redemptions
+            (wallet_id, condition_id, neg_risk, relayer_tx_id, state, expected_usdc,
+             created_at, updated_at)
+        VALUES ($1, $2, $3, $4, 'submitted', $5, now(), now())
+        ON CONFLICT (wallet_id, condition_id) DO UPDATE
+        SET relayer_tx_id = EXCLUDED.relayer_tx_id, state = 'submitted',
+            expected_usdc = EXCLUDED.expected_usdc, tx_hash = NULL, updated_at = now()
+        WHERE redemptions.state = 'failed'
+        "#,
+    )
+    .bind(wallet_id)
+    .bind(&plan.condition_id)
+    .bind(plan.neg_risk)
+    .bind(relayer_tx_id)
+    .bind(plan.expected_usdc)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, serde::Serialize)]
+pub struct RedemptionRow {
+    pub id: i64,
+    pub wallet_id: i64,
+    pub condition_id: String,
+    pub neg_risk: bool,
+    pub relayer_tx_id: Option<String>,
+    pub tx_hash: Option<String>,
+    pub state: String,
+    pub expected_usdc: f64,
+    pub created_ts: i64,
+}
+
+const REDEMPTION_COLUMNS: &str = "id, wallet_id, condition_id, neg_risk, relayer_tx_id, tx_hash, \
+     state, expected_usdc::float8 AS expected_usdc, \
+     EXTRACT(EPOCH FROM created_at)::bigint AS created_ts";
+
+pub async fn load_pending_redemptions(pool: &PgPool) -> Result<Vec<RedemptionRow>> {
+    let rows = sqlx::query_as::<_, RedemptionRow>(&format!(
+        "SELECT {REDEMPTION_COLUMNS} FROM redemptions WHERE state = 'submitted'"
+    ))
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn load_wallet_redemptions(
+    pool: &PgPool,
+    wallet_id: i64,
+    limit: i64,
+) -> Result<Vec<RedemptionRow>> {
+    let rows = sqlx::query_as::<_, RedemptionRow>(&format!(
+        "SELECT {REDEMPTION_COLUMNS} FROM redemptions WHERE wallet_id = $1 \
+         ORDER BY created_at DESC LIMIT $2"
+    ))
+    .bind(wallet_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn update_redemption_state(
+    pool: &PgPool,
+    id: i64,
+    state: &str,
+    tx_hash: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE redemptions
+        SET state = $2, tx_hash = COALESCE($3, tx_hash), updated_at = now()
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(state)
+    .bind(tx_hash)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Safe transactions
+// ---------------------------------------------------------------------------
+
+/// Record a Safe transaction accepted by the relayer.
+pub async fn insert_safe_transaction(
+    pool: &PgPool,
+    wallet_id: i64,
+    call: &SafeCall,
+    submission: &SafeTxSubmission,
+) -> Result<SafeTransactionRow> {
+    let row = sqlx::query_as::<_, SafeTransactionRow>(&format!(
+        r#"
+        INSERT INTO safe_transactions
+            (wallet_id, kind, to_address, counterparty, amount, data, nonce,
+             relayer_tx_id, state, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'submitted', now(), now())
+        RETURNING {SAFE_TRANSACTION_COLUMNS}
+        "#
+    ))
+    .bind(wallet_id)
+    .bind(call.kind.as_str())
+    .bind(format!("{:?}", call.to))
+    .bind(call.counterparty.map(|a| format!("{a:?}")))
+    .bind(call.amount)
+    .bind(format!("0x{}", hex::encode(&call.data)))
+    .bind(submission.nonce as i64)
+    .bind(&submission.transaction_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(row)
+}
+
+/// Relayer ids and nonces of a wallet's Safe transactions still submitted.
+pub async fn load_submitted_safe_nonces(
+    pool: &PgPool,
+    wallet_id: i64,
+) -> Result<Vec<(String, i64)>> {
+    let rows = sqlx::query_as::<_, (String, i64)>(
+        "SELECT relayer_tx_id, nonce FROM safe_transactions \
+         WHERE wallet_id = $1 AND state = 'submitted'",
+    )
+    .bind(wallet_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, serde::Serialize)]
+pub struct SafeTransactionRow {
+    pub id: i64,
+    pub wallet_id: i64,
+    pub kind: String,
+    pub to_address: String,
+    pub counterparty: Option<String>,
+    pub amount: Option<f64>,
+    pub nonce: i64,
+    pub relayer_tx_id: String,
+    pub tx_hash: Option<String>,
+    pub state: String,
+    pub created_ts: i64,
+}
+
+const SAFE_TRANSACTION_COLUMNS: &str = "id, wallet_id, kind, to_address, counterparty, \
+     amount::float8 AS amount, nonce, relayer_tx_id, tx_hash, state, \
+     EXTRACT(EPOCH FROM created_at)::bigint AS created_ts";
+
+pub async fn load_wallet_safe_transactions(
+    pool: &PgPool,
+    wallet_id: i64,
+    limit: i64,
+) -> Result<Vec<SafeTransactionRow>> {
+    let rows = sqlx::query_as::<_, SafeTransactionRow>(&format!(
+        "SELECT {SAFE_TRANSACTION_COLUMNS} FROM safe_transactions WHERE wallet_id = $1 \
+         ORDER BY created_at DESC LIMIT $2"
+    ))
+    .bind(wallet_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn update_safe_transaction_state(
+    pool: &PgPool,
+    id: i64,
+    state: &str,
+    tx_hash: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE safe_transactions
+        SET state = $2, tx_hash = COALESCE($3, tx_hash), updated_at = now()
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(state)
+    .bind(tx_hash)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Wallet API credentials
+// ---------------------------------------------------------------------------
+
+/// Encrypted CLOB L2 credentials of a wallet. The secrets are cleared when
+/// the CLOB refuses them or they are revoked.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ApiCredentialRow {
+    pub address: String,
+    pub api_key: String,
+    pub secret_enc: Option<String>,
+    pub passphrase_enc: Option<String>,
+    pub nonce: i64,
+    pub revoked: bool,
+}
+
+pub async fn load_wallet_api_credentials(
+    pool: &PgPool,
+    wallet_id: i64,
+) -> Result<Option<ApiCredentialRow>> {
+    let row = sqlx::query_as::<_, ApiCredentialRow>(
+        r#"
+        SELECT address, api_key, secret_enc, passphrase_enc, nonce,
+               revoked_at IS NOT NULL AS revoked
+        FROM wallet_api_credentials
+        WHERE wallet_id = $1
+        "#,
+    )
+    .bind(wallet_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn upsert_wallet_api_credentials(
+    pool: &PgPool,
+    wallet_id: i64,
+    address: &str,
+    api_key: &str,
+    secret_enc: &str,
+    passphrase_enc: &str,
+    nonce: i64,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO wallet_api_credentials
+            (wallet_id, address, api_key, secret_enc, passphrase_enc, nonce,
+             revoked_at, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, NULL, now(), now())
+        ON CONFLICT (wallet_id) DO UPDATE
+        SET address = EXCLUDED.address, api_key = EXCLUDED.api_key,
+            secret_enc = EXCLUDED.secret_enc, passphrase_enc = EXCLUDED.passphrase_enc,
+            nonce = EXCLUDED.nonce, revoked_at = NULL, updated_at = now()
+        "#,
+    )
+    .bind(wallet_id)
+    .bind(address)
+    .bind(api_key)
+    .bind(secret_enc)
+    .bind(passphrase_enc)
+    .bind(nonce)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Forget the secrets of credentials the CLOB refused; the nonce is kept so
+/// they can be derived again.
+pub async fn mark_wallet_api_credentials_stale(pool: &PgPool, wallet_id: i64) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE wallet_api_credentials
+        SET secret_enc = NULL, passphrase_enc = NULL, updated_at = now()
+        WHERE wallet_id = $1
+        "#,
+    )
+    .bind(wallet_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn revoke_wallet_api_credentials(pool: &PgPool, wallet_id: i64) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO wallet_api_credentials
+            (wallet_id, address, api_key, nonce, revoked_at, created_at, updated_at)
+        VALUES ($1, '', '', 0, now(), now(), now())
+        ON CONFLICT (wallet_id) DO UPDATE
+        SET secret_enc = NULL, passphrase_enc = NULL, revoked_at = now(), updated_at = now()
+        "#,
+    )
+    .bind(wallet_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Execution order journal
+// ---------------------------------------------------------------------------
+
+/// A journaled order that had not reached a terminal state when loaded.
+#[derive(Debug, Clone)]
+pub struct JournaledOrder {
+    pub order: ExecutionOrder,
+    pub state: String,
+    pub polymarket_order_id: Option<String>,
+    pub fee_bps: Option<u16>,
+}
+
+pub async fn insert_execution_order(pool: &PgPool, order: &ExecutionOrder) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO execution_orders (id, wallet_id, state, payload, created_at, updated_at)
+        VALUES ($1, $2, 'queued', $3, to_timestamp($4), now())
+        ON CONFLICT (id) DO NOTHING
+        "#,
+    )
+    .bind(order.id)
+    .bind(order.wallet_id as i64)
+    .bind(serde_json::to_value(order)?)
+    .bind(order.created_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn update_execution_order_state(
+    pool: &PgPool,
+    id: uuid::Uuid,
+    state: &str,
+    polymarket_order_id: Option<&str>,
+    fee_bps: Option<u16>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE execution_orders
+        SET state = $2,
+            polymarket_order_id = COALESCE($3, polymarket_order_id),
+            fee_bps = COALESCE($4, fee_bps),
+            updated_at = now()
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(state)
+    .bind(polymarket_order_id)
+    .bind(fee_bps.map(|b| b as i16))
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn load_unfinished_execution_orders(pool: &PgPool) -> Result<Vec<JournaledOrder>> {
+    let rows = sqlx::query_as::<_, (Value, String, Option<String>, Option<i16>)>(
+        r#"
+        SELECT payload, state, polymarket_order_id, fee_bps
+        FROM execution_orders
+        WHERE state IN ('queued', 'submitting', 'submitted')
+        ORDER BY created_at ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut orders = Vec::with_capacity(rows.len());
+    for (payload, state, polymarket_order_id, fee_bps) in rows {
+        match serde_json::from_value::<ExecutionOrder>(payload) {
+            Ok(order) => orders.push(JournaledOrder {
+                order,
+                state,
+                polymarket_order_id,
+                fee_bps: fee_bps.map(|b| b as u16),
+            }),
+            Err(e) => tracing::warn!(error = %e, "execution_order_payload_invalid"),
+        }
+    }
+    Ok(orders)
+}
+
+/// Re-journal an order as queued, replacing its payload (used on requeue).
+pub async fn requeue_execution_order(pool: &PgPool, order: &ExecutionOrder) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO execution_orders (id, wallet_id, state, payload, created_at, updated_at)
+        VALUES ($1, $2, 'queued', $3, to_timestamp($4), now())
+        ON CONFLICT (id) DO UPDATE
+        SET state = 'queued', payload = EXCLUDED.payload,
+            created_at = EXCLUDED.created_at, updated_at = now()
+        "#,
+    )
+    .bind(order.id)
+    .bind(order.wallet_id as i64)
+    .bind(serde_json::to_value(order)?)
+    .bind(order.created_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Execution dead letters
+// ---------------------------------------------------------------------------
+
+/// An order parked after its submission retries were exhausted.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DeadLetter {
+    pub id: i64,
+    pub order_id: uuid::Uuid,
+    pub wallet_id: i64,
+    pub reason: String,
+    pub attempts: i32,
+    pub last_error: String,
+    pub order: ExecutionOrder,
+    pub created_at: i64,
+}
+
+pub async fn insert_dead_letter(
+    pool: &PgPool,
+    order: &ExecutionOrder,
+    reason: &str,
+    attempts: u32,
+    last_error: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO execution_dead_letters
+            (order_id, wallet_id, payload, reason, attempts, last_error, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, now())
+        ON CONFLICT (order_id) DO UPDATE
+        SET payload = EXCLUDED.payload, reason = EXCLUDED.reason,
+            attempts = EXCLUDED.attempts, last_error = EXCLUDED.last_error,
+            created_at = now(), requeued_at = NULL
+        "#,
+    )
+    .bind(order.id)
+    .bind(order.wallet_id as i64)
+    .bind(serde_json::to_value(order)?)
+    .bind(reason)
+    .bind(attempts as i32)
+    .bind(last_error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn load_dead_letters(pool: &PgPool, limit: i64) -> Result<Vec<DeadLetter>> {
+    let rows = sqlx::query_as::<_, (i64, uuid::Uuid, i64, Value, String, i32, String, i64)>(
+        r#"
+        SELECT id, order_id, wallet_id, payload, reason, attempts, last_error,
+               EXTRACT(EPOCH FROM created_at)::bigint
+        FROM execution_dead_letters
+        WHERE requeued_at IS NULL
+        ORDER BY created_at DESC
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    let mut dead_letters = Vec::with_capacity(rows.len());
+    for (id, order_id, wallet_id, payload, reason, attempts, last_error, created_at) in rows {
+        match serde_json::from_value::<ExecutionOrder>(payload) {
+            Ok(order) => dead_letters.push(DeadLetter {
+                id,
+                order_id,
+                wallet_id,
+                reason,
+                attempts,
+                last_error,
+                order,
+                created_at,
+            }),
+            Err(e) => tracing::warn!(id, error = %e, "dead_letter_payload_invalid"),
+        }
+    }
+    Ok(dead_letters)
+}
+
+/// Mark a pending dead letter as requeued and return its order, or `None` if
+/// it does not exist or was already requeued.
+pub async fn claim_dead_letter(pool: &PgPool, id: i64) -> Result<Option<ExecutionOrder>> {
+    let payload = sqlx::query_scalar::<_, Value>(
+        r#"
+        UPDATE execution_dead_letters
+        SET requeued_at = now()
+        WHERE id = $1 AND requeued_at IS NULL
+        RETURNING payload
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+    payload
+        .map(serde_json::from_value::<ExecutionOrder>)
+        .transpose()
+        .map_err(Into::into)
+}
+
 // ---------------------------------------------------------------------------
 // Write copy trade
 // ---------------------------------------------------------------------------
@@ -190,6 +857,7 @@ pub async fn write_copy_trade(
     follower_price: Option<f64>,
     status: &str,
     skip_reason: Option<&str>,
+    is_paper: bool,
 ) -> Result<i64> {
     let slippage_pct = follower_price.map(|fp| (fp - leader_price) / leader_price);
     let executed_at = chrono::Utc::now().timestamp();
@@ -201,9 +869,11 @@ pub async fn write_copy_trade(
             leader_address, leader_market_id, leader_outcome,
             leader_price, leader_size_usdc, leader_tx_hash,
             follower_price, slippage_pct,
-            status, skip_reason, executed_at
+            status, skip_reason, executed_at, is_paper
+        )
+        VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, to_timestamp($13), $14
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, to_timestamp($13))
         RETURNING id
         "#,
     )
@@ -220,13 +890,387 @@ pub async fn write_copy_trade(
     .bind(status)
     .bind(skip_reason)
     .bind(executed_at)
+    .bind(is_paper)
     .fetch_one(pool)
     .await?;
 
-    tracing::info!(copy_trade_id, status, "copy_trade_written");
+    tracing::info!(copy_trade_id, status, is_paper, "copy_trade_written");
     Ok(copy_trade_id)
 }
 
+// ---------------------------------------------------------------------------
+// Copy positions — follower shares held per relationship and token
+// ---------------------------------------------------------------------------
+
+/// Apply a filled copy order to the relationship's position ledger. Buys add
+/// shares and cost; sells remove shares and the matching share of cost, and
+/// record the PnL they realize. Holdings left at resolution are emptied by
+/// [`settle_copy_positions`].
+pub async fn record_copy_fill(
+    pool: &PgPool,
+    order: &ExecutionOrder,
+    result: &OrderResult,
+) -> Result<()> {
+    let Some(copy_relationship_id) = order.copy_relationship_id else {
+        return Ok(());
+    };
+    let price = match result.filled_price.or(order.price) {
+        Some(p) if p > 0.0 => p,
+        _ => return Ok(()),
+    };
+    if order.side == Side::Sell {
+        return record_copy_exit(pool, copy_relationship_id as i64, order, price).await;
+    }
+    let (shares_delta, cost_delta) = (order.size_usdc / price, order.size_usdc);
+    let outcome_str = match order.outcome {
+        crate::strategy::Outcome::Up => "UP",
+        crate::strategy::Outcome::Down => "DOWN",
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO copy_positions
+            (copy_relationship_id, condition_id, token_id, is_paper, outcome, shares,
+             cost_usdc, created_at, updated_at)
+        VALUES ($1, $2, $3, $7, $4, GREATEST($5, 0), $6, now(), now())
+        ON CONFLICT (copy_relationship_id, token_id, is_paper) DO UPDATE
+        SET shares = GREATEST(copy_positions.shares + $5, 0),
+            cost_usdc = CASE
+                WHEN $5 >= 0 THEN copy_positions.cost_usdc + $6
+                ELSE COALESCE(copy_positions.cost_usdc
+                    * GREATEST(copy_positions.shares + $5, 0)
+                    / NULLIF(copy_positions.shares, 0), 0)
+            END,
+            updated_at = now()
+        "#,
+    )
+    .bind(copy_relationship_id as i64)
+    .bind(&order.symbol)
+    .bind(&order.token_id)
+    .bind(outcome_str)
+    .bind(shares_delta)
+    .bind(cost_delta)
+    .bind(order.is_paper)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+async fn record_copy_exit(
+    pool: &PgPool,
+    copy_relationship_id: i64,
+    order: &ExecutionOrder,
+    price: f64,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let held = sqlx::query_as::<_, (i64, f64, f64)>(
+        r#"
+        SELECT id, shares::float8, cost_usdc::float8
+        FROM copy_positions
+        WHERE copy_relationship_id = $1 AND token_id = $2 AND is_paper = $3
+        FOR UPDATE
+        "#,
+    )
+    .bind(copy_relationship_id)
+    .bind(&order.token_id)
+    .bind(order.is_paper)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some((position_id, held, cost)) = held.filter(|&(_, held, _)| held > 0.0) else {
+        return Ok(());
+    };
+
+    let shares = order.size_usdc / price;
+    let left = (held - shares).max(0.0);
+    sqlx::query(
+        "UPDATE copy_positions SET shares = $2, cost_usdc = $3, updated_at = now() WHERE id = $1",
+    )
+    .bind(position_id)
+    .bind(left)
+    .bind(cost * left / held)
+    .execute(&mut *tx)
+    .await?;
+
+    let realized = CopyRealization::exit(
+        held,
+        cost,
+        shares,
+        order.size_usdc,
+        chrono::Utc::now().timestamp(),
+    );
+    insert_copy_realization(
+        &mut tx,
+        copy_relationship_id,
+        order.is_paper,
+        &order.symbol,
+        &order.token_id,
+        "exit",
+        shares.min(held),
+        &realized,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn insert_copy_realization(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    copy_relationship_id: i64,
+    is_paper: bool,
+    condition_id: &str,
+    token_id: &str,
+    reason: &str,
+    shares: f64,
+    realized: &CopyRealization,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO copy_realizations
+            (copy_relationship_id, is_paper, condition_id, token_id, reason, shares,
+             pnl_usdc, realized_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, to_timestamp($8))
+        "#,
+    )
+    .bind(copy_relationship_id)
+    .bind(is_paper)
+    .bind(condition_id)
+    .bind(token_id)
+    .bind(reason)
+    .bind(shares)
+    .bind(realized.pnl_usdc)
+    .bind(realized.realized_at)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Shares held per token from a relationship's paper or live copied fills.
+pub async fn load_copy_positions(
+    pool: &PgPool,
+    copy_relationship_id: i64,
+    is_paper: bool,
+) -> Result<HashMap<String, f64>> {
+    let rows = sqlx::query_as::<_, (String, f64)>(
+        r#"
+        SELECT token_id, shares::float8
+        FROM copy_positions
+        WHERE copy_relationship_id = $1 AND is_paper = $2 AND shares > 0
+        "#,
+    )
+    .bind(copy_relationship_id)
+    .bind(is_paper)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Markets with copied shares still held, by condition id.
+pub async fn load_open_copy_conditions(pool: &PgPool) -> Result<Vec<String>> {
+    let rows = sqlx::query_scalar::<_, String>(
+        "SELECT DISTINCT condition_id FROM copy_positions WHERE shares > 0 ORDER BY condition_id",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Settle every relationship's holdings in a resolved market: ledger rows are
+/// emptied, their PnL realized, and copied entries marked won or lost at
+/// their token's payout. Returns the number of ledger rows settled.
+pub async fn settle_copy_positions(
+    pool: &PgPool,
+    condition_id: &str,
+    payouts: &HashMap<String, f64>,
+) -> Result<u64> {
+    let now = chrono::Utc::now().timestamp();
+    let mut tx = pool.begin().await?;
+    let mut settled = 0;
+    for (token_id, &payout) in payouts {
+        let held = sqlx::query_as::<_, (i64, i64, bool, f64, f64)>(
+            r#"
+            UPDATE copy_positions p SET shares = 0, cost_usdc = 0, updated_at = now()
+            FROM copy_positions old
+            WHERE p.id = old.id AND p.condition_id = $1 AND p.token_id = $2 AND p.shares > 0
+            RETURNING p.id, p.copy_relationship_id, p.is_paper, old.shares::float8,
+                      old.cost_usdc::float8
+            "#,
+        )
+        .bind(condition_id)
+        .bind(token_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        for (_, copy_relationship_id, is_paper, shares, cost) in held {
+            let realized = CopyRealization::settlement(shares, cost, payout, now);
+            insert_copy_realization(
+                &mut tx,
+                copy_relationship_id,
+                is_paper,
+                condition_id,
+                token_id,
+                "resolution",
+                shares,
+                &realized,
+            )
+            .await?;
+            settled += 1;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE trades
+            SET status = CASE WHEN $3 >= 0.5 THEN 'won' ELSE 'lost' END, resolved_price = $3
+            WHERE copy_relationship_id IS NOT NULL AND symbol = $1 AND token_id = $2
+              AND side = 'buy' AND status = 'filled'
+            "#,
+        )
+        .bind(condition_id)
+        .bind(token_id)
+        .bind(payout)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(settled)
+}
+
+/// A relationship's copy activity in one mode, paper or live.
+#[derive(Debug, Clone, sqlx::FromRow, serde::Serialize)]
+pub struct CopyModeStats {
+    pub is_paper: bool,
+    pub filled: i64,
+    pub skipped: i64,
+    /// Copies that reached the executor but did not fill.
+    pub failed: i64,
+    pub avg_slippage_pct: Option<f64>,
+    /// Entry and exit notional of the copied trades.
+    pub traded_usdc: f64,
+    pub open_positions: i64,
+    pub open_cost_usdc: f64,
+}
+
+/// Live and paper stats of a relationship, live first.
+pub async fn load_copy_stats(
+    pool: &PgPool,
+    copy_relationship_id: i64,
+) -> Result<Vec<CopyModeStats>> {
+    let rows = sqlx::query_as::<_, CopyModeStats>(
+        r#"
+        SELECT m.is_paper,
+               COUNT(ct.id) FILTER (WHERE ct.status = 'filled') AS filled,
+               COUNT(ct.id) FILTER (WHERE ct.status = 'skipped') AS skipped,
+               COUNT(ct.id) FILTER (WHERE ct.status NOT IN ('filled', 'skipped')) AS failed,
+               (AVG(ct.slippage_pct) FILTER (WHERE ct.status = 'filled'))::float8
+                   AS avg_slippage_pct,
+               (SELECT COALESCE(SUM(t.size_usdc), 0)::float8 FROM trades t
+                WHERE t.copy_relationship_id = $1 AND t.is_paper = m.is_paper
+                  AND t.status IN ('filled', 'closed', 'won', 'lost')) AS traded_usdc,
+               (SELECT COUNT(*) FROM copy_positions p
+                WHERE p.copy_relationship_id = $1 AND p.is_paper = m.is_paper
+                  AND p.shares > 0) AS open_positions,
+               (SELECT COALESCE(SUM(p.cost_usdc), 0)::float8 FROM copy_positions p
+                WHERE p.copy_relationship_id = $1 AND p.is_paper = m.is_paper
+                  AND p.shares > 0) AS open_cost_usdc
+        FROM (VALUES (false), (true)) AS m(is_paper)
+        LEFT JOIN copy_trades ct
+            ON ct.copy_relationship_id = $1 AND ct.is_paper = m.is_paper
+        GROUP BY m.is_paper
+        ORDER BY m.is_paper
+        "#,
+    )
+    .bind(copy_relationship_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Realized PnL and recent copy activity of a relationship in one mode, from
+/// the exits and settlements recorded in `copy_realizations`.
+pub async fn load_copy_risk_state(
+    pool: &PgPool,
+    copy_relationship_id: i64,
+    is_paper: bool,
+) -> Result<CopyRiskState> {
+    let realized = sqlx::query_as::<_, CopyRealization>(
+        r#"
+        SELECT extract(epoch FROM realized_at)::int8 AS realized_at,
+               pnl_usdc::float8 AS pnl_usdc
+        FROM copy_realizations
+        WHERE copy_relationship_id = $1 AND is_paper = $2
+        ORDER BY realized_at, id
+        "#,
+    )
+    .bind(copy_relationship_id)
+    .bind(is_paper)
+    .fetch_all(pool)
+    .await?;
+    let trades_last_hour = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*) FROM copy_trades
+        WHERE copy_relationship_id = $1 AND is_paper = $2 AND status <> 'skipped'
+          AND created_at >= now() - interval '1 hour'
+        "#,
+    )
+    .bind(copy_relationship_id)
+    .bind(is_paper)
+    .fetch_one(pool)
+    .await?;
+
+    let now = chrono::Utc::now().timestamp();
+    Ok(CopyRiskState::from_realized(
+        &realized,
+        now - now.rem_euclid(86_400),
+        trades_last_hour,
+    ))
+}
+
+/// Deactivate a relationship and record why.
+pub async fn pause_copy_relationship(
+    pool: &PgPool,
+    copy_relationship_id: i64,
+    reason: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE copy_relationships
+        SET is_active = false, paused_reason = $2, paused_at = now()
+        WHERE id = $1
+        "#,
+    )
+    .bind(copy_relationship_id)
+    .bind(reason)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Follower vs leader fill prices over a leader's live filled copies.
+#[derive(Debug, Clone, sqlx::FromRow, serde::Serialize)]
+pub struct CopyGap {
+    pub fills: i64,
+    /// Follower fill minus leader fill, in price units.
+    pub avg_price_gap: Option<f64>,
+    pub avg_slippage_pct: Option<f64>,
+}
+
+pub async fn load_copy_gap(pool: &PgPool, leader_address: &str, since: i64) -> Result<CopyGap> {
+    let gap = sqlx::query_as::<_, CopyGap>(
+        r#"
+        SELECT COUNT(*) AS fills,
+               AVG(follower_price - leader_price)::float8 AS avg_price_gap,
+               AVG(slippage_pct)::float8 AS avg_slippage_pct
+        FROM copy_trades
+        WHERE leader_address = $1 AND status = 'filled' AND is_paper = false
+          AND follower_price IS NOT NULL AND executed_at >= to_timestamp($2)
+        "#,
+    )
+    .bind(leader_address)
+    .bind(since)
+    .fetch_one(pool)
+    .await?;
+    Ok(gap)
+}
+
 // ---------------------------------------------------------------------------
 // CopyRelationship
 // ---------------------------------------------------------------------------
@@ -238,7 +1282,15 @@ pub struct CopyRelationship {
     pub size_mode: String,
     pub size_value: f64,
     pub max_position_usdc: f64,
+    /// Clamps on an entry's size, before rounding to the market minimum.
+    pub min_size_usdc: Option<f64>,
+    pub max_size_usdc: Option<f64>,
     pub markets_filter: Option<serde_json::Value>,
+    pub execution_algo: Option<ExecAlgo>,
+    pub filters: CopyFilters,
+    pub risk_limits: CopyRiskLimits,
+    /// Copies are simulated by the executor instead of sent to the CLOB.
+    pub is_paper: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -261,10 +1313,26 @@ pub async fn get_active_followers(
     pool: &PgPool,
     watched_address: &str,
 ) -> Result<Vec<CopyRelationship>> {
-    let rows = sqlx::query_as::<_, (i64, i64, String, f64, f64, Option<serde_json::Value>)>(
+    type FollowerRow = (
+        i64,
+        i64,
+        String,
+        f64,
+        f64,
+        Option<f64>,
+        Option<f64>,
+        Option<Value>,
+        Option<Value>,
+        Option<Value>,
+        Option<Value>,
+        bool,
+    );
+    let rows = sqlx::query_as::<_, FollowerRow>(
         r#"
         SELECT cr.id, cr.follower_wallet_id, cr.size_mode, cr.size_value,
-               cr.max_position_usdc, cr.markets_filter
+               cr.max_position_usdc, cr.min_size_usdc::float8, cr.max_size_usdc::float8,
+               cr.markets_filter, cr.execution_algo,
+               cr.copy_filters, cr.risk_limits, cr.is_paper
         FROM copy_relationships cr
         JOIN watched_wallets ww ON ww.id = cr.watched_wallet_id
         WHERE ww.address = $1
@@ -278,15 +1346,38 @@ pub async fn get_active_followers(
     let followers = rows
         .into_iter()
         .map(
-            |(id, follower_wallet_id, size_mode, size_value, max_position_usdc, markets_filter)| {
-                CopyRelationship {
-                    id,
-                    follower_wallet_id,
-                    size_mode,
-                    size_value,
-                    max_position_usdc,
-                    markets_filter,
-                }
+            |(
+                id,
+                follower_wallet_id,
+                size_mode,
+                size_value,
+                max_position_usdc,
+                min_size_usdc,
+                max_size_usdc,
+                markets_filter,
+                execution_algo,
+                copy_filters,
+                risk_limits,
+                is_paper,
+            )| CopyRelationship {
+                id,
+                follower_wallet_id,
+                size_mode,
+                size_value,
+                max_position_usdc,
+                min_size_usdc,
+                max_size_usdc,
+                markets_filter,
+                execution_algo: execution_algo.as_ref().and_then(ExecAlgo::from_value),
+                filters: copy_filters
+                    .as_ref()
+                    .map(CopyFilters::from_value)
+                    .unwrap_or_default(),
+                risk_limits: risk_limits
+                    .as_ref()
+                    .map(CopyRiskLimits::from_value)
+                    .unwrap_or_default(),
+                is_paper,
             },
         )
         .collect();
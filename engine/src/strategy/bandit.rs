@@ -2,6 +2,7 @@ use serde_json::Value;
 
 use super::state::{PendingBanditChoice, PendingBanditRewardObservation, StrategyState};
 use super::{OrderType, Outcome, Signal};
+use crate::execution::fees::round_trip_fee_usdc;
 use crate::fetcher::models::Tick;
 use crate::tasks::model_score_task::ModelScoreCache;
 
@@ -83,8 +84,15 @@ pub fn update_pending_rewards(graph: &Value, tick: &Tick, state: &mut StrategySt
             continue;
         }
 
-        let raw_reward_bps =
-            (mark_price - observation.entry_price) / observation.entry_price * 10_000.0;
+        let fee_drag_bps = round_trip_fee_usdc(
+            observation.fee_bps,
+            observation.entry_price,
+            mark_price,
+            1.0,
+        ) * 10_000.0;
+        let raw_reward_bps = (mark_price - observation.entry_price) / observation.entry_price
+            * 10_000.0
+            - fee_drag_bps;
         let reward_bps =
             raw_reward_bps.clamp(-observation.reward_clip_bps, observation.reward_clip_bps);
         let arm = state
@@ -239,6 +247,7 @@ pub fn record_entry_fill(
     symbol: &str,
     filled_price: f64,
     filled_at: i64,
+    fee_bps: u16,
 ) {
     let Some(choice) = state.pending_bandit_choice.take() else {
         return;
@@ -259,6 +268,7 @@ pub fn record_entry_fill(
             entry_price: filled_price,
             due_at: filled_at + choice.reward_horizon_sec.max(1),
             reward_clip_bps: config.reward_clip_bps,
+            fee_bps,
         });
 }
 
@@ -479,6 +489,7 @@ mod tests {
                 entry_price: 0.60,
                 due_at: 1_700_000_550,
                 reward_clip_bps: 500.0,
+                fee_bps: 0,
             });
 
         update_pending_rewards(&graph, &tick, &mut state);
@@ -488,4 +499,36 @@ mod tests {
         assert!(arm.total_reward_bps > 0.0);
         assert!(state.pending_bandit_reward_observations.is_empty());
     }
+
+    #[test]
+    fn scores_markout_reward_net_of_fees() {
+        let graph = bandit_graph();
+        let mut tick = test_tick();
+        tick.bid_up = 0.61;
+        tick.captured_at = time::OffsetDateTime::from_unix_timestamp(1_700_000_600).unwrap();
+
+        let mut state = StrategyState::new(32);
+        state
+            .pending_bandit_reward_observations
+            .push(PendingBanditRewardObservation {
+                profile_id: "safe".into(),
+                profile_index: 0,
+                outcome: Outcome::Up,
+                symbol: tick.symbol.clone(),
+                entry_price: 0.60,
+                due_at: 1_700_000_550,
+                reward_clip_bps: 500.0,
+                fee_bps: 200,
+            });
+
+        update_pending_rewards(&graph, &tick, &mut state);
+
+        // +166.7 bps gross markout, minus 133.3 entry and 130.0 exit fee bps.
+        let arm = state.bandit_entry_stats.get("safe").expect("safe arm");
+        assert!(
+            (arm.total_reward_bps + 96.67).abs() < 0.01,
+            "got {}",
+            arm.total_reward_bps
+        );
+    }
 }
@@ -5,8 +5,9 @@ use rdkafka::Message;
 use tokio::sync::mpsc;
 
 use super::interpreter;
-use super::registry::AssignmentRegistry;
+use super::registry::{Assignment, AssignmentRegistry};
 use super::{EngineOutput, OrderType, Outcome, Signal};
+use crate::execution::algo::ExecAlgo;
 use crate::fetcher::models::Tick;
 use crate::kafka;
 use crate::metrics as m;
@@ -77,43 +78,9 @@ pub async fn run(
         let eval_start = std::time::Instant::now();
         let signals: Vec<EngineOutput> = assignments
             .par_iter()
+            .filter(|a| !a.is_killed)
             .filter_map(|a| {
-                if a.is_killed {
-                    return None;
-                }
-                let mut state = match a.state.lock() {
-                    Ok(guard) => guard,
-                    Err(poisoned) => {
-                        tracing::warn!(
-                            wallet_id = a.wallet_id,
-                            strategy_id = a.strategy_id,
-                            "mutex_poisoned_recovering"
-                        );
-                        poisoned.into_inner()
-                    }
-                };
-                let signal = interpreter::evaluate_with_caches(
-                    &a.graph,
-                    &tick,
-                    &mut state,
-                    Some(&api_cache),
-                    Some(&model_score_cache),
-                );
-                match signal {
-                    Signal::Hold => None,
-                    s => {
-                        let reference_price = execution_reference_price(&s, &tick);
-
-                        Some(EngineOutput {
-                            wallet_id: a.wallet_id,
-                            strategy_id: a.strategy_id,
-                            symbol: tick.symbol.clone(),
-                            signal: s,
-                            reference_price,
-                            is_paper: a.is_paper,
-                        })
-                    }
-                }
+                evaluate_assignment(a, &tick, Some(&api_cache), Some(&model_score_cache))
             })
             .collect();
         histogram!(m::STRATEGY_EVAL_DURATION).record(eval_start.elapsed().as_secs_f64());
@@ -143,6 +110,50 @@ pub async fn run(
     }
 }
 
+/// Evaluate one assignment on `tick`; `None` when it holds.
+pub(crate) fn evaluate_assignment(
+    assignment: &Assignment,
+    tick: &Tick,
+    api_cache: Option<&ApiFetchCache>,
+    model_score_cache: Option<&ModelScoreCache>,
+) -> Option<EngineOutput> {
+    let mut state = match assignment.state.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            tracing::warn!(
+                wallet_id = assignment.wallet_id,
+                strategy_id = assignment.strategy_id,
+                "mutex_poisoned_recovering"
+            );
+            poisoned.into_inner()
+        }
+    };
+    let open = state.position.clone();
+    let signal = interpreter::evaluate_with_caches(
+        &assignment.graph,
+        tick,
+        &mut state,
+        api_cache,
+        model_score_cache,
+    );
+    let closes = match signal {
+        Signal::Hold => return None,
+        Signal::Sell { .. } if state.position.is_none() => open,
+        _ => None,
+    };
+
+    Some(EngineOutput {
+        wallet_id: assignment.wallet_id,
+        strategy_id: assignment.strategy_id,
+        symbol: tick.symbol.clone(),
+        reference_price: execution_reference_price(&signal, tick),
+        signal,
+        is_paper: assignment.is_paper,
+        algo: ExecAlgo::from_value(&assignment.graph["execution"]),
+        closes,
+    })
+}
+
 fn execution_reference_price(signal: &Signal, tick: &Tick) -> Option<f64> {
     match signal {
         Signal::Buy {
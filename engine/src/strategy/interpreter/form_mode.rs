@@ -101,6 +101,7 @@ mod tests {
             size_usdc: 50.0,
             entry_at: 1700000450,
             symbol: String::new(),
+            entry_fee_usdc: 0.0,
         });
         assert!(matches!(evaluate(&graph, &tick, &mut state), Signal::Hold));
     }
@@ -116,6 +117,7 @@ mod tests {
             size_usdc: 50.0,
             entry_at: 1700000000,
             symbol: String::new(),
+            entry_fee_usdc: 0.0,
         });
         let signal = evaluate(&graph, &tick, &mut state);
         assert!(matches!(
@@ -138,6 +140,7 @@ mod tests {
             size_usdc: 50.0,
             entry_at: 1700000000,
             symbol: String::new(),
+            entry_fee_usdc: 0.0,
         });
         let signal = evaluate(&graph, &tick, &mut state);
         assert!(matches!(
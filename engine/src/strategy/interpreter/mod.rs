@@ -431,6 +431,7 @@ mod tests {
             size_usdc: 42.0,
             entry_at: 1700000000,
             symbol: tick.symbol.clone(),
+            entry_fee_usdc: 0.0,
         });
 
         let signal = evaluate(&graph, &tick, &mut state);
@@ -472,6 +473,7 @@ mod tests {
             size_usdc: 42.0,
             entry_at: 1700000000,
             symbol: tick.symbol.clone(),
+            entry_fee_usdc: 0.0,
         });
 
         let signal = evaluate(&graph, &tick, &mut state);
@@ -493,6 +495,7 @@ mod tests {
             size_usdc: 42.0,
             entry_at: 1_700_000_000,
             symbol: tick.symbol.clone(),
+            entry_fee_usdc: 0.0,
         });
 
         assert_eq!(resolve_field("position_is_open", &tick, &state), Some(1.0));
@@ -239,6 +239,7 @@ mod tests {
             size_usdc: 50.0,
             entry_at: 0,
             symbol: String::new(),
+            entry_fee_usdc: 0.0,
         });
         let signal = Signal::Buy {
             outcome: Outcome::Up,
@@ -262,6 +263,7 @@ mod tests {
             size_usdc: 50.0,
             entry_at: 0,
             symbol: String::new(),
+            entry_fee_usdc: 0.0,
         });
         let signal = Signal::Buy {
             outcome: Outcome::Down,
@@ -301,6 +303,7 @@ mod tests {
             size_usdc: 50.0,
             entry_at: 0,
             symbol: String::new(),
+            entry_fee_usdc: 0.0,
         });
         let signal = Signal::Buy {
             outcome: Outcome::Up,
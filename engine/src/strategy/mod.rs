@@ -12,6 +12,8 @@ pub mod test_utils;
 
 use serde::{Deserialize, Serialize};
 
+use crate::execution::algo::ExecAlgo;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Signal {
     Buy {
@@ -34,7 +36,7 @@ pub enum Signal {
     Hold,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Outcome {
     Up,
     Down,
@@ -55,6 +57,12 @@ pub struct EngineOutput {
     pub signal: Signal,
     pub reference_price: Option<f64>,
     pub is_paper: bool,
+    /// Execution algorithm from the graph's `execution` block, if any; only
+    /// entries are sliced.
+    pub algo: Option<ExecAlgo>,
+    /// Position an exit closes: the interpreter clears it from the state as
+    /// the exit is emitted, and the fill realizes its PnL.
+    pub closes: Option<state::Position>,
 }
 
 #[cfg(test)]
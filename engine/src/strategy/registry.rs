@@ -13,6 +13,8 @@ pub struct Assignment {
     pub strategy_id: u64,
     pub graph: serde_json::Value,
     pub markets: Vec<String>,
+    /// Cap on this assignment's open entry notional, enforced pre-trade.
+    pub max_position_usdc: f64,
     pub is_paper: bool,
     pub is_killed: bool,
     pub state: Arc<Mutex<StrategyState>>,
@@ -40,7 +42,7 @@ pub async fn activate(
     strategy_id: u64,
     graph: serde_json::Value,
     markets: Vec<String>,
-    _max_position_usdc: f64,
+    max_position_usdc: f64,
     is_paper: bool,
     initial_state: Option<StrategyState>,
 ) {
@@ -50,6 +52,7 @@ pub async fn activate(
         strategy_id,
         graph,
         markets: markets.clone(),
+        max_position_usdc,
         is_paper,
         is_killed: false,
         state: Arc::new(Mutex::new(state)),
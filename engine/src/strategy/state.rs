@@ -33,6 +33,10 @@ pub struct PendingBanditRewardObservation {
     pub entry_price: f64,
     pub due_at: i64,
     pub reward_clip_bps: f64,
+    /// Taker fee rate paid on the entry fill; rewards are scored net of the
+    /// entry and a hypothetical exit at the mark.
+    #[serde(default)]
+    pub fee_bps: u16,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +48,21 @@ pub struct Position {
     /// The full slot symbol (e.g. "btc-updown-15m-1772366400") this position was opened on.
     #[serde(default)]
     pub symbol: String,
+    /// Taker fee paid on the entry fill, charged against PnL when the
+    /// position closes.
+    #[serde(default)]
+    pub entry_fee_usdc: f64,
+}
+
+impl Position {
+    /// Outcome tokens held: `size_usdc` was spent at `entry_price`.
+    pub fn shares(&self) -> f64 {
+        if self.entry_price > 0.0 {
+            self.size_usdc / self.entry_price
+        } else {
+            0.0
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,7 +72,13 @@ pub struct StrategyState {
     pub position: Option<Position>,
     #[serde(default)]
     pub pending_entry_symbol: Option<String>,
+    /// Realized PnL net of taker fees.
     pub pnl: f64,
+    /// Realized PnL before fees.
+    #[serde(default)]
+    pub gross_pnl: f64,
+    #[serde(default)]
+    pub fees_paid: f64,
     pub trades_this_slot: u32,
     pub current_slot_ts: u32,
     pub indicator_cache: HashMap<String, f64>,
@@ -79,6 +104,8 @@ impl StrategyState {
             position: None,
             pending_entry_symbol: None,
             pnl: 0.0,
+            gross_pnl: 0.0,
+            fees_paid: 0.0,
             trades_this_slot: 0,
             current_slot_ts: 0,
             indicator_cache: HashMap::new(),
@@ -97,6 +124,17 @@ impl StrategyState {
         }
         self.window.push_back(tick);
     }
+
+    /// Book a closed position: `gross` is the price PnL, `fees` the entry and
+    /// exit taker fees. Returns the net amount added to `pnl`/`daily_pnl`.
+    pub fn realize(&mut self, gross: f64, fees: f64) -> f64 {
+        let net = gross - fees;
+        self.gross_pnl += gross;
+        self.fees_paid += fees;
+        self.pnl += net;
+        self.daily_pnl += net;
+        net
+    }
 }
 
 #[cfg(test)]
@@ -138,4 +176,15 @@ mod tests {
         assert!((restored.pnl - 42.5).abs() < f64::EPSILON);
         assert!((restored.indicator_cache["ema_20"] - 0.55).abs() < f64::EPSILON);
     }
+
+    #[test]
+    fn test_realize_tracks_gross_fees_and_net() {
+        let mut state = StrategyState::new(10);
+        let net = state.realize(1.5, 2.0);
+        assert!((net + 0.5).abs() < f64::EPSILON);
+        assert!((state.gross_pnl - 1.5).abs() < f64::EPSILON);
+        assert!((state.fees_paid - 2.0).abs() < f64::EPSILON);
+        assert!((state.pnl + 0.5).abs() < f64::EPSILON);
+        assert!((state.daily_pnl + 0.5).abs() < f64::EPSILON);
+    }
 }
@@ -12,7 +12,7 @@ pub fn spawn_ws_feed(
     tasks: &mut JoinSet<anyhow::Result<()>>,
 ) {
     let ws_books = state.books.clone();
-    let ws_url = state.config.clob_ws_url.clone();
+    let ws_url = state.config.network.clob_ws_url.clone();
     tasks.spawn(async move {
         crate::fetcher::websocket::run_ws_feed(ws_url, ws_books, ws_cmd_rx).await;
         anyhow::bail!("ws_feed exited unexpectedly")
@@ -81,7 +81,7 @@ pub fn spawn_market_discovery(state: &SharedState, tasks: &mut JoinSet<anyhow::R
             let current_prices = disc_prices.read().await.clone();
             match crate::fetcher::gamma::discover_markets(
                 &disc_http,
-                &disc_cfg.gamma_api_url,
+                &disc_cfg.network.gamma_api_url,
                 &disc_cfg.sources,
                 &current_prices,
             )
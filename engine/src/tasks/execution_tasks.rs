@@ -1,36 +1,56 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use sqlx::PgPool;
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio::task::JoinSet;
 
 use super::SharedState;
+use crate::execution::balances::BalanceService;
+use crate::execution::credentials::CredentialManager;
 use crate::execution::executor;
 use crate::execution::fees::FeeCache;
+use crate::execution::journal::ExecutionJournal;
+use crate::execution::markets::MarketMetaCache;
 use crate::execution::orders::{BuilderCredentials, OrderSubmitter};
 use crate::execution::queue::ExecutionQueue;
+use crate::execution::retry::RetryPolicy;
+use crate::execution::risk::{self, RiskEngine, RiskLimits};
 use crate::execution::wallet::WalletKeyStore;
-use crate::execution::{ExecutionOrder, OrderPriority, Side};
+use crate::execution::{ExecutionOrder, MarketMeta, OrderPriority, Side};
+use crate::fetcher::models::ActiveMarket;
+use crate::notifier::WebNotifier;
 use crate::strategy::registry::AssignmentRegistry;
 use crate::strategy::{EngineOutput, OrderType, Outcome, Signal};
+use crate::watcher::chain::ChainLogSource;
+use crate::watcher::polymarket::CopyWatcher;
+use crate::watcher::source::PollingSource;
 
 // ---------------------------------------------------------------------------
 // spawn_execution — executor loop + signal-to-queue bridge
 // ---------------------------------------------------------------------------
 
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_execution(
     state: &SharedState,
     registry: AssignmentRegistry,
     signal_rx: mpsc::Receiver<EngineOutput>,
     queue: Arc<Mutex<ExecutionQueue>>,
     db: PgPool,
+    journal: Arc<ExecutionJournal>,
     wallet_keys: Arc<WalletKeyStore>,
+    api_keys: Arc<CredentialManager>,
+    balances: Arc<BalanceService>,
     tasks: &mut JoinSet<anyhow::Result<()>>,
 ) {
     let cfg = &state.config;
 
     // Fee cache
-    let fee_cache = Arc::new(FeeCache::new(state.http.clone(), &cfg.clob_api_url));
+    let fee_cache = Arc::new(FeeCache::new(state.http.clone(), &cfg.network.clob_api_url));
+    let market_cache = Arc::new(MarketMetaCache::new(
+        state.http.clone(),
+        &cfg.network.clob_api_url,
+    ));
 
     // Order submitter
     let credentials = BuilderCredentials {
@@ -40,20 +60,46 @@ pub fn spawn_execution(
     };
     let submitter = Arc::new(OrderSubmitter::new(
         state.http.clone(),
-        &cfg.clob_api_url,
+        Arc::new(cfg.network.clone()),
         credentials,
+        api_keys,
         wallet_keys,
         fee_cache,
-        cfg.neg_risk,
+        market_cache,
     ));
 
     // Signal → queue bridge
     let bridge_queue = queue.clone();
-    tasks.spawn(async move { signal_to_queue(signal_rx, bridge_queue).await });
+    let bridge_journal = journal.clone();
+    let bridge_markets = state.markets.clone();
+    tasks.spawn(async move {
+        signal_to_queue(signal_rx, bridge_queue, bridge_journal, bridge_markets).await
+    });
+
+    // Pre-trade risk engine + periodic exposure resync from `trades`
+    let risk = Arc::new(RiskEngine::new(RiskLimits::from_config(cfg)));
+    let refresh_risk = risk.clone();
+    let refresh_db = db.clone();
+    tasks.spawn(async move { risk::run_risk_refresh(refresh_risk, refresh_db).await });
 
-    // Executor loop
+    // Executor loop (replays the journal before draining the queue)
     let exec_queue = queue;
-    tasks.spawn(async move { executor::run(exec_queue, submitter, registry, db).await });
+    let retry_policy = RetryPolicy::from_config(cfg);
+    let books = state.books.clone();
+    tasks.spawn(async move {
+        executor::run(
+            exec_queue,
+            submitter,
+            registry,
+            db,
+            journal,
+            risk,
+            balances,
+            retry_policy,
+            books,
+        )
+        .await
+    });
 }
 
 // ---------------------------------------------------------------------------
@@ -64,22 +110,64 @@ pub fn spawn_watcher(
     state: &SharedState,
     queue: Arc<Mutex<ExecutionQueue>>,
     db: PgPool,
+    journal: Arc<ExecutionJournal>,
+    balances: Arc<BalanceService>,
     tasks: &mut JoinSet<anyhow::Result<()>>,
 ) {
-    let data_api_url = state.config.data_api_url.clone();
+    let network = Arc::new(state.config.network.clone());
     let http = state.http.clone();
     let redis_url = state.config.redis_url.clone();
+    let markets = state.markets.clone();
+    let books = state.books.clone();
+    let max_trade_age_secs = state.config.copy_max_trade_age_secs;
+    let notifier = WebNotifier::new(http.clone(), &state.config.web_internal_url);
+    let stream_url = match state.config.copy_trade_source.as_str() {
+        "chain" => {
+            if state.config.polygon_ws_url.is_none() {
+                tracing::warn!("copy_chain_source_without_ws_url_falling_back_to_poll");
+            }
+            state.config.polygon_ws_url.clone()
+        }
+        _ => None,
+    };
 
     tasks.spawn(crate::supervisor::supervised("copy_watcher", move || {
-        let url = data_api_url.clone();
-        let h = http.clone();
-        let q = queue.clone();
-        let d = db.clone();
+        let watcher = CopyWatcher {
+            network: network.clone(),
+            http: http.clone(),
+            queue: queue.clone(),
+            db: db.clone(),
+            journal: journal.clone(),
+            markets: markets.clone(),
+            books: books.clone(),
+            balances: balances.clone(),
+            notifier: notifier.clone(),
+        };
         let r = redis_url.clone();
+        let stream_url = stream_url.clone();
         async move {
             let client = redis::Client::open(r.as_str())?;
             let conn = client.get_multiplexed_tokio_connection().await?;
-            crate::watcher::polymarket::run(&url, h, q, d, conn).await
+            match stream_url {
+                Some(url) => {
+                    let source = ChainLogSource::spawn(
+                        &url,
+                        &watcher.network,
+                        watcher.http.clone(),
+                        watcher.markets.clone(),
+                        max_trade_age_secs,
+                    );
+                    watcher.run(source, conn).await
+                }
+                None => {
+                    let source = PollingSource::new(
+                        &watcher.network.data_api_url,
+                        watcher.http.clone(),
+                        max_trade_age_secs,
+                    );
+                    watcher.run(source, conn).await
+                }
+            }
         }
     }));
 }
@@ -88,44 +176,16 @@ pub fn spawn_watcher(
 // signal_to_queue — bridge strategy engine signals to execution queue
 // ---------------------------------------------------------------------------
 
-async fn signal_to_queue(
+pub(crate) async fn signal_to_queue(
     mut signal_rx: mpsc::Receiver<EngineOutput>,
     queue: Arc<Mutex<ExecutionQueue>>,
+    journal: Arc<ExecutionJournal>,
+    markets: Arc<RwLock<HashMap<String, ActiveMarket>>>,
 ) -> anyhow::Result<()> {
     tracing::info!("signal_to_queue_bridge_started");
 
     while let Some(output) = signal_rx.recv().await {
-        let order = match &output.signal {
-            Signal::Buy {
-                outcome,
-                size_usdc,
-                order_type,
-            } => build_order_from_signal(
-                output.wallet_id,
-                output.strategy_id,
-                &output.symbol,
-                Side::Buy,
-                *outcome,
-                *size_usdc,
-                order_type,
-                output.reference_price,
-                output.is_paper,
-            ),
-            Signal::Sell {
-                outcome,
-                size_usdc,
-                order_type,
-            } => build_order_from_signal(
-                output.wallet_id,
-                output.strategy_id,
-                &output.symbol,
-                Side::Sell,
-                *outcome,
-                *size_usdc,
-                order_type,
-                output.reference_price,
-                output.is_paper,
-            ),
+        match &output.signal {
             Signal::Cancel { outcome } => {
                 tracing::info!(
                     wallet_id = output.wallet_id,
@@ -146,8 +206,12 @@ async fn signal_to_queue(
                 );
                 continue;
             }
-            Signal::Hold => continue,
+            Signal::Buy { .. } | Signal::Sell { .. } | Signal::Hold => {}
+        }
+        let Some(mut order) = order_from_output(&output) else {
+            continue;
         };
+        attach_market(&mut order, &*markets.read().await);
 
         tracing::info!(
             wallet_id = order.wallet_id,
@@ -158,13 +222,46 @@ async fn signal_to_queue(
             "signal_queued_for_execution"
         );
 
-        let mut q = queue.lock().await;
-        q.push(order);
+        journal.enqueue(&queue, order).await;
     }
 
     Ok(())
 }
 
+/// The order a buy or sell signal places.
+pub(crate) fn order_from_output(output: &EngineOutput) -> Option<ExecutionOrder> {
+    let (side, outcome, size_usdc, order_type) = match &output.signal {
+        Signal::Buy {
+            outcome,
+            size_usdc,
+            order_type,
+        } => (Side::Buy, outcome, size_usdc, order_type),
+        Signal::Sell {
+            outcome,
+            size_usdc,
+            order_type,
+        } => (Side::Sell, outcome, size_usdc, order_type),
+        Signal::Cancel { .. } | Signal::Notify { .. } | Signal::Hold => return None,
+    };
+    let mut order = build_order_from_signal(
+        output.wallet_id,
+        output.strategy_id,
+        &output.symbol,
+        side,
+        *outcome,
+        *size_usdc,
+        order_type,
+        output.reference_price,
+        output.is_paper,
+    );
+    // Exits go out whole, carrying the position their fill closes.
+    match side {
+        Side::Buy => order.algo = output.algo,
+        Side::Sell => order.closes = output.closes.clone(),
+    }
+    Some(order)
+}
+
 fn build_order_from_signal(
     wallet_id: u64,
     strategy_id: u64,
@@ -191,7 +288,7 @@ fn build_order_from_signal(
         strategy_id: Some(strategy_id),
         copy_relationship_id: None,
         symbol: symbol.to_string(),
-        token_id: String::new(), // resolved by the executor from symbol/outcome
+        token_id: String::new(), // resolved from discovery by `attach_market`
         side,
         outcome,
         price,
@@ -203,12 +300,31 @@ fn build_order_from_signal(
         leader_address: String::new(),
         leader_tx_hash: String::new(),
         is_paper,
+        market: None,
+        algo: None,
+        parent_id: None,
+        closes: None,
     }
 }
 
+/// Resolve the outcome token and market parameters for a strategy order from
+/// the discovered market whose slug matches the order's symbol.
+fn attach_market(order: &mut ExecutionOrder, markets: &HashMap<String, ActiveMarket>) {
+    let Some(market) = markets.values().find(|m| m.slug == order.symbol) else {
+        tracing::warn!(symbol = %order.symbol, "signal_market_not_discovered");
+        return;
+    };
+    order.token_id = match order.outcome {
+        Outcome::Up => market.token_up.clone(),
+        Outcome::Down => market.token_down.clone(),
+    };
+    order.market = Some(MarketMeta::from(market));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::execution::algo::ExecAlgo;
 
     #[test]
     fn market_orders_keep_reference_price_without_turning_into_limits() {
@@ -228,4 +344,107 @@ mod tests {
         assert_eq!(order.reference_price, Some(0.62));
         assert_eq!(order.priority, OrderPriority::StrategyMarket);
     }
+
+    #[test]
+    fn attach_market_resolves_token_and_neg_risk_from_slug() {
+        let market = ActiveMarket {
+            condition_id: "0xcid".into(),
+            slug: "btc-updown-15m-1700000000".into(),
+            binance_symbol: None,
+            slot_ts: 1_700_000_000,
+            slot_duration: 900,
+            end_time: 1_700_000_900.0,
+            token_up: "tok_up".into(),
+            token_down: "tok_down".into(),
+            ref_price_start: None,
+            neg_risk: true,
+            tick_size: 0.001,
+            min_order_size: 5.0,
+        };
+        let markets = HashMap::from([(market.condition_id.clone(), market)]);
+
+        let mut order = build_order_from_signal(
+            1,
+            2,
+            "btc-updown-15m-1700000000",
+            Side::Buy,
+            Outcome::Down,
+            5.0,
+            &OrderType::Market,
+            Some(0.40),
+            false,
+        );
+        attach_market(&mut order, &markets);
+
+        assert_eq!(order.token_id, "tok_down");
+        let meta = order.market.expect("market meta attached");
+        assert!(meta.neg_risk);
+        assert!((meta.tick_size - 0.001).abs() < f64::EPSILON);
+
+        let mut unknown = build_order_from_signal(
+            1,
+            2,
+            "eth-updown-15m-1700000000",
+            Side::Buy,
+            Outcome::Up,
+            5.0,
+            &OrderType::Market,
+            None,
+            false,
+        );
+        attach_market(&mut unknown, &markets);
+        assert!(unknown.token_id.is_empty());
+        assert!(unknown.market.is_none());
+    }
+
+    #[tokio::test]
+    async fn execution_algo_only_slices_entries() {
+        let db = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(200))
+            .connect_lazy("postgres://localhost:1/test")
+            .unwrap();
+        let queue = Arc::new(Mutex::new(ExecutionQueue::new(100)));
+        let (tx, rx) = mpsc::channel(4);
+        let output = |signal| EngineOutput {
+            wallet_id: 1,
+            strategy_id: 2,
+            symbol: "btc-updown-15m-1700000000".into(),
+            signal,
+            reference_price: Some(0.5),
+            is_paper: true,
+            algo: Some(ExecAlgo::Iceberg { clip_usdc: 5.0 }),
+            closes: None,
+        };
+        tx.send(output(Signal::Buy {
+            outcome: Outcome::Up,
+            size_usdc: 20.0,
+            order_type: OrderType::Market,
+        }))
+        .await
+        .unwrap();
+        tx.send(output(Signal::Sell {
+            outcome: Outcome::Up,
+            size_usdc: 20.0,
+            order_type: OrderType::Market,
+        }))
+        .await
+        .unwrap();
+        drop(tx);
+
+        signal_to_queue(
+            rx,
+            queue.clone(),
+            Arc::new(ExecutionJournal::new(db, 120)),
+            Arc::new(RwLock::new(HashMap::new())),
+        )
+        .await
+        .unwrap();
+
+        let mut q = queue.lock().await;
+        let mut orders: Vec<ExecutionOrder> = std::iter::from_fn(|| q.pop()).collect();
+        orders.sort_by_key(|o| o.side == Side::Sell);
+        assert_eq!(orders.len(), 2);
+        assert!(orders[0].algo.is_some());
+        assert!(orders[1].algo.is_none());
+    }
 }
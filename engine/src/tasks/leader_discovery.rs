@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use clickhouse::Client;
+use tokio::sync::RwLock;
+
+use crate::fetcher::models::ActiveMarket;
+use crate::proxy::HttpPool;
+use crate::watcher::discovery::{self, LeaderDiscovery};
+
+/// Score the public trades of every tracked market once its slot resolves
+/// and publish the ranked wallets for the candidates endpoint.
+///
+/// Records are kept in memory: after a restart the list is rebuilt as new
+/// slots resolve, and the previous one stays published until then.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_leader_discovery(
+    markets: Arc<RwLock<HashMap<String, ActiveMarket>>>,
+    ch: Client,
+    http: HttpPool,
+    data_api_url: String,
+    gamma_url: String,
+    redis_url: String,
+    interval_secs: u64,
+    window_secs: i64,
+) -> Result<()> {
+    let client = redis::Client::open(redis_url.as_str())?;
+    let mut conn = client.get_multiplexed_tokio_connection().await?;
+    let mut leaders = LeaderDiscovery::new(window_secs);
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs.max(60)));
+    tracing::info!(window_secs, "leader_discovery_started");
+
+    loop {
+        interval.tick().await;
+        leaders.track(markets.read().await.values());
+
+        let now = chrono::Utc::now().timestamp();
+        let ended = leaders.ended(now);
+        let winners = if ended.is_empty() {
+            HashMap::new()
+        } else {
+            discovery::resolve(&ch, &http, &gamma_url, &ended).await
+        };
+        for market in &ended {
+            let Some(&winner) = winners.get(&market.slug) else {
+                continue;
+            };
+            match discovery::fetch_market_trades(&http, &data_api_url, &market.condition_id).await {
+                Ok(Some(trades)) => leaders.record(market, trades, winner),
+                Ok(None) => {
+                    tracing::warn!(slug = %market.slug, "discovery_market_too_busy");
+                    leaders.record_truncated(market);
+                }
+                Err(e) => {
+                    tracing::warn!(slug = %market.slug, error = %e, "discovery_trades_fetch_failed");
+                }
+            }
+        }
+        leaders.prune(now);
+
+        if leaders.markets_scored() == 0 {
+            continue;
+        }
+        let list = leaders.list(now);
+        match discovery::publish(&mut conn, &list).await {
+            Ok(()) => tracing::info!(
+                markets = list.markets_scored,
+                truncated = list.markets_truncated,
+                candidates = list.candidates.len(),
+                "leader_candidates_published"
+            ),
+            Err(e) => tracing::warn!(error = %e, "leader_candidates_publish_failed"),
+        }
+    }
+}
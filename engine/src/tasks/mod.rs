@@ -1,11 +1,14 @@
 pub mod api_fetch_task;
 mod data_feed;
 mod engine_tasks;
-mod execution_tasks;
+pub(crate) mod execution_tasks;
 mod json_path;
+mod leader_discovery;
 pub mod model_score_task;
 mod persistence;
-mod slot_resolver;
+mod reconciler;
+mod redeemer;
+pub(crate) mod slot_resolver;
 mod trade_analytics;
 mod writers;
 
@@ -25,6 +28,12 @@ use crate::proxy::HttpPool;
 pub struct SpawnedHandles {
     pub registry: crate::strategy::registry::AssignmentRegistry,
     pub wallet_keys: Arc<crate::execution::wallet::WalletKeyStore>,
+    pub balances: Arc<crate::execution::balances::BalanceService>,
+    pub api_keys: Arc<crate::execution::credentials::CredentialManager>,
+    pub relayer: Arc<crate::execution::relayer::RelayerClient>,
+    pub db: sqlx::PgPool,
+    pub journal: Arc<crate::execution::journal::ExecutionJournal>,
+    pub exec_queue: Arc<Mutex<crate::execution::queue::ExecutionQueue>>,
 }
 
 pub struct SharedState {
@@ -107,6 +116,31 @@ pub async fn spawn_all(
         ),
     );
 
+    // Chain and contract set every signer works against
+    let network = Arc::new(state.config.network.clone());
+
+    // Per-wallet CLOB API credentials (shared between execution and API)
+    let api_keys = Arc::new(crate::execution::credentials::CredentialManager::new(
+        state.http.clone(),
+        network.clone(),
+        db.clone(),
+        wallet_keys.clone(),
+    ));
+
+    // Wallet balance tracking (shared between execution and API)
+    let balances = Arc::new(crate::execution::balances::BalanceService::new(
+        state.http.clone(),
+        network.clone(),
+        api_keys.clone(),
+        wallet_keys.clone(),
+    ));
+
+    // Durable journal backing the execution queue
+    let journal = Arc::new(crate::execution::journal::ExecutionJournal::new(
+        db.clone(),
+        state.config.order_max_age_secs,
+    ));
+
     rehydrate_running_assignments(
         &state.config.redis_url,
         &db,
@@ -122,18 +156,28 @@ pub async fn spawn_all(
         signal_rx,
         exec_queue.clone(),
         db.clone(),
+        journal.clone(),
         wallet_keys.clone(),
+        api_keys.clone(),
+        balances.clone(),
         tasks,
     );
 
     // Copy trading watcher
-    execution_tasks::spawn_watcher(state, exec_queue.clone(), db.clone(), tasks);
+    execution_tasks::spawn_watcher(
+        state,
+        exec_queue.clone(),
+        db.clone(),
+        journal.clone(),
+        balances.clone(),
+        tasks,
+    );
 
     // Slot resolution (backfill winner from Gamma API + resolve trades)
     {
         let ch = crate::storage::clickhouse::create_client(&state.config.clickhouse_url);
         let http = state.http.clone();
-        let gamma_url = state.config.gamma_api_url.clone();
+        let gamma_url = state.config.network.gamma_api_url.clone();
         let resolver_db = db.clone();
         let resolver_registry = engine_registry.clone();
         tasks.spawn(async move {
@@ -142,6 +186,63 @@ pub async fn spawn_all(
         });
     }
 
+    // Position reconciliation (engine + trades vs. exchange holdings)
+    {
+        let http = state.http.clone();
+        let data_api_url = state.config.network.data_api_url.clone();
+        let reconcile_db = db.clone();
+        let reconcile_registry = engine_registry.clone();
+        let reconcile_keys = wallet_keys.clone();
+        let interval_secs = state.config.reconcile_interval_secs;
+        let auto_correct = state.config.reconcile_auto_correct;
+        tasks.spawn(async move {
+            reconciler::run_reconciler(
+                http,
+                data_api_url,
+                reconcile_db,
+                reconcile_registry,
+                reconcile_keys,
+                interval_secs,
+                auto_correct,
+            )
+            .await
+        });
+    }
+
+    // Builder Relayer client (Safe deployment and redemptions)
+    let relayer = Arc::new(crate::execution::relayer::RelayerClient::new(
+        state.http.clone(),
+        network,
+        crate::execution::orders::BuilderCredentials {
+            api_key: state.config.builder_api_key.clone(),
+            secret: state.config.builder_secret.clone(),
+            passphrase: state.config.builder_passphrase.clone(),
+        },
+        wallet_keys.clone(),
+        db.clone(),
+    ));
+
+    // Redemption of winning positions (frees USDC after slot resolution)
+    if state.config.redeem_interval_secs > 0 {
+        let http = state.http.clone();
+        let data_api_url = state.config.network.data_api_url.clone();
+        let redeem_db = db.clone();
+        let redeem_relayer = relayer.clone();
+        let redeem_keys = wallet_keys.clone();
+        let interval_secs = state.config.redeem_interval_secs;
+        tasks.spawn(async move {
+            redeemer::run_redeemer(
+                http,
+                data_api_url,
+                redeem_db,
+                redeem_relayer,
+                redeem_keys,
+                interval_secs,
+            )
+            .await
+        });
+    }
+
     // Post-fill execution analytics (60s markout from ClickHouse mid prices)
     {
         let ch = crate::storage::clickhouse::create_client(&state.config.clickhouse_url);
@@ -149,12 +250,43 @@ pub async fn spawn_all(
         tasks.spawn(async move { trade_analytics::run_trade_analytics(ch, analytics_db).await });
     }
 
+    // Leader discovery (ranks wallets trading tracked slots)
+    if state.config.leader_discovery_interval_secs > 0 {
+        let markets = state.markets.clone();
+        let ch = crate::storage::clickhouse::create_client(&state.config.clickhouse_url);
+        let http = state.http.clone();
+        let data_api_url = state.config.network.data_api_url.clone();
+        let gamma_url = state.config.network.gamma_api_url.clone();
+        let redis_url = state.config.redis_url.clone();
+        let interval_secs = state.config.leader_discovery_interval_secs;
+        let window_secs = state.config.leader_discovery_window_secs;
+        tasks.spawn(async move {
+            leader_discovery::run_leader_discovery(
+                markets,
+                ch,
+                http,
+                data_api_url,
+                gamma_url,
+                redis_url,
+                interval_secs,
+                window_secs,
+            )
+            .await
+        });
+    }
+
     // Redis state persistence
     persistence::spawn_redis_state_persister(state, engine_registry.clone(), tasks);
 
     Ok(SpawnedHandles {
         registry: engine_registry,
         wallet_keys,
+        balances,
+        api_keys,
+        relayer,
+        db,
+        journal,
+        exec_queue,
     })
 }
 
@@ -238,7 +370,12 @@ async fn rehydrate_running_assignments(
             )
             .await
             {
-                Ok(state) => state,
+                // In-flight entries are re-marked by the execution journal replay;
+                // a stale marker here would block entries forever.
+                Ok(state) => state.map(|mut s| {
+                    s.pending_entry_symbol = None;
+                    s
+                }),
                 Err(e) => {
                     tracing::warn!(
                         wallet_id = assignment.wallet_id,
@@ -326,6 +326,7 @@ mod tests {
                 ]
             }),
             markets: vec!["btc-updown-15m".into()],
+            max_position_usdc: 1000.0,
             is_paper: false,
             is_killed: false,
             state: Arc::new(std::sync::Mutex::new(
@@ -360,6 +361,7 @@ mod tests {
                 }
             }),
             markets: vec!["btc-updown-15m".into()],
+            max_position_usdc: 1000.0,
             is_paper: false,
             is_killed: false,
             state: Arc::new(std::sync::Mutex::new(
@@ -0,0 +1,545 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use metrics::counter;
+use sqlx::PgPool;
+
+use crate::execution::balances::{fetch_positions, DataApiPosition};
+use crate::execution::wallet::WalletKeyStore;
+use crate::metrics as m;
+use crate::proxy::HttpPool;
+use crate::storage::postgres::{self, LiveCopyHolding, OpenLiveTrade};
+use crate::strategy::registry::AssignmentRegistry;
+use crate::strategy::Outcome;
+
+/// Positions younger than this are still settling on-chain and are skipped.
+const GRACE_SECS: i64 = 120;
+/// Share amounts below this are treated as empty.
+const DUST_SHARES: f64 = 0.01;
+/// Relative size difference tolerated between `trades` and the exchange.
+const SIZE_TOLERANCE: f64 = 0.05;
+
+type HoldingKey = (String, Outcome);
+
+// ---------------------------------------------------------------------------
+// Divergence
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DivergenceKind {
+    /// Tokens held on the exchange that neither the engine nor `trades` knows about.
+    OrphanTokens,
+    /// An engine position with no tokens behind it.
+    PhantomPosition,
+    /// An open `trades` row with no tokens behind it.
+    PhantomTrade,
+    /// Both sides hold the position, but the share counts disagree.
+    SizeMismatch,
+}
+
+impl DivergenceKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::OrphanTokens => "orphan_tokens",
+            Self::PhantomPosition => "phantom_position",
+            Self::PhantomTrade => "phantom_trade",
+            Self::SizeMismatch => "size_mismatch",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub kind: DivergenceKind,
+    pub symbol: String,
+    pub outcome: Outcome,
+    pub strategy_id: Option<u64>,
+    pub engine_shares: f64,
+    pub db_shares: f64,
+    pub exchange_shares: f64,
+}
+
+/// An in-memory strategy position, in shares.
+#[derive(Debug, Clone)]
+pub struct EnginePosition {
+    pub strategy_id: u64,
+    pub symbol: String,
+    pub outcome: Outcome,
+    pub shares: f64,
+    pub entry_at: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct ReconcileReport {
+    pub matched: usize,
+    pub skipped: usize,
+    /// Holdings in resolved markets, left to the redeemer rather than compared.
+    pub redeemable: usize,
+    pub divergences: Vec<Divergence>,
+}
+
+fn parse_outcome(raw: &str) -> Option<Outcome> {
+    if raw.eq_ignore_ascii_case("up") || raw.eq_ignore_ascii_case("yes") {
+        Some(Outcome::Up)
+    } else if raw.eq_ignore_ascii_case("down") || raw.eq_ignore_ascii_case("no") {
+        Some(Outcome::Down)
+    } else {
+        None
+    }
+}
+
+fn shares_for(size_usdc: f64, price: Option<f64>) -> f64 {
+    match price {
+        Some(p) if p > 0.0 => size_usdc / p,
+        _ => size_usdc,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// reconcile — compare one wallet's three views of its positions
+// ---------------------------------------------------------------------------
+
+/// Copies are recorded by token: they are matched to the exchange position
+/// of that token, and reported under the token id when there is none.
+pub fn reconcile(
+    engine: &[EnginePosition],
+    trades: &[OpenLiveTrade],
+    copies: &[LiveCopyHolding],
+    exchange: &[DataApiPosition],
+    now: i64,
+) -> ReconcileReport {
+    let mut report = ReconcileReport::default();
+
+    let mut exchange_shares: HashMap<HoldingKey, f64> = HashMap::new();
+    let mut redeemable: HashSet<HoldingKey> = HashSet::new();
+    let mut token_keys: HashMap<&str, HoldingKey> = HashMap::new();
+    for p in exchange {
+        let Some(outcome) = p.outcome.as_deref().and_then(parse_outcome) else {
+            continue;
+        };
+        token_keys.insert(&p.asset, (p.slug.clone(), outcome));
+        if p.redeemable {
+            redeemable.insert((p.slug.clone(), outcome));
+            continue;
+        }
+        *exchange_shares
+            .entry((p.slug.clone(), outcome))
+            .or_insert(0.0) += p.size;
+    }
+
+    // Keys with any recent activity are still settling — leave them alone.
+    let mut settling: HashSet<HoldingKey> = HashSet::new();
+    let mut db_shares: HashMap<HoldingKey, f64> = HashMap::new();
+    for t in trades {
+        let outcome = parse_outcome(&t.outcome).unwrap_or(Outcome::Up);
+        let key = (t.symbol.clone(), outcome);
+        if now - t.executed_ts < GRACE_SECS {
+            settling.insert(key.clone());
+        }
+        *db_shares.entry(key).or_insert(0.0) += shares_for(t.size_usdc, t.price);
+    }
+    for c in copies {
+        let key = token_keys
+            .get(c.token_id.as_str())
+            .cloned()
+            .unwrap_or_else(|| {
+                let outcome = parse_outcome(&c.outcome).unwrap_or(Outcome::Up);
+                (c.token_id.clone(), outcome)
+            });
+        if now - c.updated_ts < GRACE_SECS {
+            settling.insert(key.clone());
+        }
+        *db_shares.entry(key).or_insert(0.0) += c.shares;
+    }
+
+    let mut engine_by_key: HashMap<HoldingKey, Vec<&EnginePosition>> = HashMap::new();
+    for p in engine {
+        let key = (p.symbol.clone(), p.outcome);
+        if now - p.entry_at < GRACE_SECS {
+            settling.insert(key.clone());
+        }
+        engine_by_key.entry(key).or_default().push(p);
+    }
+
+    let mut keys: Vec<HoldingKey> = exchange_shares
+        .keys()
+        .chain(&redeemable)
+        .chain(db_shares.keys())
+        .chain(engine_by_key.keys())
+        .cloned()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    keys.sort_by(|a, b| (&a.0, a.1 as u8).cmp(&(&b.0, b.1 as u8)));
+
+    for key in keys {
+        // Resolved markets keep their tokens until redeemed while engine positions
+        // and trades close at resolution.
+        if redeemable.contains(&key) {
+            report.redeemable += 1;
+            continue;
+        }
+        if settling.contains(&key) {
+            report.skipped += 1;
+            continue;
+        }
+
+        let ex = exchange_shares.get(&key).copied().unwrap_or(0.0);
+        let db = db_shares.get(&key).copied().unwrap_or(0.0);
+        let engine_positions = engine_by_key.get(&key).cloned().unwrap_or_default();
+        let eng: f64 = engine_positions.iter().map(|p| p.shares).sum();
+
+        let divergence = |kind, strategy_id, engine_shares| Divergence {
+            kind,
+            symbol: key.0.clone(),
+            outcome: key.1,
+            strategy_id,
+            engine_shares,
+            db_shares: db,
+            exchange_shares: ex,
+        };
+
+        let before = report.divergences.len();
+        if ex <= DUST_SHARES {
+            for p in &engine_positions {
+                report.divergences.push(divergence(
+                    DivergenceKind::PhantomPosition,
+                    Some(p.strategy_id),
+                    p.shares,
+                ));
+            }
+            if db > DUST_SHARES {
+                report
+                    .divergences
+                    .push(divergence(DivergenceKind::PhantomTrade, None, eng));
+            }
+        } else if db <= DUST_SHARES && engine_positions.is_empty() {
+            report
+                .divergences
+                .push(divergence(DivergenceKind::OrphanTokens, None, eng));
+        } else if db > DUST_SHARES && (ex - db).abs() > (db * SIZE_TOLERANCE).max(1.0) {
+            report
+                .divergences
+                .push(divergence(DivergenceKind::SizeMismatch, None, eng));
+        }
+        if report.divergences.len() == before {
+            report.matched += 1;
+        }
+    }
+
+    report
+}
+
+// ---------------------------------------------------------------------------
+// run_reconciler — periodic loop over every live wallet
+// ---------------------------------------------------------------------------
+
+pub async fn run_reconciler(
+    http: HttpPool,
+    data_api_url: String,
+    db: PgPool,
+    registry: AssignmentRegistry,
+    wallet_keys: Arc<WalletKeyStore>,
+    interval_secs: u64,
+    auto_correct: bool,
+) -> Result<()> {
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs.max(30)));
+
+    loop {
+        interval.tick().await;
+
+        let trades = match postgres::load_open_live_trades(&db).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::warn!(error = %e, "reconciler_trades_load_failed");
+                continue;
+            }
+        };
+        let copies = match postgres::load_live_copy_holdings(&db).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::warn!(error = %e, "reconciler_copies_load_failed");
+                continue;
+            }
+        };
+
+        let mut wallet_ids: Vec<u64> = trades
+            .iter()
+            .map(|t| t.wallet_id)
+            .chain(copies.iter().map(|c| c.wallet_id))
+            .map(|id| id as u64)
+            .collect();
+        {
+            let reg = registry.read().await;
+            wallet_ids.extend(
+                reg.values()
+                    .flatten()
+                    .filter(|a| !a.is_paper)
+                    .map(|a| a.wallet_id),
+            );
+        }
+        wallet_ids.sort_unstable();
+        wallet_ids.dedup();
+
+        let now = chrono::Utc::now().timestamp();
+        for wallet_id in wallet_ids {
+            let Ok(safe_address) = wallet_keys.get_safe_address(wallet_id) else {
+                continue;
+            };
+            let exchange = match fetch_positions(&http, &data_api_url, &safe_address).await {
+                Ok(p) => p,
+                Err(e) => {
+                    tracing::warn!(wallet_id, error = %e, "reconciler_positions_fetch_failed");
+                    continue;
+                }
+            };
+
+            let engine = engine_positions(&registry, wallet_id).await;
+            let wallet_trades: Vec<OpenLiveTrade> = trades
+                .iter()
+                .filter(|t| t.wallet_id as u64 == wallet_id)
+                .cloned()
+                .collect();
+            let wallet_copies: Vec<LiveCopyHolding> = copies
+                .iter()
+                .filter(|c| c.wallet_id as u64 == wallet_id)
+                .cloned()
+                .collect();
+            let report = reconcile(&engine, &wallet_trades, &wallet_copies, &exchange, now);
+
+            for d in &report.divergences {
+                counter!(m::RECONCILE_DIVERGENCES_TOTAL, "kind" => d.kind.as_str()).increment(1);
+                tracing::warn!(
+                    wallet_id,
+                    kind = d.kind.as_str(),
+                    symbol = %d.symbol,
+                    outcome = ?d.outcome,
+                    strategy_id = ?d.strategy_id,
+                    engine_shares = d.engine_shares,
+                    db_shares = d.db_shares,
+                    exchange_shares = d.exchange_shares,
+                    "position_divergence"
+                );
+                if auto_correct && d.kind == DivergenceKind::PhantomPosition {
+                    if let Some(strategy_id) = d.strategy_id {
+                        clear_phantom_position(&registry, wallet_id, strategy_id, &d.symbol).await;
+                    }
+                }
+            }
+
+            tracing::info!(
+                wallet_id,
+                matched = report.matched,
+                skipped = report.skipped,
+                redeemable = report.redeemable,
+                divergences = report.divergences.len(),
+                auto_correct,
+                "reconciliation_report"
+            );
+        }
+    }
+}
+
+async fn engine_positions(registry: &AssignmentRegistry, wallet_id: u64) -> Vec<EnginePosition> {
+    let reg = registry.read().await;
+    let mut seen = HashSet::new();
+    let mut positions = Vec::new();
+    for a in reg.values().flatten() {
+        if a.wallet_id != wallet_id || a.is_paper || !seen.insert(a.strategy_id) {
+            continue;
+        }
+        let state = a.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(ref pos) = state.position {
+            positions.push(EnginePosition {
+                strategy_id: a.strategy_id,
+                symbol: pos.symbol.clone(),
+                outcome: pos.outcome,
+                shares: shares_for(pos.size_usdc, Some(pos.entry_price)),
+                entry_at: pos.entry_at,
+            });
+        }
+    }
+    positions
+}
+
+async fn clear_phantom_position(
+    registry: &AssignmentRegistry,
+    wallet_id: u64,
+    strategy_id: u64,
+    symbol: &str,
+) {
+    let reg = registry.read().await;
+    let assignment = reg
+        .values()
+        .flatten()
+        .find(|a| a.wallet_id == wallet_id && a.strategy_id == strategy_id);
+    let Some(assignment) = assignment else {
+        return;
+    };
+
+    let mut state = assignment.state.lock().unwrap_or_else(|e| e.into_inner());
+    if state.position.as_ref().is_some_and(|p| p.symbol == symbol) {
+        state.position = None;
+        counter!(m::RECONCILE_CORRECTIONS_TOTAL).increment(1);
+        tracing::info!(wallet_id, strategy_id, symbol, "phantom_position_cleared");
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_010_000;
+    const OLD: i64 = NOW - 3_600;
+
+    fn exchange(slug: &str, outcome: &str, size: f64) -> DataApiPosition {
+        DataApiPosition {
+            asset: format!("{slug}-{outcome}"),
+            size,
+            slug: slug.into(),
+            outcome: Some(outcome.into()),
+            condition_id: String::new(),
+            outcome_index: None,
+            redeemable: false,
+            negative_risk: false,
+            current_value: 0.0,
+        }
+    }
+
+    fn trade(symbol: &str, outcome: &str, size_usdc: f64, executed_ts: i64) -> OpenLiveTrade {
+        OpenLiveTrade {
+            wallet_id: 1,
+            symbol: symbol.into(),
+            outcome: outcome.into(),
+            size_usdc,
+            price: Some(0.5),
+            executed_ts,
+        }
+    }
+
+    fn engine(symbol: &str, shares: f64, entry_at: i64) -> EnginePosition {
+        EnginePosition {
+            strategy_id: 7,
+            symbol: symbol.into(),
+            outcome: Outcome::Up,
+            shares,
+            entry_at,
+        }
+    }
+
+    #[test]
+    fn test_matching_views_report_no_divergence() {
+        let report = reconcile(
+            &[engine("btc-a", 20.0, OLD)],
+            &[trade("btc-a", "UP", 10.0, OLD)],
+            &[],
+            &[exchange("btc-a", "Up", 20.0)],
+            NOW,
+        );
+        assert_eq!(report.matched, 1);
+        assert!(report.divergences.is_empty());
+    }
+
+    #[test]
+    fn test_orphan_tokens_and_phantoms_detected() {
+        let report = reconcile(
+            &[engine("btc-b", 20.0, OLD)],
+            &[trade("btc-b", "UP", 10.0, OLD)],
+            &[],
+            &[exchange("eth-c", "Down", 15.0)],
+            NOW,
+        );
+        let kinds: Vec<DivergenceKind> = report.divergences.iter().map(|d| d.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DivergenceKind::PhantomPosition,
+                DivergenceKind::PhantomTrade,
+                DivergenceKind::OrphanTokens,
+            ]
+        );
+        assert_eq!(report.divergences[0].strategy_id, Some(7));
+        assert_eq!(report.divergences[2].outcome, Outcome::Down);
+    }
+
+    #[test]
+    fn test_size_mismatch_beyond_tolerance() {
+        let report = reconcile(
+            &[],
+            &[trade("btc-a", "UP", 10.0, OLD)],
+            &[],
+            &[exchange("btc-a", "Up", 12.0)],
+            NOW,
+        );
+        assert_eq!(report.divergences.len(), 1);
+        assert_eq!(report.divergences[0].kind, DivergenceKind::SizeMismatch);
+
+        let within = reconcile(
+            &[],
+            &[trade("btc-a", "UP", 10.0, OLD)],
+            &[],
+            &[exchange("btc-a", "Up", 20.5)],
+            NOW,
+        );
+        assert!(within.divergences.is_empty());
+    }
+
+    #[test]
+    fn test_copies_match_by_token() {
+        let copy = |token_id: &str| LiveCopyHolding {
+            wallet_id: 1,
+            token_id: token_id.into(),
+            outcome: "Up".into(),
+            shares: 20.0,
+            updated_ts: OLD,
+        };
+        let report = reconcile(
+            &[],
+            &[],
+            &[copy("btc-a-Up"), copy("777")],
+            &[exchange("btc-a", "Up", 20.0)],
+            NOW,
+        );
+        assert_eq!(report.matched, 1);
+        assert_eq!(report.divergences.len(), 1);
+        assert_eq!(report.divergences[0].kind, DivergenceKind::PhantomTrade);
+        assert_eq!(report.divergences[0].symbol, "777");
+    }
+
+    #[test]
+    fn test_recent_activity_is_skipped() {
+        let report = reconcile(
+            &[engine("btc-a", 20.0, NOW - 10)],
+            &[trade("btc-a", "UP", 10.0, NOW - 10)],
+            &[],
+            &[],
+            NOW,
+        );
+        assert_eq!(report.skipped, 1);
+        assert!(report.divergences.is_empty());
+    }
+
+    #[test]
+    fn test_redeemable_tokens_are_not_orphans() {
+        let resolved = DataApiPosition {
+            redeemable: true,
+            ..exchange("btc-a", "Up", 20.0)
+        };
+        let report = reconcile(&[], &[], &[], &[resolved], NOW);
+        assert_eq!(report.redeemable, 1);
+        assert_eq!(report.matched, 0);
+        assert!(report.divergences.is_empty());
+    }
+
+    #[test]
+    fn test_dust_is_ignored() {
+        let report = reconcile(&[], &[], &[], &[exchange("btc-a", "Up", 0.001)], NOW);
+        assert!(report.divergences.is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use metrics::{counter, gauge};
+use sqlx::PgPool;
+
+use crate::execution::balances::fetch_positions;
+use crate::execution::redemption::{plan_redemptions, submit_redemption};
+use crate::execution::relayer::RelayerClient;
+use crate::execution::safe_tx::{safe_tx_status, SafeTxState};
+use crate::execution::wallet::WalletKeyStore;
+use crate::metrics as m;
+use crate::proxy::HttpPool;
+use crate::storage::postgres;
+
+// ---------------------------------------------------------------------------
+// run_redeemer — redeem winning tokens of resolved markets back to USDC
+// ---------------------------------------------------------------------------
+
+/// Each cycle first settles in-flight redemptions, then submits a
+/// `redeemPositions` Safe transaction for every resolved market a live wallet
+/// still holds winning tokens in.
+pub async fn run_redeemer(
+    http: HttpPool,
+    data_api_url: String,
+    db: PgPool,
+    relayer: Arc<RelayerClient>,
+    wallet_keys: Arc<WalletKeyStore>,
+    interval_secs: u64,
+) -> Result<()> {
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs.max(30)));
+
+    loop {
+        interval.tick().await;
+        settle_pending(&db, &relayer).await;
+
+        let wallet_ids = match postgres::load_redemption_wallets(&db).await {
+            Ok(ids) => ids,
+            Err(e) => {
+                tracing::warn!(error = %e, "redeemer_wallets_load_failed");
+                continue;
+            }
+        };
+
+        for wallet_id in wallet_ids {
+            let Ok(safe_address) = wallet_keys.get_safe_address(wallet_id as u64) else {
+                continue;
+            };
+            let positions = match fetch_positions(&http, &data_api_url, &safe_address).await {
+                Ok(p) => p,
+                Err(e) => {
+                    tracing::warn!(wallet_id, error = %e, "redeemer_positions_fetch_failed");
+                    continue;
+                }
+            };
+            let active: HashSet<String> =
+                match postgres::load_active_redemption_conditions(&db, wallet_id).await {
+                    Ok(conditions) => conditions.into_iter().collect(),
+                    Err(e) => {
+                        tracing::warn!(wallet_id, error = %e, "redeemer_conditions_load_failed");
+                        continue;
+                    }
+                };
+
+            for plan in plan_redemptions(&positions, &active) {
+                let tx_id = match submit_redemption(&relayer, wallet_id as u64, &plan).await {
+                    Ok(submission) => submission.transaction_id,
+                    Err(e) => {
+                        counter!(m::REDEMPTIONS_TOTAL, "state" => "submit_failed").increment(1);
+                        tracing::warn!(
+                            wallet_id,
+                            condition_id = %plan.condition_id,
+                            error = %e,
+                            "redemption_submit_failed"
+                        );
+                        continue;
+                    }
+                };
+                counter!(m::REDEMPTIONS_TOTAL, "state" => "submitted").increment(1);
+                tracing::info!(
+                    wallet_id,
+                    condition_id = %plan.condition_id,
+                    neg_risk = plan.neg_risk,
+                    expected_usdc = plan.expected_usdc,
+                    %tx_id,
+                    "redemption_submitted"
+                );
+                if let Err(e) = postgres::insert_redemption(&db, wallet_id, &plan, &tx_id).await {
+                    tracing::warn!(wallet_id, %tx_id, error = %e, "redemption_insert_failed");
+                }
+            }
+        }
+    }
+}
+
+/// Poll the relayer for every submitted redemption and record its outcome.
+async fn settle_pending(db: &PgPool, relayer: &RelayerClient) {
+    let pending = match postgres::load_pending_redemptions(db).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::warn!(error = %e, "redeemer_pending_load_failed");
+            return;
+        }
+    };
+
+    for row in pending {
+        let Some(tx_id) = row.relayer_tx_id.as_deref() else {
+            continue;
+        };
+        let (state, tx_hash) = match safe_tx_status(relayer, tx_id).await {
+            Ok(status) => status,
+            Err(e) => {
+                tracing::warn!(id = row.id, %tx_id, error = %e, "redemption_status_failed");
+                continue;
+            }
+        };
+        if state == SafeTxState::Submitted {
+            continue;
+        }
+
+        if let Err(e) =
+            postgres::update_redemption_state(db, row.id, state.as_str(), tx_hash.as_deref()).await
+        {
+            tracing::warn!(id = row.id, error = %e, "redemption_update_failed");
+            continue;
+        }
+        counter!(m::REDEMPTIONS_TOTAL, "state" => state.as_str()).increment(1);
+        if state == SafeTxState::Confirmed {
+            gauge!(m::REDEEMED_USDC).increment(row.expected_usdc);
+        }
+        tracing::info!(
+            id = row.id,
+            wallet_id = row.wallet_id,
+            condition_id = %row.condition_id,
+            state = state.as_str(),
+            tx_hash = ?tx_hash,
+            redeemed_usdc = row.expected_usdc,
+            "redemption_settled"
+        );
+    }
+}
@@ -1,13 +1,16 @@
+use std::collections::HashMap;
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clickhouse::Client;
 use metrics::gauge;
 use serde::Deserialize;
 use sqlx::PgPool;
 
+use crate::execution::fees::taker_fee_usdc;
 use crate::metrics as m;
 use crate::proxy::HttpPool;
+use crate::storage::postgres;
 use crate::strategy::registry::AssignmentRegistry;
 use crate::strategy::Outcome;
 
@@ -32,6 +35,8 @@ struct GammaMarket {
     outcome_prices: Option<String>,
     #[serde(default)]
     closed: bool,
+    /// JSON-encoded token ids, in the order of `outcome_prices`.
+    clob_token_ids: Option<String>,
 }
 
 pub async fn run_slot_resolver(
@@ -66,31 +71,15 @@ pub async fn run_slot_resolver(
             tracing::info!(count = unresolved.len(), "slot_resolver_checking");
 
             for slot in &unresolved {
-                let url = format!("{gamma_url}/events?slug={}", slot.symbol);
-                let resp = match http.proxied().get(&url).send().await {
-                    Ok(r) if r.status().is_success() => r,
-                    Ok(r) => {
-                        tracing::warn!(slug = %slot.symbol, status = %r.status(), "slot_resolver_http_error");
-                        continue;
-                    }
+                let winner = match fetch_winner(&http, &gamma_url, &slot.symbol).await {
+                    Ok(Some(winner)) => winner,
+                    Ok(None) => continue,
                     Err(e) => {
                         tracing::warn!(slug = %slot.symbol, error = %e, "slot_resolver_request_failed");
                         continue;
                     }
                 };
 
-                let events: Vec<GammaEvent> = match resp.json().await {
-                    Ok(e) => e,
-                    Err(e) => {
-                        tracing::warn!(slug = %slot.symbol, error = %e, "slot_resolver_parse_failed");
-                        continue;
-                    }
-                };
-
-                let Some(winner) = extract_winner(&events) else {
-                    continue;
-                };
-
                 // 1. Update ClickHouse analytics
                 if let Err(e) = ch
                     .query("ALTER TABLE slot_snapshots UPDATE winner = ? WHERE symbol = ?")
@@ -118,6 +107,7 @@ pub async fn run_slot_resolver(
         }
 
         reconcile_resolved_trades(&ch, &db, &registry).await;
+        settle_copy_positions(&http, &gamma_url, &db).await;
     }
 }
 
@@ -132,8 +122,8 @@ async fn resolve_trades(
     winning_outcome: &str,
 ) {
     // Find all open trades for this symbol
-    let rows: Vec<(i64, i64, Option<i64>, String, Option<f64>, f64)> = match sqlx::query_as(
-        "SELECT id, wallet_id, strategy_id, outcome, COALESCE(filled_price, price)::float8, size_usdc::float8 \
+    let rows: Vec<(i64, i64, Option<i64>, String, Option<f64>, f64, Option<i16>)> = match sqlx::query_as(
+        "SELECT id, wallet_id, strategy_id, outcome, COALESCE(filled_price, price)::float8, size_usdc::float8, fee_bps \
          FROM trades WHERE symbol = $1 AND status = 'filled' AND side = 'buy'",
     )
     .bind(symbol)
@@ -151,12 +141,15 @@ async fn resolve_trades(
         return;
     }
 
-    for (trade_id, wallet_id, strategy_id, outcome, entry_price, size_usdc) in &rows {
+    for (trade_id, wallet_id, strategy_id, outcome, entry_price, size_usdc, fee_bps) in &rows {
         let is_winner = outcome == winning_outcome;
         let resolved_price: f64 = if is_winner { 1.0 } else { 0.0 };
         let new_status = if is_winner { "won" } else { "lost" };
         let entry = entry_price.unwrap_or(0.5);
-        let pnl = (resolved_price - entry) * size_usdc;
+        let shares = if entry > 0.0 { size_usdc / entry } else { 0.0 };
+        // Redemption is free, so the entry fill is the only fee on the trip.
+        let gross = (resolved_price - entry) * shares;
+        let fees = taker_fee_usdc(fee_bps.unwrap_or(0).max(0) as u16, entry, shares);
 
         // Update trade record
         if let Err(e) =
@@ -177,7 +170,8 @@ async fn resolve_trades(
             symbol,
             outcome = outcome.as_str(),
             result = new_status,
-            pnl = format!("{pnl:.2}"),
+            gross_pnl = format!("{gross:.2}"),
+            fees = format!("{fees:.2}"),
             "trade_resolved",
         );
 
@@ -192,7 +186,8 @@ async fn resolve_trades(
                 registry,
                 *wallet_id as u64,
                 *sid as u64,
-                pnl,
+                gross,
+                fees,
                 symbol,
                 winning,
             )
@@ -241,14 +236,83 @@ async fn reconcile_resolved_trades(db_ch: &Client, db: &PgPool, registry: &Assig
 }
 
 // ---------------------------------------------------------------------------
-// clear_position — reset the assignment's in-memory position and update PnL
+// settle_copy_positions — close copied holdings in resolved markets
+// ---------------------------------------------------------------------------
+
+/// Copies hold arbitrary markets keyed by condition id rather than tracked
+/// slots: once Gamma reports one closed, empty its ledger rows and resolve
+/// its copied entry trades.
+async fn settle_copy_positions(http: &HttpPool, gamma_url: &str, db: &PgPool) {
+    let condition_ids = match postgres::load_open_copy_conditions(db).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::warn!(error = %e, "copy_settlement_query_failed");
+            return;
+        }
+    };
+
+    for condition_id in condition_ids {
+        let payouts = match fetch_token_payouts(http, gamma_url, &condition_id).await {
+            Ok(Some(payouts)) => payouts,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!(condition_id, error = %e, "copy_settlement_request_failed");
+                continue;
+            }
+        };
+        match postgres::settle_copy_positions(db, &condition_id, &payouts).await {
+            Ok(settled) => tracing::info!(condition_id, settled, "copy_positions_settled"),
+            Err(e) => tracing::warn!(condition_id, error = %e, "copy_settlement_failed"),
+        }
+    }
+}
+
+/// USDC each token of the market `condition_id` pays out, or `None` while it
+/// is still open.
+async fn fetch_token_payouts(
+    http: &HttpPool,
+    gamma_url: &str,
+    condition_id: &str,
+) -> Result<Option<HashMap<String, f64>>> {
+    let url = format!("{gamma_url}/markets?condition_ids={condition_id}");
+    let markets: Vec<GammaMarket> = http
+        .proxied()
+        .get(&url)
+        .send()
+        .await
+        .context("gamma markets request failed")?
+        .error_for_status()
+        .context("gamma markets request rejected")?
+        .json()
+        .await
+        .context("failed to parse gamma markets")?;
+    Ok(markets.first().and_then(token_payouts))
+}
+
+fn token_payouts(market: &GammaMarket) -> Option<HashMap<String, f64>> {
+    if !market.closed {
+        return None;
+    }
+    let prices: Vec<String> = serde_json::from_str(market.outcome_prices.as_deref()?).ok()?;
+    let tokens: Vec<String> = serde_json::from_str(market.clob_token_ids.as_deref()?).ok()?;
+    let payouts: HashMap<String, f64> = tokens
+        .into_iter()
+        .zip(prices.iter().map(|p| p.parse().unwrap_or(0.0)))
+        .collect();
+    // A closed market that has not settled yet prices no outcome at 1.
+    payouts.values().any(|&p| p > 0.5).then_some(payouts)
+}
+
+// ---------------------------------------------------------------------------
+// clear_position — reset the assignment's in-memory position and book net PnL
 // ---------------------------------------------------------------------------
 
 async fn clear_position(
     registry: &AssignmentRegistry,
     wallet_id: u64,
     strategy_id: u64,
-    pnl: f64,
+    gross: f64,
+    fees: f64,
     symbol: &str,
     _winning_outcome: Outcome,
 ) {
@@ -280,9 +344,9 @@ async fn clear_position(
 
     if should_clear {
         state.position = None;
-        state.pnl += pnl;
-        state.daily_pnl += pnl;
+        let pnl = state.realize(gross, fees);
         gauge!(m::PNL_USDC).increment(pnl);
+        gauge!(m::FEES_USDC).increment(fees);
 
         tracing::info!(
             wallet_id,
@@ -293,6 +357,28 @@ async fn clear_position(
     }
 }
 
+/// Winner of the slot `slug` on Gamma: 1 for UP, 2 for DOWN, `None` while
+/// the market is still open.
+pub(crate) async fn fetch_winner(
+    http: &HttpPool,
+    gamma_url: &str,
+    slug: &str,
+) -> Result<Option<i8>> {
+    let url = format!("{gamma_url}/events?slug={slug}");
+    let events: Vec<GammaEvent> = http
+        .proxied()
+        .get(&url)
+        .send()
+        .await
+        .context("gamma events request failed")?
+        .error_for_status()
+        .context("gamma events request rejected")?
+        .json()
+        .await
+        .context("failed to parse gamma events")?;
+    Ok(extract_winner(&events))
+}
+
 fn extract_winner(events: &[GammaEvent]) -> Option<i8> {
     for event in events {
         let markets = event.markets.as_ref()?;
@@ -317,3 +403,30 @@ fn extract_winner(events: &[GammaEvent]) -> Option<i8> {
     }
     None
 }
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(closed: bool, prices: &str) -> GammaMarket {
+        GammaMarket {
+            outcome_prices: Some(prices.into()),
+            closed,
+            clob_token_ids: Some(r#"["111", "222"]"#.into()),
+        }
+    }
+
+    #[test]
+    fn test_token_payouts_of_resolved_market() {
+        let payouts = token_payouts(&market(true, r#"["0", "1"]"#)).unwrap();
+        assert_eq!(payouts["111"], 0.0);
+        assert_eq!(payouts["222"], 1.0);
+
+        assert!(token_payouts(&market(false, r#"["0", "1"]"#)).is_none());
+        assert!(token_payouts(&market(true, r#"["0.5", "0.5"]"#)).is_none());
+    }
+}
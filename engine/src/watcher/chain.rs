@@ -0,0 +1,719 @@
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use alloy::primitives::{keccak256, Address, B256, U256};
+use anyhow::{bail, Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use redis::aio::MultiplexedConnection;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::{mpsc, watch, RwLock};
+use tokio::time::{Interval, MissedTickBehavior};
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+
+use crate::fetcher::models::ActiveMarket;
+use crate::network::NetworkProfile;
+use crate::proxy::HttpPool;
+
+use super::polymarket::{load_cursor, select_new, LeaderTrade};
+use super::source::{LeaderBatch, LeaderTradeSource, PollingSource};
+
+/// The stream misses fills while reconnecting; a data API poll this often
+/// picks them up.
+const BACKSTOP_INTERVAL: Duration = Duration::from_secs(30);
+/// Longest wait for fills before handing control back to refresh addresses.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+/// Polygon produces a block every ~2s; a silent socket is dead.
+const STALE_AFTER: Duration = Duration::from_secs(30);
+/// Block timestamps kept to date fills.
+const HEADS_KEPT: u64 = 256;
+/// USDC and conditional tokens both use 6 decimals on Polygon.
+const TOKEN_UNIT: f64 = 1_000_000.0;
+
+// ---------------------------------------------------------------------------
+// ChainLogSource
+// ---------------------------------------------------------------------------
+
+/// Streams `OrderFilled` logs of the CTF exchanges made by watched leaders
+/// from a Polygon websocket RPC, with a slow data API poll as a backstop.
+pub struct ChainLogSource {
+    fills: mpsc::Receiver<Fill>,
+    addresses: watch::Sender<Vec<Address>>,
+    backstop: PollingSource,
+    backstop_interval: Interval,
+    markets: Arc<RwLock<HashMap<String, ActiveMarket>>>,
+    http: HttpPool,
+    gamma_url: String,
+    tokens: HashMap<String, TokenMarket>,
+}
+
+impl ChainLogSource {
+    /// Start streaming from `ws_url`; the stream stops with the source.
+    pub fn spawn(
+        ws_url: &str,
+        network: &NetworkProfile,
+        http: HttpPool,
+        markets: Arc<RwLock<HashMap<String, ActiveMarket>>>,
+        max_trade_age_secs: i64,
+    ) -> Self {
+        let (fills_tx, fills) = mpsc::channel(1_024);
+        let (addresses, addresses_rx) = watch::channel(Vec::new());
+        tokio::spawn(run_log_stream(
+            ws_url.to_string(),
+            [network.ctf_exchange, network.neg_risk_exchange],
+            addresses_rx,
+            fills_tx,
+        ));
+
+        let mut backstop_interval = tokio::time::interval(BACKSTOP_INTERVAL);
+        backstop_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            fills,
+            addresses,
+            backstop: PollingSource::new(&network.data_api_url, http.clone(), max_trade_age_secs),
+            backstop_interval,
+            markets,
+            http,
+            gamma_url: network.gamma_api_url.clone(),
+            tokens: HashMap::new(),
+        }
+    }
+
+    /// Market of a token: from discovery when tracked, otherwise from Gamma.
+    async fn token_market(&mut self, token_id: &str) -> Option<TokenMarket> {
+        if let Some(market) = self.tokens.get(token_id) {
+            return Some(market.clone());
+        }
+        let tracked = self.markets.read().await.values().find_map(|m| {
+            let outcome = if m.token_up == token_id {
+                "Up"
+            } else if m.token_down == token_id {
+                "Down"
+            } else {
+                return None;
+            };
+            Some(TokenMarket {
+                condition_id: m.condition_id.clone(),
+                outcome: outcome.to_string(),
+                slug: m.slug.clone(),
+                event_slug: String::new(),
+            })
+        });
+        let market = match tracked {
+            Some(market) => market,
+            None => match fetch_token_market(&self.http, &self.gamma_url, token_id).await {
+                Ok(Some(market)) => market,
+                Ok(None) => return None,
+                Err(e) => {
+                    tracing::warn!(token_id, error = %e, "copy_token_lookup_failed");
+                    return None;
+                }
+            },
+        };
+        self.tokens.insert(token_id.to_string(), market.clone());
+        Some(market)
+    }
+}
+
+impl LeaderTradeSource for ChainLogSource {
+    async fn next_batches(
+        &mut self,
+        addresses: &[String],
+        redis: &mut MultiplexedConnection,
+    ) -> Result<Vec<LeaderBatch>> {
+        let mut watched: Vec<Address> = addresses.iter().filter_map(|a| a.parse().ok()).collect();
+        watched.sort();
+        self.addresses.send_if_modified(|current| {
+            let changed = *current != watched;
+            *current = watched;
+            changed
+        });
+
+        let fills = tokio::select! {
+            fill = self.fills.recv() => {
+                let Some(fill) = fill else {
+                    bail!("chain log stream stopped");
+                };
+                let mut fills = vec![fill];
+                while let Ok(fill) = self.fills.try_recv() {
+                    fills.push(fill);
+                }
+                fills
+            }
+            _ = self.backstop_interval.tick() => {
+                return Ok(self.backstop.poll(addresses, redis).await);
+            }
+            _ = tokio::time::sleep(REFRESH_INTERVAL) => return Ok(Vec::new()),
+        };
+
+        // Fills of tokens we cannot place in a market are left to the backstop,
+        // which is why streamed batches never move the cursor.
+        let mut by_address: HashMap<&String, Vec<LeaderTrade>> = HashMap::new();
+        for fill in fills {
+            let Some(address) = addresses
+                .iter()
+                .find(|a| a.parse::<Address>().is_ok_and(|a| a == fill.maker))
+            else {
+                continue;
+            };
+            let Some(market) = self.token_market(&fill.token_id).await else {
+                continue;
+            };
+            by_address
+                .entry(address)
+                .or_default()
+                .push(fill.into_trade(market));
+        }
+
+        let mut batches = Vec::new();
+        for (address, trades) in by_address {
+            let cursor = load_cursor(redis, address).await?;
+            let trades = select_new(trades, &cursor.seen);
+            if !trades.is_empty() {
+                batches.push(LeaderBatch {
+                    address: address.clone(),
+                    trades,
+                    cursor: None,
+                    source: "chain",
+                });
+            }
+        }
+        Ok(batches)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Token markets
+// ---------------------------------------------------------------------------
+
+/// What a leader trade needs to know about the market of a token.
+#[derive(Debug, Clone, PartialEq)]
+struct TokenMarket {
+    condition_id: String,
+    outcome: String,
+    slug: String,
+    event_slug: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GammaTokenMarket {
+    condition_id: String,
+    #[serde(default)]
+    slug: String,
+    /// JSON-encoded list, e.g. `["Up", "Down"]`.
+    outcomes: Option<String>,
+    /// JSON-encoded list aligned with `outcomes`.
+    clob_token_ids: Option<String>,
+    #[serde(default)]
+    events: Vec<GammaTokenEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GammaTokenEvent {
+    #[serde(default)]
+    slug: String,
+}
+
+async fn fetch_token_market(
+    http: &HttpPool,
+    gamma_url: &str,
+    token_id: &str,
+) -> Result<Option<TokenMarket>> {
+    let url = format!("{gamma_url}/markets?clob_token_ids={token_id}");
+    let markets: Vec<GammaTokenMarket> = http
+        .proxied()
+        .get(&url)
+        .send()
+        .await
+        .context("gamma markets request failed")?
+        .error_for_status()
+        .context("gamma markets request rejected")?
+        .json()
+        .await
+        .context("failed to parse gamma markets")?;
+    Ok(markets.into_iter().find_map(|m| token_market(m, token_id)))
+}
+
+fn token_market(market: GammaTokenMarket, token_id: &str) -> Option<TokenMarket> {
+    let tokens: Vec<String> = serde_json::from_str(market.clob_token_ids.as_deref()?).ok()?;
+    let outcomes: Vec<String> = serde_json::from_str(market.outcomes.as_deref()?).ok()?;
+    let index = tokens.iter().position(|t| t == token_id)?;
+    Some(TokenMarket {
+        condition_id: market.condition_id,
+        outcome: outcomes.get(index)?.clone(),
+        slug: market.slug,
+        event_slug: market
+            .events
+            .into_iter()
+            .next()
+            .map(|e| e.slug)
+            .unwrap_or_default(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// OrderFilled logs
+// ---------------------------------------------------------------------------
+
+/// A watched maker's fill decoded from an `OrderFilled` log.
+#[derive(Debug, Clone, PartialEq)]
+struct Fill {
+    maker: Address,
+    token_id: String,
+    is_sell: bool,
+    shares: f64,
+    usdc: f64,
+    transaction_hash: String,
+    log_index: u64,
+    block_number: u64,
+    timestamp: i64,
+}
+
+impl Fill {
+    fn into_trade(self, market: TokenMarket) -> LeaderTrade {
+        LeaderTrade {
+            side: if self.is_sell { "SELL" } else { "BUY" }.to_string(),
+            asset: self.token_id,
+            condition_id: market.condition_id,
+            size: self.shares,
+            price: self.usdc / self.shares,
+            timestamp: self.timestamp,
+            transaction_hash: self.transaction_hash,
+            outcome: Some(market.outcome),
+            slug: market.slug,
+            event_slug: market.event_slug,
+            log_index: Some(self.log_index),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcLog {
+    topics: Vec<String>,
+    data: String,
+    block_number: String,
+    transaction_hash: String,
+    log_index: String,
+    #[serde(default)]
+    removed: bool,
+    /// Reported by some nodes; otherwise taken from `newHeads`.
+    #[serde(default)]
+    block_timestamp: Option<String>,
+}
+
+fn order_filled_topic() -> B256 {
+    keccak256("OrderFilled(bytes32,address,address,uint256,uint256,uint256,uint256,uint256)")
+}
+
+fn parse_quantity(hex: &str) -> Option<u64> {
+    u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok()
+}
+
+/// Decode a fill. The maker gives `makerAssetId`: asset 0 is USDC, so a
+/// maker paying USDC buys the taker asset and a maker giving tokens sells.
+/// Removed (reorged) logs decode to nothing.
+fn decode_fill(log: &RpcLog, heads: &BTreeMap<u64, i64>) -> Option<Fill> {
+    if log.removed || log.topics.len() < 3 {
+        return None;
+    }
+    if B256::from_str(&log.topics[0]).ok()? != order_filled_topic() {
+        return None;
+    }
+    let maker = Address::from_word(B256::from_str(&log.topics[2]).ok()?);
+    let data = hex::decode(log.data.trim_start_matches("0x")).ok()?;
+    if data.len() < 4 * 32 {
+        return None;
+    }
+    let word = |i: usize| U256::from_be_slice(&data[i * 32..(i + 1) * 32]);
+    let amount = |i: usize| u128::try_from(word(i)).ok().map(|v| v as f64 / TOKEN_UNIT);
+    let (maker_asset, taker_asset) = (word(0), word(1));
+    let (maker_amount, taker_amount) = (amount(2)?, amount(3)?);
+
+    let is_sell = !maker_asset.is_zero();
+    let (token, shares, usdc) = if is_sell {
+        (maker_asset, maker_amount, taker_amount)
+    } else {
+        (taker_asset, taker_amount, maker_amount)
+    };
+    if shares <= 0.0 {
+        return None;
+    }
+
+    let block_number = parse_quantity(&log.block_number)?;
+    let timestamp = log
+        .block_timestamp
+        .as_deref()
+        .and_then(parse_quantity)
+        .map(|t| t as i64)
+        .or_else(|| heads.get(&block_number).copied())
+        .unwrap_or_else(|| chrono::Utc::now().timestamp());
+    Some(Fill {
+        maker,
+        token_id: token.to_string(),
+        is_sell,
+        shares,
+        usdc,
+        transaction_hash: log.transaction_hash.clone(),
+        log_index: parse_quantity(&log.log_index)?,
+        block_number,
+        timestamp,
+    })
+}
+
+/// `eth_subscribe` params for the fills made by `makers` on `exchanges`.
+fn logs_filter(exchanges: &[Address], makers: &[Address]) -> Value {
+    let makers: Vec<String> = makers.iter().map(|m| m.into_word().to_string()).collect();
+    serde_json::json!([
+        "logs",
+        {
+            "address": exchanges,
+            "topics": [order_filled_topic().to_string(), null, makers],
+        }
+    ])
+}
+
+// ---------------------------------------------------------------------------
+// Websocket stream
+// ---------------------------------------------------------------------------
+
+/// Keep a log subscription for the current watched makers, reconnecting with
+/// backoff, until the source is dropped.
+async fn run_log_stream(
+    ws_url: String,
+    exchanges: [Address; 2],
+    mut makers: watch::Receiver<Vec<Address>>,
+    fills: mpsc::Sender<Fill>,
+) {
+    let mut backoff = Duration::from_secs(1);
+    let max_backoff = Duration::from_secs(30);
+
+    loop {
+        tracing::info!("chain_ws_connecting");
+        let connected_at = Instant::now();
+
+        match stream_logs(&ws_url, &exchanges, &mut makers, &fills).await {
+            Ok(()) => tracing::warn!("chain_ws_disconnected"),
+            Err(e) => tracing::warn!(error = %e, "chain_ws_error"),
+        }
+        if fills.is_closed() || makers.has_changed().is_err() {
+            tracing::info!("chain_ws_stopped");
+            return;
+        }
+
+        if connected_at.elapsed() > Duration::from_secs(60) {
+            backoff = Duration::from_secs(1);
+        }
+        tracing::warn!(
+            backoff_ms = backoff.as_millis() as u64,
+            "chain_ws_reconnecting"
+        );
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(max_backoff);
+    }
+}
+
+async fn stream_logs(
+    ws_url: &str,
+    exchanges: &[Address],
+    makers: &mut watch::Receiver<Vec<Address>>,
+    fills: &mpsc::Sender<Fill>,
+) -> Result<()> {
+    let (ws, _) = connect_async(ws_url).await?;
+    let (mut write, mut read) = ws.split();
+    tracing::info!("chain_ws_connected");
+
+    let mut next_id = 1u64;
+    let mut request = |method: &str, params: Value| {
+        let msg = serde_json::json!({
+            "jsonrpc": "2.0", "id": next_id, "method": method, "params": params,
+        });
+        next_id += 1;
+        (next_id - 1, Message::Text(msg.to_string().into()))
+    };
+
+    let (_, msg) = request("eth_subscribe", serde_json::json!(["newHeads"]));
+    write.send(msg).await?;
+    let mut logs_request = None;
+    let mut logs_subscription: Option<String> = None;
+    let current = makers.borrow_and_update().clone();
+    if !current.is_empty() {
+        let (id, msg) = request("eth_subscribe", logs_filter(exchanges, &current));
+        write.send(msg).await?;
+        logs_request = Some(id);
+    }
+
+    let mut heads = BTreeMap::new();
+    let mut last_message = Instant::now();
+    let mut stale_check = tokio::time::interval(Duration::from_secs(10));
+
+    loop {
+        tokio::select! {
+            msg = read.next() => {
+                let text = match msg {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None => return Ok(()),
+                    Some(Err(e)) => return Err(e.into()),
+                    _ => continue,
+                };
+                last_message = Instant::now();
+                let Ok(msg) = serde_json::from_str::<Value>(&text) else {
+                    continue;
+                };
+                if let Some(error) = msg.get("error") {
+                    bail!("rpc error: {error}");
+                }
+                if logs_request.is_some() && msg["id"].as_u64() == logs_request {
+                    logs_subscription = msg["result"].as_str().map(String::from);
+                    continue;
+                }
+                let result = &msg["params"]["result"];
+                if result.get("topics").is_some() {
+                    let Ok(log) = serde_json::from_value::<RpcLog>(result.clone()) else {
+                        continue;
+                    };
+                    if let Some(fill) = decode_fill(&log, &heads) {
+                        tracing::debug!(tx = %fill.transaction_hash, block = fill.block_number, "chain_fill_detected");
+                        if fills.send(fill).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                } else if let (Some(number), Some(timestamp)) = (
+                    result["number"].as_str().and_then(parse_quantity),
+                    result["timestamp"].as_str().and_then(parse_quantity),
+                ) {
+                    heads.insert(number, timestamp as i64);
+                    heads = heads.split_off(&number.saturating_sub(HEADS_KEPT));
+                }
+            }
+            changed = makers.changed() => {
+                if changed.is_err() {
+                    return Ok(());
+                }
+                let current = makers.borrow_and_update().clone();
+                if let Some(subscription) = logs_subscription.take() {
+                    let (_, msg) = request("eth_unsubscribe", serde_json::json!([subscription]));
+                    write.send(msg).await?;
+                }
+                logs_request = None;
+                if !current.is_empty() {
+                    let (id, msg) = request("eth_subscribe", logs_filter(exchanges, &current));
+                    write.send(msg).await?;
+                    logs_request = Some(id);
+                }
+                tracing::info!(makers = current.len(), "chain_ws_resubscribed");
+            }
+            _ = stale_check.tick() => {
+                if last_message.elapsed() > STALE_AFTER {
+                    tracing::warn!("chain_ws_stale");
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEADER: &str = "0x00000000000000000000000000000000000000aa";
+
+    fn word(value: u128) -> String {
+        format!("{value:064x}")
+    }
+
+    /// A log of `LEADER` giving `maker_asset` for `taker_asset`.
+    fn log(maker_asset: u128, taker_asset: u128, maker_amount: u128, taker_amount: u128) -> Value {
+        let leader: Address = LEADER.parse().unwrap();
+        serde_json::json!({
+            "address": "0x4bfb41d5b3570defd03c39a9a4d8de6bd8b8982e",
+            "topics": [
+                order_filled_topic().to_string(),
+                B256::repeat_byte(0x11).to_string(),
+                leader.into_word().to_string(),
+                B256::ZERO.to_string(),
+            ],
+            "data": format!(
+                "0x{}{}{}{}{}",
+                word(maker_asset), word(taker_asset), word(maker_amount), word(taker_amount), word(0)
+            ),
+            "blockNumber": "0x10",
+            "transactionHash": "0xabc",
+            "logIndex": "0x3",
+            "removed": false,
+        })
+    }
+
+    #[test]
+    fn test_decode_buy_and_sell() {
+        let heads = BTreeMap::from([(16, 1_700_000_000)]);
+
+        // Pays 30 USDC for 60 shares of token 77.
+        let buy: RpcLog = serde_json::from_value(log(0, 77, 30_000_000, 60_000_000)).unwrap();
+        let fill = decode_fill(&buy, &heads).unwrap();
+        assert_eq!(fill.maker, LEADER.parse::<Address>().unwrap());
+        assert_eq!(fill.token_id, "77");
+        assert!(!fill.is_sell);
+        assert_eq!((fill.shares, fill.usdc), (60.0, 30.0));
+        assert_eq!((fill.log_index, fill.timestamp), (3, 1_700_000_000));
+
+        // Gives 10 shares of token 77 for 7 USDC.
+        let sell: RpcLog = serde_json::from_value(log(77, 0, 10_000_000, 7_000_000)).unwrap();
+        let trade = decode_fill(&sell, &heads).unwrap().into_trade(TokenMarket {
+            condition_id: "0xcond".into(),
+            outcome: "Down".into(),
+            slug: "btc-updown-15m-1".into(),
+            event_slug: String::new(),
+        });
+        assert!(trade.is_sell());
+        assert_eq!(trade.asset, "77");
+        assert!((trade.price - 0.7).abs() < 1e-9);
+        assert_eq!(trade.key(), "0xabc:3");
+    }
+
+    #[tokio::test]
+    async fn test_streamed_fill_is_not_copied_again_by_a_poll() {
+        use axum::routing::get;
+        use axum::{Json, Router};
+
+        use std::collections::HashSet;
+
+        use crate::watcher::polymarket::{fetch_trades_since, seen_keys};
+
+        let heads = BTreeMap::from([(16, 1_700_000_000)]);
+        let sell: RpcLog = serde_json::from_value(log(77, 0, 10_000_000, 7_000_000)).unwrap();
+        let streamed = decode_fill(&sell, &heads).unwrap().into_trade(TokenMarket {
+            condition_id: "0xcond".into(),
+            outcome: "Down".into(),
+            slug: "btc-updown-15m-1".into(),
+            event_slug: String::new(),
+        });
+        let seen: HashSet<String> = seen_keys(&streamed).into_iter().collect();
+
+        // The data API reports the same fill without a log index, plus a newer one.
+        let app = Router::new().route(
+            "/trades",
+            get(|| async {
+                Json(serde_json::json!([
+                    {
+                        "side": "BUY", "asset": "77", "conditionId": "0xcond", "size": 5.0,
+                        "price": 0.5, "timestamp": 1_700_000_004, "transactionHash": "0xdef",
+                        "outcome": "Down",
+                    },
+                    {
+                        "side": "SELL", "asset": "77", "conditionId": "0xcond", "size": 10.0,
+                        "price": 0.7, "timestamp": 1_700_000_000, "transactionHash": "0xABC",
+                        "outcome": "Down",
+                    },
+                ]))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        let http = HttpPool::new(&[], Duration::from_secs(5)).unwrap();
+
+        let polled = fetch_trades_since(&format!("http://{addr}"), &http, LEADER, 0)
+            .await
+            .unwrap();
+        let new = select_new(polled, &seen);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].transaction_hash, "0xdef");
+    }
+
+    #[test]
+    fn test_decode_skips_removed_and_foreign_logs() {
+        let heads = BTreeMap::new();
+        let mut removed = log(0, 77, 1_000_000, 2_000_000);
+        removed["removed"] = Value::Bool(true);
+        let removed: RpcLog = serde_json::from_value(removed).unwrap();
+        assert_eq!(decode_fill(&removed, &heads), None);
+
+        let mut other = log(0, 77, 1_000_000, 2_000_000);
+        other["topics"][0] = Value::String(B256::ZERO.to_string());
+        let other: RpcLog = serde_json::from_value(other).unwrap();
+        assert_eq!(decode_fill(&other, &heads), None);
+    }
+
+    #[test]
+    fn test_token_market_from_gamma() {
+        let market: GammaTokenMarket = serde_json::from_value(serde_json::json!({
+            "conditionId": "0xcond",
+            "slug": "btc-updown-15m-1",
+            "outcomes": "[\"Up\", \"Down\"]",
+            "clobTokenIds": "[\"11\", \"22\"]",
+            "events": [{"slug": "btc-updown-15m"}],
+        }))
+        .unwrap();
+        let market = token_market(market, "22").unwrap();
+        assert_eq!(market.outcome, "Down");
+        assert_eq!(market.event_slug, "btc-updown-15m");
+    }
+
+    #[tokio::test]
+    async fn test_stream_subscribes_and_forwards_fills() {
+        use tokio_tungstenite::accept_async;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (requests_tx, mut requests) = mpsc::channel::<Value>(8);
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            loop {
+                let Some(Ok(Message::Text(text))) = ws.next().await else {
+                    return;
+                };
+                let request: Value = serde_json::from_str(&text).unwrap();
+                let is_logs = request["params"][0] == "logs";
+                let reply =
+                    serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": "0x1"});
+                ws.send(Message::Text(reply.to_string().into()))
+                    .await
+                    .unwrap();
+                requests_tx.send(request).await.unwrap();
+                if is_logs {
+                    let head = serde_json::json!({"method": "eth_subscription", "params": {
+                        "subscription": "0x2", "result": {"number": "0x10", "timestamp": "0x64"},
+                    }});
+                    ws.send(Message::Text(head.to_string().into()))
+                        .await
+                        .unwrap();
+                    let fill = serde_json::json!({"method": "eth_subscription", "params": {
+                        "subscription": "0x1", "result": log(0, 77, 1_000_000, 2_000_000),
+                    }});
+                    ws.send(Message::Text(fill.to_string().into()))
+                        .await
+                        .unwrap();
+                }
+            }
+        });
+
+        let (makers_tx, makers_rx) = watch::channel(vec![LEADER.parse::<Address>().unwrap()]);
+        let (fills_tx, mut fills) = mpsc::channel(8);
+        tokio::spawn(run_log_stream(
+            format!("ws://{addr}"),
+            [Address::ZERO, Address::repeat_byte(1)],
+            makers_rx,
+            fills_tx,
+        ));
+
+        assert_eq!(requests.recv().await.unwrap()["params"][0], "newHeads");
+        let logs = requests.recv().await.unwrap();
+        assert_eq!(
+            logs["params"][1]["topics"][2][0],
+            LEADER.parse::<Address>().unwrap().into_word().to_string()
+        );
+        let fill = fills.recv().await.unwrap();
+        assert_eq!((fill.token_id.as_str(), fill.timestamp), ("77", 100));
+        drop(makers_tx);
+    }
+}
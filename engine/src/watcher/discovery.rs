@@ -0,0 +1,492 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use redis::aio::MultiplexedConnection;
+use serde::{Deserialize, Serialize};
+
+use super::polymarket::LeaderTrade;
+use super::scorecard::{self, market_family};
+use crate::fetcher::models::ActiveMarket;
+use crate::proxy::HttpPool;
+use crate::strategy::Outcome;
+
+const CANDIDATES_KEY: &str = "craftstrat:discovery:candidates";
+const PAGE_SIZE: usize = 500;
+/// Pages read per market; busier slots are left out of the ranking rather
+/// than scored on their latest trades only.
+const MAX_PAGES: usize = 10;
+/// Wallets published, best first.
+const MAX_CANDIDATES: usize = 500;
+/// Ended markets still unresolved after this long are dropped.
+const RESOLUTION_TIMEOUT_SECS: i64 = 3_600;
+const DEFAULT_LIMIT: usize = 50;
+
+// ---------------------------------------------------------------------------
+// Candidates
+// ---------------------------------------------------------------------------
+
+/// A wallet's record over the resolved tracked markets it traded.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LeaderCandidate {
+    pub address: String,
+    pub trades: usize,
+    pub volume_usdc: f64,
+    pub realized_pnl_usdc: f64,
+    pub closed_positions: usize,
+    pub wins: usize,
+    pub win_rate: Option<f64>,
+    /// Slots traded.
+    pub slots: usize,
+    /// Market families traded (slug without its slot timestamp), busiest first.
+    pub markets: Vec<String>,
+}
+
+/// Ranked candidates as published by the discovery job.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CandidateList {
+    pub generated_at: Option<i64>,
+    pub window_secs: i64,
+    pub markets_scored: usize,
+    /// Resolved markets left out for having more trades than can be read.
+    #[serde(default)]
+    pub markets_truncated: usize,
+    pub candidates: Vec<LeaderCandidate>,
+}
+
+/// Query filters of the candidates endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CandidateFilter {
+    #[serde(default)]
+    pub min_trades: usize,
+    pub min_win_rate: Option<f64>,
+    /// Comma-separated market families; candidates must have traded one.
+    pub markets: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+impl CandidateFilter {
+    /// Keep the candidates passing every filter, in rank order.
+    pub fn apply(&self, candidates: Vec<LeaderCandidate>) -> Vec<LeaderCandidate> {
+        let markets: Vec<&str> = self
+            .markets
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .collect();
+        candidates
+            .into_iter()
+            .filter(|c| c.trades >= self.min_trades)
+            .filter(|c| {
+                self.min_win_rate
+                    .is_none_or(|min| c.win_rate.is_some_and(|w| w >= min))
+            })
+            .filter(|c| {
+                markets.is_empty() || c.markets.iter().any(|m| markets.contains(&m.as_str()))
+            })
+            .take(self.limit)
+            .collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// LeaderDiscovery — per-wallet records over tracked slots
+// ---------------------------------------------------------------------------
+
+/// One public trade of a tracked market.
+#[derive(Debug, Deserialize)]
+pub(crate) struct MarketTrade {
+    #[serde(alias = "proxyWallet")]
+    proxy_wallet: String,
+    #[serde(flatten)]
+    trade: LeaderTrade,
+}
+
+/// A wallet's record in one resolved slot.
+#[derive(Debug, Clone, PartialEq)]
+struct SlotScore {
+    family: String,
+    ended_at: i64,
+    trades: usize,
+    volume_usdc: f64,
+    realized_pnl_usdc: f64,
+    closed_positions: usize,
+    wins: usize,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PendingMarket {
+    pub(crate) condition_id: String,
+    pub(crate) slug: String,
+    pub(crate) ended_at: i64,
+}
+
+/// Records of every wallet trading the tracked slots resolved within the
+/// window. Slots are scored once, after they resolve, from all their public
+/// trades; only per-wallet totals are kept.
+pub struct LeaderDiscovery {
+    window_secs: i64,
+    /// Tracked markets not scored yet, by condition id.
+    pending: HashMap<String, PendingMarket>,
+    /// End time of scored markets, by condition id.
+    scored: HashMap<String, i64>,
+    /// End time of markets with too many trades to score, by condition id.
+    truncated: HashMap<String, i64>,
+    wallets: HashMap<String, Vec<SlotScore>>,
+}
+
+impl LeaderDiscovery {
+    pub fn new(window_secs: i64) -> Self {
+        Self {
+            window_secs,
+            pending: HashMap::new(),
+            scored: HashMap::new(),
+            truncated: HashMap::new(),
+            wallets: HashMap::new(),
+        }
+    }
+
+    /// Start tracking the markets not seen yet.
+    pub fn track<'a>(&mut self, markets: impl IntoIterator<Item = &'a ActiveMarket>) {
+        for market in markets {
+            if self.scored.contains_key(&market.condition_id)
+                || self.truncated.contains_key(&market.condition_id)
+            {
+                continue;
+            }
+            self.pending
+                .entry(market.condition_id.clone())
+                .or_insert_with(|| PendingMarket {
+                    condition_id: market.condition_id.clone(),
+                    slug: market.slug.clone(),
+                    ended_at: market.end_time as i64,
+                });
+        }
+    }
+
+    /// Tracked markets whose slot has ended by `now`.
+    pub(crate) fn ended(&self, now: i64) -> Vec<PendingMarket> {
+        self.pending
+            .values()
+            .filter(|m| m.ended_at <= now)
+            .cloned()
+            .collect()
+    }
+
+    /// Score a resolved market from its public trades.
+    pub(crate) fn record(
+        &mut self,
+        market: &PendingMarket,
+        trades: Vec<MarketTrade>,
+        winner: Outcome,
+    ) {
+        self.pending.remove(&market.condition_id);
+        self.scored
+            .insert(market.condition_id.clone(), market.ended_at);
+
+        let mut by_wallet: HashMap<String, Vec<LeaderTrade>> = HashMap::new();
+        for t in trades {
+            by_wallet
+                .entry(t.proxy_wallet.to_lowercase())
+                .or_default()
+                .push(t.trade);
+        }
+        let winners = HashMap::from([(market.slug.clone(), winner)]);
+        for (address, mut trades) in by_wallet {
+            trades.sort_by_key(|t| t.timestamp);
+            let card = scorecard::compute(&address, &trades, &winners, 0);
+            self.wallets.entry(address).or_default().push(SlotScore {
+                family: market_family(&trades[0]),
+                ended_at: market.ended_at,
+                trades: card.trades,
+                volume_usdc: card.volume_usdc,
+                realized_pnl_usdc: card.realized_pnl_usdc,
+                closed_positions: card.closed_positions,
+                wins: card.wins,
+            });
+        }
+    }
+
+    /// Leave out a resolved market whose trades could not all be read.
+    pub(crate) fn record_truncated(&mut self, market: &PendingMarket) {
+        self.pending.remove(&market.condition_id);
+        self.truncated
+            .insert(market.condition_id.clone(), market.ended_at);
+    }
+
+    /// Forget slots that left the window and ended markets that never resolved.
+    pub fn prune(&mut self, now: i64) {
+        let since = now - self.window_secs;
+        self.pending
+            .retain(|_, m| m.ended_at > now - RESOLUTION_TIMEOUT_SECS);
+        self.scored.retain(|_, ended_at| *ended_at >= since);
+        self.truncated.retain(|_, ended_at| *ended_at >= since);
+        self.wallets.retain(|_, slots| {
+            slots.retain(|s| s.ended_at >= since);
+            !slots.is_empty()
+        });
+    }
+
+    pub fn markets_scored(&self) -> usize {
+        self.scored.len()
+    }
+
+    /// Wallets with at least one closed position, by realized PnL then win rate.
+    pub fn candidates(&self) -> Vec<LeaderCandidate> {
+        let mut candidates: Vec<LeaderCandidate> = self
+            .wallets
+            .iter()
+            .filter_map(|(address, slots)| {
+                let mut c = LeaderCandidate {
+                    address: address.clone(),
+                    slots: slots.len(),
+                    ..Default::default()
+                };
+                let mut families: BTreeMap<&str, usize> = BTreeMap::new();
+                for s in slots {
+                    c.trades += s.trades;
+                    c.volume_usdc += s.volume_usdc;
+                    c.realized_pnl_usdc += s.realized_pnl_usdc;
+                    c.closed_positions += s.closed_positions;
+                    c.wins += s.wins;
+                    *families.entry(&s.family).or_default() += s.trades;
+                }
+                if c.closed_positions == 0 {
+                    return None;
+                }
+                c.win_rate = Some(c.wins as f64 / c.closed_positions as f64);
+                let mut families: Vec<(&str, usize)> = families.into_iter().collect();
+                families.sort_by_key(|&(_, trades)| std::cmp::Reverse(trades));
+                c.markets = families.into_iter().map(|(f, _)| f.to_string()).collect();
+                Some(c)
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            b.realized_pnl_usdc.total_cmp(&a.realized_pnl_usdc).then(
+                b.win_rate
+                    .unwrap_or(0.0)
+                    .total_cmp(&a.win_rate.unwrap_or(0.0)),
+            )
+        });
+        candidates.truncate(MAX_CANDIDATES);
+        candidates
+    }
+
+    pub fn list(&self, now: i64) -> CandidateList {
+        CandidateList {
+            generated_at: Some(now),
+            window_secs: self.window_secs,
+            markets_scored: self.markets_scored(),
+            markets_truncated: self.truncated.len(),
+            candidates: self.candidates(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Data API and Redis I/O
+// ---------------------------------------------------------------------------
+
+/// Public trades of a market, makers included, newest first. `None` when the
+/// market has more than [`MAX_PAGES`] pages of them.
+pub(crate) async fn fetch_market_trades(
+    http: &HttpPool,
+    data_api_url: &str,
+    condition_id: &str,
+) -> Result<Option<Vec<MarketTrade>>> {
+    let mut trades = Vec::new();
+    for page in 0..MAX_PAGES {
+        let url = format!(
+            "{}/trades?market={}&limit={}&offset={}&takerOnly=false",
+            data_api_url.trim_end_matches('/'),
+            condition_id,
+            PAGE_SIZE,
+            page * PAGE_SIZE
+        );
+        let batch: Vec<MarketTrade> = http
+            .proxied()
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let last_page = batch.len() < PAGE_SIZE;
+        trades.extend(batch);
+        if last_page {
+            return Ok(Some(trades));
+        }
+    }
+    Ok(None)
+}
+
+/// Winners of `markets`' slots, from ClickHouse or Gamma.
+pub(crate) async fn resolve(
+    ch: &clickhouse::Client,
+    http: &HttpPool,
+    gamma_url: &str,
+    markets: &[PendingMarket],
+) -> HashMap<String, Outcome> {
+    let slugs = markets.iter().map(|m| m.slug.clone()).collect();
+    scorecard::resolve_winners(ch, http, gamma_url, &slugs).await
+}
+
+pub async fn publish(conn: &mut MultiplexedConnection, list: &CandidateList) -> Result<()> {
+    redis::cmd("SET")
+        .arg(CANDIDATES_KEY)
+        .arg(serde_json::to_string(list)?)
+        .query_async::<()>(conn)
+        .await?;
+    Ok(())
+}
+
+pub async fn load(conn: &mut MultiplexedConnection) -> Result<CandidateList> {
+    let raw: Option<String> = redis::cmd("GET")
+        .arg(CANDIDATES_KEY)
+        .query_async(conn)
+        .await?;
+    Ok(match raw {
+        Some(raw) => serde_json::from_str(&raw)?,
+        None => CandidateList::default(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLUG: &str = "btc-updown-15m-1000";
+
+    fn trade(wallet: &str, side: &str, asset: &str, size: f64, price: f64, ts: i64) -> MarketTrade {
+        let outcome = if asset == "up" { "Up" } else { "Down" };
+        serde_json::from_value(serde_json::json!({
+            "proxyWallet": wallet, "side": side, "asset": asset, "conditionId": "c",
+            "size": size, "price": price, "timestamp": ts, "transactionHash": format!("0x{ts}"),
+            "outcome": outcome, "slug": SLUG,
+        }))
+        .unwrap()
+    }
+
+    fn market(ended_at: i64) -> PendingMarket {
+        PendingMarket {
+            condition_id: format!("c{ended_at}"),
+            slug: SLUG.to_string(),
+            ended_at,
+        }
+    }
+
+    fn candidate(address: &str, trades: usize, win_rate: f64, markets: &[&str]) -> LeaderCandidate {
+        LeaderCandidate {
+            address: address.to_string(),
+            trades,
+            win_rate: Some(win_rate),
+            markets: markets.iter().map(|m| m.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_ranks_wallets_by_realized_pnl() {
+        let mut discovery = LeaderDiscovery::new(86_400);
+        let trades = vec![
+            // Winner: 100 Up @ 0.40 held into an UP resolution → +60.
+            trade("0xAAA", "BUY", "up", 100.0, 0.40, 1_000),
+            // Scalper: 50 Up @ 0.50, sold @ 0.60 → +5.
+            trade("0xbbb", "BUY", "up", 50.0, 0.50, 1_010),
+            trade("0xbbb", "SELL", "up", 50.0, 0.60, 1_020),
+            // Loser: 40 Down @ 0.50 → −20.
+            trade("0xccc", "BUY", "down", 40.0, 0.50, 1_030),
+        ];
+        discovery.record(&market(1_900), trades, Outcome::Up);
+
+        let candidates = discovery.candidates();
+        let ranked: Vec<&str> = candidates.iter().map(|c| c.address.as_str()).collect();
+        assert_eq!(ranked, ["0xaaa", "0xbbb", "0xccc"]);
+        assert!((candidates[0].realized_pnl_usdc - 60.0).abs() < 1e-9);
+        assert_eq!(candidates[1].trades, 2);
+        assert_eq!(candidates[2].win_rate, Some(0.0));
+        assert_eq!(candidates[0].markets, ["btc-updown-15m"]);
+        assert_eq!(discovery.markets_scored(), 1);
+    }
+
+    #[test]
+    fn test_prune_drops_slots_outside_window() {
+        let mut discovery = LeaderDiscovery::new(3_600);
+        discovery.record(
+            &market(1_000),
+            vec![trade("0xaaa", "BUY", "up", 10.0, 0.5, 900)],
+            Outcome::Up,
+        );
+        discovery.record(
+            &market(5_000),
+            vec![trade("0xaaa", "BUY", "up", 10.0, 0.5, 4_900)],
+            Outcome::Up,
+        );
+        discovery.prune(6_000);
+
+        let candidates = discovery.candidates();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].slots, 1);
+        assert_eq!(discovery.markets_scored(), 1);
+    }
+
+    #[test]
+    fn test_truncated_markets_are_counted_not_ranked() {
+        let mut discovery = LeaderDiscovery::new(3_600);
+        let busy = market(1_000);
+        discovery
+            .pending
+            .insert(busy.condition_id.clone(), busy.clone());
+        discovery.record_truncated(&busy);
+        discovery.record(
+            &market(2_000),
+            vec![trade("0xaaa", "BUY", "up", 10.0, 0.5, 1_900)],
+            Outcome::Up,
+        );
+
+        let list = discovery.list(2_500);
+        assert_eq!(list.markets_scored, 1);
+        assert_eq!(list.markets_truncated, 1);
+        assert_eq!(list.candidates.len(), 1);
+        assert!(discovery.ended(2_500).is_empty());
+
+        discovery.prune(4_800);
+        assert_eq!(discovery.list(4_800).markets_truncated, 0);
+    }
+
+    #[test]
+    fn test_filter() {
+        let candidates = vec![
+            candidate("a", 50, 0.7, &["btc-updown-15m"]),
+            candidate("b", 5, 0.9, &["btc-updown-15m"]),
+            candidate("c", 50, 0.4, &["eth-updown-1h"]),
+        ];
+        let filter = |query: serde_json::Value| -> Vec<String> {
+            serde_json::from_value::<CandidateFilter>(query)
+                .unwrap()
+                .apply(candidates.clone())
+                .into_iter()
+                .map(|c| c.address)
+                .collect()
+        };
+
+        assert_eq!(filter(serde_json::json!({})), ["a", "b", "c"]);
+        assert_eq!(filter(serde_json::json!({"min_trades": 10})), ["a", "c"]);
+        assert_eq!(filter(serde_json::json!({"min_win_rate": 0.6})), ["a", "b"]);
+        assert_eq!(
+            filter(serde_json::json!({"markets": "eth-updown-1h, sol-updown-15m"})),
+            ["c"]
+        );
+        assert_eq!(filter(serde_json::json!({"limit": 1})), ["a"]);
+    }
+}
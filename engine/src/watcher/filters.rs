@@ -0,0 +1,299 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::polymarket::LeaderTrade;
+
+// ---------------------------------------------------------------------------
+// CopyFilters
+// ---------------------------------------------------------------------------
+
+/// Rules deciding which leader trades a copy relationship follows.
+///
+/// Configured as JSON in `copy_relationships.copy_filters`, e.g.
+/// `{"max_entry_price": 0.9, "market_patterns": ["*-updown-15m-*"], "side": "buy"}`.
+/// Price band, leader size, delay and drift only gate entries: once a position
+/// is copied, the leader's exits are mirrored unless `side` excludes sells.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CopyFilters {
+    /// Skip entries the leader paid less than this for.
+    pub min_entry_price: Option<f64>,
+    /// Skip entries the leader paid more than this for.
+    pub max_entry_price: Option<f64>,
+    /// Market or event slug globs (`*` matches any run); empty allows all.
+    pub market_patterns: Vec<String>,
+    /// Only copy this side of the leader's trades.
+    pub side: Option<CopySide>,
+    /// Skip leader entries under this notional.
+    pub min_leader_size_usdc: Option<f64>,
+    /// Skip entries first seen more than this long after the leader's fill.
+    pub max_delay_secs: Option<i64>,
+    /// Skip entries when the best ask has moved more than this above the
+    /// leader's fill price.
+    pub max_drift_bps: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CopySide {
+    Buy,
+    Sell,
+}
+
+impl CopyFilters {
+    pub fn from_value(value: &Value) -> Self {
+        if value.is_null() {
+            return Self::default();
+        }
+        match serde_json::from_value(value.clone()) {
+            Ok(filters) => filters,
+            Err(e) => {
+                tracing::warn!(error = %e, config = %value, "copy_filters_invalid");
+                Self::default()
+            }
+        }
+    }
+
+    /// True when an entry needs the current best ask to be judged.
+    pub fn needs_book(&self) -> bool {
+        self.max_drift_bps.is_some()
+    }
+
+    /// Check `trade` against every rule. `slugs` are the market and event
+    /// slugs the trade belongs to, `best_ask` the current ask of its token.
+    pub fn check(
+        &self,
+        trade: &LeaderTrade,
+        slugs: &[&str],
+        now: i64,
+        best_ask: Option<f64>,
+    ) -> Result<(), SkipReason> {
+        if !self.market_patterns.is_empty()
+            && !self
+                .market_patterns
+                .iter()
+                .any(|p| slugs.iter().any(|s| glob_match(p, s)))
+        {
+            return Err(SkipReason::MarketPattern);
+        }
+        let side = if trade.is_sell() {
+            CopySide::Sell
+        } else {
+            CopySide::Buy
+        };
+        if self.side.is_some_and(|s| s != side) {
+            return Err(SkipReason::Side);
+        }
+        if side == CopySide::Sell {
+            return Ok(());
+        }
+
+        if self.min_entry_price.is_some_and(|min| trade.price < min) {
+            return Err(SkipReason::EntryPriceBelowMin);
+        }
+        if self.max_entry_price.is_some_and(|max| trade.price > max) {
+            return Err(SkipReason::EntryPriceAboveMax);
+        }
+        if self
+            .min_leader_size_usdc
+            .is_some_and(|min| trade.size * trade.price < min)
+        {
+            return Err(SkipReason::LeaderSizeBelowMin);
+        }
+        if self
+            .max_delay_secs
+            .is_some_and(|max| now - trade.timestamp > max)
+        {
+            return Err(SkipReason::TooLate);
+        }
+        if let Some(max_bps) = self.max_drift_bps {
+            let ask = best_ask.ok_or(SkipReason::PriceUnavailable)?;
+            let drift_bps = (ask - trade.price) / trade.price * 10_000.0;
+            if drift_bps > f64::from(max_bps) {
+                return Err(SkipReason::PriceDrift);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `*` matches any run of characters, everything else matches itself.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No `*`: the pattern is a literal.
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+// ---------------------------------------------------------------------------
+// SkipReason
+// ---------------------------------------------------------------------------
+
+/// Why a leader trade was not copied, as recorded in `copy_trades.skip_reason`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    MarketNotAllowed,
+    MarketPattern,
+    Side,
+    EntryPriceBelowMin,
+    EntryPriceAboveMax,
+    LeaderSizeBelowMin,
+    TooLate,
+    PriceUnavailable,
+    PriceDrift,
+    ExceedsMaxPosition,
+    NoCopiedPosition,
+    DailyLossLimit,
+    MaxOpenPositions,
+    TradeRateLimit,
+    Paused,
+    BalanceUnavailable,
+    LeaderValueUnavailable,
+    BelowMinOrderSize,
+}
+
+impl SkipReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MarketNotAllowed => "market_not_allowed",
+            Self::MarketPattern => "market_pattern",
+            Self::Side => "side_filtered",
+            Self::EntryPriceBelowMin => "entry_price_below_min",
+            Self::EntryPriceAboveMax => "entry_price_above_max",
+            Self::LeaderSizeBelowMin => "leader_size_below_min",
+            Self::TooLate => "too_late",
+            Self::PriceUnavailable => "price_unavailable",
+            Self::PriceDrift => "price_drift",
+            Self::ExceedsMaxPosition => "exceeds_max_position",
+            Self::NoCopiedPosition => "no_copied_position",
+            Self::DailyLossLimit => "daily_loss_limit",
+            Self::MaxOpenPositions => "max_open_positions",
+            Self::TradeRateLimit => "trade_rate_limit",
+            Self::Paused => "relationship_paused",
+            Self::BalanceUnavailable => "balance_unavailable",
+            Self::LeaderValueUnavailable => "leader_value_unavailable",
+            Self::BelowMinOrderSize => "below_min_order_size",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(side: &str, price: f64, size: f64) -> LeaderTrade {
+        serde_json::from_value(serde_json::json!({
+            "side": side, "asset": "tok", "conditionId": "c", "size": size,
+            "price": price, "timestamp": 1_000, "transactionHash": "0xaa",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*-updown-15m-*", "btc-updown-15m-1760000000"));
+        assert!(!glob_match("*-updown-15m-*", "btc-updown-5m-1760000000"));
+        assert!(glob_match("btc-*", "btc-updown-5m-1"));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("exact", "exact"));
+        assert!(!glob_match("exact", "exactly"));
+        assert!(!glob_match("a*b*c", "acb"));
+    }
+
+    #[test]
+    fn test_parses_config_and_rejects_unknown_fields() {
+        let filters = CopyFilters::from_value(&serde_json::json!({
+            "max_entry_price": 0.9, "side": "buy", "market_patterns": ["*-15m-*"],
+        }));
+        assert_eq!(filters.max_entry_price, Some(0.9));
+        assert_eq!(filters.side, Some(CopySide::Buy));
+        assert_eq!(
+            CopyFilters::from_value(&serde_json::json!({"max_price": 0.9})),
+            CopyFilters::default()
+        );
+        assert_eq!(
+            CopyFilters::from_value(&Value::Null),
+            CopyFilters::default()
+        );
+    }
+
+    #[test]
+    fn test_entry_rules_each_report_their_reason() {
+        let filters = CopyFilters {
+            min_entry_price: Some(0.1),
+            max_entry_price: Some(0.9),
+            min_leader_size_usdc: Some(20.0),
+            max_delay_secs: Some(30),
+            max_drift_bps: Some(200),
+            ..Default::default()
+        };
+        let check = |t: &LeaderTrade, now, ask| filters.check(t, &["slug"], now, ask);
+
+        assert_eq!(check(&trade("BUY", 0.5, 100.0), 1_010, Some(0.505)), Ok(()));
+        assert_eq!(
+            check(&trade("BUY", 0.95, 100.0), 1_010, Some(0.95)),
+            Err(SkipReason::EntryPriceAboveMax)
+        );
+        assert_eq!(
+            check(&trade("BUY", 0.05, 1_000.0), 1_010, Some(0.05)),
+            Err(SkipReason::EntryPriceBelowMin)
+        );
+        assert_eq!(
+            check(&trade("BUY", 0.5, 10.0), 1_010, Some(0.5)),
+            Err(SkipReason::LeaderSizeBelowMin)
+        );
+        assert_eq!(
+            check(&trade("BUY", 0.5, 100.0), 1_031, Some(0.5)),
+            Err(SkipReason::TooLate)
+        );
+        assert_eq!(
+            check(&trade("BUY", 0.5, 100.0), 1_010, Some(0.52)),
+            Err(SkipReason::PriceDrift)
+        );
+        assert_eq!(
+            check(&trade("BUY", 0.5, 100.0), 1_010, None),
+            Err(SkipReason::PriceUnavailable)
+        );
+        // Exits are not held to entry rules.
+        assert_eq!(check(&trade("SELL", 0.95, 1.0), 5_000, None), Ok(()));
+    }
+
+    #[test]
+    fn test_side_and_pattern_apply_to_both_sides() {
+        let filters = CopyFilters {
+            market_patterns: vec!["*-updown-15m-*".into()],
+            side: Some(CopySide::Buy),
+            ..Default::default()
+        };
+        let slugs = ["btc-updown-15m-1", "btc-event"];
+        assert_eq!(
+            filters.check(&trade("BUY", 0.5, 10.0), &slugs, 0, None),
+            Ok(())
+        );
+        assert_eq!(
+            filters.check(&trade("SELL", 0.5, 10.0), &slugs, 0, None),
+            Err(SkipReason::Side)
+        );
+        assert_eq!(
+            filters.check(&trade("BUY", 0.5, 10.0), &["eth-daily"], 0, None),
+            Err(SkipReason::MarketPattern)
+        );
+    }
+}
@@ -1 +1,8 @@
+pub mod chain;
+pub mod discovery;
+pub mod filters;
 pub mod polymarket;
+pub mod risk;
+pub mod scorecard;
+pub mod sizing;
+pub mod source;
@@ -1,18 +1,47 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use anyhow::Result;
-use metrics::counter;
+use futures_util::future::join_all;
+use metrics::{counter, histogram};
 use sqlx::PgPool;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 use uuid::Uuid;
 
+use alloy::primitives::Address;
+
+use crate::execution::balances::{fetch_positions, BalanceService};
+use crate::execution::journal::ExecutionJournal;
 use crate::execution::queue::ExecutionQueue;
-use crate::execution::{ExecutionOrder, OrderPriority, Side};
+use crate::execution::{ExecutionOrder, MarketMeta, OrderPriority, Side};
+use crate::fetcher::models::ActiveMarket;
+use crate::fetcher::websocket::OrderBookCache;
 use crate::metrics as m;
+use crate::network::NetworkProfile;
+use crate::notifier::WebNotifier;
 use crate::proxy::HttpPool;
 use crate::storage::postgres::{self, CopyRelationship};
 use crate::strategy::{OrderType, Outcome};
 
+use super::filters::SkipReason;
+use super::risk::CopyRiskState;
+use super::sizing::{entry_size, Bankrolls, SizeMode};
+use super::source::{LeaderBatch, LeaderTradeSource};
+
+/// Trades requested per data API page.
+const PAGE_SIZE: usize = 100;
+/// Pages fetched per poll at most, so one very active leader cannot stall the loop.
+const MAX_PAGES: usize = 20;
+/// Pages fetched at most when reading a leader's history for a report.
+pub(crate) const HISTORY_MAX_PAGES: usize = 100;
+/// The data API may index a trade shortly after its timestamp. Polls look back
+/// this far behind the cursor and rely on dedupe for trades already handled.
+const INDEX_LAG_SECS: i64 = 30;
+/// Seen-trade sets of leaders that stop trading expire after a day.
+const SEEN_TTL_SECS: i64 = 86_400;
+/// A leader sell leaving less than this share of the position closes ours entirely.
+pub(crate) const FLATTEN_BELOW: f64 = 0.01;
+
 // ---------------------------------------------------------------------------
 // LeaderTrade
 // ---------------------------------------------------------------------------
@@ -29,134 +58,719 @@ pub struct LeaderTrade {
     #[serde(alias = "transactionHash")]
     pub transaction_hash: String,
     pub outcome: Option<String>,
+    #[serde(default)]
+    pub slug: String,
+    #[serde(default, alias = "eventSlug")]
+    pub event_slug: String,
+    /// Position of the fill's event in its transaction, when reported.
+    #[serde(default, alias = "logIndex")]
+    pub log_index: Option<u64>,
+}
+
+impl LeaderTrade {
+    pub fn is_sell(&self) -> bool {
+        self.side == "SELL"
+    }
+
+    /// Outcome bought or sold; the data API labels up/down markets `Up` /
+    /// `Down` and binary ones `Yes` / `No`.
+    pub fn outcome(&self) -> Outcome {
+        match self
+            .outcome
+            .as_deref()
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("no") | Some("down") => Outcome::Down,
+            _ => Outcome::Up,
+        }
+    }
+
+    /// Identity of the fill: transaction hash plus log index. Without a log
+    /// index, asset, side, size and price tell the fills of one transaction apart.
+    pub fn key(&self) -> String {
+        match self.log_index {
+            Some(index) => format!("{}:{index}", self.transaction_hash),
+            None => format!(
+                "{}:{}:{}:{}:{}",
+                self.transaction_hash, self.asset, self.side, self.size, self.price
+            ),
+        }
+    }
+
+    /// Identity of the fill both the chain stream and the data API produce:
+    /// the data API reports no log index and may merge a taker's fills of one
+    /// transaction, so only the transaction, token and side are kept.
+    pub fn fill_key(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.transaction_hash.to_lowercase(),
+            self.asset,
+            self.side
+        )
+    }
 }
 
 // ---------------------------------------------------------------------------
-// run() — main watcher loop
+// CopyWatcher — main watcher loop
 // ---------------------------------------------------------------------------
 
-pub async fn run(
-    data_api_url: &str,
-    http: HttpPool,
-    queue: Arc<Mutex<ExecutionQueue>>,
-    db: PgPool,
-    mut redis_conn: redis::aio::MultiplexedConnection,
-) -> Result<()> {
-    loop {
-        let addresses = postgres::load_watched_addresses(&db).await?;
-
-        let mut handles = Vec::new();
-        for address in &addresses {
-            let url = data_api_url.to_string();
-            let client = http.clone();
-            let addr = address.clone();
-            let mut redis = redis_conn.clone();
-
-            handles.push(tokio::spawn(async move {
-                let last_seen = get_last_seen(&mut redis, &addr).await.unwrap_or(0);
-                let trades = check_new_trades(&url, &client, &addr, last_seen).await;
-                (addr, trades)
-            }));
+/// Queues the copy orders of followers for the leader trades a
+/// [`LeaderTradeSource`] detects.
+pub struct CopyWatcher {
+    pub network: Arc<NetworkProfile>,
+    pub http: HttpPool,
+    pub queue: Arc<Mutex<ExecutionQueue>>,
+    pub db: PgPool,
+    pub journal: Arc<ExecutionJournal>,
+    pub markets: Arc<RwLock<HashMap<String, ActiveMarket>>>,
+    pub books: OrderBookCache,
+    /// Follower balances, for balance-relative size modes.
+    pub balances: Arc<BalanceService>,
+    /// Tells followers when their relationship is paused.
+    pub notifier: WebNotifier,
+}
+
+/// Risk state of a follower with limits, updated as copies are queued.
+struct FollowerRisk {
+    state: CopyRiskState,
+    /// Tokens held from copied fills in unresolved markets, when open
+    /// positions are limited.
+    open_tokens: HashSet<String>,
+    paused: bool,
+}
+
+/// What the watcher knows about a leader trade when deciding to copy it.
+pub(crate) struct TradeContext<'a> {
+    /// Market parameters from discovery; `None` lets the submitter look them up.
+    pub(crate) market: Option<MarketMeta>,
+    /// Market and event slugs, for pattern filters.
+    pub(crate) slugs: Vec<&'a str>,
+    pub(crate) now: i64,
+    /// Current best ask of the traded token, when an entry filter needs it.
+    pub(crate) best_ask: Option<f64>,
+}
+
+impl CopyWatcher {
+    /// Copy the trades `source` detects until an error stops the watcher.
+    pub async fn run<S: LeaderTradeSource>(
+        self,
+        mut source: S,
+        mut redis_conn: redis::aio::MultiplexedConnection,
+    ) -> Result<()> {
+        loop {
+            let addresses = postgres::load_watched_addresses(&self.db).await?;
+            let batches = source.next_batches(&addresses, &mut redis_conn).await?;
+            let exits = join_all(batches.iter().map(|b| self.exit_fractions(b))).await;
+
+            for (batch, exits) in batches.iter().zip(exits) {
+                let exits = match exits {
+                    Ok(exits) => exits,
+                    Err(e) => {
+                        tracing::warn!(address = %batch.address, error = %e, "check_new_trades_failed");
+                        continue;
+                    }
+                };
+                record_detection_latency(batch);
+
+                if !batch.trades.is_empty() {
+                    self.copy_trades(&batch.address, &batch.trades, exits, &mut redis_conn)
+                        .await?;
+                }
+                if let Some(cursor) = batch.cursor {
+                    advance_cursor(&mut redis_conn, &batch.address, cursor).await?;
+                }
+            }
         }
+    }
 
-        for handle in handles {
-            let (address, trades_result) = handle.await?;
-            let trades = match trades_result {
-                Ok(t) => t,
-                Err(e) => {
-                    tracing::warn!(address = %address, error = %e, "check_new_trades_failed");
-                    continue;
+    /// Exit fractions of a batch's sells, from the leader's current holdings.
+    ///
+    /// Streamed fills can be ahead of the data API: the holdings it reports
+    /// are brought forward over the batch's trades it has not indexed yet.
+    async fn exit_fractions(&self, batch: &LeaderBatch) -> Result<Vec<Option<f64>>> {
+        if !batch.trades.iter().any(LeaderTrade::is_sell) {
+            return Ok(vec![None; batch.trades.len()]);
+        }
+        let leader: Address = batch.address.parse()?;
+        // Read trades before positions: a trade indexed by then is in the positions.
+        let indexed = if batch.cursor.is_none() {
+            let oldest = batch.trades.iter().map(|t| t.timestamp).min().unwrap_or(0);
+            let trades = fetch_trades_since(
+                &self.network.data_api_url,
+                &self.http,
+                &batch.address,
+                oldest,
+            )
+            .await?;
+            Some(trades.iter().map(LeaderTrade::fill_key).collect())
+        } else {
+            None
+        };
+        let mut holdings = fetch_positions(&self.http, &self.network.data_api_url, &leader)
+            .await?
+            .into_iter()
+            .map(|p| (p.asset, p.size))
+            .collect();
+        if let Some(indexed) = indexed {
+            apply_unindexed(&mut holdings, &batch.trades, &indexed);
+        }
+        Ok(exit_fractions(&batch.trades, &holdings))
+    }
+
+    /// Queue or skip every follower's copy of a leader's new trades, oldest first.
+    async fn copy_trades(
+        &self,
+        address: &str,
+        trades: &[LeaderTrade],
+        exits: Vec<Option<f64>>,
+        redis_conn: &mut redis::aio::MultiplexedConnection,
+    ) -> Result<()> {
+        let followers = postgres::get_active_followers(&self.db, address).await?;
+        // Follower holdings from copied fills, drawn down locally as exits
+        // are queued so that several leader sells in one batch do not
+        // oversell.
+        let mut held = HashMap::new();
+        if exits.iter().any(Option::is_some) {
+            for follower in &followers {
+                held.insert(
+                    follower.id,
+                    postgres::load_copy_positions(&self.db, follower.id, follower.is_paper).await?,
+                );
+            }
+        }
+        let mut risk = HashMap::new();
+        for follower in followers.iter().filter(|f| !f.risk_limits.is_empty()) {
+            let state =
+                postgres::load_copy_risk_state(&self.db, follower.id, follower.is_paper).await?;
+            let paused = match follower.risk_limits.pause_reason(&state) {
+                Some(reason) => {
+                    self.pause(follower, address, &reason).await?;
+                    true
                 }
+                None => false,
             };
-
-            if trades.is_empty() {
-                continue;
+            let open_tokens = if follower.risk_limits.max_open_positions.is_some() {
+                postgres::load_copy_positions(&self.db, follower.id, follower.is_paper)
+                    .await?
+                    .into_keys()
+                    .collect()
+            } else {
+                HashSet::new()
+            };
+            risk.insert(
+                follower.id,
+                FollowerRisk {
+                    state,
+                    open_tokens,
+                    paused,
+                },
+            );
+        }
+        // Bankrolls for balance-relative sizing. Follower balances are drawn
+        // down locally as entries are queued, ahead of executor reservations.
+        let mut balances = HashMap::new();
+        let mut leader_value = None;
+        if trades.iter().any(|t| !t.is_sell()) {
+            for follower in &followers {
+                if SizeMode::parse(&follower.size_mode).needs_follower_balance() {
+                    let wallet_id = follower.follower_wallet_id as u64;
+                    if let Some(snapshot) = self.balances.snapshot(wallet_id).await {
+                        balances.insert(follower.id, snapshot.usdc_available);
+                    }
+                }
             }
+            if followers
+                .iter()
+                .any(|f| SizeMode::parse(&f.size_mode).needs_leader_value())
+            {
+                leader_value =
+                    match fetch_portfolio_value(&self.http, &self.network.data_api_url, address)
+                        .await
+                    {
+                        Ok(value) => value,
+                        Err(e) => {
+                            tracing::warn!(address, error = %e, "leader_value_fetch_failed");
+                            None
+                        }
+                    };
+            }
+        }
+        let needs_book = followers.iter().any(|f| f.filters.needs_book());
 
-            let followers = postgres::get_active_followers(&db, &address).await?;
+        for (trade, exit) in trades.iter().zip(exits) {
+            // Markets we discover carry neg-risk/tick size and their slug.
+            let (market, market_slug) = {
+                let markets = self.markets.read().await;
+                let active = markets.get(&trade.condition_id);
+                (
+                    active.map(MarketMeta::from),
+                    active.map(|a| a.slug.clone()).unwrap_or_default(),
+                )
+            };
+            let best_ask = if needs_book && !trade.is_sell() {
+                self.best_ask(&trade.asset).await
+            } else {
+                None
+            };
+            let ctx = TradeContext {
+                market,
+                slugs: [trade.slug.as_str(), trade.event_slug.as_str(), &market_slug]
+                    .into_iter()
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+                now: chrono::Utc::now().timestamp(),
+                best_ask,
+            };
 
-            for trade in &trades {
-                for follower in &followers {
-                    match build_copy_order(trade, follower, &address) {
-                        Some(order) => {
-                            let mut q = queue.lock().await;
-                            q.push(order);
-                            counter!(m::COPY_TRADES_TOTAL, "status" => "queued").increment(1);
+            for follower in &followers {
+                let mut follower_risk = risk.get_mut(&follower.id);
+                if follower_risk.as_ref().is_some_and(|r| r.paused) {
+                    self.skip(follower, address, trade, SkipReason::Paused)
+                        .await;
+                    continue;
+                }
+                let bankrolls = Bankrolls {
+                    follower: balances.get(&follower.id).copied(),
+                    leader: leader_value,
+                };
+                let exit_shares = exit.map(|fraction| {
+                    let holding = held
+                        .get_mut(&follower.id)
+                        .and_then(|h| h.get_mut(&trade.asset));
+                    match holding {
+                        Some(shares) => {
+                            let sold = *shares * fraction;
+                            *shares -= sold;
+                            sold
+                        }
+                        None => 0.0,
+                    }
+                });
+                let order =
+                    build_copy_order(trade, follower, address, &ctx, bankrolls, exit_shares)
+                        .and_then(|order| match &follower_risk {
+                            Some(r) if order.side == Side::Buy => follower
+                                .risk_limits
+                                .check_entry(
+                                    &r.state,
+                                    r.open_tokens.len(),
+                                    !r.open_tokens.contains(&trade.asset),
+                                )
+                                .map(|()| order),
+                            _ => Ok(order),
+                        });
+                match order {
+                    Ok(order) => {
+                        if let Some(r) = follower_risk.as_mut() {
+                            r.state.trades_last_hour += 1;
+                            if order.side == Side::Buy {
+                                r.open_tokens.insert(trade.asset.clone());
+                            }
                         }
-                        None => {
-                            let outcome_str = trade.outcome.as_deref().unwrap_or("UP");
-                            let _ = postgres::write_copy_trade(
-                                &db,
-                                follower.id,
-                                None,
-                                &address,
-                                &trade.condition_id,
-                                outcome_str,
-                                trade.price,
-                                trade.size,
-                                &trade.transaction_hash,
-                                None,
-                                "skipped",
-                                Some("exceeds_max_position_or_filtered"),
-                            )
-                            .await;
-                            counter!(m::COPY_TRADES_TOTAL, "status" => "skipped").increment(1);
+                        if let Some(balance) = balances.get_mut(&follower.id) {
+                            if order.side == Side::Buy {
+                                *balance = (*balance - order.size_usdc).max(0.0);
+                            }
                         }
+                        self.journal.enqueue(&self.queue, order).await;
+                        counter!(
+                            m::COPY_TRADES_TOTAL,
+                            "status" => "queued",
+                            "mode" => mode_label(follower.is_paper)
+                        )
+                        .increment(1);
                     }
+                    Err(reason) => self.skip(follower, address, trade, reason).await,
                 }
+            }
 
-                update_last_seen(&mut redis_conn, &address, trade.timestamp).await?;
+            mark_seen(redis_conn, address, trade).await?;
+        }
+        Ok(())
+    }
+
+    /// Record a leader trade the follower does not copy.
+    async fn skip(
+        &self,
+        follower: &CopyRelationship,
+        address: &str,
+        trade: &LeaderTrade,
+        reason: SkipReason,
+    ) {
+        let outcome_str = trade.outcome.as_deref().unwrap_or("UP");
+        let _ = postgres::write_copy_trade(
+            &self.db,
+            follower.id,
+            None,
+            address,
+            &trade.condition_id,
+            outcome_str,
+            trade.price,
+            trade.size,
+            &trade.transaction_hash,
+            None,
+            "skipped",
+            Some(reason.as_str()),
+            follower.is_paper,
+        )
+        .await;
+        counter!(
+            m::COPY_TRADES_TOTAL,
+            "status" => "skipped",
+            "reason" => reason.as_str(),
+            "mode" => mode_label(follower.is_paper)
+        )
+        .increment(1);
+    }
+
+    /// Deactivate a relationship that breached its drawdown limit and tell
+    /// the follower. The notification is best-effort.
+    async fn pause(&self, follower: &CopyRelationship, address: &str, reason: &str) -> Result<()> {
+        postgres::pause_copy_relationship(&self.db, follower.id, reason).await?;
+        tracing::warn!(
+            copy_relationship_id = follower.id,
+            leader = %address,
+            reason,
+            "copy_relationship_paused"
+        );
+        let message = format!("Copying {address} was paused: {reason}.");
+        if let Err(e) = self
+            .notifier
+            .send(
+                follower.follower_wallet_id,
+                "Copy trading paused",
+                &message,
+                true,
+            )
+            .await
+        {
+            tracing::warn!(copy_relationship_id = follower.id, error = %e, "copy_pause_notify_failed");
+        }
+        Ok(())
+    }
+
+    /// Best ask of `token_id`: from the streamed book when the token is
+    /// subscribed, otherwise from the CLOB's REST book.
+    async fn best_ask(&self, token_id: &str) -> Option<f64> {
+        let cached = self
+            .books
+            .read()
+            .await
+            .get(token_id)
+            .and_then(|b| b.best_ask().map(|l| f64::from(l.price)));
+        if cached.is_some() {
+            return cached;
+        }
+        match fetch_best_ask(&self.http, &self.network.clob_api_url, token_id).await {
+            Ok(ask) => ask,
+            Err(e) => {
+                tracing::warn!(token_id, error = %e, "copy_book_fetch_failed");
+                None
             }
         }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct PortfolioValue {
+    value: f64,
+}
 
-        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+/// Value of `address`'s open positions from the data API `/value` endpoint.
+pub(crate) async fn fetch_portfolio_value(
+    http: &HttpPool,
+    data_api_url: &str,
+    address: &str,
+) -> Result<Option<f64>> {
+    let url = format!(
+        "{}/value?user={}",
+        data_api_url.trim_end_matches('/'),
+        address
+    );
+    let values: Vec<PortfolioValue> = http
+        .proxied()
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(values.first().map(|v| v.value))
+}
+
+/// Time from each leader fill to its detection, per source.
+fn record_detection_latency(batch: &LeaderBatch) {
+    let now = chrono::Utc::now().timestamp_millis() as f64 / 1000.0;
+    for trade in &batch.trades {
+        histogram!(m::COPY_DETECTION_LATENCY, "source" => batch.source)
+            .record((now - trade.timestamp as f64).max(0.0));
     }
 }
 
+fn mode_label(is_paper: bool) -> &'static str {
+    if is_paper {
+        "paper"
+    } else {
+        "live"
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct RestBook {
+    #[serde(default)]
+    asks: Vec<RestLevel>,
+}
+
+#[derive(serde::Deserialize)]
+struct RestLevel {
+    price: String,
+}
+
+/// Lowest ask on the CLOB's REST book, whatever order the levels come in.
+async fn fetch_best_ask(
+    http: &HttpPool,
+    clob_api_url: &str,
+    token_id: &str,
+) -> Result<Option<f64>> {
+    let url = format!("{}/book?token_id={}", clob_api_url, token_id);
+    let book: RestBook = http
+        .proxied()
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(book
+        .asks
+        .iter()
+        .filter_map(|l| l.price.parse::<f64>().ok())
+        .reduce(f64::min))
+}
+
 // ---------------------------------------------------------------------------
-// check_new_trades() — fetch recent trades from the data API
+// fetch_trades_since() — paginate a leader's trades from the data API
 // ---------------------------------------------------------------------------
 
-async fn check_new_trades(
+/// Oldest timestamp a poll asks for: a little before the cursor to absorb
+/// indexing lag, and never older than `max_age_secs` so that a restart or a
+/// new leader does not copy stale trades.
+pub(super) fn backfill_start(last_seen: i64, now: i64, max_age_secs: i64) -> i64 {
+    (last_seen - INDEX_LAG_SECS).max(now - max_age_secs)
+}
+
+/// Every trade of `address` at or after `since`, newest first.
+///
+/// Pages by offset until a page reaches `since` or comes back short. Trades
+/// landing meanwhile shift later pages, which can repeat a trade but never
+/// skip one; [`select_new`] drops the repeats.
+pub(crate) async fn fetch_trades_since(
     data_api_url: &str,
     http: &HttpPool,
     address: &str,
-    last_seen: i64,
+    since: i64,
 ) -> Result<Vec<LeaderTrade>> {
-    let url = format!(
-        "{}/trades?user={}&limit=5&sortBy=TIMESTAMP&sortDirection=DESC",
-        data_api_url, address
-    );
+    let history = fetch_trade_history(data_api_url, http, address, since, MAX_PAGES).await?;
+    if history.truncated {
+        tracing::warn!(
+            address,
+            pages = MAX_PAGES,
+            "leader_trades_page_limit_reached"
+        );
+    }
+    Ok(history.trades)
+}
 
-    let trades: Vec<LeaderTrade> = http.proxied().get(&url).send().await?.json().await?;
+/// A leader's trades since some time, newest first.
+pub(crate) struct TradeHistory {
+    pub(crate) trades: Vec<LeaderTrade>,
+    /// `max_pages` ran out before reaching the start: older trades are missing.
+    pub(crate) truncated: bool,
+}
 
-    let new_trades: Vec<LeaderTrade> = trades
+/// Like [`fetch_trades_since`], for reads of a past range that may need many
+/// more pages than a poll.
+pub(crate) async fn fetch_trade_history(
+    data_api_url: &str,
+    http: &HttpPool,
+    address: &str,
+    since: i64,
+    max_pages: usize,
+) -> Result<TradeHistory> {
+    let mut trades = Vec::new();
+    for page in 0..max_pages {
+        let url = format!(
+            "{}/trades?user={}&limit={}&offset={}&sortBy=TIMESTAMP&sortDirection=DESC",
+            data_api_url,
+            address,
+            PAGE_SIZE,
+            page * PAGE_SIZE
+        );
+        let batch: Vec<LeaderTrade> = http
+            .proxied()
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let last_page = batch.len() < PAGE_SIZE || batch.iter().any(|t| t.timestamp < since);
+        trades.extend(batch.into_iter().filter(|t| t.timestamp >= since));
+        if last_page {
+            return Ok(TradeHistory {
+                trades,
+                truncated: false,
+            });
+        }
+    }
+    Ok(TradeHistory {
+        trades,
+        truncated: true,
+    })
+}
+
+/// Trades not handled yet, oldest first. A trade is handled when either of
+/// its [`seen_keys`] is in `seen`, whichever source read it; repeats within
+/// `trades` are dropped by [`LeaderTrade::key`].
+pub(super) fn select_new(trades: Vec<LeaderTrade>, seen: &HashSet<String>) -> Vec<LeaderTrade> {
+    let mut keys = HashSet::new();
+    let mut new: Vec<LeaderTrade> = trades
         .into_iter()
-        .filter(|t| t.timestamp > last_seen)
+        .filter(|t| !seen_keys(t).iter().any(|k| seen.contains(k)) && keys.insert(t.key()))
         .collect();
+    new.sort_by_key(|t| t.timestamp);
+    new
+}
 
-    Ok(new_trades)
+/// Fraction of the leader's position each SELL in `trades` (oldest first)
+/// closed, indexed like `trades`; `None` for buys.
+///
+/// Walks back from the leader's current `holdings` by token: before a trade the
+/// leader held what they hold now, plus everything sold since, minus everything
+/// bought since. A sell leaving less than [`FLATTEN_BELOW`] of the position
+/// counts as a full exit.
+fn exit_fractions(trades: &[LeaderTrade], holdings: &HashMap<String, f64>) -> Vec<Option<f64>> {
+    let mut after: HashMap<&str, f64> = HashMap::new();
+    let mut fractions = vec![None; trades.len()];
+    for (i, trade) in trades.iter().enumerate().rev() {
+        let held_after = *after
+            .entry(&trade.asset)
+            .or_insert_with(|| holdings.get(&trade.asset).copied().unwrap_or(0.0));
+        let held_before = if trade.is_sell() {
+            let before = held_after + trade.size;
+            fractions[i] = Some(if held_after < before * FLATTEN_BELOW {
+                1.0
+            } else {
+                trade.size / before
+            });
+            before
+        } else {
+            (held_after - trade.size).max(0.0)
+        };
+        after.insert(&trade.asset, held_before);
+    }
+    fractions
+}
+
+/// Apply to `holdings` the `trades` whose fill is not in `indexed` yet.
+fn apply_unindexed(
+    holdings: &mut HashMap<String, f64>,
+    trades: &[LeaderTrade],
+    indexed: &HashSet<String>,
+) {
+    for trade in trades.iter().filter(|t| !indexed.contains(&t.fill_key())) {
+        let held = holdings.entry(trade.asset.clone()).or_insert(0.0);
+        *held = if trade.is_sell() {
+            (*held - trade.size).max(0.0)
+        } else {
+            *held + trade.size
+        };
+    }
 }
 
 // ---------------------------------------------------------------------------
-// Redis helpers — get/update last_seen
+// Redis helpers — ingestion cursor
 // ---------------------------------------------------------------------------
 
-async fn get_last_seen(conn: &mut redis::aio::MultiplexedConnection, address: &str) -> Result<i64> {
-    let key = format!("craftstrat:watcher:last_seen:{}", address);
-    let val: Option<String> = redis::cmd("GET").arg(&key).query_async(conn).await?;
-    Ok(val.and_then(|v| v.parse().ok()).unwrap_or(0))
+/// Newest trade timestamp handled for a leader, plus the keys of the trades
+/// handled within the look-back window before it.
+pub(super) struct Cursor {
+    pub(super) last_seen: i64,
+    pub(super) seen: HashSet<String>,
+}
+
+fn last_seen_key(address: &str) -> String {
+    format!("craftstrat:watcher:last_seen:{}", address)
 }
 
-async fn update_last_seen(
+fn seen_key(address: &str) -> String {
+    format!("craftstrat:watcher:seen:{}", address)
+}
+
+pub(super) async fn load_cursor(
+    conn: &mut redis::aio::MultiplexedConnection,
+    address: &str,
+) -> Result<Cursor> {
+    let val: Option<String> = redis::cmd("GET")
+        .arg(last_seen_key(address))
+        .query_async(conn)
+        .await?;
+    let last_seen = val.and_then(|v| v.parse().ok()).unwrap_or(0);
+    let seen: Vec<String> = redis::cmd("ZRANGEBYSCORE")
+        .arg(seen_key(address))
+        .arg(last_seen - INDEX_LAG_SECS)
+        .arg("+inf")
+        .query_async(conn)
+        .await?;
+    Ok(Cursor {
+        last_seen,
+        seen: seen.into_iter().collect(),
+    })
+}
+
+/// Members a handled trade is recorded under in the seen set.
+pub(super) fn seen_keys(trade: &LeaderTrade) -> [String; 2] {
+    [trade.key(), trade.fill_key()]
+}
+
+/// Record a trade as handled, scored by its timestamp.
+async fn mark_seen(
     conn: &mut redis::aio::MultiplexedConnection,
     address: &str,
-    timestamp: i64,
+    trade: &LeaderTrade,
 ) -> Result<()> {
-    let key = format!("craftstrat:watcher:last_seen:{}", address);
-    redis::cmd("SET")
+    let key = seen_key(address);
+    let mut zadd = redis::cmd("ZADD");
+    zadd.arg(&key);
+    for member in seen_keys(trade) {
+        zadd.arg(trade.timestamp).arg(member);
+    }
+    zadd.query_async::<()>(conn).await?;
+    redis::cmd("EXPIRE")
         .arg(&key)
-        .arg(timestamp.to_string())
+        .arg(SEEN_TTL_SECS)
+        .query_async::<()>(conn)
+        .await?;
+    Ok(())
+}
+
+/// Move the cursor to `last_seen` and forget trades outside the look-back window.
+async fn advance_cursor(
+    conn: &mut redis::aio::MultiplexedConnection,
+    address: &str,
+    last_seen: i64,
+) -> Result<()> {
+    redis::cmd("SET")
+        .arg(last_seen_key(address))
+        .arg(last_seen.to_string())
+        .query_async::<()>(conn)
+        .await?;
+    redis::cmd("ZREMRANGEBYSCORE")
+        .arg(seen_key(address))
+        .arg("-inf")
+        .arg(format!("({}", last_seen - INDEX_LAG_SECS))
         .query_async::<()>(conn)
         .await?;
     Ok(())
@@ -166,12 +780,18 @@ async fn update_last_seen(
 // build_copy_order() — pure function that maps a leader trade to an order
 // ---------------------------------------------------------------------------
 
-fn build_copy_order(
+/// Map a leader trade to the follower's order, or say why it is not copied.
+/// Buys are sized by the relationship's size mode against `bankrolls`; sells
+/// close `exit_shares` of the copied position.
+pub(crate) fn build_copy_order(
     trade: &LeaderTrade,
     follower: &CopyRelationship,
     leader_address: &str,
-) -> Option<ExecutionOrder> {
-    // 1. Check markets_filter
+    ctx: &TradeContext,
+    bankrolls: Bankrolls,
+    exit_shares: Option<f64>,
+) -> Result<ExecutionOrder, SkipReason> {
+    // 1. Check markets_filter and copy_filters
     if let Some(ref filter_value) = follower.markets_filter {
         if let Some(arr) = filter_value.as_array() {
             let allowed: Vec<String> = arr
@@ -179,37 +799,38 @@ fn build_copy_order(
                 .filter_map(|v| v.as_str().map(String::from))
                 .collect();
             if !allowed.contains(&trade.condition_id) {
-                return None;
+                return Err(SkipReason::MarketNotAllowed);
             }
         }
     }
+    follower
+        .filters
+        .check(trade, &ctx.slugs, ctx.now, ctx.best_ask)?;
 
-    // 2. Calculate size
-    let size = match follower.size_mode.as_str() {
-        "fixed" => follower.size_value,
-        "proportional" => trade.size * follower.size_value,
-        _ => follower.size_value,
-    };
-
-    // 3. Check max_position
-    if size > follower.max_position_usdc {
-        return None;
-    }
-
-    // 4. Map outcome
-    let outcome = match trade.outcome.as_deref() {
-        Some("Yes") | Some("UP") => Outcome::Up,
-        Some("No") | Some("DOWN") => Outcome::Down,
-        _ => Outcome::Up,
+    // 2. Calculate size: exits mirror the leader's fraction of our position,
+    //    entries follow the size mode and are capped by max_position
+    let size = if trade.is_sell() {
+        let shares = exit_shares.unwrap_or(0.0);
+        if shares <= 0.0 {
+            return Err(SkipReason::NoCopiedPosition);
+        }
+        shares * trade.price
+    } else {
+        let size = entry_size(trade, follower, bankrolls, ctx.market.as_ref())?;
+        if size > follower.max_position_usdc {
+            return Err(SkipReason::ExceedsMaxPosition);
+        }
+        size
     };
 
-    // 5. Map side
-    let side = match trade.side.as_str() {
-        "SELL" => Side::Sell,
-        _ => Side::Buy,
+    // 3. Map side
+    let side = if trade.is_sell() {
+        Side::Sell
+    } else {
+        Side::Buy
     };
 
-    Some(ExecutionOrder {
+    Ok(ExecutionOrder {
         id: Uuid::new_v4(),
         wallet_id: follower.follower_wallet_id as u64,
         strategy_id: None,
@@ -217,7 +838,7 @@ fn build_copy_order(
         symbol: trade.condition_id.clone(),
         token_id: trade.asset.clone(),
         side,
-        outcome,
+        outcome: trade.outcome(),
         price: Some(trade.price),
         reference_price: Some(trade.price),
         size_usdc: size,
@@ -226,7 +847,11 @@ fn build_copy_order(
         created_at: chrono::Utc::now().timestamp(),
         leader_address: leader_address.to_string(),
         leader_tx_hash: trade.transaction_hash.clone(),
-        is_paper: false,
+        is_paper: follower.is_paper,
+        market: ctx.market,
+        algo: follower.execution_algo,
+        parent_id: None,
+        closes: None,
     })
 }
 
@@ -237,6 +862,8 @@ fn build_copy_order(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::execution::algo::ExecAlgo;
+    use crate::watcher::filters::{CopyFilters, CopySide};
 
     fn test_trade() -> LeaderTrade {
         LeaderTrade {
@@ -248,6 +875,18 @@ mod tests {
             timestamp: 1_700_000_000,
             transaction_hash: "0xdeadbeef".to_string(),
             outcome: Some("Yes".to_string()),
+            slug: "btc-updown-15m-1700000000".to_string(),
+            event_slug: String::new(),
+            log_index: None,
+        }
+    }
+
+    fn ctx() -> TradeContext<'static> {
+        TradeContext {
+            market: None,
+            slugs: vec!["btc-updown-15m-1700000000"],
+            now: 1_700_000_005,
+            best_ask: None,
         }
     }
 
@@ -258,7 +897,13 @@ mod tests {
             size_mode: "fixed".to_string(),
             size_value: 50.0,
             max_position_usdc: 200.0,
+            min_size_usdc: None,
+            max_size_usdc: None,
             markets_filter: None,
+            execution_algo: None,
+            filters: CopyFilters::default(),
+            risk_limits: Default::default(),
+            is_paper: false,
         }
     }
 
@@ -267,7 +912,15 @@ mod tests {
         let trade = test_trade();
         let follower = test_follower();
 
-        let order = build_copy_order(&trade, &follower, "0xleader").unwrap();
+        let order = build_copy_order(
+            &trade,
+            &follower,
+            "0xleader",
+            &ctx(),
+            Bankrolls::default(),
+            None,
+        )
+        .unwrap();
 
         assert!((order.size_usdc - 50.0).abs() < f64::EPSILON);
         assert_eq!(order.priority, OrderPriority::CopyMarket);
@@ -286,7 +939,15 @@ mod tests {
         follower.size_mode = "proportional".to_string();
         follower.size_value = 0.5;
 
-        let order = build_copy_order(&trade, &follower, "0xleader").unwrap();
+        let order = build_copy_order(
+            &trade,
+            &follower,
+            "0xleader",
+            &ctx(),
+            Bankrolls::default(),
+            None,
+        )
+        .unwrap();
 
         assert!((order.size_usdc - 50.0).abs() < f64::EPSILON);
     }
@@ -297,9 +958,16 @@ mod tests {
         let mut follower = test_follower();
         follower.max_position_usdc = 10.0;
 
-        let result = build_copy_order(&trade, &follower, "0xleader");
+        let result = build_copy_order(
+            &trade,
+            &follower,
+            "0xleader",
+            &ctx(),
+            Bankrolls::default(),
+            None,
+        );
 
-        assert!(result.is_none());
+        assert_eq!(result.unwrap_err(), SkipReason::ExceedsMaxPosition);
     }
 
     #[test]
@@ -308,9 +976,16 @@ mod tests {
         let mut follower = test_follower();
         follower.markets_filter = Some(serde_json::json!(["condition_456"]));
 
-        let result = build_copy_order(&trade, &follower, "0xleader");
+        let result = build_copy_order(
+            &trade,
+            &follower,
+            "0xleader",
+            &ctx(),
+            Bankrolls::default(),
+            None,
+        );
 
-        assert!(result.is_some());
+        assert!(result.is_ok());
     }
 
     #[test]
@@ -319,9 +994,16 @@ mod tests {
         let mut follower = test_follower();
         follower.markets_filter = Some(serde_json::json!(["other"]));
 
-        let result = build_copy_order(&trade, &follower, "0xleader");
+        let result = build_copy_order(
+            &trade,
+            &follower,
+            "0xleader",
+            &ctx(),
+            Bankrolls::default(),
+            None,
+        );
 
-        assert!(result.is_none());
+        assert_eq!(result.unwrap_err(), SkipReason::MarketNotAllowed);
     }
 
     #[test]
@@ -329,9 +1011,16 @@ mod tests {
         let trade = test_trade();
         let follower = test_follower(); // markets_filter is None
 
-        let result = build_copy_order(&trade, &follower, "0xleader");
+        let result = build_copy_order(
+            &trade,
+            &follower,
+            "0xleader",
+            &ctx(),
+            Bankrolls::default(),
+            None,
+        );
 
-        assert!(result.is_some());
+        assert!(result.is_ok());
     }
 
     #[test]
@@ -340,8 +1029,338 @@ mod tests {
         trade.side = "SELL".to_string();
         let follower = test_follower();
 
-        let order = build_copy_order(&trade, &follower, "0xleader").unwrap();
+        let order = build_copy_order(
+            &trade,
+            &follower,
+            "0xleader",
+            &ctx(),
+            Bankrolls::default(),
+            Some(40.0),
+        )
+        .unwrap();
 
         assert_eq!(order.side, Side::Sell);
+        // Sized from the copied shares at the leader's price, not size_value.
+        assert!((order.size_usdc - 26.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_build_copy_order_sell_without_position_is_skipped() {
+        let mut trade = test_trade();
+        trade.side = "SELL".to_string();
+        let follower = test_follower();
+
+        for exit in [None, Some(0.0)] {
+            assert_eq!(
+                build_copy_order(
+                    &trade,
+                    &follower,
+                    "0xleader",
+                    &ctx(),
+                    Bankrolls::default(),
+                    exit
+                )
+                .unwrap_err(),
+                SkipReason::NoCopiedPosition
+            );
+        }
+    }
+
+    fn leader_trade(side: &str, asset: &str, size: f64) -> LeaderTrade {
+        LeaderTrade {
+            side: side.to_string(),
+            asset: asset.to_string(),
+            size,
+            ..test_trade()
+        }
+    }
+
+    #[test]
+    fn test_exit_fractions_walk_back_from_holdings() {
+        // Leader now holds 60 of token a after selling 40 (40%), and nothing of
+        // token b after selling 10 then 30.
+        let trades = vec![
+            leader_trade("BUY", "a", 50.0),
+            leader_trade("SELL", "a", 40.0),
+            leader_trade("SELL", "b", 10.0),
+            leader_trade("SELL", "b", 30.0),
+        ];
+        let holdings = HashMap::from([("a".to_string(), 60.0)]);
+        let fractions = exit_fractions(&trades, &holdings);
+
+        assert_eq!(fractions[0], None);
+        assert!((fractions[1].unwrap() - 0.4).abs() < 1e-9);
+        assert!((fractions[2].unwrap() - 0.25).abs() < 1e-9);
+        assert_eq!(fractions[3], Some(1.0), "closed entirely");
+    }
+
+    #[test]
+    fn test_exit_fractions_of_unindexed_sells() {
+        // The leader held 100 of token a and streamed sells of 50 then 50;
+        // positions only reflect the first.
+        let mut first = leader_trade("SELL", "a", 50.0);
+        first.transaction_hash = "0xAA".to_string();
+        let mut second = leader_trade("SELL", "a", 50.0);
+        second.transaction_hash = "0xbb".to_string();
+        let trades = vec![first, second];
+        let mut holdings = HashMap::from([("a".to_string(), 50.0)]);
+        let indexed = HashSet::from(["0xaa:a:SELL".to_string()]);
+
+        apply_unindexed(&mut holdings, &trades, &indexed);
+
+        assert_eq!(holdings["a"], 0.0);
+        assert_eq!(
+            exit_fractions(&trades, &holdings),
+            vec![Some(0.5), Some(1.0)]
+        );
+    }
+
+    #[test]
+    fn test_exit_fractions_flatten_dust() {
+        let trades = vec![leader_trade("SELL", "a", 99.5)];
+        let holdings = HashMap::from([("a".to_string(), 0.5)]);
+        assert_eq!(exit_fractions(&trades, &holdings), vec![Some(1.0)]);
+    }
+
+    #[test]
+    fn test_build_copy_order_carries_market_meta() {
+        let meta = MarketMeta {
+            neg_risk: true,
+            tick_size: 0.001,
+            min_order_size: 5.0,
+        };
+
+        let order = build_copy_order(
+            &test_trade(),
+            &test_follower(),
+            "0xleader",
+            &TradeContext {
+                market: Some(meta),
+                ..ctx()
+            },
+            Bankrolls::default(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(order.market, Some(meta));
+    }
+
+    #[test]
+    fn test_build_copy_order_carries_execution_algo() {
+        let trade = test_trade();
+        let mut follower = test_follower();
+        follower.execution_algo = Some(ExecAlgo::Iceberg { clip_usdc: 10.0 });
+
+        let order = build_copy_order(
+            &trade,
+            &follower,
+            "0xleader",
+            &ctx(),
+            Bankrolls::default(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(order.algo, Some(ExecAlgo::Iceberg { clip_usdc: 10.0 }));
+    }
+
+    fn trade_at(ts: i64, hash: &str, log_index: Option<u64>) -> LeaderTrade {
+        LeaderTrade {
+            timestamp: ts,
+            transaction_hash: hash.to_string(),
+            log_index,
+            ..test_trade()
+        }
+    }
+
+    #[test]
+    fn test_trade_key_uses_log_index() {
+        assert_eq!(trade_at(1, "0xaa", Some(3)).key(), "0xaa:3");
+        let fill_a = trade_at(1, "0xaa", None);
+        let fill_b = LeaderTrade {
+            price: 0.66,
+            ..fill_a.clone()
+        };
+        assert_ne!(fill_a.key(), fill_b.key());
+    }
+
+    #[test]
+    fn test_backfill_start_is_capped_by_max_age() {
+        // Recent cursor: look back by the indexing lag.
+        assert_eq!(backfill_start(1_000, 1_010, 120), 1_000 - INDEX_LAG_SECS);
+        // Restart after a long outage or a new leader: only the max age.
+        assert_eq!(backfill_start(1_000, 5_000, 120), 4_880);
+        assert_eq!(backfill_start(0, 5_000, 120), 4_880);
+    }
+
+    #[test]
+    fn test_select_new_dedupes_and_orders_oldest_first() {
+        let seen = HashSet::from(["0xaa:0".to_string()]);
+        let trades = vec![
+            trade_at(30, "0xcc", Some(0)),
+            trade_at(20, "0xbb", Some(1)),
+            trade_at(20, "0xbb", Some(0)),
+            trade_at(20, "0xbb", Some(0)), // repeated across pages
+            trade_at(10, "0xaa", Some(0)), // already handled
+        ];
+        let keys: Vec<_> = select_new(trades, &seen).iter().map(|t| t.key()).collect();
+        assert_eq!(keys.len(), 3);
+        assert_eq!(keys[2], "0xcc:0");
+        assert!(keys[..2].contains(&"0xbb:0".to_string()));
+        assert!(keys[..2].contains(&"0xbb:1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_paginates_until_since() {
+        use axum::extract::Query;
+        use axum::routing::get;
+        use axum::{Json, Router};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // 250 trades, newest first, one per second.
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = Router::new().route(
+            "/trades",
+            get(move |Query(q): Query<HashMap<String, String>>| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let limit: usize = q["limit"].parse().unwrap();
+                let offset: usize = q["offset"].parse().unwrap();
+                let page: Vec<_> = (offset..(offset + limit).min(250))
+                    .map(|i| {
+                        serde_json::json!({
+                            "side": "BUY", "asset": "tok", "conditionId": "c",
+                            "size": 1.0, "price": 0.5, "timestamp": 1_000 - i as i64,
+                            "transactionHash": format!("0x{i}"), "logIndex": 0,
+                        })
+                    })
+                    .collect();
+                async move { Json(page) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        let http = HttpPool::new(&[], std::time::Duration::from_secs(5)).unwrap();
+        let url = format!("http://{addr}");
+
+        // The cutoff falls on the second page: no third request.
+        let trades = fetch_trades_since(&url, &http, "0xleader", 850)
+            .await
+            .unwrap();
+        assert_eq!(trades.len(), 151);
+        assert!(trades.iter().all(|t| t.timestamp >= 850));
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        // A cutoff older than the history stops at the short last page.
+        let trades = fetch_trades_since(&url, &http, "0xleader", 0)
+            .await
+            .unwrap();
+        assert_eq!(trades.len(), 250);
+        assert_eq!(hits.load(Ordering::SeqCst), 5);
+
+        // Running out of pages first is reported.
+        let history = fetch_trade_history(&url, &http, "0xleader", 0, 2)
+            .await
+            .unwrap();
+        assert_eq!(history.trades.len(), 200);
+        assert!(history.truncated);
+    }
+
+    #[test]
+    fn test_build_copy_order_carries_paper_flag() {
+        let mut follower = test_follower();
+        follower.is_paper = true;
+
+        let order = build_copy_order(
+            &test_trade(),
+            &follower,
+            "0xleader",
+            &ctx(),
+            Bankrolls::default(),
+            None,
+        )
+        .unwrap();
+        assert!(order.is_paper);
+    }
+
+    #[test]
+    fn test_build_copy_order_applies_copy_filters() {
+        let mut follower = test_follower();
+        follower.filters = CopyFilters {
+            max_entry_price: Some(0.6),
+            side: Some(CopySide::Buy),
+            ..Default::default()
+        };
+
+        let result = build_copy_order(
+            &test_trade(),
+            &follower,
+            "0xleader",
+            &ctx(),
+            Bankrolls::default(),
+            None,
+        );
+        assert_eq!(result.unwrap_err(), SkipReason::EntryPriceAboveMax);
+
+        let mut sell = test_trade();
+        sell.side = "SELL".to_string();
+        let result = build_copy_order(
+            &sell,
+            &follower,
+            "0xleader",
+            &ctx(),
+            Bankrolls::default(),
+            Some(10.0),
+        );
+        assert_eq!(result.unwrap_err(), SkipReason::Side);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_best_ask_takes_lowest_level() {
+        use axum::routing::get;
+        use axum::{Json, Router};
+
+        // The REST book lists asks worst first.
+        let app = Router::new().route(
+            "/book",
+            get(|| async {
+                Json(serde_json::json!({
+                    "asks": [{"price": "0.60", "size": "5"}, {"price": "0.55", "size": "9"}],
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        let http = HttpPool::new(&[], std::time::Duration::from_secs(5)).unwrap();
+
+        let ask = fetch_best_ask(&http, &format!("http://{addr}"), "tok")
+            .await
+            .unwrap();
+        assert_eq!(ask, Some(0.55));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_portfolio_value() {
+        use axum::extract::Query;
+        use axum::routing::get;
+        use axum::{Json, Router};
+
+        let app = Router::new().route(
+            "/value",
+            get(|Query(q): Query<HashMap<String, String>>| async move {
+                Json(serde_json::json!([{"user": q["user"], "value": 1234.5}]))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        let http = HttpPool::new(&[], std::time::Duration::from_secs(5)).unwrap();
+
+        let value = fetch_portfolio_value(&http, &format!("http://{addr}"), "0xleader")
+            .await
+            .unwrap();
+        assert_eq!(value, Some(1234.5));
     }
 }
@@ -0,0 +1,261 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::filters::SkipReason;
+
+// ---------------------------------------------------------------------------
+// CopyRiskLimits
+// ---------------------------------------------------------------------------
+
+/// Follower-side limits of a copy relationship.
+///
+/// Configured as JSON in `copy_relationships.risk_limits`, e.g.
+/// `{"max_daily_loss_usdc": 50, "max_open_positions": 3, "max_drawdown_usdc": 150}`.
+/// Daily loss, open positions and trade rate gate entries only, so exits are
+/// never blocked. A drawdown breach pauses the relationship.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CopyRiskLimits {
+    /// Stop entering once today's realized loss reaches this.
+    pub max_daily_loss_usdc: Option<f64>,
+    /// Tokens held at once from this relationship.
+    pub max_open_positions: Option<u32>,
+    /// Copies sent in the last hour, entries and exits alike.
+    pub max_trades_per_hour: Option<u32>,
+    /// Pause once realized PnL falls this far below its peak.
+    pub max_drawdown_usdc: Option<f64>,
+}
+
+/// Realized results and recent activity of a relationship in one mode.
+#[derive(Debug, Clone, Default)]
+pub struct CopyRiskState {
+    /// PnL realized since midnight UTC.
+    pub daily_pnl_usdc: f64,
+    /// Peak cumulative realized PnL minus the current one.
+    pub drawdown_usdc: f64,
+    pub trades_last_hour: i64,
+}
+
+impl CopyRiskState {
+    /// Daily PnL and drawdown from a relationship's realizations, oldest first.
+    pub fn from_realized(
+        realized: &[CopyRealization],
+        day_start: i64,
+        trades_last_hour: i64,
+    ) -> Self {
+        let (mut running, mut peak, mut daily) = (0.0, 0.0_f64, 0.0);
+        for r in realized {
+            running += r.pnl_usdc;
+            peak = peak.max(running);
+            if r.realized_at >= day_start {
+                daily += r.pnl_usdc;
+            }
+        }
+        Self {
+            daily_pnl_usdc: daily,
+            drawdown_usdc: peak - running,
+            trades_last_hour,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CopyRealization
+// ---------------------------------------------------------------------------
+
+/// PnL realized on copied holdings, from the `copy_realizations` table.
+#[derive(Debug, Clone, Copy, PartialEq, sqlx::FromRow)]
+pub struct CopyRealization {
+    /// Unix seconds.
+    pub realized_at: i64,
+    pub pnl_usdc: f64,
+}
+
+impl CopyRealization {
+    /// Selling `shares` for `proceeds` out of `held` shares bought for `cost`.
+    /// A sell beyond the holding only realizes the held part.
+    pub fn exit(held: f64, cost: f64, shares: f64, proceeds: f64, realized_at: i64) -> Self {
+        let sold = shares.min(held);
+        let pnl_usdc = if sold > 0.0 {
+            proceeds * sold / shares - cost * sold / held
+        } else {
+            0.0
+        };
+        Self {
+            realized_at,
+            pnl_usdc,
+        }
+    }
+
+    /// Settling `shares` bought for `cost` in a market that resolved to
+    /// `payout` USDC per share.
+    pub fn settlement(shares: f64, cost: f64, payout: f64, realized_at: i64) -> Self {
+        Self {
+            realized_at,
+            pnl_usdc: shares * payout - cost,
+        }
+    }
+}
+
+impl CopyRiskLimits {
+    pub fn from_value(value: &Value) -> Self {
+        if value.is_null() {
+            return Self::default();
+        }
+        match serde_json::from_value(value.clone()) {
+            Ok(limits) => limits,
+            Err(e) => {
+                tracing::warn!(error = %e, config = %value, "copy_risk_limits_invalid");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Why the relationship should be paused, if it should.
+    pub fn pause_reason(&self, state: &CopyRiskState) -> Option<String> {
+        let max = self.max_drawdown_usdc?;
+        (state.drawdown_usdc >= max).then(|| {
+            format!(
+                "drawdown of {:.2} USDC reached the {:.2} USDC limit",
+                state.drawdown_usdc, max
+            )
+        })
+    }
+
+    /// Check an entry. `open_positions` is the number of tokens held and
+    /// `opens_position` whether the entry adds a new one.
+    pub fn check_entry(
+        &self,
+        state: &CopyRiskState,
+        open_positions: usize,
+        opens_position: bool,
+    ) -> Result<(), SkipReason> {
+        if self
+            .max_daily_loss_usdc
+            .is_some_and(|max| -state.daily_pnl_usdc >= max)
+        {
+            return Err(SkipReason::DailyLossLimit);
+        }
+        if opens_position
+            && self
+                .max_open_positions
+                .is_some_and(|max| open_positions >= max as usize)
+        {
+            return Err(SkipReason::MaxOpenPositions);
+        }
+        if self
+            .max_trades_per_hour
+            .is_some_and(|max| state.trades_last_hour >= i64::from(max))
+        {
+            return Err(SkipReason::TradeRateLimit);
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> CopyRiskLimits {
+        CopyRiskLimits {
+            max_daily_loss_usdc: Some(50.0),
+            max_open_positions: Some(2),
+            max_trades_per_hour: Some(10),
+            max_drawdown_usdc: Some(100.0),
+        }
+    }
+
+    #[test]
+    fn test_parses_config() {
+        let parsed = CopyRiskLimits::from_value(&serde_json::json!({
+            "max_daily_loss_usdc": 50, "max_open_positions": 2,
+            "max_trades_per_hour": 10, "max_drawdown_usdc": 100,
+        }));
+        assert_eq!(parsed, limits());
+        assert!(CopyRiskLimits::from_value(&Value::Null).is_empty());
+        assert!(CopyRiskLimits::from_value(&serde_json::json!({"max_loss": 1})).is_empty());
+    }
+
+    #[test]
+    fn test_entry_limits() {
+        let limits = limits();
+        let ok = CopyRiskState::default();
+        assert_eq!(limits.check_entry(&ok, 1, true), Ok(()));
+
+        let losing = CopyRiskState {
+            daily_pnl_usdc: -50.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            limits.check_entry(&losing, 0, true),
+            Err(SkipReason::DailyLossLimit)
+        );
+
+        assert_eq!(
+            limits.check_entry(&ok, 2, true),
+            Err(SkipReason::MaxOpenPositions)
+        );
+        // Adding to a held token does not open a new position.
+        assert_eq!(limits.check_entry(&ok, 2, false), Ok(()));
+
+        let busy = CopyRiskState {
+            trades_last_hour: 10,
+            ..Default::default()
+        };
+        assert_eq!(
+            limits.check_entry(&busy, 0, true),
+            Err(SkipReason::TradeRateLimit)
+        );
+    }
+
+    #[test]
+    fn test_drawdown_pauses() {
+        let limits = limits();
+        let mut state = CopyRiskState {
+            drawdown_usdc: 99.0,
+            ..Default::default()
+        };
+        assert_eq!(limits.pause_reason(&state), None);
+        state.drawdown_usdc = 100.0;
+        assert!(limits.pause_reason(&state).unwrap().contains("100.00 USDC"));
+        assert_eq!(CopyRiskLimits::default().pause_reason(&state), None);
+    }
+
+    #[test]
+    fn test_exits_and_settlements_feed_the_drawdown_pause() {
+        const DAY: i64 = 1_700_006_400;
+        // 100 shares for 50 USDC sold at 0.80, then 200 shares for 120 USDC:
+        // half exited at 0.20 and the rest resolved worthless.
+        let realized = [
+            CopyRealization::exit(100.0, 50.0, 100.0, 80.0, DAY - 60),
+            CopyRealization::exit(200.0, 120.0, 100.0, 20.0, DAY + 60),
+            CopyRealization::settlement(100.0, 60.0, 0.0, DAY + 120),
+        ];
+        assert!((realized[0].pnl_usdc - 30.0).abs() < 1e-9);
+        assert!((realized[1].pnl_usdc + 40.0).abs() < 1e-9);
+
+        let state = CopyRiskState::from_realized(&realized, DAY, 3);
+        assert!((state.daily_pnl_usdc + 100.0).abs() < 1e-9);
+        assert!((state.drawdown_usdc - 100.0).abs() < 1e-9);
+        assert!(limits().pause_reason(&state).is_some());
+        assert!(limits()
+            .pause_reason(&CopyRiskState::from_realized(&realized[..2], DAY, 3))
+            .is_none());
+    }
+
+    #[test]
+    fn test_oversized_exit_realizes_held_shares_only() {
+        let r = CopyRealization::exit(50.0, 25.0, 100.0, 60.0, 0);
+        assert!((r.pnl_usdc - 5.0).abs() < 1e-9);
+        assert_eq!(CopyRealization::exit(0.0, 0.0, 10.0, 6.0, 0).pnl_usdc, 0.0);
+    }
+}
@@ -0,0 +1,410 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::Result;
+use clickhouse::Client;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use super::polymarket::{fetch_trade_history, LeaderTrade, HISTORY_MAX_PAGES};
+use crate::proxy::HttpPool;
+use crate::storage::postgres::{self, CopyGap};
+use crate::strategy::Outcome;
+use crate::tasks::slot_resolver::fetch_winner;
+
+/// Positions smaller than this many shares count as closed.
+const DUST_SHARES: f64 = 1e-6;
+/// Gamma lookups per scorecard for slots ClickHouse has not resolved.
+const MAX_GAMMA_LOOKUPS: usize = 50;
+
+// ---------------------------------------------------------------------------
+// LeaderScorecard
+// ---------------------------------------------------------------------------
+
+/// Performance of a watched wallet over a window of its data API trades.
+///
+/// Positions are costed at the leader's average entry price and closed by
+/// sells or by slot resolution; fees are not known and not deducted. Shares
+/// sold beyond what the window saw bought have no cost basis and are ignored.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LeaderScorecard {
+    pub address: String,
+    pub window_secs: i64,
+    pub trades: usize,
+    pub volume_usdc: f64,
+    pub trades_per_day: f64,
+    /// Volume-weighted price paid on buys.
+    pub avg_entry_price: Option<f64>,
+    pub realized_pnl_usdc: f64,
+    pub closed_positions: usize,
+    pub wins: usize,
+    pub win_rate: Option<f64>,
+    /// From first buy to the sell or slot end that closed the position.
+    pub avg_holding_secs: Option<f64>,
+    /// Positions still held or whose slot has not resolved.
+    pub open_positions: usize,
+    /// Per market family (slug without its slot timestamp), busiest first.
+    pub markets: Vec<MarketBreakdown>,
+    /// How closely followers' fills tracked the leader's.
+    pub copyability: Option<CopyGap>,
+    /// The leader traded more in the window than the data API pages back to:
+    /// the stats only cover the newest trades.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MarketBreakdown {
+    pub market: String,
+    pub trades: usize,
+    pub volume_usdc: f64,
+    pub realized_pnl_usdc: f64,
+    pub closed_positions: usize,
+    pub wins: usize,
+    pub win_rate: Option<f64>,
+}
+
+impl MarketBreakdown {
+    fn close(&mut self, pnl: f64) {
+        self.realized_pnl_usdc += pnl;
+        self.closed_positions += 1;
+        if pnl > 0.0 {
+            self.wins += 1;
+        }
+    }
+}
+
+/// One token held by the leader, built up from its trades.
+struct Position {
+    market: String,
+    slug: String,
+    outcome: Outcome,
+    shares: f64,
+    cost: f64,
+    realized: f64,
+    opened_at: i64,
+}
+
+/// Score `trades` (oldest first) given the winners of resolved slots.
+pub fn compute(
+    address: &str,
+    trades: &[LeaderTrade],
+    winners: &HashMap<String, Outcome>,
+    window_secs: i64,
+) -> LeaderScorecard {
+    let mut card = LeaderScorecard {
+        address: address.to_string(),
+        window_secs,
+        trades: trades.len(),
+        ..Default::default()
+    };
+    let mut markets: BTreeMap<String, MarketBreakdown> = BTreeMap::new();
+    let mut positions: HashMap<&str, Position> = HashMap::new();
+    let mut holdings: Vec<i64> = Vec::new();
+    let (mut bought_usdc, mut bought_shares) = (0.0, 0.0);
+
+    for trade in trades {
+        let notional = trade.size * trade.price;
+        let market = market_family(trade);
+        card.volume_usdc += notional;
+        let breakdown = markets.entry(market.clone()).or_default();
+        breakdown.trades += 1;
+        breakdown.volume_usdc += notional;
+
+        if !trade.is_sell() {
+            bought_usdc += notional;
+            bought_shares += trade.size;
+            let position = positions.entry(&trade.asset).or_insert_with(|| Position {
+                market,
+                slug: trade.slug.clone(),
+                outcome: trade.outcome(),
+                shares: 0.0,
+                cost: 0.0,
+                realized: 0.0,
+                opened_at: trade.timestamp,
+            });
+            if position.shares < DUST_SHARES {
+                position.opened_at = trade.timestamp;
+                position.realized = 0.0;
+            }
+            position.shares += trade.size;
+            position.cost += notional;
+            continue;
+        }
+
+        let Some(position) = positions.get_mut(trade.asset.as_str()) else {
+            continue;
+        };
+        if position.shares < DUST_SHARES {
+            continue;
+        }
+        let sold = trade.size.min(position.shares);
+        let cost = position.cost * sold / position.shares;
+        position.realized += sold * trade.price - cost;
+        position.cost -= cost;
+        position.shares -= sold;
+        if position.shares < DUST_SHARES {
+            let pnl = position.realized;
+            card.realized_pnl_usdc += pnl;
+            card.closed_positions += 1;
+            card.wins += usize::from(pnl > 0.0);
+            holdings.push(trade.timestamp - position.opened_at);
+            markets
+                .entry(position.market.clone())
+                .or_default()
+                .close(pnl);
+        }
+    }
+
+    for position in positions.values() {
+        if position.shares < DUST_SHARES {
+            continue;
+        }
+        let Some(&winner) = winners.get(&position.slug) else {
+            card.open_positions += 1;
+            continue;
+        };
+        let payout = if winner == position.outcome {
+            position.shares
+        } else {
+            0.0
+        };
+        let pnl = position.realized + payout - position.cost;
+        card.realized_pnl_usdc += pnl;
+        card.closed_positions += 1;
+        card.wins += usize::from(pnl > 0.0);
+        if let Some(end) = slot_end(&position.slug) {
+            holdings.push(end - position.opened_at);
+        }
+        markets
+            .entry(position.market.clone())
+            .or_default()
+            .close(pnl);
+    }
+
+    card.trades_per_day = if window_secs > 0 {
+        card.trades as f64 * 86_400.0 / window_secs as f64
+    } else {
+        0.0
+    };
+    card.avg_entry_price = (bought_shares > 0.0).then(|| bought_usdc / bought_shares);
+    card.win_rate = win_rate(card.wins, card.closed_positions);
+    card.avg_holding_secs =
+        (!holdings.is_empty()).then(|| holdings.iter().sum::<i64>() as f64 / holdings.len() as f64);
+    card.markets = markets
+        .into_iter()
+        .map(|(market, mut b)| {
+            b.market = market;
+            b.win_rate = win_rate(b.wins, b.closed_positions);
+            b
+        })
+        .collect();
+    card.markets
+        .sort_by(|a, b| b.volume_usdc.total_cmp(&a.volume_usdc));
+    card
+}
+
+fn win_rate(wins: usize, closed: usize) -> Option<f64> {
+    (closed > 0).then(|| wins as f64 / closed as f64)
+}
+
+/// Slug without its trailing slot timestamp (`btc-updown-15m-1771910100` →
+/// `btc-updown-15m`), or the condition id when the trade carries no slug.
+pub(super) fn market_family(trade: &LeaderTrade) -> String {
+    if trade.slug.is_empty() {
+        return trade.condition_id.clone();
+    }
+    match trade.slug.rsplit_once('-') {
+        Some((family, ts)) if ts.chars().all(|c| c.is_ascii_digit()) => family.to_string(),
+        _ => trade.slug.clone(),
+    }
+}
+
+/// End of an up/down slot from its slug (`…-15m-<slot start>`).
+fn slot_end(slug: &str) -> Option<i64> {
+    let mut parts = slug.rsplit('-');
+    let start: i64 = parts.next()?.parse().ok()?;
+    let duration = parts.next()?;
+    let (n, unit) = duration.split_at(duration.len().checked_sub(1)?);
+    let secs = match unit {
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        _ => return None,
+    };
+    Some(start + n.parse::<i64>().ok()? * secs)
+}
+
+// ---------------------------------------------------------------------------
+// build() — fetch trades, resolutions and copy fills, then score
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, clickhouse::Row, Deserialize)]
+struct SlotWinner {
+    symbol: String,
+    winner: i8,
+}
+
+/// Winners of the slots in `slugs`: recorded ones from `slot_snapshots`, the
+/// rest from Gamma.
+pub(super) async fn resolve_winners(
+    ch: &Client,
+    http: &HttpPool,
+    gamma_url: &str,
+    slugs: &HashSet<String>,
+) -> HashMap<String, Outcome> {
+    let to_outcome = |w: i8| if w == 1 { Outcome::Up } else { Outcome::Down };
+    let list: Vec<&str> = slugs.iter().map(String::as_str).collect();
+    let mut winners: HashMap<String, Outcome> = match ch
+        .query(
+            "SELECT symbol, assumeNotNull(any(winner)) AS winner FROM slot_snapshots \
+             WHERE symbol IN ? AND winner IS NOT NULL GROUP BY symbol",
+        )
+        .bind(list)
+        .fetch_all::<SlotWinner>()
+        .await
+    {
+        Ok(rows) => rows
+            .into_iter()
+            .map(|r| (r.symbol, to_outcome(r.winner)))
+            .collect(),
+        Err(e) => {
+            tracing::warn!(error = %e, "scorecard_winners_query_failed");
+            HashMap::new()
+        }
+    };
+
+    let missing: Vec<&String> = slugs
+        .iter()
+        .filter(|s| !winners.contains_key(*s))
+        .take(MAX_GAMMA_LOOKUPS)
+        .collect();
+    for slug in missing {
+        match fetch_winner(http, gamma_url, slug).await {
+            Ok(Some(w)) => {
+                winners.insert(slug.clone(), to_outcome(w));
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!(slug = %slug, error = %e, "scorecard_gamma_lookup_failed"),
+        }
+    }
+    winners
+}
+
+/// Scorecard of `address` over the last `window_secs`.
+pub async fn build(
+    ch: &Client,
+    db: &PgPool,
+    http: &HttpPool,
+    data_api_url: &str,
+    gamma_url: &str,
+    address: &str,
+    window_secs: i64,
+) -> Result<LeaderScorecard> {
+    let since = chrono::Utc::now().timestamp() - window_secs;
+    let history =
+        fetch_trade_history(data_api_url, http, address, since, HISTORY_MAX_PAGES).await?;
+    let mut trades = history.trades;
+    trades.sort_by_key(|t| t.timestamp);
+
+    let slugs: HashSet<String> = trades
+        .iter()
+        .filter(|t| !t.slug.is_empty())
+        .map(|t| t.slug.clone())
+        .collect();
+    let winners = resolve_winners(ch, http, gamma_url, &slugs).await;
+
+    let mut card = compute(address, &trades, &winners, window_secs);
+    card.truncated = history.truncated;
+    card.copyability = match postgres::load_copy_gap(db, address, since).await {
+        Ok(gap) => (gap.fills > 0).then_some(gap),
+        Err(e) => {
+            tracing::warn!(address, error = %e, "scorecard_copy_gap_failed");
+            None
+        }
+    };
+    Ok(card)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLUG: &str = "btc-updown-15m-1000";
+
+    fn trade(
+        side: &str,
+        asset: &str,
+        outcome: &str,
+        size: f64,
+        price: f64,
+        ts: i64,
+    ) -> LeaderTrade {
+        serde_json::from_value(serde_json::json!({
+            "side": side, "asset": asset, "conditionId": "c", "size": size, "price": price,
+            "timestamp": ts, "transactionHash": format!("0x{ts}"), "outcome": outcome,
+            "slug": SLUG,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_slug_helpers() {
+        assert_eq!(slot_end(SLUG), Some(1_900));
+        assert_eq!(slot_end("eth-updown-1h-3600"), Some(7_200));
+        assert_eq!(slot_end("will-it-rain"), None);
+        assert_eq!(
+            market_family(&trade("BUY", "a", "Up", 1.0, 0.5, 1)),
+            "btc-updown-15m"
+        );
+    }
+
+    #[test]
+    fn test_sell_and_resolution_close_positions() {
+        let trades = vec![
+            // Up: buy 100 @ 0.40, sell 50 @ 0.60, sell 50 @ 0.50 → +15
+            trade("BUY", "up", "Up", 100.0, 0.40, 1_000),
+            trade("SELL", "up", "Up", 50.0, 0.60, 1_100),
+            trade("SELL", "up", "Up", 50.0, 0.50, 1_300),
+            // Down: buy 20 @ 0.50, held into an UP resolution → −10
+            trade("BUY", "down", "Down", 20.0, 0.50, 1_200),
+        ];
+        let winners = HashMap::from([(SLUG.to_string(), Outcome::Up)]);
+        let card = compute("0xleader", &trades, &winners, 86_400);
+
+        assert_eq!(card.trades, 4);
+        assert!((card.realized_pnl_usdc - 5.0).abs() < 1e-9);
+        assert_eq!(card.closed_positions, 2);
+        assert_eq!(card.wins, 1);
+        assert_eq!(card.win_rate, Some(0.5));
+        assert_eq!(card.open_positions, 0);
+        // $50 paid for 120 shares.
+        assert!((card.avg_entry_price.unwrap() - 50.0 / 120.0).abs() < 1e-9);
+        // Up held 300s, Down held until the slot end at 1900 (700s).
+        assert_eq!(card.avg_holding_secs, Some(500.0));
+        assert!((card.trades_per_day - 4.0).abs() < 1e-9);
+
+        assert_eq!(card.markets.len(), 1);
+        let market = &card.markets[0];
+        assert_eq!(market.market, "btc-updown-15m");
+        assert_eq!(market.closed_positions, 2);
+        assert!((market.realized_pnl_usdc - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unresolved_and_unknown_basis_positions() {
+        let trades = vec![
+            // Sold without a buy in the window: no cost basis, ignored.
+            trade("SELL", "old", "Up", 10.0, 0.9, 900),
+            trade("BUY", "up", "Up", 10.0, 0.5, 1_000),
+        ];
+        let card = compute("0xleader", &trades, &HashMap::new(), 86_400);
+
+        assert_eq!(card.closed_positions, 0);
+        assert_eq!(card.open_positions, 1);
+        assert_eq!(card.win_rate, None);
+        assert_eq!(card.realized_pnl_usdc, 0.0);
+    }
+}
@@ -0,0 +1,260 @@
+use crate::execution::MarketMeta;
+use crate::storage::postgres::CopyRelationship;
+
+use super::filters::SkipReason;
+use super::polymarket::LeaderTrade;
+
+// ---------------------------------------------------------------------------
+// SizeMode
+// ---------------------------------------------------------------------------
+
+/// How a relationship sizes copied entries, from `copy_relationships.size_mode`.
+/// `size_value` is read per mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeMode {
+    /// `size_value` USDC per entry.
+    Fixed,
+    /// The leader's shares × `size_value`.
+    Proportional,
+    /// `size_value` percent of the follower's available USDC.
+    PercentOfFollowerBalance,
+    /// The leader's entry as a share of their portfolio value, × `size_value`,
+    /// applied to the follower's available USDC.
+    MirrorLeaderAllocation,
+}
+
+impl SizeMode {
+    /// Unknown modes size like `fixed`.
+    pub fn parse(mode: &str) -> Self {
+        match mode {
+            "proportional" => Self::Proportional,
+            "percent_of_follower_balance" => Self::PercentOfFollowerBalance,
+            "mirror_leader_allocation" => Self::MirrorLeaderAllocation,
+            _ => Self::Fixed,
+        }
+    }
+
+    pub fn needs_follower_balance(self) -> bool {
+        matches!(
+            self,
+            Self::PercentOfFollowerBalance | Self::MirrorLeaderAllocation
+        )
+    }
+
+    pub fn needs_leader_value(self) -> bool {
+        self == Self::MirrorLeaderAllocation
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Entry sizing
+// ---------------------------------------------------------------------------
+
+/// Bankrolls a balance-relative entry is sized against.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bankrolls {
+    /// Follower's available USDC.
+    pub follower: Option<f64>,
+    /// Leader's portfolio value.
+    pub leader: Option<f64>,
+}
+
+/// USDC to spend copying an entry: the size mode's amount, clamped to the
+/// relationship's min/max, then rounded to the market's minimum order size.
+/// Entries under half the minimum are skipped, the rest are raised to it.
+pub fn entry_size(
+    trade: &LeaderTrade,
+    follower: &CopyRelationship,
+    bankrolls: Bankrolls,
+    market: Option<&MarketMeta>,
+) -> Result<f64, SkipReason> {
+    let raw = match SizeMode::parse(&follower.size_mode) {
+        SizeMode::Fixed => follower.size_value,
+        SizeMode::Proportional => trade.size * follower.size_value,
+        SizeMode::PercentOfFollowerBalance => {
+            let balance = bankrolls.follower.ok_or(SkipReason::BalanceUnavailable)?;
+            balance * follower.size_value / 100.0
+        }
+        SizeMode::MirrorLeaderAllocation => {
+            let balance = bankrolls.follower.ok_or(SkipReason::BalanceUnavailable)?;
+            let leader_value = bankrolls
+                .leader
+                .filter(|v| *v > 0.0)
+                .ok_or(SkipReason::LeaderValueUnavailable)?;
+            let allocation = (trade.size * trade.price / leader_value).min(1.0);
+            balance * allocation * follower.size_value
+        }
+    };
+
+    let mut size = raw;
+    if let Some(min) = follower.min_size_usdc {
+        size = size.max(min);
+    }
+    if let Some(max) = follower.max_size_usdc {
+        size = size.min(max);
+    }
+
+    let min_shares = market.map_or(0.0, |m| m.min_order_size);
+    if min_shares > 0.0 && trade.price > 0.0 {
+        let shares = size / trade.price;
+        if shares < min_shares {
+            let min_size = min_shares * trade.price;
+            let fits_max = follower.max_size_usdc.is_none_or(|max| min_size <= max);
+            if shares < min_shares / 2.0 || !fits_max {
+                return Err(SkipReason::BelowMinOrderSize);
+            }
+            size = min_size;
+        }
+    }
+    if size <= 0.0 {
+        return Err(SkipReason::BelowMinOrderSize);
+    }
+    Ok(size)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(price: f64, size: f64) -> LeaderTrade {
+        serde_json::from_value(serde_json::json!({
+            "side": "BUY", "asset": "tok", "conditionId": "c", "size": size,
+            "price": price, "timestamp": 1_000, "transactionHash": "0xaa",
+        }))
+        .unwrap()
+    }
+
+    fn follower(mode: &str, value: f64) -> CopyRelationship {
+        CopyRelationship {
+            id: 1,
+            follower_wallet_id: 42,
+            size_mode: mode.to_string(),
+            size_value: value,
+            max_position_usdc: 1_000.0,
+            min_size_usdc: None,
+            max_size_usdc: None,
+            markets_filter: None,
+            execution_algo: None,
+            filters: Default::default(),
+            risk_limits: Default::default(),
+            is_paper: false,
+        }
+    }
+
+    fn market(min_order_size: f64) -> MarketMeta {
+        MarketMeta {
+            neg_risk: false,
+            tick_size: 0.01,
+            min_order_size,
+        }
+    }
+
+    const BANKROLLS: Bankrolls = Bankrolls {
+        follower: Some(500.0),
+        leader: Some(10_000.0),
+    };
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(SizeMode::parse("fixed"), SizeMode::Fixed);
+        assert_eq!(
+            SizeMode::parse("percent_of_follower_balance"),
+            SizeMode::PercentOfFollowerBalance
+        );
+        assert_eq!(SizeMode::parse("unknown"), SizeMode::Fixed);
+        assert!(SizeMode::MirrorLeaderAllocation.needs_leader_value());
+        assert!(!SizeMode::Proportional.needs_follower_balance());
+    }
+
+    #[test]
+    fn test_percent_of_follower_balance() {
+        let size = entry_size(
+            &trade(0.5, 100.0),
+            &follower("percent_of_follower_balance", 4.0),
+            BANKROLLS,
+            None,
+        );
+        assert!((size.unwrap() - 20.0).abs() < 1e-9);
+        assert_eq!(
+            entry_size(
+                &trade(0.5, 100.0),
+                &follower("percent_of_follower_balance", 4.0),
+                Bankrolls::default(),
+                None,
+            ),
+            Err(SkipReason::BalanceUnavailable)
+        );
+    }
+
+    #[test]
+    fn test_mirror_leader_allocation() {
+        // 1,000 USDC of a 10,000 portfolio is 10%, of 500 is 50.
+        let size = entry_size(
+            &trade(0.5, 2_000.0),
+            &follower("mirror_leader_allocation", 1.0),
+            BANKROLLS,
+            None,
+        );
+        assert!((size.unwrap() - 50.0).abs() < 1e-9);
+        assert_eq!(
+            entry_size(
+                &trade(0.5, 2_000.0),
+                &follower("mirror_leader_allocation", 1.0),
+                Bankrolls {
+                    leader: None,
+                    ..BANKROLLS
+                },
+                None,
+            ),
+            Err(SkipReason::LeaderValueUnavailable)
+        );
+    }
+
+    #[test]
+    fn test_clamps() {
+        let mut f = follower("percent_of_follower_balance", 50.0);
+        f.max_size_usdc = Some(100.0);
+        let size = entry_size(&trade(0.5, 1.0), &f, BANKROLLS, None).unwrap();
+        assert!((size - 100.0).abs() < 1e-9);
+
+        f.size_value = 0.1;
+        f.min_size_usdc = Some(5.0);
+        let size = entry_size(&trade(0.5, 1.0), &f, BANKROLLS, None).unwrap();
+        assert!((size - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rounds_to_min_order_size() {
+        // 2 USDC at 0.5 is 4 shares: raised to the 5-share minimum.
+        let size = entry_size(
+            &trade(0.5, 1.0),
+            &follower("fixed", 2.0),
+            BANKROLLS,
+            Some(&market(5.0)),
+        );
+        assert!((size.unwrap() - 2.5).abs() < 1e-9);
+
+        // 1 USDC is 2 shares, under half the minimum.
+        assert_eq!(
+            entry_size(
+                &trade(0.5, 1.0),
+                &follower("fixed", 1.0),
+                BANKROLLS,
+                Some(&market(5.0)),
+            ),
+            Err(SkipReason::BelowMinOrderSize)
+        );
+
+        // Raising would break the max clamp.
+        let mut f = follower("fixed", 2.0);
+        f.max_size_usdc = Some(2.0);
+        assert_eq!(
+            entry_size(&trade(0.5, 1.0), &f, BANKROLLS, Some(&market(5.0))),
+            Err(SkipReason::BelowMinOrderSize)
+        );
+    }
+}
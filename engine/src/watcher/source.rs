@@ -0,0 +1,140 @@
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::Result;
+use redis::aio::MultiplexedConnection;
+use tokio::time::{Interval, MissedTickBehavior};
+
+use crate::proxy::HttpPool;
+
+use super::polymarket::{backfill_start, fetch_trades_since, load_cursor, select_new, LeaderTrade};
+
+/// Seconds between data API polls of the polling source.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// ---------------------------------------------------------------------------
+// LeaderTradeSource
+// ---------------------------------------------------------------------------
+
+/// New trades of one leader, oldest first.
+#[derive(Debug)]
+pub struct LeaderBatch {
+    pub address: String,
+    /// Possibly empty when the batch only moves the cursor.
+    pub trades: Vec<LeaderTrade>,
+    /// Where the leader's cursor moves once the batch is copied: the newest
+    /// trade of a complete data API read. `None` for streamed fills, which
+    /// can have gaps the next complete read must still cover.
+    pub cursor: Option<i64>,
+    /// Source that detected the trades, for metrics.
+    pub source: &'static str,
+}
+
+/// Detects the trades of watched leaders for the copy watcher.
+///
+/// Sources only return trades not handled yet: the watcher marks each trade
+/// seen once copied and advances the leader's cursor to the batch's.
+pub trait LeaderTradeSource: Send {
+    /// Wait for new trades of `addresses`. Returns at least every few
+    /// seconds, possibly empty, so the watcher can refresh `addresses`.
+    fn next_batches(
+        &mut self,
+        addresses: &[String],
+        redis: &mut MultiplexedConnection,
+    ) -> impl Future<Output = Result<Vec<LeaderBatch>>> + Send;
+}
+
+// ---------------------------------------------------------------------------
+// PollingSource — data API trades endpoint
+// ---------------------------------------------------------------------------
+
+/// Polls every leader's trades from the data API once per second.
+pub struct PollingSource {
+    data_api_url: String,
+    http: HttpPool,
+    max_trade_age_secs: i64,
+    interval: Interval,
+}
+
+impl PollingSource {
+    pub fn new(data_api_url: &str, http: HttpPool, max_trade_age_secs: i64) -> Self {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            data_api_url: data_api_url.to_string(),
+            http,
+            max_trade_age_secs,
+            interval,
+        }
+    }
+
+    /// Poll every address once, concurrently. Leaders whose poll fails are
+    /// logged and retried on the next poll.
+    pub async fn poll(
+        &self,
+        addresses: &[String],
+        redis: &mut MultiplexedConnection,
+    ) -> Vec<LeaderBatch> {
+        let mut handles = Vec::new();
+        for address in addresses {
+            let url = self.data_api_url.clone();
+            let client = self.http.clone();
+            let addr = address.clone();
+            let mut redis = redis.clone();
+            let max_trade_age_secs = self.max_trade_age_secs;
+
+            handles.push(tokio::spawn(async move {
+                let batch = async {
+                    let cursor = load_cursor(&mut redis, &addr).await?;
+                    let now = chrono::Utc::now().timestamp();
+                    let since = backfill_start(cursor.last_seen, now, max_trade_age_secs);
+                    let fetched = fetch_trades_since(&url, &client, &addr, since).await?;
+                    let newest = fetched.iter().map(|t| t.timestamp).max().unwrap_or(0);
+                    let trades = select_new(fetched, &cursor.seen);
+                    Ok::<_, anyhow::Error>((trades, cursor.last_seen, newest))
+                }
+                .await;
+                (addr, batch)
+            }));
+        }
+
+        let mut batches = Vec::new();
+        for handle in handles {
+            let (address, batch) = match handle.await {
+                Ok(joined) => joined,
+                Err(e) => {
+                    tracing::warn!(error = %e, "leader_poll_panicked");
+                    continue;
+                }
+            };
+            match batch {
+                // Trades streamed since the last poll are seen under their
+                // fill key, but the cursor still has to move past them.
+                Ok((trades, last_seen, newest)) if !trades.is_empty() || newest > last_seen => {
+                    batches.push(LeaderBatch {
+                        address,
+                        trades,
+                        cursor: Some(newest.max(last_seen)),
+                        source: "poll",
+                    })
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(address = %address, error = %e, "check_new_trades_failed");
+                }
+            }
+        }
+        batches
+    }
+}
+
+impl LeaderTradeSource for PollingSource {
+    async fn next_batches(
+        &mut self,
+        addresses: &[String],
+        redis: &mut MultiplexedConnection,
+    ) -> Result<Vec<LeaderBatch>> {
+        self.interval.tick().await;
+        Ok(self.poll(addresses, redis).await)
+    }
+}
@@ -115,7 +115,11 @@ private function backtestResultPayload(array $engineResult): array
             'total_pnl_usdc' => $engineResult['total_pnl_usdc'] ?? null,
             'max_drawdown' => $engineResult['max_drawdown'] ?? null,
             'sharpe_ratio' => $engineResult['sharpe_ratio'] ?? null,
-            'result_detail' => ['trades' => $this->transformTrades($engineResult['trades'] ?? [])],
+            'result_detail' => [
+                'gross_pnl_usdc' => $engineResult['gross_pnl_usdc'] ?? null,
+                'total_fees_usdc' => $engineResult['total_fees_usdc'] ?? null,
+                'trades' => $this->transformTrades($engineResult['trades'] ?? []),
+            ],
         ];
     }
 
@@ -142,6 +146,8 @@ private function transformTrades(array $trades): array
                 'exit_book_depth_usdc' => isset($trade['exit_book_depth_usdc']) ? round((float) $trade['exit_book_depth_usdc'], 4) : null,
                 'exit_depth_ratio' => isset($trade['exit_depth_ratio']) ? round((float) $trade['exit_depth_ratio'], 6) : null,
                 'pnl' => round($pnl, 6),
+                'gross_pnl' => round((float) ($trade['gross_pnl_usdc'] ?? $pnl), 6),
+                'fees' => round((float) ($trade['fees_usdc'] ?? 0), 6),
                 'cumulative_pnl' => round($cumulative, 6),
                 'symbol' => $trade['symbol'] ?? null,
                 'entry_at' => $trade['entry_at'] ?? null,
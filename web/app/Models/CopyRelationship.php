@@ -20,8 +20,16 @@ class CopyRelationship extends Model
         'size_mode',
         'size_value',
         'max_position_usdc',
+        'min_size_usdc',
+        'max_size_usdc',
         'markets_filter',
+        'execution_algo',
+        'copy_filters',
+        'risk_limits',
         'is_active',
+        'is_paper',
+        'paused_reason',
+        'paused_at',
     ];
 
     protected function casts(): array
@@ -29,8 +37,15 @@ protected function casts(): array
         return [
             'size_value' => 'decimal:6',
             'max_position_usdc' => 'decimal:6',
+            'min_size_usdc' => 'decimal:6',
+            'max_size_usdc' => 'decimal:6',
             'markets_filter' => 'array',
+            'execution_algo' => 'array',
+            'copy_filters' => 'array',
+            'risk_limits' => 'array',
             'is_active' => 'boolean',
+            'is_paper' => 'boolean',
+            'paused_at' => 'datetime',
         ];
     }
 
@@ -25,6 +25,7 @@ class CopyTrade extends Model
         'follower_price',
         'slippage_pct',
         'status',
+        'is_paper',
         'skip_reason',
         'detected_at',
         'executed_at',
@@ -37,6 +38,7 @@ protected function casts(): array
             'leader_size_usdc' => 'decimal:6',
             'follower_price' => 'decimal:6',
             'slippage_pct' => 'decimal:6',
+            'is_paper' => 'boolean',
             'detected_at' => 'datetime',
             'executed_at' => 'datetime',
         ];
@@ -38,6 +38,9 @@ class Trade extends Model
         'markout_at_60s',
         'markout_bps_60s',
         'executed_at',
+        'reject_reason',
+        'clob_rejection',
+        'parent_order_id',
     ];
 
     protected function casts(): array
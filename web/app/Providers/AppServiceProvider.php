@@ -23,6 +23,7 @@ public function register(): void
             return new \App\Services\EngineService(
                 baseUrl: config('services.engine.url'),
                 timeout: (int) config('services.engine.timeout'),
+                feeBps: (int) config('services.engine.fee_bps'),
             );
         });
 
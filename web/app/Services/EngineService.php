@@ -11,6 +11,7 @@ class EngineService
     public function __construct(
         private readonly string $baseUrl,
         private readonly int $timeout,
+        private readonly int $feeBps = 1000,
     ) {}
 
     /**
@@ -85,6 +86,48 @@ public function walletState(int $walletId): array
             ->json();
     }
 
+    /**
+     * Withdraw USDC from the wallet's Safe to an external address.
+     *
+     * @return array{id: int, kind: string, state: string, relayer_tx_id: string, nonce: int}
+     */
+    public function withdraw(int $walletId, string $toAddress, float $amountUsdc): array
+    {
+        return $this->client()
+            ->post("/internal/wallet/{$walletId}/withdraw", [
+                'to_address' => $toAddress,
+                'amount_usdc' => $amountUsdc,
+            ])
+            ->throw()
+            ->json();
+    }
+
+    public function walletTransactions(int $walletId): array
+    {
+        return $this->client()
+            ->get("/internal/wallet/{$walletId}/transactions")
+            ->throw()
+            ->json();
+    }
+
+    /**
+     * Replace the wallet's CLOB API key; also lifts a revocation.
+     *
+     * @return array{wallet_id: int, api_key: string, nonce: int}
+     */
+    public function rotateApiKey(int $walletId): array
+    {
+        return $this->client()
+            ->post("/internal/wallet/{$walletId}/api-key/rotate")
+            ->throw()
+            ->json();
+    }
+
+    public function revokeApiKey(int $walletId): void
+    {
+        $this->client()->delete("/internal/wallet/{$walletId}/api-key")->throw();
+    }
+
     public function runBacktest(array $strategyGraph, array $marketFilter, string $dateFrom, string $dateTo): array
     {
         return $this->client()
@@ -94,6 +137,7 @@ public function runBacktest(array $strategyGraph, array $marketFilter, string $d
                 'market_filter' => $marketFilter,
                 'date_from' => Carbon::parse($dateFrom)->startOfDay()->toIso8601ZuluString(),
                 'date_to' => Carbon::parse($dateTo)->endOfDay()->toIso8601ZuluString(),
+                'fee_bps' => $this->feeBps,
             ])
             ->throw()
             ->json();
@@ -114,6 +158,7 @@ public function slotStats(int $slotDuration, array $symbols = [], float $hours =
                 'slot_duration' => $slotDuration,
                 'symbols' => ! empty($symbols) ? implode(',', $symbols) : null,
                 'hours' => $hours,
+                'fee_bps' => $this->feeBps,
             ]))
             ->throw()
             ->json();
@@ -20,6 +20,9 @@
 
         $middleware->encryptCookies(except: ['appearance', 'sidebar_state']);
 
+        // Engine → web calls carry no session.
+        $middleware->validateCsrfTokens(except: ['internal/*']);
+
         $middleware->alias([
             'plan.limit' => CheckPlanLimits::class,
         ]);
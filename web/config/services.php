@@ -43,6 +43,7 @@
     'engine' => [
         'url' => env('ENGINE_INTERNAL_URL', 'http://engine:8080'),
         'timeout' => env('ENGINE_TIMEOUT', 30),
+        'fee_bps' => env('ENGINE_FEE_BPS', 1000),
     ],
 
     'ml_trainer' => [
@@ -0,0 +1,34 @@
+<?php
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Database\Schema\Blueprint;
+use Illuminate\Support\Facades\Schema;
+
+return new class extends Migration
+{
+    /**
+     * Run the migrations.
+     */
+    public function up(): void
+    {
+        Schema::create('execution_orders', function (Blueprint $table) {
+            $table->uuid('id')->primary();
+            $table->foreignId('wallet_id')->constrained()->cascadeOnDelete();
+            $table->string('state', 20)->default('queued');
+            $table->jsonb('payload');
+            $table->string('polymarket_order_id')->nullable();
+            $table->smallInteger('fee_bps')->nullable();
+            $table->timestamp('created_at')->useCurrent();
+            $table->timestamp('updated_at')->nullable();
+            $table->index(['state', 'created_at'], 'idx_execution_orders_state');
+        });
+    }
+
+    /**
+     * Reverse the migrations.
+     */
+    public function down(): void
+    {
+        Schema::dropIfExists('execution_orders');
+    }
+};
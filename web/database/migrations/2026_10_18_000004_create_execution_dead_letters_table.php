@@ -0,0 +1,35 @@
+<?php
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Database\Schema\Blueprint;
+use Illuminate\Support\Facades\Schema;
+
+return new class extends Migration
+{
+    /**
+     * Run the migrations.
+     */
+    public function up(): void
+    {
+        Schema::create('execution_dead_letters', function (Blueprint $table) {
+            $table->id();
+            $table->uuid('order_id')->unique();
+            $table->foreignId('wallet_id')->constrained()->cascadeOnDelete();
+            $table->jsonb('payload');
+            $table->string('reason', 32);
+            $table->integer('attempts')->default(0);
+            $table->text('last_error');
+            $table->timestamp('created_at')->useCurrent();
+            $table->timestamp('requeued_at')->nullable();
+            $table->index(['requeued_at', 'created_at'], 'idx_execution_dead_letters_pending');
+        });
+    }
+
+    /**
+     * Reverse the migrations.
+     */
+    public function down(): void
+    {
+        Schema::dropIfExists('execution_dead_letters');
+    }
+};
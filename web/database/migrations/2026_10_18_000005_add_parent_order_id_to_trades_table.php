@@ -0,0 +1,30 @@
+<?php
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Database\Schema\Blueprint;
+use Illuminate\Support\Facades\Schema;
+
+return new class extends Migration
+{
+    /**
+     * Run the migrations.
+     */
+    public function up(): void
+    {
+        Schema::table('trades', function (Blueprint $table) {
+            $table->uuid('parent_order_id')->nullable();
+            $table->index('parent_order_id', 'idx_trades_parent_order');
+        });
+    }
+
+    /**
+     * Reverse the migrations.
+     */
+    public function down(): void
+    {
+        Schema::table('trades', function (Blueprint $table) {
+            $table->dropIndex('idx_trades_parent_order');
+            $table->dropColumn('parent_order_id');
+        });
+    }
+};
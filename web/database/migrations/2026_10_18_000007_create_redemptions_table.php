@@ -0,0 +1,37 @@
+<?php
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Database\Schema\Blueprint;
+use Illuminate\Support\Facades\Schema;
+
+return new class extends Migration
+{
+    /**
+     * Run the migrations.
+     */
+    public function up(): void
+    {
+        Schema::create('redemptions', function (Blueprint $table) {
+            $table->id();
+            $table->foreignId('wallet_id')->constrained()->cascadeOnDelete();
+            $table->string('condition_id', 66);
+            $table->boolean('neg_risk')->default(false);
+            $table->string('relayer_tx_id')->nullable();
+            $table->string('tx_hash', 66)->nullable();
+            $table->string('state', 16)->default('submitted');
+            $table->decimal('expected_usdc', 18, 6)->default(0);
+            $table->timestamp('created_at')->useCurrent();
+            $table->timestamp('updated_at')->nullable();
+            $table->unique(['wallet_id', 'condition_id']);
+            $table->index('state', 'idx_redemptions_state');
+        });
+    }
+
+    /**
+     * Reverse the migrations.
+     */
+    public function down(): void
+    {
+        Schema::dropIfExists('redemptions');
+    }
+};
@@ -0,0 +1,40 @@
+<?php
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Database\Schema\Blueprint;
+use Illuminate\Support\Facades\Schema;
+
+return new class extends Migration
+{
+    /**
+     * Run the migrations.
+     */
+    public function up(): void
+    {
+        Schema::create('safe_transactions', function (Blueprint $table) {
+            $table->id();
+            $table->foreignId('wallet_id')->constrained()->cascadeOnDelete();
+            $table->string('kind', 32);
+            $table->string('to_address', 42);
+            $table->string('counterparty', 42)->nullable();
+            $table->decimal('amount', 18, 6)->nullable();
+            $table->text('data');
+            $table->unsignedBigInteger('nonce');
+            $table->string('relayer_tx_id');
+            $table->string('tx_hash', 66)->nullable();
+            $table->string('state', 16)->default('submitted');
+            $table->timestamp('created_at')->useCurrent();
+            $table->timestamp('updated_at')->nullable();
+            $table->index(['wallet_id', 'created_at'], 'idx_safe_transactions_wallet');
+            $table->index('state', 'idx_safe_transactions_state');
+        });
+    }
+
+    /**
+     * Reverse the migrations.
+     */
+    public function down(): void
+    {
+        Schema::dropIfExists('safe_transactions');
+    }
+};
@@ -0,0 +1,35 @@
+<?php
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Database\Schema\Blueprint;
+use Illuminate\Support\Facades\Schema;
+
+return new class extends Migration
+{
+    /**
+     * Run the migrations.
+     */
+    public function up(): void
+    {
+        Schema::create('wallet_api_credentials', function (Blueprint $table) {
+            $table->id();
+            $table->foreignId('wallet_id')->unique()->constrained()->cascadeOnDelete();
+            $table->string('address', 42);
+            $table->string('api_key');
+            $table->text('secret_enc')->nullable();
+            $table->text('passphrase_enc')->nullable();
+            $table->unsignedBigInteger('nonce')->default(0);
+            $table->timestamp('revoked_at')->nullable();
+            $table->timestamp('created_at')->useCurrent();
+            $table->timestamp('updated_at')->nullable();
+        });
+    }
+
+    /**
+     * Reverse the migrations.
+     */
+    public function down(): void
+    {
+        Schema::dropIfExists('wallet_api_credentials');
+    }
+};
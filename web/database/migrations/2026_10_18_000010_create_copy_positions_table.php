@@ -0,0 +1,35 @@
+<?php
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Database\Schema\Blueprint;
+use Illuminate\Support\Facades\Schema;
+
+return new class extends Migration
+{
+    /**
+     * Run the migrations.
+     */
+    public function up(): void
+    {
+        Schema::create('copy_positions', function (Blueprint $table) {
+            $table->id();
+            $table->foreignId('copy_relationship_id')->constrained()->cascadeOnDelete();
+            $table->string('condition_id', 66);
+            $table->string('token_id', 100);
+            $table->string('outcome', 10)->nullable();
+            $table->decimal('shares', 18, 6)->default(0);
+            $table->decimal('cost_usdc', 18, 6)->default(0);
+            $table->timestamp('created_at')->useCurrent();
+            $table->timestamp('updated_at')->nullable();
+            $table->unique(['copy_relationship_id', 'token_id']);
+        });
+    }
+
+    /**
+     * Reverse the migrations.
+     */
+    public function down(): void
+    {
+        Schema::dropIfExists('copy_positions');
+    }
+};
@@ -0,0 +1,51 @@
+<?php
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Database\Schema\Blueprint;
+use Illuminate\Support\Facades\Schema;
+
+return new class extends Migration
+{
+    /**
+     * Run the migrations.
+     */
+    public function up(): void
+    {
+        Schema::table('copy_relationships', function (Blueprint $table) {
+            $table->boolean('is_paper')->default(false)->after('is_active');
+        });
+
+        Schema::table('copy_trades', function (Blueprint $table) {
+            $table->boolean('is_paper')->default(false)->after('status');
+            $table->index(['copy_relationship_id', 'is_paper']);
+        });
+
+        // Paper and live copies of one relationship keep separate ledgers.
+        Schema::table('copy_positions', function (Blueprint $table) {
+            $table->boolean('is_paper')->default(false)->after('token_id');
+            $table->dropUnique(['copy_relationship_id', 'token_id']);
+            $table->unique(['copy_relationship_id', 'token_id', 'is_paper']);
+        });
+    }
+
+    /**
+     * Reverse the migrations.
+     */
+    public function down(): void
+    {
+        Schema::table('copy_positions', function (Blueprint $table) {
+            $table->dropUnique(['copy_relationship_id', 'token_id', 'is_paper']);
+            $table->unique(['copy_relationship_id', 'token_id']);
+            $table->dropColumn('is_paper');
+        });
+
+        Schema::table('copy_trades', function (Blueprint $table) {
+            $table->dropIndex(['copy_relationship_id', 'is_paper']);
+            $table->dropColumn('is_paper');
+        });
+
+        Schema::table('copy_relationships', function (Blueprint $table) {
+            $table->dropColumn('is_paper');
+        });
+    }
+};
@@ -0,0 +1,30 @@
+<?php
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Database\Schema\Blueprint;
+use Illuminate\Support\Facades\Schema;
+
+return new class extends Migration
+{
+    /**
+     * Run the migrations.
+     */
+    public function up(): void
+    {
+        Schema::table('copy_relationships', function (Blueprint $table) {
+            $table->jsonb('risk_limits')->nullable();
+            $table->string('paused_reason')->nullable();
+            $table->timestamp('paused_at')->nullable();
+        });
+    }
+
+    /**
+     * Reverse the migrations.
+     */
+    public function down(): void
+    {
+        Schema::table('copy_relationships', function (Blueprint $table) {
+            $table->dropColumn(['risk_limits', 'paused_reason', 'paused_at']);
+        });
+    }
+};
@@ -0,0 +1,32 @@
+<?php
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Database\Schema\Blueprint;
+use Illuminate\Support\Facades\Schema;
+
+return new class extends Migration
+{
+    /**
+     * Run the migrations.
+     */
+    public function up(): void
+    {
+        Schema::table('copy_relationships', function (Blueprint $table) {
+            // Fits percent_of_follower_balance and mirror_leader_allocation.
+            $table->string('size_mode', 32)->default('proportional')->change();
+            $table->decimal('min_size_usdc', 18, 6)->nullable();
+            $table->decimal('max_size_usdc', 18, 6)->nullable();
+        });
+    }
+
+    /**
+     * Reverse the migrations.
+     */
+    public function down(): void
+    {
+        Schema::table('copy_relationships', function (Blueprint $table) {
+            $table->dropColumn(['min_size_usdc', 'max_size_usdc']);
+            $table->string('size_mode', 20)->default('proportional')->change();
+        });
+    }
+};
@@ -0,0 +1,36 @@
+<?php
+
+use Illuminate\Database\Migrations\Migration;
+use Illuminate\Database\Schema\Blueprint;
+use Illuminate\Support\Facades\Schema;
+
+return new class extends Migration
+{
+    /**
+     * Run the migrations.
+     */
+    public function up(): void
+    {
+        // Realized PnL of copied holdings, written on exits and at resolution.
+        Schema::create('copy_realizations', function (Blueprint $table) {
+            $table->id();
+            $table->foreignId('copy_relationship_id')->constrained()->cascadeOnDelete();
+            $table->boolean('is_paper')->default(false);
+            $table->string('condition_id', 66);
+            $table->string('token_id', 100);
+            $table->string('reason', 16);
+            $table->decimal('shares', 18, 6);
+            $table->decimal('pnl_usdc', 18, 6);
+            $table->timestamp('realized_at')->useCurrent();
+            $table->index(['copy_relationship_id', 'is_paper', 'realized_at']);
+        });
+    }
+
+    /**
+     * Reverse the migrations.
+     */
+    public function down(): void
+    {
+        Schema::dropIfExists('copy_realizations');
+    }
+};
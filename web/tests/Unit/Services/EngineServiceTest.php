@@ -65,6 +65,17 @@
     expect($result)
         ->toHaveKey('total_trades', 5)
         ->toHaveKey('win_rate', 0.6);
+
+    Http::assertSent(fn ($request) => $request['fee_bps'] === 1000);
+});
+
+it('sends the configured fee rate with slot stats', function () {
+    Http::fake(['engine:8080/internal/stats/slots*' => Http::response(['summary' => []])]);
+
+    $service = new EngineService(baseUrl: 'http://engine:8080', timeout: 10, feeBps: 250);
+    $service->slotStats(900, ['btc']);
+
+    Http::assertSent(fn ($request) => str_contains($request->url(), 'fee_bps=250'));
 });
 
 it('fetches engine status', function () {